@@ -0,0 +1,84 @@
+//! Cost of telemetry on the request path.
+//!
+//! Compares a bare service with the same service behind a timeout layer that reports to
+//! `NullSink` (the default) and to a sink that accepts and discards every event. The `NullSink`
+//! row still includes building each event and awaiting an emit that returns at once, so it sits
+//! slightly above the layer's own cost; the gap to the discarding sink is what driving a real
+//! sink adds.
+//!
+//! Run with `cargo bench --bench telemetry_overhead`.
+
+use ninelives::telemetry::{emit_best_effort, NullSink, PolicyEvent, RequestOutcome};
+use ninelives::TimeoutLayer;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use std::hint::black_box;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service, ServiceExt};
+
+const ITERATIONS: u32 = 200_000;
+
+/// A real (non-null) sink that does no work, isolating the cost of the emit path itself.
+#[derive(Clone, Copy)]
+struct DiscardSink;
+
+impl Service<PolicyEvent> for DiscardSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Ready<Result<(), Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        black_box(event);
+        ready(Ok(()))
+    }
+}
+
+async fn per_call<S>(svc: S) -> Duration
+where
+    S: Service<u64, Response = u64> + Clone,
+    S::Error: std::fmt::Debug,
+{
+    let start = Instant::now();
+    for n in 0..u64::from(ITERATIONS) {
+        black_box(svc.clone().oneshot(black_box(n)).await.unwrap());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+async fn emit_per_call<S>(sink: S) -> Duration
+where
+    S: Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    let event = PolicyEvent::Request(RequestOutcome::Success { duration: Duration::ZERO });
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        emit_best_effort(sink.clone(), black_box(event.clone())).await;
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    let inner = tower::service_fn(|n: u64| async move { Ok::<_, Infallible>(n) });
+    let timeout = TimeoutLayer::new(Duration::from_secs(1)).unwrap();
+
+    rt.block_on(async {
+        let rows = [
+            ("emit_best_effort(NullSink)", emit_per_call(NullSink).await),
+            ("emit_best_effort(DiscardSink)", emit_per_call(DiscardSink).await),
+            ("service, no layer", per_call(inner).await),
+            ("timeout + NullSink", per_call(timeout.clone().layer(inner)).await),
+            ("timeout + DiscardSink", per_call(timeout.with_sink(DiscardSink).layer(inner)).await),
+        ];
+        for (name, cost) in rows {
+            println!("{:<32} {:>8.1?} per call", name, cost);
+        }
+    });
+}
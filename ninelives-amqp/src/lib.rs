@@ -0,0 +1,196 @@
+//! AMQP (RabbitMQ) telemetry sink for `ninelives` (optional companion crate).
+//!
+//! Publishes each event as a JSON `EventEnvelope` to an exchange with the routing key
+//! `<layer>.<event>` (optionally prefixed), so a topic exchange can route, for example,
+//! `circuit_breaker.*` to an alerting queue and `#` to an archive.
+//!
+//! The channel runs in publisher-confirm mode and every publish waits for the broker's ack. A
+//! nack or a failed publish counts in [`AmqpSink::failures`]. Broken connections are replaced
+//! lazily: the next publish reconnects and retries once.
+//!
+//! Default build is a no-op sink to keep dependencies light. Enable the `client` feature to
+//! publish.
+//!
+//! ```rust,no_run
+//! use ninelives_amqp::AmqpSink;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = AmqpSink::new("amqp://127.0.0.1:5672/%2f", "ninelives.events")
+//!     .await?
+//!     .with_routing_prefix("checkout");
+//! // attach with .with_sink(sink)
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Routing key for an event of `layer`/`event`: `<prefix>.<layer>.<event>` or `<layer>.<event>`.
+pub fn routing_key(prefix: Option<&str>, layer: &str, event: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}.{layer}.{event}"),
+        None => format!("{layer}.{event}"),
+    }
+}
+
+/// Sink publishing policy events to an AMQP exchange with publisher confirms.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct AmqpSink {
+    uri: Arc<str>,
+    exchange: Arc<str>,
+    prefix: Option<Arc<str>>,
+    failures: Arc<AtomicU64>,
+    #[cfg(feature = "client")]
+    link: Arc<tokio::sync::Mutex<Option<Link>>>,
+}
+
+impl std::fmt::Debug for AmqpSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AmqpSink")
+            .field("exchange", &self.exchange)
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AmqpSink {
+    /// Connect to the broker at `uri` and publish to `exchange`, which must already exist.
+    ///
+    /// Connecting up front surfaces bad URIs and credentials here rather than as dropped events.
+    pub async fn new<S: Into<String>>(
+        uri: S,
+        exchange: S,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let uri: Arc<str> = Arc::from(uri.into());
+        let exchange = Arc::from(exchange.into());
+        let failures = Arc::new(AtomicU64::new(0));
+        #[cfg(feature = "client")]
+        let sink = {
+            let link = Arc::new(tokio::sync::Mutex::new(Some(Link::open(&uri).await?)));
+            Self { uri, exchange, prefix: None, failures, link }
+        };
+
+        #[cfg(not(feature = "client"))]
+        let sink = Self { uri, exchange, prefix: None, failures };
+
+        Ok(sink)
+    }
+
+    /// Prefix routing keys with `prefix`, e.g. a service name.
+    pub fn with_routing_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(Arc::from(prefix.into()));
+        self
+    }
+
+    /// Events the broker nacked or that could not be published after a reconnect.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Publish and wait for the confirm, reconnecting once if the channel is broken.
+    #[cfg(feature = "client")]
+    async fn publish(&self, routing_key: &str, payload: &[u8]) -> bool {
+        use lapin::options::BasicPublishOptions;
+        use lapin::BasicProperties;
+
+        // Publishes share one channel; the lock also keeps reconnects from racing.
+        let mut slot = self.link.lock().await;
+        for _ in 0..2 {
+            let link = match slot.take().filter(|link| link.channel.status().connected()) {
+                Some(link) => link,
+                None => match Link::open(&self.uri).await {
+                    Ok(link) => link,
+                    Err(_) => return false,
+                },
+            };
+            let properties =
+                BasicProperties::default().with_content_type("application/json".into());
+            let confirm = link
+                .channel
+                .basic_publish(
+                    &self.exchange,
+                    routing_key,
+                    BasicPublishOptions::default(),
+                    payload,
+                    properties,
+                )
+                .await;
+            let confirmation = match confirm {
+                Ok(pending) => pending.await,
+                Err(err) => Err(err),
+            };
+            match confirmation {
+                Ok(confirmation) => {
+                    *slot = Some(link);
+                    return confirmation.is_ack();
+                }
+                // The channel or connection is gone; drop it and try a fresh one.
+                Err(_) => continue,
+            }
+        }
+        false
+    }
+}
+
+/// A connection and its confirm-mode channel, kept together so the connection lives as long as
+/// the channel is in use.
+#[cfg(feature = "client")]
+struct Link {
+    _connection: lapin::Connection,
+    channel: lapin::Channel,
+}
+
+#[cfg(feature = "client")]
+impl Link {
+    async fn open(uri: &str) -> Result<Self, lapin::Error> {
+        use lapin::options::ConfirmSelectOptions;
+
+        let connection =
+            lapin::Connection::connect(uri, lapin::ConnectionProperties::default()).await?;
+        let channel = connection.create_channel().await?;
+        channel.confirm_select(ConfirmSelectOptions::default()).await?;
+        Ok(Self { _connection: connection, channel })
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for AmqpSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let key = routing_key(self.prefix.as_deref(), event.layer_kind(), event.event_name());
+            let payload =
+                ninelives::telemetry::EventEnvelope::capture(event).to_json().into_bytes();
+            let sink = self.clone();
+            Box::pin(async move {
+                if !sink.publish(&key, &payload).await {
+                    sink.failures.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for AmqpSink {
+    type SinkError = Infallible;
+}
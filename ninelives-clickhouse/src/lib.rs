@@ -0,0 +1,214 @@
+//! ClickHouse telemetry sink for `ninelives`.
+//!
+//! Events are batched into a wide events table, one row per event: attribution and the event
+//! kind as columns, the main duration pulled out for aggregation, and the full event as JSON for
+//! anything else (`JSONExtractUInt(payload, 'attempt')`). Create the table with
+//! [`create_table_sql`].
+//!
+//! Default build is a no-op; enable the `client` feature to insert rows.
+//!
+//! ```rust,no_run
+//! use ninelives_clickhouse::{create_table_sql, ClickHouseSink};
+//! println!("{}", create_table_sql("policy_events"));
+//! let sink = ClickHouseSink::new("http://localhost:8123", "default", "policy_events")
+//!     .with_batch_size(10_000);
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One row of the events table; see [`create_table_sql`] for the column types.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[cfg_attr(feature = "client", derive(clickhouse::Row))]
+pub struct EventRow {
+    /// Nanoseconds since the Unix epoch (`DateTime64(9)`).
+    pub timestamp: i64,
+    /// `EVENT_SCHEMA_VERSION` of the producer.
+    pub schema_version: u32,
+    /// `PolicyEvent::layer_kind`, e.g. `retry`.
+    pub layer: String,
+    /// `PolicyEvent::event_name`, e.g. `attempt`.
+    pub event: String,
+    /// `Policy::named` name, empty outside a named stack.
+    pub policy_name: String,
+    /// Instance id of the named stack.
+    pub instance_id: Option<u64>,
+    /// `PolicyEvent::is_error`.
+    pub is_error: bool,
+    /// The event's main duration in milliseconds, if it reports one.
+    pub duration_ms: Option<f64>,
+    /// `EnrichSink` attributes in scope, sorted by key.
+    pub attributes: Vec<(String, String)>,
+    /// The event's own fields as JSON.
+    pub payload: String,
+}
+
+/// Duration fields in the order they are preferred for `duration_ms`.
+const DURATION_FIELDS: &[&str] = &[
+    "duration_ms",
+    "total_duration_ms",
+    "open_duration_ms",
+    "elapsed_ms",
+    "delay_ms",
+    "timeout_ms",
+];
+
+impl EventRow {
+    /// Row for `envelope`, observed at `timestamp`.
+    pub fn new(envelope: &EventEnvelope, timestamp: SystemTime) -> Self {
+        let fields = serde_json::to_value(&envelope.event).unwrap_or_default();
+        let duration_ms = DURATION_FIELDS.iter().find_map(|key| fields.get(*key)?.as_f64());
+        let nanos = timestamp.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        Self {
+            timestamp: i64::try_from(nanos).unwrap_or(i64::MAX),
+            schema_version: envelope.schema_version,
+            layer: envelope.event.layer_kind().to_owned(),
+            event: envelope.event.event_name().to_owned(),
+            policy_name: envelope.policy_name.as_deref().unwrap_or("").to_owned(),
+            instance_id: envelope.instance_id,
+            is_error: envelope.event.is_error(),
+            duration_ms,
+            attributes: envelope
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            payload: fields.to_string(),
+        }
+    }
+}
+
+/// `CREATE TABLE IF NOT EXISTS` statement for an events table matching [`EventRow`].
+///
+/// Partitioned by day and ordered for per-policy queries; add a `TTL` clause for retention.
+pub fn create_table_sql(table: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {table} (
+    timestamp DateTime64(9),
+    schema_version UInt32,
+    layer LowCardinality(String),
+    event LowCardinality(String),
+    policy_name LowCardinality(String),
+    instance_id Nullable(UInt64),
+    is_error Bool,
+    duration_ms Nullable(Float64),
+    attributes Map(String, String),
+    payload String
+) ENGINE = MergeTree
+PARTITION BY toDate(timestamp)
+ORDER BY (policy_name, layer, event, timestamp)"
+    )
+}
+
+/// Sink inserting policy events into a ClickHouse table in batches.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct ClickHouseSink {
+    table: String,
+    batch_size: usize,
+    pending: Arc<Mutex<Vec<EventRow>>>,
+    #[cfg(feature = "client")]
+    client: clickhouse::Client,
+}
+
+impl std::fmt::Debug for ClickHouseSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClickHouseSink")
+            .field("table", &self.table)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClickHouseSink {
+    /// Sink inserting into `table` of `database` on the server at `url`, 1000 rows per insert.
+    pub fn new<S: Into<String>>(url: S, database: S, table: S) -> Self {
+        let table = table.into();
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        #[cfg(feature = "client")]
+        {
+            let client =
+                clickhouse::Client::default().with_url(url.into()).with_database(database.into());
+            return Self { table, batch_size: 1000, pending, client };
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = (url, database);
+            Self { table, batch_size: 1000, pending }
+        }
+    }
+
+    /// Insert once `batch_size` rows are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Insert any buffered rows now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.insert(batch).await;
+    }
+
+    async fn insert(&self, batch: Vec<EventRow>) {
+        #[cfg(feature = "client")]
+        if !batch.is_empty() {
+            let write = async {
+                let mut insert = self.client.insert(&self.table)?;
+                for row in &batch {
+                    insert.write(row).await?;
+                }
+                insert.end().await
+            };
+            let _: Result<(), clickhouse::error::Error> = write.await;
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = batch;
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for ClickHouseSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        {
+            let row = EventRow::new(&EventEnvelope::capture(event), SystemTime::now());
+            let batch = {
+                let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                pending.push(row);
+                if pending.len() >= self.batch_size {
+                    std::mem::take(&mut *pending)
+                } else {
+                    Vec::new()
+                }
+            };
+            let sink = self.clone();
+            return Box::pin(async move {
+                sink.insert(batch).await;
+                Ok(())
+            });
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        }
+    }
+}
+
+impl TelemetrySink for ClickHouseSink {
+    type SinkError = Infallible;
+}
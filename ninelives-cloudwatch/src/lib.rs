@@ -0,0 +1,254 @@
+//! AWS CloudWatch telemetry sink for `ninelives`.
+//!
+//! Events are written to CloudWatch Logs as Embedded Metric Format (EMF) documents, so each one
+//! is a searchable log entry and CloudWatch extracts metrics from it without a separate
+//! `PutMetricData` call:
+//!
+//! - `Count`: 1 per event
+//! - `Errors`: 1 when the event is an error (`PolicyEvent::is_error`), else 0
+//! - `DurationMs`: the event's main duration, when it reports one
+//!
+//! Metrics land in a configurable namespace (default `NineLives`) under configurable
+//! dimensions drawn from `layer`, `event`, `policy_name`, `instance_id`, or any `EnrichSink`
+//! attribute. A dimension the event has no value for is left out of that document.
+//!
+//! Default build is a no-op; enable the `client` feature to call `PutLogEvents`.
+//!
+//! ```rust,no_run
+//! use ninelives_cloudwatch::CloudWatchSink;
+//! # async fn demo() {
+//! let sink = CloudWatchSink::new("/app/resilience", "checkout-1")
+//!     .await
+//!     .with_namespace("Checkout")
+//!     .with_dimensions(["policy_name", "layer"]);
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! # }
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use serde_json::{json, Map, Value};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Duration fields in the order they are preferred for `DurationMs`.
+const DURATION_FIELDS: &[&str] = &[
+    "duration_ms",
+    "total_duration_ms",
+    "open_duration_ms",
+    "elapsed_ms",
+    "delay_ms",
+    "timeout_ms",
+];
+
+/// Build the EMF document for `envelope`, observed at `timestamp`.
+pub fn emf_document(
+    envelope: &EventEnvelope,
+    namespace: &str,
+    dimensions: &[String],
+    timestamp: SystemTime,
+) -> Value {
+    let mut doc = match serde_json::to_value(&envelope.event) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let duration_ms = DURATION_FIELDS.iter().find_map(|key| doc.get(*key)?.as_f64());
+
+    doc.insert("layer".into(), envelope.event.layer_kind().into());
+    if let Some(name) = &envelope.policy_name {
+        doc.insert("policy_name".into(), Value::from(&**name));
+    }
+    if let Some(instance) = envelope.instance_id {
+        doc.insert("instance_id".into(), instance.to_string().into());
+    }
+    for (key, value) in &envelope.attributes {
+        doc.entry(key.to_string()).or_insert_with(|| Value::from(&**value));
+    }
+    // EMF dimension values must be strings.
+    let present: Vec<&String> = dimensions
+        .iter()
+        .filter(|key| doc.get(key.as_str()).is_some_and(Value::is_string))
+        .collect();
+
+    let mut metrics =
+        vec![json!({"Name": "Count", "Unit": "Count"}), json!({"Name": "Errors", "Unit": "Count"})];
+    doc.insert("Count".into(), 1.into());
+    doc.insert("Errors".into(), u8::from(envelope.event.is_error()).into());
+    if let Some(ms) = duration_ms {
+        metrics.push(json!({"Name": "DurationMs", "Unit": "Milliseconds"}));
+        doc.insert("DurationMs".into(), ms.into());
+    }
+    let millis = timestamp.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    doc.insert(
+        "_aws".into(),
+        json!({
+            "Timestamp": millis as u64,
+            "CloudWatchMetrics": [{
+                "Namespace": namespace,
+                "Dimensions": [present],
+                "Metrics": metrics,
+            }],
+        }),
+    );
+    Value::Object(doc)
+}
+
+/// Sink writing policy events to a CloudWatch Logs stream as EMF documents, in batches.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct CloudWatchSink {
+    log_group: Arc<str>,
+    log_stream: Arc<str>,
+    namespace: Arc<str>,
+    dimensions: Arc<[String]>,
+    batch_size: usize,
+    pending: Arc<Mutex<Vec<(i64, String)>>>,
+    #[cfg(feature = "client")]
+    client: aws_sdk_cloudwatchlogs::Client,
+}
+
+impl std::fmt::Debug for CloudWatchSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudWatchSink")
+            .field("log_group", &self.log_group)
+            .field("log_stream", &self.log_stream)
+            .field("namespace", &self.namespace)
+            .field("dimensions", &self.dimensions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CloudWatchSink {
+    /// Sink writing to `log_stream` in `log_group`, with AWS configuration from the environment.
+    ///
+    /// The log group must exist; the stream is created if missing. Defaults: namespace
+    /// `NineLives`, dimensions `layer` and `policy_name`, 100 entries per `PutLogEvents`.
+    pub async fn new<S: Into<String>>(log_group: S, log_stream: S) -> Self {
+        let log_group: Arc<str> = Arc::from(log_group.into());
+        let log_stream: Arc<str> = Arc::from(log_stream.into());
+        #[cfg(feature = "client")]
+        let client = {
+            let config = aws_config::load_from_env().await;
+            let client = aws_sdk_cloudwatchlogs::Client::new(&config);
+            // Best-effort: an existing stream is the common case.
+            let _ = client
+                .create_log_stream()
+                .log_group_name(&*log_group)
+                .log_stream_name(&*log_stream)
+                .send()
+                .await;
+            client
+        };
+        Self {
+            log_group,
+            log_stream,
+            namespace: Arc::from("NineLives"),
+            dimensions: Arc::from(vec!["layer".to_owned(), "policy_name".to_owned()]),
+            batch_size: 100,
+            pending: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(feature = "client")]
+            client,
+        }
+    }
+
+    /// Publish metrics under `namespace` instead of `NineLives`.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Arc::from(namespace.into());
+        self
+    }
+
+    /// Use `dimensions` (keys from the document, at most 30) as the metrics' dimension set.
+    pub fn with_dimensions<I, K>(mut self, dimensions: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        self.dimensions = dimensions.into_iter().map(Into::into).take(30).collect();
+        self
+    }
+
+    /// Call `PutLogEvents` once `batch_size` entries are buffered (1 to 10,000).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, 10_000);
+        self
+    }
+
+    /// Write any buffered entries now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.put(batch).await;
+    }
+
+    async fn put(&self, batch: Vec<(i64, String)>) {
+        #[cfg(feature = "client")]
+        if !batch.is_empty() {
+            use aws_sdk_cloudwatchlogs::types::InputLogEvent;
+
+            let events = batch
+                .into_iter()
+                .filter_map(|(timestamp, message)| {
+                    InputLogEvent::builder().timestamp(timestamp).message(message).build().ok()
+                })
+                .collect();
+            let _ = self
+                .client
+                .put_log_events()
+                .log_group_name(&*self.log_group)
+                .log_stream_name(&*self.log_stream)
+                .set_log_events(Some(events))
+                .send()
+                .await;
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = batch;
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for CloudWatchSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let now = SystemTime::now();
+            let envelope = EventEnvelope::capture(event);
+            let doc = emf_document(&envelope, &self.namespace, &self.dimensions, now);
+            let millis = now.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+            let batch = {
+                let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                pending.push((millis as i64, doc.to_string()));
+                if pending.len() >= self.batch_size {
+                    std::mem::take(&mut *pending)
+                } else {
+                    Vec::new()
+                }
+            };
+            let sink = self.clone();
+            Box::pin(async move {
+                sink.put(batch).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for CloudWatchSink {
+    type SinkError = Infallible;
+}
@@ -0,0 +1,228 @@
+//! CSV file sink for `ninelives`.
+//!
+//! Writes one row per event with the fixed column set in [`COLUMNS`], so files load straight
+//! into a spreadsheet or `pandas.read_csv` without a JSON step:
+//!
+//! ```text
+//! timestamp,policy_name,instance_id,kind,event,severity,reason,active_count,...,window_ms
+//! 2024-05-01T12:00:00.123Z,checkout,3,retry,attempt,info,,,,2,,,100.0,...,
+//! ```
+//!
+//! Every numeric field of any event has a column of its own (durations in milliseconds); cells
+//! that do not apply to an event are left empty. Columns only ever get appended, so readers keyed
+//! on column names keep working across versions.
+//!
+//! Files rotate by size: once the active file would exceed the limit it is renamed to
+//! `<stem>.1.<ext>`, older files shift up (`.2`, `.3`, ...), the oldest beyond the retention
+//! count is deleted, and a new file starts with a fresh header.
+//!
+//! Rows are written synchronously; wrap the sink in `NonBlockingSink` to keep file I/O off the
+//! request path.
+//!
+//! ```rust,no_run
+//! use ninelives_csv::CsvSink;
+//! # fn demo() -> std::io::Result<()> {
+//! let sink = CsvSink::open("resilience.csv")?.with_rotation(16 * 1024 * 1024, 10);
+//! // attach with .with_sink(sink)
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+/// Columns of every file, in order.
+pub const COLUMNS: &[&str] = &[
+    "timestamp",
+    "policy_name",
+    "instance_id",
+    "kind",
+    "event",
+    "severity",
+    "reason",
+    "active_count",
+    "age_ms",
+    "attempt",
+    "branch",
+    "branches",
+    "delay_ms",
+    "duration_ms",
+    "elapsed_ms",
+    "failure_count",
+    "failures",
+    "in_flight",
+    "interval_ms",
+    "latency_ms",
+    "limit",
+    "load",
+    "max_concurrency",
+    "open_duration_ms",
+    "opens",
+    "probability",
+    "ratio",
+    "rejections",
+    "requests",
+    "retries",
+    "retry_after_ms",
+    "threshold_ms",
+    "timeout_ms",
+    "total_attempts",
+    "total_duration_ms",
+    "wait_ms",
+    "waiters",
+    "window_ms",
+];
+
+/// Header line (without newline).
+pub fn header() -> String {
+    COLUMNS.join(",")
+}
+
+/// One row (without newline) for `envelope` observed at `timestamp`.
+pub fn csv_row(envelope: &EventEnvelope, timestamp: SystemTime) -> String {
+    let fields = match serde_json::to_value(&envelope.event) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let cells: Vec<String> = COLUMNS
+        .iter()
+        .map(|&column| match column {
+            "timestamp" => chrono::DateTime::<chrono::Utc>::from(timestamp)
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "policy_name" => envelope.policy_name.as_deref().map(quote).unwrap_or_default(),
+            "instance_id" => envelope.instance_id.map(|id| id.to_string()).unwrap_or_default(),
+            "kind" => envelope.event.layer_kind().to_owned(),
+            "event" => envelope.event.event_name().to_owned(),
+            "severity" => envelope.event.severity().to_string(),
+            _ => match fields.get(column) {
+                Some(serde_json::Value::Number(n)) => n.to_string(),
+                Some(serde_json::Value::String(s)) => quote(s),
+                Some(serde_json::Value::Null) | None => String::new(),
+                Some(other) => quote(&other.to_string()),
+            },
+        })
+        .collect();
+    cells.join(",")
+}
+
+/// Quote a cell if it contains a delimiter, quote, or line break (RFC 4180).
+fn quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+/// `<stem>.<index>.<ext>` next to `path`.
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{index}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{index}"),
+    };
+    path.with_file_name(name)
+}
+
+/// The active file and its rotation policy.
+#[derive(Debug)]
+struct Writer {
+    path: PathBuf,
+    file: LineWriter<File>,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl Writer {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut written = file.metadata()?.len();
+        if written == 0 {
+            let header = header() + "\n";
+            file.write_all(header.as_bytes())?;
+            written = header.len() as u64;
+        }
+        Ok(Self { path, file: LineWriter::new(file), written, max_bytes: u64::MAX, max_files: 5 })
+    }
+
+    fn write_row(&mut self, row: &str) -> io::Result<()> {
+        let len = row.len() as u64 + 1;
+        if self.written.saturating_add(len) > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(row.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.written += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let _ = std::fs::remove_file(rotated_path(&self.path, self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        let fresh = Self::open(self.path.clone())?;
+        self.file = fresh.file;
+        self.written = fresh.written;
+        Ok(())
+    }
+}
+
+/// Sink appending one CSV row per policy event to a size-rotated file.
+#[derive(Clone, Debug)]
+pub struct CsvSink {
+    writer: Arc<Mutex<Writer>>,
+}
+
+impl CsvSink {
+    /// Append to `path`, writing the header first if the file is new or empty.
+    ///
+    /// Files are not rotated until [`with_rotation`](Self::with_rotation) is set.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let writer = Writer::open(path.as_ref().to_path_buf())?;
+        Ok(Self { writer: Arc::new(Mutex::new(writer)) })
+    }
+
+    /// Rotate once the active file would exceed `max_bytes`, keeping `max_files` rotated files
+    /// (minimum 1).
+    pub fn with_rotation(self, max_bytes: u64, max_files: usize) -> Self {
+        {
+            let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+            writer.max_bytes = max_bytes;
+            writer.max_files = max_files.max(1);
+        }
+        self
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for CsvSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let row = csv_row(&EventEnvelope::capture(event), SystemTime::now());
+        let _ = self.writer.lock().unwrap_or_else(PoisonError::into_inner).write_row(&row);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for CsvSink {
+    type SinkError = Infallible;
+}
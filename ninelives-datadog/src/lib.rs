@@ -0,0 +1,245 @@
+//! Datadog Events API sink for `ninelives`.
+//!
+//! Posts notable resilience transitions as Datadog events so they can be overlaid on dashboards
+//! during incidents:
+//!
+//! - circuit opened (`error`) and closed again (`success`);
+//! - sustained bulkhead rejection (`warning`): at least `threshold` rejections for one policy
+//!   within `window`, reported once per window.
+//!
+//! Everything else is ignored; this is an annotation feed, not a metrics pipeline. Events are
+//! tagged with the configured tags plus `policy`, `instance`, `layer`, and `event`, and share an
+//! aggregation key per policy and layer so an open/close pair rolls up together.
+//!
+//! Default build is a no-op; enable the `client` feature to post.
+//!
+//! ```rust,no_run
+//! use ninelives_datadog::DatadogEventsSink;
+//! let sink = DatadogEventsSink::new(std::env::var("DD_API_KEY").unwrap_or_default())
+//!     .with_site("datadoghq.eu")
+//!     .with_tags(["env:prod", "service:checkout"]);
+//! // attach with .with_sink(sink)
+//! ```
+
+use ninelives::telemetry::{BulkheadEvent, CircuitBreakerEvent, PolicyEvent, TelemetrySink};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// An event for the Datadog Events API (`POST /api/v1/events`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatadogEvent {
+    /// Event title.
+    pub title: String,
+    /// Event body.
+    pub text: String,
+    /// `error`, `warning`, `info`, or `success`.
+    pub alert_type: &'static str,
+    /// Groups related events, e.g. a breaker opening and closing.
+    pub aggregation_key: String,
+    /// `key:value` tags.
+    pub tags: Vec<String>,
+}
+
+impl DatadogEvent {
+    /// Request body for the Events API.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "title": self.title,
+            "text": self.text,
+            "alert_type": self.alert_type,
+            "aggregation_key": self.aggregation_key,
+            "source_type_name": "ninelives",
+            "tags": self.tags,
+        })
+    }
+}
+
+/// Rejections seen for one policy in the current window.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+struct RejectionWindow {
+    started: Instant,
+    count: u32,
+    reported: bool,
+}
+
+/// Sink posting circuit transitions and sustained bulkhead rejection to Datadog.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct DatadogEventsSink {
+    api_key: Arc<str>,
+    site: Arc<str>,
+    tags: Arc<[String]>,
+    rejection_threshold: u32,
+    rejection_window: Duration,
+    rejections: Arc<Mutex<HashMap<String, RejectionWindow>>>,
+    #[cfg(feature = "client")]
+    client: reqwest::Client,
+}
+
+impl std::fmt::Debug for DatadogEventsSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatadogEventsSink")
+            .field("site", &self.site)
+            .field("tags", &self.tags)
+            .field("rejection_threshold", &self.rejection_threshold)
+            .field("rejection_window", &self.rejection_window)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DatadogEventsSink {
+    /// Sink posting to `datadoghq.com` with `api_key`.
+    ///
+    /// Bulkhead rejection is reported after 20 rejections within 60 seconds.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: Arc::from(api_key.into()),
+            site: Arc::from("datadoghq.com"),
+            tags: Arc::from(Vec::new()),
+            rejection_threshold: 20,
+            rejection_window: Duration::from_secs(60),
+            rejections: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "client")]
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Post to another Datadog site, e.g. `datadoghq.eu` or `us5.datadoghq.com`.
+    pub fn with_site(mut self, site: impl Into<String>) -> Self {
+        self.site = Arc::from(site.into());
+        self
+    }
+
+    /// Add `key:value` tags to every event.
+    pub fn with_tags<I, T>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Report bulkhead rejection once `threshold` rejections for a policy land within `window`.
+    pub fn with_rejection_alert(mut self, threshold: u32, window: Duration) -> Self {
+        self.rejection_threshold = threshold.max(1);
+        self.rejection_window = window;
+        self
+    }
+
+    /// The Datadog event `event` warrants, if any, given the current request context.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    fn notable(&self, event: &PolicyEvent) -> Option<DatadogEvent> {
+        let ctx = ninelives::RequestContext::current();
+        let policy = ctx.policy_name().unwrap_or("unnamed").to_owned();
+        let (title, text, alert_type) = match event {
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count }) => (
+                format!("Circuit opened: {policy}"),
+                format!("Circuit breaker for {policy} opened after {failure_count} failures."),
+                "error",
+            ),
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Closed { open_duration }) => (
+                format!("Circuit closed: {policy}"),
+                format!("Circuit breaker for {policy} closed after {open_duration:?} open."),
+                "success",
+            ),
+            PolicyEvent::Bulkhead(BulkheadEvent::Rejected { max_concurrency, .. }) => {
+                let count = self.note_rejection(&policy)?;
+                (
+                    format!("Bulkhead rejecting: {policy}"),
+                    format!(
+                        "Bulkhead for {policy} rejected {count} requests within {:?} \
+                         (limit {max_concurrency} concurrent).",
+                        self.rejection_window
+                    ),
+                    "warning",
+                )
+            }
+            _ => return None,
+        };
+
+        let mut tags = self.tags.to_vec();
+        tags.push(format!("policy:{policy}"));
+        if let Some(instance) = ctx.policy_instance() {
+            tags.push(format!("instance:{instance}"));
+        }
+        tags.push(format!("layer:{}", event.layer_kind()));
+        tags.push(format!("event:{}", event.event_name()));
+        Some(DatadogEvent {
+            title,
+            text,
+            alert_type,
+            aggregation_key: format!("ninelives:{policy}:{}", event.layer_kind()),
+            tags,
+        })
+    }
+
+    /// Count a rejection for `policy`; returns the count when it first crosses the threshold
+    /// in the current window.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    fn note_rejection(&self, policy: &str) -> Option<u32> {
+        let now = Instant::now();
+        let mut rejections = self.rejections.lock().unwrap_or_else(PoisonError::into_inner);
+        let window = rejections.entry(policy.to_owned()).or_insert(RejectionWindow {
+            started: now,
+            count: 0,
+            reported: false,
+        });
+        if now.duration_since(window.started) > self.rejection_window {
+            *window = RejectionWindow { started: now, count: 0, reported: false };
+        }
+        window.count += 1;
+        if window.reported || window.count < self.rejection_threshold {
+            return None;
+        }
+        window.reported = true;
+        Some(window.count)
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for DatadogEventsSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let notable = self.notable(&event);
+            let sink = self.clone();
+            Box::pin(async move {
+                if let Some(notable) = notable {
+                    let _ = sink
+                        .client
+                        .post(format!("https://api.{}/api/v1/events", sink.site))
+                        .header("DD-API-KEY", &*sink.api_key)
+                        .json(&notable.to_json())
+                        .send()
+                        .await;
+                }
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for DatadogEventsSink {
+    type SinkError = Infallible;
+}
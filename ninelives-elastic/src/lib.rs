@@ -1,41 +1,230 @@
 //! Elasticsearch telemetry sink for `ninelives`.
 //! Default build is a no-op; enable the `client` feature to index events.
+//!
+//! Events are buffered and written with the `_bulk` API, one `create` action per event, once
+//! `batch_size` documents are pending or `flush_interval` after the first of them arrived,
+//! whichever comes first. Each document is the JSON `EventEnvelope` plus an `@timestamp`.
+//!
+//! With [`ElasticSink::with_daily_indices`] documents go to `<index>-YYYY.MM.DD` (UTC), so
+//! retention is a matter of deleting old indices. [`ElasticSink::install_template`] installs an
+//! index template mapping the envelope fields (keywords for names, `date` for `@timestamp`,
+//! `double` for every `*_ms` duration) for the index or index pattern.
+//!
+//! ```rust,no_run
+//! use ninelives_elastic::ElasticSink;
+//! use std::time::Duration;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = ElasticSink::new("http://localhost:9200", "ninelives")?
+//!     .with_daily_indices()
+//!     .with_batch_size(1_000)
+//!     .with_flush_interval(Duration::from_secs(2));
+//! sink.install_template().await?;
+//! // attach with .with_sink(sink.clone()); call sink.flush().await on shutdown
+//! # Ok(()) }
+//! ```
 
-use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
 use std::convert::Infallible;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
 
-#[derive(Clone, Debug)]
+/// Index for a document written at `timestamp`: `index`, or `index-YYYY.MM.DD` when `daily`.
+pub fn index_name(index: &str, timestamp: SystemTime, daily: bool) -> String {
+    if !daily {
+        return index.to_owned();
+    }
+    let date = chrono::DateTime::<chrono::Utc>::from(timestamp).format("%Y.%m.%d");
+    format!("{index}-{date}")
+}
+
+/// Document for `envelope`: its JSON with an `@timestamp` added.
+pub fn document(envelope: &EventEnvelope, timestamp: SystemTime) -> String {
+    let mut doc = serde_json::to_value(envelope).unwrap_or_default();
+    if let serde_json::Value::Object(map) = &mut doc {
+        let time = chrono::DateTime::<chrono::Utc>::from(timestamp)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        map.insert("@timestamp".to_owned(), time.into());
+    }
+    doc.to_string()
+}
+
+/// `_bulk` request lines for `(index, document)` pairs: a `create` action before each document.
+pub fn bulk_lines(docs: &[(String, String)]) -> Vec<String> {
+    docs.iter()
+        .flat_map(|(index, doc)| {
+            [serde_json::json!({ "create": { "_index": index } }).to_string(), doc.clone()]
+        })
+        .collect()
+}
+
+/// Index template covering `patterns`, mapping the envelope fields.
+pub fn index_template(patterns: &[String]) -> serde_json::Value {
+    serde_json::json!({
+        "index_patterns": patterns,
+        "template": {
+            "mappings": {
+                "dynamic_templates": [
+                    { "durations": {
+                        "match": "*_ms",
+                        "mapping": { "type": "double" }
+                    } },
+                    { "strings_as_keywords": {
+                        "match_mapping_type": "string",
+                        "mapping": { "type": "keyword", "ignore_above": 1024 }
+                    } }
+                ],
+                "properties": {
+                    "@timestamp": { "type": "date" },
+                    "schema_version": { "type": "integer" },
+                    "policy_name": { "type": "keyword" },
+                    "instance_id": { "type": "long" },
+                    "policy": { "type": "keyword" },
+                    "event": { "type": "keyword" },
+                    "attributes": { "type": "object" }
+                }
+            }
+        },
+        "_meta": { "managed_by": "ninelives-elastic" }
+    })
+}
+
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
 pub struct ElasticSink {
     index: String,
+    daily: bool,
+    batch_size: usize,
+    flush_interval: Duration,
+    pending: Arc<Mutex<Vec<(String, String)>>>,
+    failures: Arc<AtomicU64>,
     #[cfg(feature = "client")]
     client: elasticsearch::Elasticsearch,
 }
 
+impl std::fmt::Debug for ElasticSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElasticSink")
+            .field("index", &self.index)
+            .field("daily", &self.daily)
+            .field("batch_size", &self.batch_size)
+            .field("flush_interval", &self.flush_interval)
+            .finish_non_exhaustive()
+    }
+}
+
 impl ElasticSink {
+    /// Sink writing to `index` at `endpoint`; bulk requests of 500 documents or every second.
     pub fn new<S: Into<String>>(endpoint: S, index: S) -> Result<Self, Box<dyn std::error::Error>> {
         let index = index.into();
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        let failures = Arc::new(AtomicU64::new(0));
+        let flush_interval = Duration::from_secs(1);
         #[cfg(feature = "client")]
-        {
+        let sink = {
             let transport =
                 elasticsearch::http::transport::Transport::single_node(&endpoint.into())?;
             let client = elasticsearch::Elasticsearch::new(transport);
-            return Ok(Self { index, client });
-        }
+            Self { index, daily: false, batch_size: 500, flush_interval, pending, failures, client }
+        };
+
         #[cfg(not(feature = "client"))]
-        {
+        let sink = {
             let _ = endpoint;
-            Ok(Self { index, ..Self::noop() })
+            Self { index, daily: false, batch_size: 500, flush_interval, pending, failures }
+        };
+
+        Ok(sink)
+    }
+
+    /// Write to one index per UTC day, `<index>-YYYY.MM.DD`.
+    pub fn with_daily_indices(mut self) -> Self {
+        self.daily = true;
+        self
+    }
+
+    /// Send a bulk request once `batch_size` documents are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Send buffered documents at most `interval` after the first of them arrived.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Documents Elasticsearch rejected or that were lost to a failed bulk request.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Install (or replace) an index template named after the index, covering the index or,
+    /// with daily indices, `<index>-*`.
+    pub async fn install_template(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let pattern = if self.daily { format!("{}-*", self.index) } else { self.index.clone() };
+        let template = index_template(&[pattern]);
+        #[cfg(feature = "client")]
+        {
+            use elasticsearch::indices::IndicesPutIndexTemplateParts;
+            self.client
+                .indices()
+                .put_index_template(IndicesPutIndexTemplateParts::Name(&self.index))
+                .body(template)
+                .send()
+                .await?
+                .error_for_status_code()?;
         }
+        #[cfg(not(feature = "client"))]
+        let _ = template;
+        Ok(())
     }
 
-    #[cfg(not(feature = "client"))]
-    fn noop() -> Self {
-        Self { index: String::new() }
+    /// Send any buffered documents now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.send(batch).await;
+    }
+
+    async fn send(&self, batch: Vec<(String, String)>) {
+        #[cfg(feature = "client")]
+        if !batch.is_empty() {
+            use elasticsearch::BulkParts;
+
+            let result = self.client.bulk(BulkParts::None).body(bulk_lines(&batch)).send().await;
+            let rejected = match result {
+                Ok(response) if response.status_code().is_success() => {
+                    match response.json::<serde_json::Value>().await {
+                        Ok(body) => rejected_items(&body),
+                        Err(_) => 0,
+                    }
+                }
+                _ => batch.len() as u64,
+            };
+            if rejected > 0 {
+                self.failures.fetch_add(rejected, Ordering::Relaxed);
+                tracing::warn!(index = %self.index, rejected, "elasticsearch rejected policy events");
+            }
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = batch;
     }
 }
 
+/// Items of a `_bulk` response that failed.
+#[cfg(feature = "client")]
+fn rejected_items(body: &serde_json::Value) -> u64 {
+    if body["errors"] != serde_json::Value::Bool(true) {
+        return 0;
+    }
+    let items = body["items"].as_array().map(Vec::as_slice).unwrap_or_default();
+    items.iter().filter(|item| item["create"]["error"].is_object()).count() as u64
+}
+
 impl tower_service::Service<PolicyEvent> for ElasticSink {
     type Response = ();
     type Error = Infallible;
@@ -48,24 +237,40 @@ impl tower_service::Service<PolicyEvent> for ElasticSink {
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
         #[cfg(feature = "client")]
         let fut = {
-            use elasticsearch::http::request::JsonBody;
-            use elasticsearch::indices::IndicesCreateParts;
-            use elasticsearch::IndexParts;
-
-            let client = self.client.clone();
-            let index = self.index.clone();
+            let now = SystemTime::now();
+            let entry = (
+                index_name(&self.index, now, self.daily),
+                document(&EventEnvelope::capture(event), now),
+            );
+            let (batch, first) = {
+                let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                pending.push(entry);
+                if pending.len() >= self.batch_size {
+                    (std::mem::take(&mut *pending), false)
+                } else {
+                    (Vec::new(), pending.len() == 1)
+                }
+            };
+            if first {
+                // The first buffered document arms the timer that bounds its latency.
+                let sink = self.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(sink.flush_interval).await;
+                    sink.flush().await;
+                });
+            }
+            let sink = self.clone();
             Box::pin(async move {
-                // ensure index exists (best-effort)
-                let _ = client.indices().create(IndicesCreateParts::Index(&index)).send().await;
-
-                let body = JsonBody::new(format!("{{\"event\":\"{:?}\"}}", event));
-                let _ = client.index(IndexParts::Index(&index)).body(body).send().await;
+                sink.send(batch).await;
                 Ok(())
             })
         };
 
         #[cfg(not(feature = "client"))]
-        let fut = Box::pin(async move { Ok(()) });
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
 
         fut
     }
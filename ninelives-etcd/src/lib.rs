@@ -1,18 +1,68 @@
 //! etcd telemetry sink for `ninelives` (companion crate).
 //! Default build is no-op; enable `client` to write events to etcd keys.
+//!
+//! Events are stored under `prefix/<nanos since the Unix epoch>`, so keys sort by time. Left
+//! alone they accumulate forever; two options bound them:
+//!
+//! - [`EtcdSink::with_ttl`] attaches a lease to every key so etcd expires it. Leases are shared
+//!   by all events written in the same tenth of the TTL, so a key lives between `ttl` and
+//!   `ttl * 1.1` without granting a lease per event.
+//! - [`EtcdSink::with_retention`] deletes keys older than a window, sweeping the prefix with a
+//!   range delete at most once per sweep interval.
+//!
+//! ```rust,no_run
+//! use ninelives_etcd::EtcdSink;
+//! use std::time::Duration;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = EtcdSink::new("http://127.0.0.1:2379", "policy/events")
+//!     .await?
+//!     .with_ttl(Duration::from_secs(3_600));
+//! # Ok(()) }
+//! ```
 
 use ninelives::telemetry::{PolicyEvent, TelemetrySink};
 use std::convert::Infallible;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-#[derive(Clone, Debug)]
+/// Key for an event written at `at`: `prefix/<nanos since the Unix epoch>`.
+pub fn event_key(prefix: &str, at: SystemTime) -> String {
+    let nanos = at.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    format!("{prefix}/{nanos}")
+}
+
+/// Shared lease for the current TTL bucket.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+struct Lease {
+    id: i64,
+    granted: Instant,
+}
+
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
 pub struct EtcdSink {
     prefix: String,
+    ttl: Option<Duration>,
+    retention: Option<(Duration, Duration)>,
+    lease: Arc<tokio::sync::Mutex<Option<Lease>>>,
+    last_sweep: Arc<Mutex<Instant>>,
     #[cfg(feature = "client")]
     client: etcd_client::Client,
 }
 
+impl std::fmt::Debug for EtcdSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EtcdSink")
+            .field("prefix", &self.prefix)
+            .field("ttl", &self.ttl)
+            .field("retention", &self.retention)
+            .finish_non_exhaustive()
+    }
+}
+
 impl EtcdSink {
     /// `endpoint` like "http://127.0.0.1:2379"; events stored under `prefix/<nanos>`
     pub async fn new<S: Into<String>>(
@@ -20,21 +70,93 @@ impl EtcdSink {
         prefix: S,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let prefix = prefix.into();
+        let lease = Arc::new(tokio::sync::Mutex::new(None));
+        let last_sweep = Arc::new(Mutex::new(Instant::now()));
         #[cfg(feature = "client")]
-        {
+        let sink = {
             let client = etcd_client::Client::connect([endpoint.into()], None).await?;
-            return Ok(Self { prefix, client });
-        }
+            Self { prefix, ttl: None, retention: None, lease, last_sweep, client }
+        };
+
         #[cfg(not(feature = "client"))]
-        {
+        let sink = {
             let _ = endpoint;
-            Ok(Self { prefix, ..Self::noop() })
+            Self { prefix, ttl: None, retention: None, lease, last_sweep }
+        };
+
+        Ok(sink)
+    }
+
+    /// Expire each key roughly `ttl` after it is written (at least one second).
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl.max(Duration::from_secs(1)));
+        self
+    }
+
+    /// Delete keys older than `window`, checking at most every `sweep_every`.
+    ///
+    /// Deleted keys stay in etcd's revision history until the cluster compacts; run etcd with
+    /// `--auto-compaction-retention` to reclaim the space.
+    pub fn with_retention(mut self, window: Duration, sweep_every: Duration) -> Self {
+        self.retention = Some((window, sweep_every));
+        self
+    }
+
+    /// Delete keys older than the retention window now, returning how many were removed.
+    ///
+    /// Does nothing without [`with_retention`](Self::with_retention).
+    pub async fn sweep(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let Some((window, _)) = self.retention else {
+            return Ok(0);
+        };
+        let oldest = SystemTime::now().checked_sub(window).unwrap_or(UNIX_EPOCH);
+        let cutoff = event_key(&self.prefix, oldest);
+        #[cfg(feature = "client")]
+        let deleted = {
+            use etcd_client::DeleteOptions;
+            let start = format!("{}/", self.prefix);
+            let mut client = self.client.clone();
+            let response =
+                client.delete(start, Some(DeleteOptions::new().with_range(cutoff))).await?;
+            response.deleted().max(0) as u64
+        };
+
+        #[cfg(not(feature = "client"))]
+        let deleted = {
+            let _ = cutoff;
+            0
+        };
+
+        Ok(deleted)
+    }
+
+    /// Whether a sweep is due, claiming it if so.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    fn sweep_due(&self) -> bool {
+        let Some((_, every)) = self.retention else {
+            return false;
+        };
+        let mut last = self.last_sweep.lock().unwrap_or_else(PoisonError::into_inner);
+        if last.elapsed() < every {
+            return false;
         }
+        *last = Instant::now();
+        true
     }
 
-    #[cfg(not(feature = "client"))]
-    fn noop() -> Self {
-        Self { prefix: String::new() }
+    /// Lease for keys written now, granting a new one when the current bucket has passed.
+    #[cfg(feature = "client")]
+    async fn lease(&self, ttl: Duration) -> Option<i64> {
+        let bucket = ttl / 10;
+        let mut lease = self.lease.lock().await;
+        if let Some(current) = lease.as_ref().filter(|l| l.granted.elapsed() < bucket) {
+            return Some(current.id);
+        }
+        let seconds = (ttl + bucket).as_secs_f64().ceil() as i64;
+        let mut client = self.client.clone();
+        let granted = client.lease_grant(seconds, None).await.ok()?;
+        *lease = Some(Lease { id: granted.id(), granted: Instant::now() });
+        Some(granted.id())
     }
 }
 
@@ -50,17 +172,34 @@ impl tower_service::Service<PolicyEvent> for EtcdSink {
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
         #[cfg(feature = "client")]
         let fut = {
-            let mut client = self.client.clone();
-            let key = format!("{}/{}", self.prefix, chrono::Utc::now().timestamp_nanos());
-            let val = format!("{:?}", event);
+            let key = event_key(&self.prefix, SystemTime::now());
+            let val = ninelives::telemetry::EventEnvelope::capture(event).to_json();
+            if self.sweep_due() {
+                let sink = self.clone();
+                tokio::spawn(async move {
+                    let _ = sink.sweep().await;
+                });
+            }
+            let sink = self.clone();
             Box::pin(async move {
-                let _ = client.put(key, val, None).await;
+                let options = match sink.ttl {
+                    Some(ttl) => sink
+                        .lease(ttl)
+                        .await
+                        .map(|id| etcd_client::PutOptions::new().with_lease(id)),
+                    None => None,
+                };
+                let mut client = sink.client.clone();
+                let _ = client.put(key, val, options).await;
                 Ok(())
             })
         };
 
         #[cfg(not(feature = "client"))]
-        let fut = Box::pin(async move { Ok(()) });
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
 
         fut
     }
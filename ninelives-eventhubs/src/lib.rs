@@ -0,0 +1,164 @@
+//! Azure Event Hubs telemetry sink for `ninelives` (companion crate).
+//! Default build is a no-op to keep the core light; enable `client` to publish to an Event Hub.
+//!
+//! Events are sent as JSON `EventEnvelope`s in batches: once `batch_size` are buffered they go
+//! out in as few `EventDataBatch`es as the hub's size limit allows.
+//!
+//! ```rust,no_run
+//! use ninelives_eventhubs::EventHubsSink;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = EventHubsSink::new("acme.servicebus.windows.net", "policy-events").await?;
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+/// Sink publishing policy events to an Azure Event Hub in batches.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct EventHubsSink {
+    eventhub: String,
+    batch_size: usize,
+    pending: Arc<Mutex<Vec<String>>>,
+    #[cfg(feature = "client")]
+    producer: Arc<azure_messaging_eventhubs::ProducerClient>,
+}
+
+impl std::fmt::Debug for EventHubsSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventHubsSink")
+            .field("eventhub", &self.eventhub)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EventHubsSink {
+    /// Connect to `eventhub` in the namespace `fully_qualified_namespace`
+    /// (`<name>.servicebus.windows.net`) with `DefaultAzureCredential`.
+    pub async fn new<S: Into<String>>(
+        fully_qualified_namespace: S,
+        eventhub: S,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let eventhub = eventhub.into();
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        #[cfg(feature = "client")]
+        {
+            use azure_messaging_eventhubs::ProducerClient;
+            let credential = azure_identity::DefaultAzureCredential::new()?;
+            let producer = ProducerClient::new(
+                fully_qualified_namespace.into(),
+                eventhub.clone(),
+                credential,
+                None,
+            );
+            producer.open().await?;
+            return Ok(Self { eventhub, batch_size: 100, pending, producer: Arc::new(producer) });
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = fully_qualified_namespace; // silence unused
+            Ok(Self { eventhub, batch_size: 100, pending })
+        }
+    }
+
+    /// Send once `batch_size` events are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Send any buffered events now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.send(batch).await;
+    }
+
+    async fn send(&self, payloads: Vec<String>) {
+        #[cfg(feature = "client")]
+        if !payloads.is_empty() {
+            let _ = self.send_batches(payloads).await;
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = payloads;
+    }
+
+    /// Pack `payloads` into as few batches as the hub accepts; oversized single events are
+    /// dropped.
+    #[cfg(feature = "client")]
+    async fn send_batches(
+        &self,
+        payloads: Vec<String>,
+    ) -> Result<(), azure_messaging_eventhubs::error::Error> {
+        use azure_messaging_eventhubs::models::EventData;
+
+        let event = |payload: &str| {
+            EventData::builder()
+                .with_body(payload.as_bytes().to_vec())
+                .with_content_type("application/json".to_owned())
+                .build()
+        };
+        let mut batch = self.producer.create_batch(None).await?;
+        for payload in &payloads {
+            if batch.try_add_event_data(event(payload), None)? {
+                continue;
+            }
+            self.producer.submit_batch(&batch).await?;
+            batch = self.producer.create_batch(None).await?;
+            batch.try_add_event_data(event(payload), None)?;
+        }
+        if batch.len() > 0 {
+            self.producer.submit_batch(&batch).await?;
+        }
+        Ok(())
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for EventHubsSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let payload = ninelives::telemetry::EventEnvelope::capture(event).to_json();
+            let batch = {
+                let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                pending.push(payload);
+                if pending.len() >= self.batch_size {
+                    std::mem::take(&mut *pending)
+                } else {
+                    Vec::new()
+                }
+            };
+            let sink = self.clone();
+            Box::pin(async move {
+                sink.send(batch).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for EventHubsSink {
+    type SinkError = Infallible;
+}
@@ -0,0 +1,243 @@
+//! Honeycomb telemetry sink for `ninelives`.
+//!
+//! Each event becomes one wide Honeycomb event with flat, queryable fields:
+//!
+//! ```text
+//! ninelives.layer=retry ninelives.event=attempt ninelives.policy=checkout ninelives.instance=3
+//! ninelives.attempt=2 ninelives.delay_ms=100 error=true region=eu-west-1 ...
+//! ```
+//!
+//! Numeric event fields (`duration_ms`, `attempt`, ...) keep their type so they can be used in
+//! heatmaps and percentiles; `EnrichSink` attributes become top-level columns.
+//!
+//! Transmission follows libhoney: events are buffered and posted to the batch endpoint
+//! (`/1/batch/<dataset>`) once `batch_size` are pending, at most `max_pending` wait (newer events
+//! are dropped beyond that), and failed batches are dropped rather than retried. Drops are
+//! counted in [`HoneycombSink::dropped`]. With a sample rate of `n`, one in `n` routine events
+//! is kept and tagged `samplerate: n` so Honeycomb reweights it; error events are always sent.
+//!
+//! Default build is a no-op; enable the `client` feature to post.
+//!
+//! ```rust,no_run
+//! use ninelives_honeycomb::HoneycombSink;
+//! let api_key = std::env::var("HONEYCOMB_API_KEY").unwrap_or_default();
+//! let sink = HoneycombSink::new(api_key, "resilience".into()).with_sample_rate(10);
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+/// Flat Honeycomb fields for `envelope`.
+///
+/// `ninelives.policy` and `ninelives.instance` are omitted outside a `Policy::named` stack.
+pub fn event_fields(envelope: &EventEnvelope) -> serde_json::Map<String, serde_json::Value> {
+    let mut fields = serde_json::Map::new();
+    fields.insert("ninelives.layer".into(), envelope.event.layer_kind().into());
+    fields.insert("ninelives.event".into(), envelope.event.event_name().into());
+    if let Some(name) = &envelope.policy_name {
+        fields.insert("ninelives.policy".into(), name.to_string().into());
+    }
+    if let Some(instance) = envelope.instance_id {
+        fields.insert("ninelives.instance".into(), instance.into());
+    }
+    if let Ok(serde_json::Value::Object(map)) = serde_json::to_value(&envelope.event) {
+        for (key, value) in map {
+            if key == "policy" || key == "event" {
+                continue;
+            }
+            if value.is_number() || value.is_string() || value.is_boolean() {
+                fields.insert(format!("ninelives.{key}"), value);
+            }
+        }
+    }
+    fields.insert("error".into(), envelope.event.is_error().into());
+    for (key, value) in &envelope.attributes {
+        fields.insert(key.to_string(), value.to_string().into());
+    }
+    fields
+}
+
+/// One entry of a batch request: the fields plus timestamp and sample rate.
+pub fn batch_entry(
+    envelope: &EventEnvelope,
+    timestamp: SystemTime,
+    sample_rate: u32,
+) -> serde_json::Value {
+    let time = chrono::DateTime::<chrono::Utc>::from(timestamp)
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    serde_json::json!({
+        "time": time,
+        "samplerate": sample_rate,
+        "data": event_fields(envelope),
+    })
+}
+
+/// Sink posting policy events to a Honeycomb dataset in batches.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct HoneycombSink {
+    api_host: Arc<str>,
+    dataset: Arc<str>,
+    api_key: Arc<str>,
+    batch_size: usize,
+    max_pending: usize,
+    sample_rate: u32,
+    seen: Arc<AtomicU64>,
+    pending: Arc<Mutex<Vec<serde_json::Value>>>,
+    dropped: Arc<AtomicU64>,
+    #[cfg(feature = "client")]
+    client: reqwest::Client,
+}
+
+impl std::fmt::Debug for HoneycombSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HoneycombSink")
+            .field("api_host", &self.api_host)
+            .field("dataset", &self.dataset)
+            .field("batch_size", &self.batch_size)
+            .field("sample_rate", &self.sample_rate)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HoneycombSink {
+    /// Sink posting to `dataset` on `api.honeycomb.io` with `api_key`.
+    ///
+    /// Defaults: batches of 50 events, at most 10,000 pending, no sampling.
+    pub fn new<S: Into<String>>(api_key: S, dataset: S) -> Self {
+        Self {
+            api_host: Arc::from("https://api.honeycomb.io"),
+            dataset: Arc::from(dataset.into()),
+            api_key: Arc::from(api_key.into()),
+            batch_size: 50,
+            max_pending: 10_000,
+            sample_rate: 1,
+            seen: Arc::new(AtomicU64::new(0)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+            dropped: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "client")]
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Post to another API host, e.g. `https://api.eu1.honeycomb.io` or a Refinery proxy.
+    pub fn with_api_host(mut self, host: impl Into<String>) -> Self {
+        self.api_host = Arc::from(host.into().trim_end_matches('/'));
+        self
+    }
+
+    /// Post once `batch_size` events are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Drop new events while `max_pending` are already buffered.
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    /// Keep one in `sample_rate` non-error events (minimum 1, meaning keep all).
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate.max(1);
+        self
+    }
+
+    /// Events dropped because the buffer was full or a batch failed.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Post any buffered events now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.send(batch).await;
+    }
+
+    /// Sample and buffer `envelope`, returning a full batch when one is ready.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    fn enqueue(&self, envelope: &EventEnvelope) -> Vec<serde_json::Value> {
+        let sample_rate = if envelope.event.is_error() {
+            1
+        } else {
+            let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+            if !seen.is_multiple_of(u64::from(self.sample_rate)) {
+                return Vec::new();
+            }
+            self.sample_rate
+        };
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        if pending.len() >= self.max_pending {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return Vec::new();
+        }
+        pending.push(batch_entry(envelope, SystemTime::now(), sample_rate));
+        if pending.len() >= self.batch_size {
+            std::mem::take(&mut *pending)
+        } else {
+            Vec::new()
+        }
+    }
+
+    async fn send(&self, batch: Vec<serde_json::Value>) {
+        if batch.is_empty() {
+            return;
+        }
+        #[cfg(feature = "client")]
+        {
+            let response = self
+                .client
+                .post(format!("{}/1/batch/{}", self.api_host, self.dataset))
+                .header("X-Honeycomb-Team", &*self.api_key)
+                .json(&batch)
+                .send()
+                .await;
+            if matches!(&response, Ok(response) if response.status().is_success()) {
+                return;
+            }
+        }
+        self.dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for HoneycombSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let batch = self.enqueue(&EventEnvelope::capture(event));
+            let sink = self.clone();
+            Box::pin(async move {
+                sink.send(batch).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for HoneycombSink {
+    type SinkError = Infallible;
+}
@@ -0,0 +1,248 @@
+//! InfluxDB telemetry sink for `ninelives`.
+//!
+//! Each event becomes one line-protocol point: the measurement is `ninelives_<layer>`; the
+//! event name, `Policy::named` name and instance, `EnrichSink` attributes, and the low-cardinality
+//! event fields in [`TAG_FIELDS`] are tags; every other event field (`duration_ms`, `attempt`,
+//! ...) is a field alongside `count=1i`. Tags with empty values are left out. Lines are buffered
+//! and written in batches; points in failed writes are logged and counted in
+//! [`InfluxSink::failures`].
+//!
+//! Default build is a no-op; enable the `client` feature to write to InfluxDB 2.x.
+//!
+//! ```rust,no_run
+//! use ninelives_influx::InfluxSink;
+//! let sink = InfluxSink::new("http://localhost:8086", "my-org", "resilience", "token")
+//!     .with_batch_size(500);
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::fmt::Write as _;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sink writing policy events to an InfluxDB bucket in batches.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct InfluxSink {
+    org: String,
+    bucket: String,
+    batch_size: usize,
+    pending: Arc<Mutex<Vec<String>>>,
+    failures: Arc<AtomicU64>,
+    #[cfg(feature = "client")]
+    client: influxdb2::Client,
+}
+
+impl InfluxSink {
+    /// Sink writing to `bucket` in `org` on the server at `url`, 100 points per write.
+    pub fn new<S: Into<String>>(url: S, org: S, bucket: S, token: S) -> Self {
+        let org = org.into();
+        let bucket = bucket.into();
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        let failures = Arc::new(AtomicU64::new(0));
+        #[cfg(feature = "client")]
+        {
+            let client = influxdb2::Client::new(url.into(), org.clone(), token.into());
+            return Self { org, bucket, batch_size: 100, pending, failures, client };
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = (url, token);
+            Self { org, bucket, batch_size: 100, pending, failures }
+        }
+    }
+
+    /// Write once `batch_size` points are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Points in writes that failed.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Write any buffered points now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.write(batch).await;
+    }
+
+    async fn write(&self, batch: Vec<String>) {
+        #[cfg(feature = "client")]
+        if !batch.is_empty() {
+            let written =
+                self.client.write_line_protocol(&self.org, &self.bucket, batch.join("\n")).await;
+            if let Err(error) = written {
+                self.failures.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                tracing::warn!(bucket = %self.bucket, %error, "failed to write policy events to InfluxDB");
+            }
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = batch;
+    }
+}
+
+/// Event fields written as tags rather than fields: enum-valued, so their values are few.
+pub const TAG_FIELDS: &[&str] = &["reason", "kind", "priority"];
+
+/// Encode an envelope as a single line-protocol point with a nanosecond timestamp.
+pub fn line_protocol(envelope: &EventEnvelope, timestamp: SystemTime) -> String {
+    let mut line = format!("ninelives_{}", escape(envelope.event.layer_kind(), ", "));
+    let mut tag = |key: &str, value: &str| {
+        if !key.is_empty() && !value.is_empty() {
+            let _ = write!(line, ",{}={}", escape(key, ",= "), escape(value, ",= "));
+        }
+    };
+    tag("event", envelope.event.event_name());
+    if let Some(name) = &envelope.policy_name {
+        tag("policy_name", name);
+    }
+    if let Some(instance) = envelope.instance_id {
+        tag("instance_id", &instance.to_string());
+    }
+    for (key, value) in &envelope.attributes {
+        tag(key, value);
+    }
+
+    let mut fields = String::from("count=1i");
+    if let Ok(serde_json::Value::Object(map)) = serde_json::to_value(&envelope.event) {
+        for (key, value) in map {
+            if key == "policy" || key == "event" {
+                continue;
+            }
+            match value {
+                serde_json::Value::Number(n) if n.is_f64() => {
+                    let _ = write!(fields, ",{}={n}", escape(&key, ",= "));
+                }
+                serde_json::Value::Number(n) => {
+                    let _ = write!(fields, ",{}={n}i", escape(&key, ",= "));
+                }
+                serde_json::Value::Bool(b) => {
+                    let _ = write!(fields, ",{}={b}", escape(&key, ",= "));
+                }
+                serde_json::Value::String(s) if TAG_FIELDS.contains(&key.as_str()) => tag(&key, &s),
+                serde_json::Value::String(s) => {
+                    let _ = write!(fields, ",{}=\"{}\"", escape(&key, ",= "), escape(&s, "\""));
+                }
+                _ => {}
+            }
+        }
+    }
+    let nanos = timestamp.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let _ = write!(line, " {fields} {nanos}");
+    line
+}
+
+/// Backslash-escape the characters line protocol treats as delimiters in this position.
+fn escape(raw: &str, special: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if c == '\\' || special.contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+impl tower_service::Service<PolicyEvent> for InfluxSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        {
+            let line = line_protocol(&EventEnvelope::capture(event), SystemTime::now());
+            let batch = {
+                let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                pending.push(line);
+                if pending.len() >= self.batch_size {
+                    std::mem::take(&mut *pending)
+                } else {
+                    Vec::new()
+                }
+            };
+            let sink = self.clone();
+            return Box::pin(async move {
+                sink.write(batch).await;
+                Ok(())
+            });
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        }
+    }
+}
+
+impl TelemetrySink for InfluxSink {
+    type SinkError = Infallible;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ninelives::telemetry::{BulkheadEvent, BulkheadRejectReason, RequestOutcome};
+    use ninelives::RequestContext;
+    use std::time::Duration;
+
+    fn encode(ctx: RequestContext, event: PolicyEvent) -> String {
+        let envelope = ctx.sync_scope(|| EventEnvelope::capture(event));
+        line_protocol(&envelope, UNIX_EPOCH + Duration::from_secs(1))
+    }
+
+    #[test]
+    fn escapes_spaces_commas_and_equals_in_tags() {
+        let ctx =
+            RequestContext::new().with_policy_name("check out").with_attribute("team,name", "a=b");
+        let line = encode(ctx, PolicyEvent::Bulkhead(BulkheadEvent::Closed));
+        assert_eq!(
+            line,
+            "ninelives_bulkhead,event=closed,policy_name=check\\ out,team\\,name=a\\=b \
+             count=1i 1000000000"
+        );
+    }
+
+    #[test]
+    fn skips_empty_tag_values() {
+        let ctx = RequestContext::new().with_policy_name("").with_attribute("region", "");
+        let line = encode(ctx, PolicyEvent::Bulkhead(BulkheadEvent::Closed));
+        assert_eq!(line, "ninelives_bulkhead,event=closed count=1i 1000000000");
+    }
+
+    #[test]
+    fn numbers_are_fields_and_enum_values_are_tags() {
+        let rejected = PolicyEvent::Bulkhead(BulkheadEvent::Rejected {
+            active_count: 4,
+            max_concurrency: 4,
+            reason: BulkheadRejectReason::Saturated,
+        });
+        let line = encode(RequestContext::new(), rejected);
+        assert!(line.starts_with("ninelives_bulkhead,event=rejected,reason=saturated count=1i,"));
+        assert!(line.contains(",active_count=4i") && line.contains(",max_concurrency=4i"));
+
+        let success =
+            PolicyEvent::Request(RequestOutcome::Success { duration: Duration::from_millis(12) });
+        let line = encode(RequestContext::new(), success);
+        assert_eq!(line, "ninelives_request,event=success count=1i,duration_ms=12.0 1000000000");
+    }
+
+    #[test]
+    fn string_fields_are_quoted() {
+        assert_eq!(escape(r#"say "hi""#, "\""), r#"say \"hi\""#);
+    }
+}
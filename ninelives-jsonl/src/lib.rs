@@ -1,22 +1,256 @@
 //! JSONL sink for `ninelives`. Writes one event per line.
 //! Default build is no-op. Enable `async-fs` to write with tokio fs.
+//!
+//! By default the file grows forever. Rotation moves the active file aside as
+//! `<stem>.<YYYYMMDDTHHMMSSmmm>.<ext>` (with a `-<n>` suffix on the stamp if that name is
+//! taken) and starts a new one:
+//!
+//! - [`JsonlSink::with_max_bytes`] rotates before a line would take the file past the limit;
+//! - [`JsonlSink::with_daily_rotation`] rotates at the first write on a new UTC day;
+//! - [`JsonlSink::with_retention`] keeps only the newest rotated files;
+//! - `JsonlSink::with_gzip` (`gzip` feature) compresses rotated files to `.gz`.
+//!
+//! Only the rename happens on the write path; compression and retention run on a spawned task.
+//!
+//! ```rust
+//! use ninelives_jsonl::JsonlSink;
+//! let sink = JsonlSink::new("./policy-events.jsonl")
+//!     .with_max_bytes(100 * 1024 * 1024)
+//!     .with_daily_rotation()
+//!     .with_retention(14);
+//! ```
 
 use ninelives::telemetry::{PolicyEvent, TelemetrySink};
 use std::convert::Infallible;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::SystemTime;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "async-fs"), allow(dead_code))]
 pub struct JsonlSink {
     path: String,
+    max_bytes: Option<u64>,
+    daily: bool,
+    #[cfg(feature = "gzip")]
+    gzip: bool,
+    retention: Option<usize>,
+    #[cfg(feature = "async-fs")]
+    file: std::sync::Arc<tokio::sync::Mutex<Option<ActiveFile>>>,
 }
 
 impl JsonlSink {
     pub fn new<S: Into<String>>(path: S) -> Self {
-        Self { path: path.into() }
+        Self {
+            path: path.into(),
+            max_bytes: None,
+            daily: false,
+            #[cfg(feature = "gzip")]
+            gzip: false,
+            retention: None,
+            #[cfg(feature = "async-fs")]
+            file: Default::default(),
+        }
+    }
+
+    /// Rotate before a line would take the file past `max_bytes`.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Rotate at the first write on a new UTC day.
+    pub fn with_daily_rotation(mut self) -> Self {
+        self.daily = true;
+        self
+    }
+
+    /// Keep at most `count` rotated files, deleting the oldest.
+    pub fn with_retention(mut self, count: usize) -> Self {
+        self.retention = Some(count);
+        self
+    }
+
+    /// Gzip rotated files (`<name>.gz`).
+    #[cfg(feature = "gzip")]
+    pub fn with_gzip(mut self) -> Self {
+        self.gzip = true;
+        self
+    }
+}
+
+/// Where the file at `path` goes when rotated at `at`: `<stem>.<YYYYMMDDTHHMMSSmmm>.<ext>`,
+/// or `<stem>.<YYYYMMDDTHHMMSSmmm>-<seq>.<ext>` for the `seq`-th rotation in the same
+/// millisecond.
+pub fn rotated_path(path: &Path, at: SystemTime, seq: u32) -> PathBuf {
+    let mut stamp =
+        chrono::DateTime::<chrono::Utc>::from(at).format("%Y%m%dT%H%M%S%3f").to_string();
+    if seq > 0 {
+        stamp = format!("{stamp}-{seq}");
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{stamp}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{stamp}"),
+    };
+    path.with_file_name(name)
+}
+
+/// If `name` is a rotated file of `path` (compressed or not), its age order: the stamp, then
+/// the same-millisecond sequence.
+#[cfg_attr(not(feature = "async-fs"), allow(dead_code))]
+fn rotation_key<'a>(path: &Path, name: &'a str) -> Option<(&'a str, u32)> {
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let suffix = path.extension().map(|ext| format!(".{}", ext.to_string_lossy()));
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    let rest = name.strip_prefix(&*stem)?.strip_prefix('.')?;
+    let stamp = match &suffix {
+        Some(suffix) => rest.strip_suffix(suffix.as_str())?,
+        None => rest,
+    };
+    let (stamp, seq) = match stamp.split_once('-') {
+        Some((stamp, seq)) if !seq.is_empty() && seq.bytes().all(|b| b.is_ascii_digit()) => {
+            (stamp, seq.parse().ok()?)
+        }
+        Some(_) => return None,
+        None => (stamp, 0),
+    };
+    let well_formed = stamp.len() == 18
+        && stamp
+            .bytes()
+            .enumerate()
+            .all(|(i, b)| if i == 8 { b == b'T' } else { b.is_ascii_digit() });
+    well_formed.then_some((stamp, seq))
+}
+
+/// The file being appended to.
+#[cfg(feature = "async-fs")]
+#[derive(Debug)]
+struct ActiveFile {
+    file: tokio::fs::File,
+    size: u64,
+    day: chrono::NaiveDate,
+}
+
+#[cfg(feature = "async-fs")]
+impl JsonlSink {
+    async fn append(&self, line: &str) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut slot = self.file.lock().await;
+        let today = chrono::DateTime::<chrono::Utc>::from(SystemTime::now()).date_naive();
+        if slot.is_none() {
+            *slot = Some(self.open().await?);
+        }
+        if let Some(active) = slot.as_ref() {
+            let full = self
+                .max_bytes
+                .is_some_and(|max| active.size > 0 && active.size + line.len() as u64 > max);
+            let stale = self.daily && active.day != today;
+            if full || stale {
+                slot.take();
+                self.rotate().await?;
+                *slot = Some(self.open().await?);
+            }
+        }
+        let active = slot.as_mut().expect("file opened above");
+        active.file.write_all(line.as_bytes()).await?;
+        active.size += line.len() as u64;
+        Ok(())
+    }
+
+    async fn open(&self) -> std::io::Result<ActiveFile> {
+        let file = tokio::fs::OpenOptions::new().create(true).append(true).open(&self.path).await?;
+        let metadata = file.metadata().await?;
+        let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+        let day = chrono::DateTime::<chrono::Utc>::from(modified).date_naive();
+        Ok(ActiveFile { file, size: metadata.len(), day })
+    }
+
+    /// Move the active file aside under a name no earlier rotation holds, then compress and
+    /// prune in the background.
+    async fn rotate(&self) -> std::io::Result<()> {
+        let path = Path::new(&self.path);
+        let now = SystemTime::now();
+        let mut seq = 0;
+        let rotated = loop {
+            let candidate = rotated_path(path, now, seq);
+            let mut gz = candidate.clone().into_os_string();
+            gz.push(".gz");
+            if !tokio::fs::try_exists(&candidate).await? && !tokio::fs::try_exists(&gz).await? {
+                break candidate;
+            }
+            seq += 1;
+        };
+        tokio::fs::rename(path, &rotated).await?;
+
+        let sink = self.clone();
+        tokio::spawn(async move {
+            if let Err(error) = sink.after_rotate(&rotated).await {
+                tracing::warn!(
+                    path = %rotated.display(),
+                    %error,
+                    "failed to compress or prune rotated event log"
+                );
+            }
+        });
+        Ok(())
+    }
+
+    async fn after_rotate(&self, rotated: &Path) -> std::io::Result<()> {
+        #[cfg(feature = "gzip")]
+        if self.gzip {
+            compress(rotated).await?;
+        }
+        #[cfg(not(feature = "gzip"))]
+        let _ = rotated;
+        if let Some(retention) = self.retention {
+            self.prune(retention).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete all but the newest `retention` rotated files, ordered by [`rotation_key`].
+    async fn prune(&self, retention: usize) -> std::io::Result<()> {
+        let path = Path::new(&self.path);
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        // A file being compressed shows up both plain and as `.gz`; count it once.
+        let mut rotated: std::collections::BTreeMap<(String, u32), Vec<PathBuf>> =
+            Default::default();
+        let mut entries = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if let Some((stamp, seq)) = rotation_key(path, &name) {
+                rotated.entry((stamp.to_owned(), seq)).or_default().push(entry.path());
+            }
+        }
+        let excess = rotated.len().saturating_sub(retention);
+        for old in rotated.into_values().take(excess).flatten() {
+            let _ = tokio::fs::remove_file(old).await;
+        }
+        Ok(())
     }
 }
 
+/// Replace `path` with a gzipped `<path>.gz`.
+#[cfg(feature = "gzip")]
+async fn compress(path: &Path) -> std::io::Result<()> {
+    use async_compression::tokio::write::GzipEncoder;
+    use tokio::io::AsyncWriteExt;
+
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    let mut input = tokio::fs::File::open(path).await?;
+    let mut encoder = GzipEncoder::new(tokio::fs::File::create(&gz).await?);
+    tokio::io::copy(&mut input, &mut encoder).await?;
+    encoder.shutdown().await?;
+    tokio::fs::remove_file(path).await
+}
+
 impl tower_service::Service<PolicyEvent> for JsonlSink {
     type Response = ();
     type Error = Infallible;
@@ -28,28 +262,100 @@ impl tower_service::Service<PolicyEvent> for JsonlSink {
 
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
         #[cfg(feature = "async-fs")]
-        {
-            let path = self.path.clone();
-            let line = serde_json::json!({ "event": format!("{:?}", event) }).to_string() + "\n";
-            return Box::pin(async move {
-                use tokio::io::AsyncWriteExt;
-                let mut file = tokio::fs::OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(path)
-                    .await
-                    .map_err(|_| Infallible)?;
-                let _ = file.write_all(line.as_bytes()).await;
+        let fut = {
+            let sink = self.clone();
+            let line = ninelives::telemetry::EventEnvelope::capture(event).to_json() + "\n";
+            Box::pin(async move {
+                let _ = sink.append(&line).await;
                 Ok(())
-            });
-        }
+            })
+        };
+
         #[cfg(not(feature = "async-fs"))]
-        {
-            return Box::pin(async move { Ok(()) });
-        }
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
     }
 }
 
 impl TelemetrySink for JsonlSink {
     type SinkError = Infallible;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn rotated_path_stamps_and_sequences_the_name() {
+        let path = Path::new("/var/log/events.jsonl");
+        assert_eq!(
+            rotated_path(path, at(1_700_000_000_123), 0),
+            Path::new("/var/log/events.20231114T221320123.jsonl")
+        );
+        assert_eq!(
+            rotated_path(path, at(1_700_000_000_123), 2),
+            Path::new("/var/log/events.20231114T221320123-2.jsonl")
+        );
+        assert_eq!(
+            rotated_path(Path::new("events"), at(0), 0),
+            Path::new("events.19700101T000000000")
+        );
+        assert_eq!(
+            rotated_path(Path::new("app.policy.v2.jsonl"), at(0), 1),
+            Path::new("app.policy.v2.19700101T000000000-1.jsonl")
+        );
+    }
+
+    #[test]
+    fn rotation_key_matches_only_rotations_of_this_file() {
+        let path = Path::new("logs/app.policy.v2.jsonl");
+        for seq in [0, 1, 12] {
+            let name = rotated_path(path, at(1_700_000_000_123), seq);
+            let name = name.file_name().unwrap().to_str().unwrap();
+            assert!(rotation_key(path, name).is_some(), "{name}");
+            assert!(rotation_key(path, &format!("{name}.gz")).is_some(), "{name}.gz");
+        }
+        assert_eq!(rotation_key(path, "app.policy.v2.jsonl"), None);
+        assert_eq!(rotation_key(path, "app.policy.20231114T221320123.jsonl"), None);
+        assert_eq!(rotation_key(path, "app.policy.v2.20231114T221320123.json"), None);
+        assert_eq!(rotation_key(path, "app.policy.v2.20231114X221320123.jsonl"), None);
+        assert_eq!(rotation_key(path, "app.policy.v2.20231114T221320123-.jsonl"), None);
+
+        let bare = Path::new("events");
+        assert!(rotation_key(bare, "events.20231114T221320123").is_some());
+        assert!(rotation_key(bare, "events.20231114T221320123-3.gz").is_some());
+        assert_eq!(rotation_key(bare, "events.20231114T221320123.jsonl"), None);
+    }
+
+    #[test]
+    fn rotation_keys_sort_oldest_first() {
+        let path = Path::new("events.jsonl");
+        let mut names: Vec<String> = [(2_000, 0), (1_000, 10), (1_000, 0), (1_000, 2)]
+            .into_iter()
+            .map(|(millis, seq)| {
+                let rotated = rotated_path(path, at(millis), seq);
+                rotated.to_string_lossy().into_owned()
+            })
+            .collect();
+        names.sort_by(|a, b| rotation_key(path, a).cmp(&rotation_key(path, b)));
+        let order: Vec<_> = names.iter().map(|name| rotation_key(path, name).unwrap()).collect();
+        assert_eq!(
+            order,
+            [
+                ("19700101T000001000", 0),
+                ("19700101T000001000", 2),
+                ("19700101T000001000", 10),
+                ("19700101T000002000", 0),
+            ]
+        );
+    }
+}
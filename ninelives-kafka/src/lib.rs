@@ -1,37 +1,233 @@
 //! Kafka telemetry sink for `ninelives` (companion crate).
 //! Default build is a no-op to keep the core light; enable `client` to emit to Kafka.
+//!
+//! Each event is produced as a JSON `EventEnvelope` with headers describing it, so consumers
+//! can route or filter without parsing the payload:
+//!
+//! - `ninelives-schema-version`, `ninelives-layer`, `ninelives-event`, `content-type`;
+//! - `host`, taken from the `HOSTNAME` environment variable when set;
+//! - any extra headers added with [`KafkaSink::with_header`].
+//!
+//! By default messages are keyless, so events spread over all partitions. Key them with
+//! [`KeyStrategy::Policy`] to keep each policy's events in order on one partition, or with
+//! [`KeyStrategy::Fixed`] (e.g. a service name) to keep a whole service together.
+//!
+//! [`Delivery`] picks between handing messages to the producer queue without waiting
+//! (the default) and awaiting the broker's ack with bounded retries. Failed deliveries are
+//! logged and counted in [`KafkaSink::failures`].
+//!
+//! ```rust,no_run
+//! use ninelives_kafka::{Delivery, KafkaSink, KeyStrategy};
+//! # fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = KafkaSink::new("localhost:9092", "policy-events")?
+//!     .with_key(KeyStrategy::Policy)
+//!     .with_header("service", "checkout")
+//!     .with_delivery(Delivery::AwaitAck { retries: 3 });
+//! # Ok(()) }
+//! ```
 
-use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
 use std::convert::Infallible;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
-#[derive(Clone, Debug)]
+/// How messages are keyed, which decides their partition.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeyStrategy {
+    /// No key; the producer spreads messages over partitions.
+    #[default]
+    None,
+    /// `<policy name>#<instance>` (or just the name), so each policy's events stay ordered.
+    /// Events outside a `Policy::named` stack are keyless.
+    Policy,
+    /// The same key for every message, e.g. a service name.
+    Fixed(String),
+}
+
+impl KeyStrategy {
+    /// Message key for `envelope`, if any.
+    pub fn key_for(&self, envelope: &EventEnvelope) -> Option<String> {
+        match self {
+            KeyStrategy::None => None,
+            KeyStrategy::Policy => {
+                let name = envelope.policy_name.as_deref()?;
+                Some(match envelope.instance_id {
+                    Some(instance) => format!("{name}#{instance}"),
+                    None => name.to_owned(),
+                })
+            }
+            KeyStrategy::Fixed(key) => Some(key.clone()),
+        }
+    }
+}
+
+/// Whether `call` waits for the broker to acknowledge each message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Delivery {
+    /// Enqueue on the producer and return; delivery failures surface only in librdkafka logs.
+    #[default]
+    FireAndForget,
+    /// Wait for the ack, retrying a failed delivery up to `retries` times with backoff.
+    AwaitAck {
+        /// Retries after the first failed attempt.
+        retries: u32,
+    },
+}
+
+/// Headers for `envelope`: the built-in ones followed by `extra`.
+pub fn message_headers(
+    envelope: &EventEnvelope,
+    extra: &[(String, String)],
+) -> Vec<(String, String)> {
+    let mut headers = vec![
+        ("content-type".to_owned(), "application/json".to_owned()),
+        ("ninelives-schema-version".to_owned(), envelope.schema_version.to_string()),
+        ("ninelives-layer".to_owned(), envelope.event.layer_kind().to_owned()),
+        ("ninelives-event".to_owned(), envelope.event.event_name().to_owned()),
+    ];
+    if let Ok(host) = std::env::var("HOSTNAME") {
+        headers.push(("host".to_owned(), host));
+    }
+    headers.extend(extra.iter().cloned());
+    headers
+}
+
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
 pub struct KafkaSink {
     topic: String,
+    key: KeyStrategy,
+    headers: Arc<[(String, String)]>,
+    delivery: Delivery,
+    failures: Arc<AtomicU64>,
     #[cfg(feature = "client")]
     producer: rdkafka::producer::FutureProducer,
 }
 
+impl std::fmt::Debug for KafkaSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSink")
+            .field("topic", &self.topic)
+            .field("key", &self.key)
+            .field("delivery", &self.delivery)
+            .finish_non_exhaustive()
+    }
+}
+
 impl KafkaSink {
     pub fn new<S: Into<String>>(brokers: S, topic: S) -> Result<Self, Box<dyn std::error::Error>> {
-        let topic = topic.into();
         #[cfg(feature = "client")]
-        {
-            use rdkafka::config::ClientConfig;
-            let producer = ClientConfig::new().set("bootstrap.servers", brokers.into()).create()?;
-            return Ok(Self { topic, producer });
-        }
+        let sink = {
+            let mut config = rdkafka::ClientConfig::new();
+            config.set("bootstrap.servers", brokers.into());
+            Self::from_config(&config, topic)
+        };
+
         #[cfg(not(feature = "client"))]
-        {
+        let sink = {
             let _ = brokers; // silence unused
-            Ok(Self { topic, ..Self::noop() })
-        }
+            Ok(Self::with_topic(topic.into()))
+        };
+
+        sink
+    }
+
+    /// Sink producing to `topic` with a producer built from `config`, for settings such as
+    /// `acks`, `enable.idempotence`, or `compression.type`.
+    #[cfg(feature = "client")]
+    pub fn from_config(
+        config: &rdkafka::ClientConfig,
+        topic: impl Into<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let producer = config.create()?;
+        Ok(Self {
+            topic: topic.into(),
+            key: KeyStrategy::None,
+            headers: Arc::from(Vec::new()),
+            delivery: Delivery::FireAndForget,
+            failures: Arc::new(AtomicU64::new(0)),
+            producer,
+        })
     }
 
     #[cfg(not(feature = "client"))]
-    fn noop() -> Self {
-        Self { topic: String::new() }
+    fn with_topic(topic: String) -> Self {
+        Self {
+            topic,
+            key: KeyStrategy::None,
+            headers: Arc::from(Vec::new()),
+            delivery: Delivery::FireAndForget,
+            failures: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Key messages with `key` (keyless by default).
+    pub fn with_key(mut self, key: KeyStrategy) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Add a header to every message.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let mut headers = self.headers.to_vec();
+        headers.push((name.into(), value.into()));
+        self.headers = Arc::from(headers);
+        self
+    }
+
+    /// Choose whether to wait for acks (fire-and-forget by default).
+    pub fn with_delivery(mut self, delivery: Delivery) -> Self {
+        self.delivery = delivery;
+        self
+    }
+
+    /// Messages that could not be enqueued or, with [`Delivery::AwaitAck`], delivered.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "client")]
+    async fn produce(&self, key: Option<String>, headers: Vec<(String, String)>, payload: Vec<u8>) {
+        use rdkafka::message::{Header, OwnedHeaders};
+        use rdkafka::producer::FutureRecord;
+        use std::time::Duration;
+
+        let record = || {
+            let headers = headers.iter().fold(OwnedHeaders::new(), |acc, (name, value)| {
+                acc.insert(Header { key: name, value: Some(value) })
+            });
+            let record = FutureRecord::to(&self.topic).payload(&payload).headers(headers);
+            match &key {
+                Some(key) => record.key(key),
+                None => record,
+            }
+        };
+
+        let error = match self.delivery {
+            Delivery::FireAndForget => match self.producer.send_result(record()) {
+                Ok(_) => return,
+                Err((error, _)) => error,
+            },
+            Delivery::AwaitAck { retries } => {
+                let mut backoff = Duration::from_millis(100);
+                let mut attempt = 0;
+                loop {
+                    match self.producer.send(record(), Duration::ZERO).await {
+                        Ok(_) => return,
+                        Err((error, _)) if attempt >= retries => break error,
+                        Err(_) => {
+                            attempt += 1;
+                            tokio::time::sleep(backoff).await;
+                            backoff *= 2;
+                        }
+                    }
+                }
+            }
+        };
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(topic = %self.topic, %error, "failed to deliver policy event to kafka");
     }
 }
 
@@ -47,18 +243,22 @@ impl tower_service::Service<PolicyEvent> for KafkaSink {
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
         #[cfg(feature = "client")]
         let fut = {
-            use rdkafka::producer::FutureRecord;
-            let topic = self.topic.clone();
-            let producer = self.producer.clone();
-            let payload = format!("{:?}", event).into_bytes();
+            let envelope = EventEnvelope::capture(event);
+            let key = self.key.key_for(&envelope);
+            let headers = message_headers(&envelope, &self.headers);
+            let payload = envelope.to_json().into_bytes();
+            let sink = self.clone();
             Box::pin(async move {
-                let _ = producer.send(FutureRecord::to(&topic).payload(&payload), 0).await;
+                sink.produce(key, headers, payload).await;
                 Ok(())
             })
         };
 
         #[cfg(not(feature = "client"))]
-        let fut = Box::pin(async move { Ok(()) });
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
 
         fut
     }
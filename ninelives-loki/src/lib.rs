@@ -0,0 +1,229 @@
+//! Grafana Loki telemetry sink for `ninelives`.
+//!
+//! Events are pushed as structured log lines (the JSON `EventEnvelope`) over the Loki push API,
+//! in streams labelled `service`, `policy` (the layer kind), and `event`. Policy names,
+//! instance ids, and attributes stay in the line so label cardinality stays bounded.
+//!
+//! Lines are batched; a failed push is retried with exponential backoff on connection errors,
+//! `429`, and `5xx`, then dropped. At most `max_pending` lines wait for a push; beyond that new
+//! lines are dropped and counted in [`LokiSink::dropped`].
+//!
+//! Default build is a no-op; enable the `client` feature to push over HTTP.
+//!
+//! ```rust,no_run
+//! use ninelives_loki::LokiSink;
+//! let sink = LokiSink::new("http://loki:3100", "checkout").with_batch_size(200);
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A log line waiting to be pushed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LokiEntry {
+    /// `PolicyEvent::layer_kind`, sent as the `policy` label.
+    pub layer: &'static str,
+    /// `PolicyEvent::event_name`, sent as the `event` label.
+    pub event: &'static str,
+    /// Nanoseconds since the Unix epoch.
+    pub timestamp_ns: u128,
+    /// The JSON envelope.
+    pub line: String,
+}
+
+impl LokiEntry {
+    /// Entry for `envelope`, observed at `timestamp`.
+    pub fn new(envelope: &EventEnvelope, timestamp: SystemTime) -> Self {
+        Self {
+            layer: envelope.event.layer_kind(),
+            event: envelope.event.event_name(),
+            timestamp_ns: timestamp.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0),
+            line: envelope.to_json(),
+        }
+    }
+}
+
+/// Body of a `POST /loki/api/v1/push` request carrying `entries`, one stream per label set.
+pub fn push_body(service: &str, entries: &[LokiEntry]) -> String {
+    let mut streams: BTreeMap<(&str, &str), Vec<serde_json::Value>> = BTreeMap::new();
+    for entry in entries {
+        streams
+            .entry((entry.layer, entry.event))
+            .or_default()
+            .push(serde_json::json!([entry.timestamp_ns.to_string(), entry.line]));
+    }
+    let streams: Vec<_> = streams
+        .into_iter()
+        .map(|((layer, event), values)| {
+            serde_json::json!({
+                "stream": { "service": service, "policy": layer, "event": event },
+                "values": values,
+            })
+        })
+        .collect();
+    serde_json::json!({ "streams": streams }).to_string()
+}
+
+/// Sink pushing policy events to Grafana Loki in batches.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct LokiSink {
+    push_url: Arc<str>,
+    service: Arc<str>,
+    batch_size: usize,
+    max_pending: usize,
+    max_retries: u32,
+    pending: Arc<Mutex<Vec<LokiEntry>>>,
+    dropped: Arc<AtomicU64>,
+    #[cfg(feature = "client")]
+    client: hyper::Client<hyper::client::HttpConnector>,
+}
+
+impl std::fmt::Debug for LokiSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LokiSink")
+            .field("push_url", &self.push_url)
+            .field("service", &self.service)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LokiSink {
+    /// Sink pushing to the Loki server at `url` under the `service` label.
+    ///
+    /// Defaults: batches of 100 lines, at most 10,000 pending, 3 retries per push.
+    pub fn new<S: Into<String>>(url: S, service: S) -> Self {
+        let push_url = format!("{}/loki/api/v1/push", url.into().trim_end_matches('/'));
+        Self {
+            push_url: Arc::from(push_url),
+            service: Arc::from(service.into()),
+            batch_size: 100,
+            max_pending: 10_000,
+            max_retries: 3,
+            pending: Arc::new(Mutex::new(Vec::new())),
+            dropped: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "client")]
+            client: hyper::Client::new(),
+        }
+    }
+
+    /// Push once `batch_size` lines are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Drop new lines while `max_pending` are already buffered.
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    /// Retry a failed push up to `max_retries` times before dropping it.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Lines dropped because the buffer was full or a push kept failing.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Push any buffered lines now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.push(batch).await;
+    }
+
+    /// Buffer `entry`, returning a full batch when one is ready.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    fn enqueue(&self, entry: LokiEntry) -> Vec<LokiEntry> {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        if pending.len() >= self.max_pending {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return Vec::new();
+        }
+        pending.push(entry);
+        if pending.len() >= self.batch_size {
+            std::mem::take(&mut *pending)
+        } else {
+            Vec::new()
+        }
+    }
+
+    async fn push(&self, batch: Vec<LokiEntry>) {
+        if batch.is_empty() {
+            return;
+        }
+        #[cfg(feature = "client")]
+        {
+            let body = push_body(&self.service, &batch);
+            let mut backoff = std::time::Duration::from_millis(100);
+            for attempt in 0..=self.max_retries {
+                if attempt > 0 {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                match self.send(body.clone()).await {
+                    Ok(status) if status.is_success() => return,
+                    Ok(status) if status.as_u16() != 429 && !status.is_server_error() => break,
+                    _ => {}
+                }
+            }
+        }
+        self.dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "client")]
+    async fn send(&self, body: String) -> Result<hyper::StatusCode, Box<dyn std::error::Error>> {
+        let request = hyper::Request::post(&*self.push_url)
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(hyper::Body::from(body))?;
+        Ok(self.client.request(request).await?.status())
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for LokiSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let entry = LokiEntry::new(&EventEnvelope::capture(event), SystemTime::now());
+            let batch = self.enqueue(entry);
+            let sink = self.clone();
+            Box::pin(async move {
+                sink.push(batch).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for LokiSink {
+    type SinkError = Infallible;
+}
@@ -0,0 +1,158 @@
+//! MQTT telemetry sink for `ninelives` (optional companion crate).
+//!
+//! Publishes each event as a JSON `EventEnvelope` to a topic built from a template, for IoT and
+//! edge deployments where MQTT is the only egress. The template may use `{layer}`, `{event}`,
+//! and `{policy}` (the `Policy::named` name, or `unnamed`); the default is
+//! `ninelives/{policy}/{layer}/{event}`, so subscribers can pick events with wildcards such as
+//! `ninelives/+/circuit_breaker/#`.
+//!
+//! Default build is a no-op sink to keep dependencies light. Enable the `client` feature to
+//! publish.
+//!
+//! ```rust,no_run
+//! use ninelives_mqtt::{MqttSink, Qos};
+//! # async fn demo() {
+//! let sink = MqttSink::new("edge-agent-7", "broker.local", 1883)
+//!     .with_topic("site-3/{layer}/{event}")
+//!     .with_qos(Qos::AtLeastOnce);
+//! // attach with .with_sink(sink)
+//! # }
+//! ```
+
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// MQTT delivery guarantee for published events.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Qos {
+    /// QoS 0: fire and forget.
+    #[default]
+    AtMostOnce,
+    /// QoS 1: acknowledged, may duplicate.
+    AtLeastOnce,
+    /// QoS 2: acknowledged exactly once.
+    ExactlyOnce,
+}
+
+/// Fill `template` for an event of `layer`/`event` from the policy `policy`.
+///
+/// MQTT wildcard and separator characters in the values are replaced with `_`.
+pub fn topic_for(template: &str, layer: &str, event: &str, policy: Option<&str>) -> String {
+    let clean = |value: &str| value.replace(['/', '+', '#'], "_");
+    template
+        .replace("{layer}", &clean(layer))
+        .replace("{event}", &clean(event))
+        .replace("{policy}", &clean(policy.unwrap_or("unnamed")))
+}
+
+/// Sink publishing policy events to an MQTT broker.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct MqttSink {
+    topic: String,
+    qos: Qos,
+    #[cfg(feature = "client")]
+    client: rumqttc::AsyncClient,
+}
+
+impl std::fmt::Debug for MqttSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MqttSink")
+            .field("topic", &self.topic)
+            .field("qos", &self.qos)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MqttSink {
+    /// Connect to the broker at `host:port` as `client_id`.
+    ///
+    /// The connection is driven by a task spawned on the current Tokio runtime, which
+    /// reconnects after failures; events published while disconnected are queued up to the
+    /// client's capacity.
+    pub fn new<S: Into<String>>(client_id: S, host: S, port: u16) -> Self {
+        let topic = "ninelives/{policy}/{layer}/{event}".to_owned();
+        #[cfg(feature = "client")]
+        let sink = {
+            let mut options = rumqttc::MqttOptions::new(client_id, host, port);
+            options.set_keep_alive(std::time::Duration::from_secs(30));
+            let (client, mut eventloop) = rumqttc::AsyncClient::new(options, 256);
+            tokio::spawn(async move {
+                loop {
+                    if eventloop.poll().await.is_err() {
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            });
+            Self { topic, qos: Qos::default(), client }
+        };
+
+        #[cfg(not(feature = "client"))]
+        let sink = {
+            let _ = (client_id, host, port); // silence unused
+            Self { topic, qos: Qos::default() }
+        };
+
+        sink
+    }
+
+    /// Publish to topics built from `template` (see the crate docs for placeholders).
+    pub fn with_topic(mut self, template: impl Into<String>) -> Self {
+        self.topic = template.into();
+        self
+    }
+
+    /// Publish with `qos` instead of QoS 0.
+    pub fn with_qos(mut self, qos: Qos) -> Self {
+        self.qos = qos;
+        self
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for MqttSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let envelope = ninelives::telemetry::EventEnvelope::capture(event);
+            let topic = topic_for(
+                &self.topic,
+                envelope.event.layer_kind(),
+                envelope.event.event_name(),
+                envelope.policy_name.as_deref(),
+            );
+            let qos = match self.qos {
+                Qos::AtMostOnce => rumqttc::QoS::AtMostOnce,
+                Qos::AtLeastOnce => rumqttc::QoS::AtLeastOnce,
+                Qos::ExactlyOnce => rumqttc::QoS::ExactlyOnce,
+            };
+            let payload = envelope.to_json().into_bytes();
+            let client = self.client.clone();
+            Box::pin(async move {
+                let _ = client.publish(topic, qos, false, payload).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for MqttSink {
+    type SinkError = Infallible;
+}
@@ -15,37 +15,174 @@
 //! // wrap with NonBlockingSink if desired
 //! # Ok(()) }
 //! ```
+//!
+//! # JetStream
+//!
+//! Core NATS publishes are fire-and-forget: events sent while the server is unreachable are
+//! lost. [`NatsSink::with_jetstream`] switches to JetStream publishing instead. Each event is
+//! stored in a stream and acknowledged by the server; events are queued in a small in-memory
+//! buffer and published in order, and one that fails stays at the head of the buffer and is
+//! retried on the next event or [`NatsSink::flush`]. When the buffer is full the oldest event is
+//! dropped and counted in [`NatsSink::dropped`].
+//!
+//! ```rust,no_run
+//! use ninelives_nats::NatsSink;
+//! # fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = NatsSink::new("nats://127.0.0.1:4222", "ninelives.events")?
+//!     .with_jetstream("NINELIVES")?
+//!     .with_max_buffered(4096);
+//! # Ok(()) }
+//! ```
 
 use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::collections::VecDeque;
 use std::convert::Infallible;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::task::{Context, Poll};
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
 pub struct NatsSink {
+    server: String,
     subject: String,
+    max_buffered: usize,
+    buffer: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    draining: Arc<Mutex<()>>,
+    dropped: Arc<AtomicU64>,
     #[cfg(feature = "client")]
     client: nats::asynk::Connection,
+    #[cfg(feature = "client")]
+    jetstream: Option<nats::jetstream::JetStream>,
+}
+
+impl std::fmt::Debug for NatsSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NatsSink")
+            .field("subject", &self.subject)
+            .field("max_buffered", &self.max_buffered)
+            .finish_non_exhaustive()
+    }
 }
 
 impl NatsSink {
     pub fn new<S: Into<String>>(server: S, subject: S) -> Result<Self, Box<dyn std::error::Error>> {
+        let server = server.into();
         let subject = subject.into();
+        let buffer = Arc::new(Mutex::new(VecDeque::new()));
+        let draining = Arc::new(Mutex::new(()));
+        let dropped = Arc::new(AtomicU64::new(0));
         #[cfg(feature = "client")]
         {
-            let client = nats::asynk::connect(server.into())?;
-            return Ok(Self { subject, client });
+            let client = nats::asynk::connect(server.as_str())?;
+            return Ok(Self {
+                server,
+                subject,
+                max_buffered: 1024,
+                buffer,
+                draining,
+                dropped,
+                client,
+                jetstream: None,
+            });
         }
         #[cfg(not(feature = "client"))]
         {
-            let _ = server; // unused
-            Ok(Self { subject, ..Self::noop() })
+            Ok(Self { server, subject, max_buffered: 1024, buffer, draining, dropped })
+        }
+    }
+
+    /// Publish through JetStream with acks, storing events in `stream`.
+    ///
+    /// The stream is created for this sink's subject if it does not exist yet; an existing
+    /// stream must already cover the subject.
+    pub fn with_jetstream(
+        self,
+        stream: impl Into<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let stream = stream.into();
+        #[cfg(feature = "client")]
+        {
+            let context = nats::jetstream::new(nats::connect(self.server.as_str())?);
+            if context.stream_info(&stream).is_err() {
+                context.add_stream(nats::jetstream::StreamConfig {
+                    name: stream,
+                    subjects: vec![self.subject.clone()],
+                    ..Default::default()
+                })?;
+            }
+            return Ok(Self { jetstream: Some(context), ..self });
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = stream;
+            Ok(self)
+        }
+    }
+
+    /// Keep at most `max_buffered` unacknowledged events in JetStream mode (minimum 1).
+    pub fn with_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered.max(1);
+        self
+    }
+
+    /// Events waiting for a JetStream ack, including ones being retried.
+    pub fn buffered(&self) -> usize {
+        self.buffer.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Events dropped because the JetStream buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Retry publishing buffered JetStream events now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        #[cfg(feature = "client")]
+        if self.jetstream.is_some() {
+            let sink = self.clone();
+            let _ = tokio::task::spawn_blocking(move || sink.drain()).await;
         }
     }
 
-    #[cfg(not(feature = "client"))]
-    fn noop() -> Self {
-        Self { subject: String::new() }
+    /// Queue `payload` for JetStream, dropping the oldest event if the buffer is full.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    fn enqueue(&self, payload: Vec<u8>) {
+        let mut buffer = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+        if buffer.len() >= self.max_buffered {
+            buffer.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        buffer.push_back(payload);
+    }
+
+    /// Publish buffered events in order until one fails or the buffer is empty.
+    ///
+    /// Blocking; only one drain runs at a time and the others return at once.
+    #[cfg(feature = "client")]
+    fn drain(&self) {
+        let Some(context) = &self.jetstream else {
+            return;
+        };
+        let Ok(_guard) = self.draining.try_lock() else {
+            return;
+        };
+        loop {
+            let head = self.buffer.lock().unwrap_or_else(PoisonError::into_inner).front().cloned();
+            let Some(payload) = head else {
+                return;
+            };
+            if context.publish(&self.subject, &payload).is_err() {
+                // Leave it at the head; the next event or flush retries it.
+                return;
+            }
+            let mut buffer = self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+            // The head may have been dropped for space while publishing.
+            if buffer.front() == Some(&payload) {
+                buffer.pop_front();
+            }
+        }
     }
 }
 
@@ -60,18 +197,31 @@ impl tower_service::Service<PolicyEvent> for NatsSink {
 
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
         #[cfg(feature = "client")]
-        let fut = {
-            let subject = self.subject.clone();
-            let mut client = self.client.clone();
-            let payload = format!("{:?}", event).into_bytes();
-            Box::pin(async move {
-                let _ = client.publish(subject, payload).await;
-                Ok(())
-            })
+        let fut: Self::Future = {
+            let payload =
+                ninelives::telemetry::EventEnvelope::capture(event).to_json().into_bytes();
+            if self.jetstream.is_some() {
+                self.enqueue(payload);
+                let sink = self.clone();
+                Box::pin(async move {
+                    let _ = tokio::task::spawn_blocking(move || sink.drain()).await;
+                    Ok(())
+                })
+            } else {
+                let subject = self.subject.clone();
+                let mut client = self.client.clone();
+                Box::pin(async move {
+                    let _ = client.publish(subject, payload).await;
+                    Ok(())
+                })
+            }
         };
 
         #[cfg(not(feature = "client"))]
-        let fut = Box::pin(async move { Ok(()) });
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
 
         fut
     }
@@ -1,5 +1,5 @@
 //! OTLP telemetry sink for `ninelives`.
-//! Default build is no-op; enable `client` to export events as OTLP logs.
+//! Default build is no-op; enable `client` to export events as OTLP logs and trace spans.
 
 use ninelives::telemetry::{PolicyEvent, TelemetrySink};
 use std::convert::Infallible;
@@ -13,6 +13,9 @@ use opentelemetry::{global, KeyValue};
 #[cfg(feature = "client")]
 use opentelemetry_otlp::WithExportConfig;
 
+mod trace;
+pub use trace::OtlpTraceSink;
+
 #[derive(Clone, Debug)]
 pub struct OtlpSink {
     #[cfg(feature = "client")]
@@ -81,62 +84,46 @@ fn map_event(event: &PolicyEvent) -> (Severity, Vec<KeyValue>, String) {
         BulkheadEvent, CircuitBreakerEvent, RequestOutcome, RetryEvent, TimeoutEvent,
     };
 
-    let mut attrs =
-        vec![KeyValue::new("component", "ninelives"), KeyValue::new("event_kind", kind(event))];
+    let mut attrs = vec![
+        KeyValue::new("component", "ninelives"),
+        KeyValue::new("event_kind", event.layer_kind()),
+    ];
 
     match event {
         PolicyEvent::Retry(RetryEvent::Attempt { attempt, delay }) => {
             attrs.push(KeyValue::new("attempt", (*attempt as i64).into()));
             attrs.push(KeyValue::new("delay_ms", delay.as_millis() as i64));
-            (Severity::Info, attrs, "retry_attempt".to_string())
         }
         PolicyEvent::Retry(RetryEvent::Exhausted { total_attempts, total_duration }) => {
             attrs.push(KeyValue::new("total_attempts", (*total_attempts as i64).into()));
             attrs.push(KeyValue::new("total_duration_ms", total_duration.as_millis() as i64));
-            (Severity::Warn, attrs, "retry_exhausted".to_string())
         }
         PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count }) => {
             attrs.push(KeyValue::new("failure_count", (*failure_count as i64).into()));
-            (Severity::Warn, attrs, "circuit_opened".to_string())
-        }
-        PolicyEvent::CircuitBreaker(CircuitBreakerEvent::HalfOpen) => {
-            (Severity::Info, attrs, "circuit_half_open".to_string())
         }
-        PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Closed) => {
-            (Severity::Info, attrs, "circuit_closed".to_string())
-        }
-        PolicyEvent::Bulkhead(BulkheadEvent::Acquired { active_count, max_concurrency }) => {
+        PolicyEvent::Bulkhead(
+            BulkheadEvent::Acquired { active_count, max_concurrency }
+            | BulkheadEvent::Released { active_count, max_concurrency }
+            | BulkheadEvent::Rejected { active_count, max_concurrency, .. },
+        ) => {
             attrs.push(KeyValue::new("active", (*active_count as i64).into()));
             attrs.push(KeyValue::new("max", (*max_concurrency as i64).into()));
-            (Severity::Info, attrs, "bulkhead_acquired".to_string())
-        }
-        PolicyEvent::Bulkhead(BulkheadEvent::Rejected { active_count, max_concurrency }) => {
-            attrs.push(KeyValue::new("active", (*active_count as i64).into()));
-            attrs.push(KeyValue::new("max", (*max_concurrency as i64).into()));
-            (Severity::Warn, attrs, "bulkhead_rejected".to_string())
         }
         PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout }) => {
             attrs.push(KeyValue::new("timeout_ms", timeout.as_millis() as i64));
-            (Severity::Warn, attrs, "timeout".to_string())
         }
-        PolicyEvent::Request(RequestOutcome::Success { duration }) => {
+        PolicyEvent::Request(
+            RequestOutcome::Success { duration } | RequestOutcome::Failure { duration },
+        ) => {
             attrs.push(KeyValue::new("duration_ms", duration.as_millis() as i64));
-            (Severity::Info, attrs, "request_success".to_string())
-        }
-        PolicyEvent::Request(RequestOutcome::Failure { duration }) => {
-            attrs.push(KeyValue::new("duration_ms", duration.as_millis() as i64));
-            (Severity::Warn, attrs, "request_failure".to_string())
         }
+        _ => {}
     }
-}
 
-#[cfg(feature = "client")]
-fn kind(event: &PolicyEvent) -> &'static str {
-    match event {
-        PolicyEvent::Retry(_) => "retry",
-        PolicyEvent::CircuitBreaker(_) => "circuit_breaker",
-        PolicyEvent::Bulkhead(_) => "bulkhead",
-        PolicyEvent::Timeout(_) => "timeout",
-        PolicyEvent::Request(_) => "request",
-    }
+    let severity = match event.severity() {
+        ninelives::telemetry::Severity::Info => Severity::Info,
+        ninelives::telemetry::Severity::Warn => Severity::Warn,
+        ninelives::telemetry::Severity::Error => Severity::Error,
+    };
+    (severity, attrs, format!("{}_{}", event.layer_kind(), event.event_name()))
 }
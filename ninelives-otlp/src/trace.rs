@@ -0,0 +1,178 @@
+//! Policy events as OTLP trace spans.
+//!
+//! Events arrive after the work they describe, so each span is backdated from the event's own
+//! duration and ended at once. Spans are parented on the OpenTelemetry context current when the
+//! sink is called, which is the caller's: attach an extracted remote context (or run under a
+//! `tracing-opentelemetry` span) and the spans join the incoming trace. Behind a
+//! `NonBlockingSink` the worker task has no such context and spans start new traces.
+//!
+//! - `Request` outcomes become `ninelives.request` spans covering the call, with error status on
+//!   failure.
+//! - Each failed retry attempt becomes a zero-length `ninelives.retry.attempt` span, marked as
+//!   an error, at the moment the attempt failed. Retry events carry neither attempt timings nor
+//!   an id tying attempts to one retry, so these spans are siblings under the caller's context,
+//!   not children of a retry span.
+//! - Only a retry that gives up becomes a `ninelives.retry` span, backdated to cover every
+//!   attempt. A retry that eventually succeeds leaves its attempt spans and the request span.
+//! - Fallback branches become `ninelives.fallback.branch` spans in a trace of their own, linked
+//!   to the caller's span.
+//! - Timeouts, breaker transitions, and rejections become zero-length `ninelives.<layer>` spans
+//!   named after the event.
+//!
+//! Every span carries `ninelives.layer`, `ninelives.event`, the `Policy::named` name and
+//! instance, and any `EnrichSink` attributes in scope.
+
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[cfg(feature = "client")]
+use opentelemetry::trace::{Link, Span, SpanBuilder, SpanKind, Status, TraceContextExt};
+#[cfg(feature = "client")]
+use opentelemetry::{global, KeyValue};
+#[cfg(feature = "client")]
+use std::sync::Arc;
+#[cfg(feature = "client")]
+use std::time::{Duration, SystemTime};
+
+/// Sink that records policy events as spans on the global OpenTelemetry tracer.
+///
+/// Install a tracer provider first, for example with
+/// `opentelemetry_otlp::new_pipeline().tracing().install_batch(..)`.
+#[derive(Clone)]
+pub struct OtlpTraceSink {
+    #[cfg(feature = "client")]
+    tracer: Arc<global::BoxedTracer>,
+}
+
+impl std::fmt::Debug for OtlpTraceSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtlpTraceSink").finish_non_exhaustive()
+    }
+}
+
+impl Default for OtlpTraceSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OtlpTraceSink {
+    /// Sink using the global tracer named `ninelives`.
+    pub fn new() -> Self {
+        #[cfg(feature = "client")]
+        {
+            Self { tracer: Arc::new(global::tracer("ninelives")) }
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            Self {}
+        }
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for OtlpTraceSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        self.record(&event);
+        #[cfg(not(feature = "client"))]
+        let _ = event;
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for OtlpTraceSink {
+    type SinkError = Infallible;
+}
+
+#[cfg(feature = "client")]
+impl OtlpTraceSink {
+    fn record(&self, event: &PolicyEvent) {
+        use ninelives::telemetry::{FallbackEvent, RequestOutcome, RetryEvent};
+
+        let parent = opentelemetry::Context::current();
+        let now = SystemTime::now();
+        let (name, took, failed, linked) = match event {
+            PolicyEvent::Request(RequestOutcome::Success { duration }) => {
+                ("ninelives.request".to_owned(), *duration, false, false)
+            }
+            PolicyEvent::Request(RequestOutcome::Failure { duration }) => {
+                ("ninelives.request".to_owned(), *duration, true, false)
+            }
+            PolicyEvent::Retry(RetryEvent::Attempt { .. }) => {
+                ("ninelives.retry.attempt".to_owned(), Duration::ZERO, true, false)
+            }
+            PolicyEvent::Retry(RetryEvent::Exhausted { total_duration, .. }) => {
+                ("ninelives.retry".to_owned(), *total_duration, true, false)
+            }
+            PolicyEvent::Fallback(FallbackEvent::Served { duration, .. }) => {
+                ("ninelives.fallback.branch".to_owned(), *duration, false, true)
+            }
+            _ => (
+                format!("ninelives.{}.{}", event.layer_kind(), event.event_name()),
+                Duration::ZERO,
+                event.is_error(),
+                false,
+            ),
+        };
+
+        let mut builder = SpanBuilder::from_name(name)
+            .with_kind(SpanKind::Internal)
+            .with_start_time(now.checked_sub(took).unwrap_or(now))
+            .with_attributes(attributes(event));
+        let parent_span = parent.span().span_context().clone();
+        let mut span = if linked && parent_span.is_valid() {
+            builder = builder.with_links(vec![Link::new(parent_span, Vec::new())]);
+            builder.start_with_context(&*self.tracer, &opentelemetry::Context::new())
+        } else {
+            builder.start_with_context(&*self.tracer, &parent)
+        };
+        if failed {
+            span.set_status(Status::error(event.to_string()));
+        }
+        span.end_with_timestamp(now);
+    }
+}
+
+#[cfg(feature = "client")]
+fn attributes(event: &PolicyEvent) -> Vec<KeyValue> {
+    use ninelives::telemetry::{FallbackEvent, RetryEvent};
+
+    let ctx = ninelives::RequestContext::current();
+    let mut attrs = vec![
+        KeyValue::new("ninelives.layer", event.layer_kind()),
+        KeyValue::new("ninelives.event", event.event_name()),
+    ];
+    if let Some(name) = ctx.policy_name() {
+        attrs.push(KeyValue::new("ninelives.policy", name.to_owned()));
+    }
+    if let Some(instance) = ctx.policy_instance() {
+        attrs.push(KeyValue::new("ninelives.instance", instance as i64));
+    }
+    for (key, value) in ctx.attributes() {
+        attrs.push(KeyValue::new(key.to_owned(), value.to_owned()));
+    }
+    match event {
+        PolicyEvent::Retry(RetryEvent::Attempt { attempt, delay }) => {
+            attrs.push(KeyValue::new("ninelives.retry.attempt", *attempt as i64));
+            attrs.push(KeyValue::new("ninelives.retry.delay_ms", delay.as_millis() as i64));
+        }
+        PolicyEvent::Retry(RetryEvent::Exhausted { total_attempts, .. }) => {
+            attrs.push(KeyValue::new("ninelives.retry.attempts", *total_attempts as i64));
+        }
+        PolicyEvent::Fallback(FallbackEvent::Served { branch, .. }) => {
+            attrs.push(KeyValue::new("ninelives.fallback.branch", *branch as i64));
+        }
+        _ => {}
+    }
+    attrs
+}
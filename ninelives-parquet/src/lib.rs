@@ -0,0 +1,341 @@
+//! Arrow/Parquet file sink for `ninelives`.
+//!
+//! Events are buffered into Arrow record batches (one row group each) and written to rolling
+//! Parquet files in a directory, one row per event: attribution and the event kind as columns,
+//! the main duration pulled out for aggregation, and the full event and attributes as JSON for
+//! anything else. Query the directory as one table:
+//!
+//! ```sql
+//! SELECT policy_name, quantile_cont(duration_ms, 0.99)
+//! FROM 'events/*.parquet' WHERE layer = 'request' GROUP BY ALL;
+//! ```
+//!
+//! A file rolls over after `max_rows_per_file` rows or `roll_interval`, whichever comes first.
+//! Files are written as `ninelives-<unix_ms>.parquet.tmp` and renamed when closed, so readers
+//! globbing `*.parquet` never see a file without its footer. Call [`ParquetSink::close`] on
+//! shutdown to write the last batch and finish the open file.
+//!
+//! Batches are encoded and written synchronously; wrap the sink in `NonBlockingSink` to keep
+//! that off the request path.
+//!
+//! Default build is a no-op; enable the `write` feature to produce files.
+//!
+//! ```rust,no_run
+//! use ninelives_parquet::ParquetSink;
+//! use std::time::Duration;
+//! # fn demo() -> std::io::Result<()> {
+//! let sink = ParquetSink::new("events")?
+//!     .with_batch_size(16_384)
+//!     .with_roll_interval(Duration::from_secs(15 * 60));
+//! // attach with .with_sink(sink.clone()); call sink.close() on shutdown
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// One row of a Parquet file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventRow {
+    /// Microseconds since the Unix epoch (`TIMESTAMP(MICROS, UTC)`).
+    pub timestamp: i64,
+    /// `EVENT_SCHEMA_VERSION` of the producer.
+    pub schema_version: u32,
+    /// `PolicyEvent::layer_kind`, e.g. `retry`.
+    pub layer: String,
+    /// `PolicyEvent::event_name`, e.g. `attempt`.
+    pub event: String,
+    /// `Policy::named` name, if any.
+    pub policy_name: Option<String>,
+    /// Instance id of the named stack.
+    pub instance_id: Option<u64>,
+    /// `PolicyEvent::is_error`.
+    pub is_error: bool,
+    /// The event's main duration in milliseconds, if it reports one.
+    pub duration_ms: Option<f64>,
+    /// `EnrichSink` attributes in scope as a JSON object.
+    pub attributes: String,
+    /// The event's own fields as JSON.
+    pub payload: String,
+}
+
+/// Duration fields in the order they are preferred for `duration_ms`.
+const DURATION_FIELDS: &[&str] = &[
+    "duration_ms",
+    "total_duration_ms",
+    "open_duration_ms",
+    "elapsed_ms",
+    "delay_ms",
+    "timeout_ms",
+];
+
+impl EventRow {
+    /// Row for `envelope`, observed at `timestamp`.
+    pub fn new(envelope: &EventEnvelope, timestamp: SystemTime) -> Self {
+        let fields = serde_json::to_value(&envelope.event).unwrap_or_default();
+        let duration_ms = DURATION_FIELDS.iter().find_map(|key| fields.get(*key)?.as_f64());
+        let micros = timestamp.duration_since(UNIX_EPOCH).map(|d| d.as_micros()).unwrap_or(0);
+        let attributes: serde_json::Map<String, serde_json::Value> = envelope
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string().into()))
+            .collect();
+        Self {
+            timestamp: i64::try_from(micros).unwrap_or(i64::MAX),
+            schema_version: envelope.schema_version,
+            layer: envelope.event.layer_kind().to_owned(),
+            event: envelope.event.event_name().to_owned(),
+            policy_name: envelope.policy_name.as_deref().map(str::to_owned),
+            instance_id: envelope.instance_id,
+            is_error: envelope.event.is_error(),
+            duration_ms,
+            attributes: serde_json::Value::Object(attributes).to_string(),
+            payload: fields.to_string(),
+        }
+    }
+}
+
+/// Settings and buffered rows shared by every clone of the sink.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "write"), allow(dead_code))]
+struct State {
+    dir: PathBuf,
+    batch_size: usize,
+    max_rows_per_file: usize,
+    roll_interval: Duration,
+    pending: Vec<EventRow>,
+    #[cfg(feature = "write")]
+    file: Option<ActiveFile>,
+}
+
+/// Sink writing policy events to rolling Parquet files.
+#[derive(Clone, Debug)]
+pub struct ParquetSink {
+    state: Arc<Mutex<State>>,
+}
+
+impl ParquetSink {
+    /// Write files into `dir`, creating it if needed.
+    ///
+    /// Defaults: record batches of 8,192 rows, files of at most 1,000,000 rows or one hour.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let state = State {
+            dir: dir.as_ref().to_path_buf(),
+            batch_size: 8_192,
+            max_rows_per_file: 1_000_000,
+            roll_interval: Duration::from_secs(3_600),
+            pending: Vec::new(),
+            #[cfg(feature = "write")]
+            file: None,
+        };
+        Ok(Self { state: Arc::new(Mutex::new(state)) })
+    }
+
+    /// Write a record batch once `batch_size` rows are buffered (minimum 1).
+    pub fn with_batch_size(self, batch_size: usize) -> Self {
+        self.lock().batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Start a new file after `max_rows` rows (minimum 1).
+    pub fn with_max_rows_per_file(self, max_rows: usize) -> Self {
+        self.lock().max_rows_per_file = max_rows.max(1);
+        self
+    }
+
+    /// Start a new file once the open one is `interval` old.
+    pub fn with_roll_interval(self, interval: Duration) -> Self {
+        self.lock().roll_interval = interval;
+        self
+    }
+
+    /// Write buffered rows to the open file without closing it.
+    ///
+    /// The rows become readable once the file is closed.
+    pub fn flush(&self) -> io::Result<()> {
+        self.lock().write_pending()
+    }
+
+    /// Write buffered rows and finish the open file, e.g. before shutdown.
+    pub fn close(&self) -> io::Result<()> {
+        let mut state = self.lock();
+        state.write_pending()?;
+        state.finish_file()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(not(feature = "write"))]
+impl State {
+    fn write_pending(&mut self) -> io::Result<()> {
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn finish_file(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "write")]
+mod writer {
+    use super::{EventRow, State};
+    use arrow_array::{
+        ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+        UInt32Array, UInt64Array,
+    };
+    use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::fs::File;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    /// The file currently being written.
+    pub(super) struct ActiveFile {
+        writer: ArrowWriter<File>,
+        path: PathBuf,
+        rows: usize,
+        opened: Instant,
+    }
+
+    impl std::fmt::Debug for ActiveFile {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ActiveFile")
+                .field("path", &self.path)
+                .field("rows", &self.rows)
+                .finish_non_exhaustive()
+        }
+    }
+
+    /// Path of a file opened at `opened` in `dir`, while it is still being written.
+    fn pending_path(dir: &Path, opened: SystemTime) -> PathBuf {
+        let millis = opened.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+        dir.join(format!("ninelives-{millis}.parquet.tmp"))
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                false,
+            ),
+            Field::new("schema_version", DataType::UInt32, false),
+            Field::new("layer", DataType::Utf8, false),
+            Field::new("event", DataType::Utf8, false),
+            Field::new("policy_name", DataType::Utf8, true),
+            Field::new("instance_id", DataType::UInt64, true),
+            Field::new("is_error", DataType::Boolean, false),
+            Field::new("duration_ms", DataType::Float64, true),
+            Field::new("attributes", DataType::Utf8, false),
+            Field::new("payload", DataType::Utf8, false),
+        ]))
+    }
+
+    fn record_batch(rows: &[EventRow]) -> Result<RecordBatch, arrow_schema::ArrowError> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                TimestampMicrosecondArray::from_iter_values(rows.iter().map(|r| r.timestamp))
+                    .with_timezone("UTC"),
+            ),
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.schema_version))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.layer))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.event))),
+            Arc::new(rows.iter().map(|r| r.policy_name.as_deref()).collect::<StringArray>()),
+            Arc::new(rows.iter().map(|r| r.instance_id).collect::<UInt64Array>()),
+            Arc::new(rows.iter().map(|r| Some(r.is_error)).collect::<BooleanArray>()),
+            Arc::new(rows.iter().map(|r| r.duration_ms).collect::<Float64Array>()),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.attributes))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.payload))),
+        ];
+        RecordBatch::try_new(schema(), columns)
+    }
+
+    impl State {
+        pub(super) fn write_pending(&mut self) -> io::Result<()> {
+            if self.pending.is_empty() {
+                return Ok(());
+            }
+            let rows = std::mem::take(&mut self.pending);
+            let batch = record_batch(&rows).map_err(io::Error::other)?;
+            let due = self.file.as_ref().is_some_and(|file| {
+                file.rows >= self.max_rows_per_file || file.opened.elapsed() >= self.roll_interval
+            });
+            if due {
+                self.finish_file()?;
+            }
+            if self.file.is_none() {
+                self.file = Some(self.open_file()?);
+            }
+            let file = self.file.as_mut().expect("file opened above");
+            file.writer.write(&batch).map_err(io::Error::other)?;
+            // One record batch per row group keeps memory bounded by `batch_size`.
+            file.writer.flush().map_err(io::Error::other)?;
+            file.rows += rows.len();
+            Ok(())
+        }
+
+        pub(super) fn finish_file(&mut self) -> io::Result<()> {
+            let Some(file) = self.file.take() else {
+                return Ok(());
+            };
+            file.writer.close().map_err(io::Error::other)?;
+            std::fs::rename(&file.path, file.path.with_extension(""))
+        }
+
+        fn open_file(&self) -> io::Result<ActiveFile> {
+            let path = pending_path(&self.dir, SystemTime::now());
+            let properties =
+                WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+            let writer = ArrowWriter::try_new(File::create(&path)?, schema(), Some(properties))
+                .map_err(io::Error::other)?;
+            Ok(ActiveFile { writer, path, rows: 0, opened: Instant::now() })
+        }
+    }
+}
+
+#[cfg(feature = "write")]
+use writer::ActiveFile;
+
+impl tower_service::Service<PolicyEvent> for ParquetSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "write")]
+        {
+            let row = EventRow::new(&EventEnvelope::capture(event), SystemTime::now());
+            let mut state = self.lock();
+            state.pending.push(row);
+            if state.pending.len() >= state.batch_size {
+                let _ = state.write_pending();
+            }
+        }
+        #[cfg(not(feature = "write"))]
+        let _ = event;
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for ParquetSink {
+    type SinkError = Infallible;
+}
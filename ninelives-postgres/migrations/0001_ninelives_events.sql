@@ -0,0 +1,17 @@
+-- Policy events written by ninelives-postgres, partitioned by month on `ts`.
+-- Create monthly partitions ahead of time (see `partition_sql`); the default partition
+-- catches anything else so inserts never fail for a missing range.
+CREATE TABLE IF NOT EXISTS ninelives_events (
+    ts          timestamptz NOT NULL,
+    layer       text        NOT NULL,
+    event       text        NOT NULL,
+    policy_name text,
+    instance_id bigint,
+    is_error    boolean     NOT NULL,
+    payload     jsonb       NOT NULL
+) PARTITION BY RANGE (ts);
+
+CREATE TABLE IF NOT EXISTS ninelives_events_default PARTITION OF ninelives_events DEFAULT;
+
+CREATE INDEX IF NOT EXISTS ninelives_events_policy_ts ON ninelives_events (policy_name, ts);
+CREATE INDEX IF NOT EXISTS ninelives_events_layer_event_ts ON ninelives_events (layer, event, ts);
@@ -0,0 +1,230 @@
+//! PostgreSQL telemetry sink for `ninelives`.
+//!
+//! Events are buffered and written with multi-row `INSERT`s into a table partitioned by month,
+//! one row per event: timestamp, layer, event, `Policy::named` attribution, error flag, and the
+//! full envelope as `jsonb`. Run [`MIGRATION`] once to create the table, then add monthly
+//! partitions with [`partition_sql`] (a default partition catches the rest).
+//!
+//! Default build is a no-op; enable the `client` feature to insert rows.
+//!
+//! ```rust,no_run
+//! use ninelives_postgres::PostgresSink;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = PostgresSink::new("host=localhost user=app dbname=app").await?.with_batch_size(500);
+//! // attach with .with_sink(sink); call sink.flush().await on shutdown
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::fmt::Write as _;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+/// Schema migration creating the `ninelives_events` table, its default partition, and indexes.
+pub const MIGRATION: &str = include_str!("../migrations/0001_ninelives_events.sql");
+
+/// Columns written per row, in insert order.
+const COLUMNS: &[&str] =
+    &["ts", "layer", "event", "policy_name", "instance_id", "is_error", "payload"];
+
+/// Postgres caps a statement at 65,535 bind parameters.
+const MAX_BATCH: usize = u16::MAX as usize / 7;
+
+/// One row of the events table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventRow {
+    /// When the sink received the event.
+    pub ts: SystemTime,
+    /// `PolicyEvent::layer_kind`, e.g. `retry`.
+    pub layer: &'static str,
+    /// `PolicyEvent::event_name`, e.g. `attempt`.
+    pub event: &'static str,
+    /// Name of the innermost `Policy::named` stack.
+    pub policy_name: Option<String>,
+    /// Instance id of that stack.
+    pub instance_id: Option<i64>,
+    /// `PolicyEvent::is_error`.
+    pub is_error: bool,
+    /// The full `EventEnvelope` as JSON.
+    pub payload: serde_json::Value,
+}
+
+impl EventRow {
+    /// Row for `envelope`, observed at `ts`.
+    pub fn new(envelope: &EventEnvelope, ts: SystemTime) -> Self {
+        Self {
+            ts,
+            layer: envelope.event.layer_kind(),
+            event: envelope.event.event_name(),
+            policy_name: envelope.policy_name.as_deref().map(str::to_owned),
+            instance_id: envelope.instance_id.and_then(|id| i64::try_from(id).ok()),
+            is_error: envelope.event.is_error(),
+            payload: serde_json::to_value(envelope).unwrap_or_default(),
+        }
+    }
+}
+
+/// `INSERT` statement for `rows` rows into `table`, with numbered parameters.
+pub fn insert_sql(table: &str, rows: usize) -> String {
+    let mut sql = format!("INSERT INTO {table} ({}) VALUES ", COLUMNS.join(", "));
+    for row in 0..rows {
+        sql.push_str(if row == 0 { "(" } else { ", (" });
+        for column in 0..COLUMNS.len() {
+            let sep = if column == 0 { "" } else { ", " };
+            let _ = write!(sql, "{sep}${}", row * COLUMNS.len() + column + 1);
+        }
+        sql.push(')');
+    }
+    sql
+}
+
+/// DDL for the monthly partition of `table` covering `year`-`month` (1-12).
+pub fn partition_sql(table: &str, year: i32, month: u32) -> String {
+    let (next_year, next_month) = if month >= 12 { (year + 1, 1) } else { (year, month + 1) };
+    format!(
+        "CREATE TABLE IF NOT EXISTS {table}_{year:04}_{month:02} PARTITION OF {table} \
+         FOR VALUES FROM ('{year:04}-{month:02}-01') TO ('{next_year:04}-{next_month:02}-01')"
+    )
+}
+
+/// Sink inserting policy events into PostgreSQL in batches.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct PostgresSink {
+    table: Arc<str>,
+    batch_size: usize,
+    pending: Arc<Mutex<Vec<EventRow>>>,
+    #[cfg(feature = "client")]
+    client: Arc<tokio_postgres::Client>,
+}
+
+impl std::fmt::Debug for PostgresSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresSink")
+            .field("table", &self.table)
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PostgresSink {
+    /// Connect with the libpq-style `config` (no TLS) and insert into `ninelives_events`,
+    /// 100 rows per statement.
+    ///
+    /// The connection is driven by a task spawned on the current Tokio runtime.
+    pub async fn new<S: Into<String>>(config: S) -> Result<Self, Box<dyn std::error::Error>> {
+        let table = Arc::from("ninelives_events");
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        #[cfg(feature = "client")]
+        let sink = {
+            let (client, connection) =
+                tokio_postgres::connect(&config.into(), tokio_postgres::NoTls).await?;
+            tokio::spawn(async move {
+                let _ = connection.await;
+            });
+            Self { table, batch_size: 100, pending, client: Arc::new(client) }
+        };
+
+        #[cfg(not(feature = "client"))]
+        let sink = {
+            let _ = config;
+            Self { table, batch_size: 100, pending }
+        };
+
+        Ok(sink)
+    }
+
+    /// Insert into `table` instead of `ninelives_events`; it must have the migration's columns.
+    pub fn with_table(mut self, table: impl Into<String>) -> Self {
+        self.table = Arc::from(table.into());
+        self
+    }
+
+    /// Insert once `batch_size` rows are buffered (1 to 9,362, the parameter limit).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_BATCH);
+        self
+    }
+
+    /// Run [`MIGRATION`] on this sink's connection.
+    #[cfg(feature = "client")]
+    pub async fn migrate(&self) -> Result<(), tokio_postgres::Error> {
+        self.client.batch_execute(MIGRATION).await
+    }
+
+    /// Insert any buffered rows now, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let batch =
+            std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        self.insert(batch).await;
+    }
+
+    async fn insert(&self, batch: Vec<EventRow>) {
+        #[cfg(feature = "client")]
+        if !batch.is_empty() {
+            use tokio_postgres::types::ToSql;
+
+            let mut params: Vec<&(dyn ToSql + Sync)> = Vec::with_capacity(batch.len() * 7);
+            for row in &batch {
+                params.extend([
+                    &row.ts as &(dyn ToSql + Sync),
+                    &row.layer,
+                    &row.event,
+                    &row.policy_name,
+                    &row.instance_id,
+                    &row.is_error,
+                    &row.payload,
+                ]);
+            }
+            let _ = self.client.execute(&insert_sql(&self.table, batch.len()), &params).await;
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = batch;
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for PostgresSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let row = EventRow::new(&EventEnvelope::capture(event), SystemTime::now());
+            let batch = {
+                let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                pending.push(row);
+                if pending.len() >= self.batch_size {
+                    std::mem::take(&mut *pending)
+                } else {
+                    Vec::new()
+                }
+            };
+            let sink = self.clone();
+            Box::pin(async move {
+                sink.insert(batch).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for PostgresSink {
+    type SinkError = Infallible;
+}
@@ -1,5 +1,6 @@
 //! Prometheus metrics sink for `ninelives`.
-//! Collects counters in-process; expose via your HTTP endpoint using `prometheus::TextEncoder`.
+//! Collects counters and duration histograms in-process; expose via your HTTP endpoint using
+//! `prometheus::TextEncoder`.
 //! Default build is no-op; enable `client` to record metrics.
 
 use ninelives::telemetry::{PolicyEvent, TelemetrySink};
@@ -13,25 +14,20 @@ pub struct PrometheusSink {
     registry: prometheus::Registry,
     #[cfg(feature = "client")]
     counter: prometheus::IntCounterVec,
+    #[cfg(feature = "client")]
+    histograms: Histograms,
+    #[cfg(feature = "client")]
+    gauges: Gauges,
 }
 
 impl PrometheusSink {
     pub fn new() -> Self {
-        #[cfg(feature = "client")]
-        {
-            let registry = prometheus::Registry::new();
-            let counter = prometheus::IntCounterVec::new(
-                prometheus::Opts::new("ninelives_events_total", "Policy events"),
-                &["policy", "event"],
-            )
-            .expect("create counter");
-            registry.register(Box::new(counter.clone())).ok();
-            return Self { registry, counter };
-        }
-        #[cfg(not(feature = "client"))]
-        {
-            Self {}
-        }
+        Self::builder().build()
+    }
+
+    /// Configure histogram buckets before creating the sink.
+    pub fn builder() -> PrometheusSinkBuilder {
+        PrometheusSinkBuilder::default()
     }
 
     /// Expose the registry for HTTP scraping.
@@ -41,6 +37,63 @@ impl PrometheusSink {
     }
 }
 
+#[cfg(feature = "serve")]
+impl PrometheusSink {
+    /// Serve the registry in the Prometheus text format at `GET /metrics` on `addr`.
+    ///
+    /// Runs until the returned future is dropped or the listener fails; spawn it on the Tokio
+    /// runtime next to your service. Every other path answers 404.
+    pub async fn serve(&self, addr: std::net::SocketAddr) -> Result<(), hyper::Error> {
+        use hyper::service::{make_service_fn, service_fn};
+
+        let registry = self.registry.clone();
+        let make_service = make_service_fn(move |_conn| {
+            let registry = registry.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let response = scrape(&registry, &req);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        hyper::Server::try_bind(&addr)?.serve(make_service).await
+    }
+}
+
+#[cfg(feature = "serve")]
+fn scrape(
+    registry: &prometheus::Registry,
+    req: &hyper::Request<hyper::Body>,
+) -> hyper::Response<hyper::Body> {
+    use hyper::{header, Body, Method, Response, StatusCode};
+    use prometheus::Encoder;
+
+    let status = |code: StatusCode| {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = code;
+        response
+    };
+    if req.method() != Method::GET || req.uri().path() != "/metrics" {
+        return status(StatusCode::NOT_FOUND);
+    }
+    let encoder = prometheus::TextEncoder::new();
+    let mut body = Vec::new();
+    match encoder.encode(&registry.gather(), &mut body) {
+        Ok(()) => {
+            let mut response = Response::new(Body::from(body));
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static(prometheus::TEXT_FORMAT),
+            );
+            response
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "ninelives-prometheus: failed to encode metrics");
+            status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 impl tower_service::Service<PolicyEvent> for PrometheusSink {
     type Response = ();
     type Error = Infallible;
@@ -58,12 +111,35 @@ impl tower_service::Service<PolicyEvent> for PrometheusSink {
                 PolicyEvent::CircuitBreaker(_) => ("circuit", "event"),
                 PolicyEvent::Bulkhead(_) => ("bulkhead", "event"),
                 PolicyEvent::Timeout(_) => ("timeout", "event"),
+                PolicyEvent::Fallback(_) => ("fallback", "event"),
+                PolicyEvent::ForkJoin(_) => ("fork_join", "event"),
+                PolicyEvent::Hedge(_) => ("hedge", "event"),
+                PolicyEvent::RateLimit(_) => ("rate_limit", "event"),
+                PolicyEvent::LoadShed(_) => ("load_shed", "event"),
+                PolicyEvent::Concurrency(_) => ("concurrency", "event"),
+                PolicyEvent::Cache(_) => ("cache", "event"),
+                PolicyEvent::Coalesce(_) => ("coalesce", "event"),
+                PolicyEvent::Throttle(_) => ("throttle", "event"),
+                PolicyEvent::Idempotency(_) => ("idempotency", "event"),
+                PolicyEvent::Priority(_) => ("priority", "event"),
+                PolicyEvent::Spillover(_) => ("spillover", "event"),
+                PolicyEvent::Watchdog(_) => ("watchdog", "event"),
+                PolicyEvent::Alert(_) => ("alert", "event"),
+                PolicyEvent::Summary(_) => ("summary", "event"),
                 PolicyEvent::Request(_) => ("request", "event"),
             };
             let c = self.counter.clone();
             let (p, e) = labels;
+            // Stacks wrapped with `Policy::named` get their own series.
+            let ctx = ninelives::RequestContext::current();
+            let name = ctx.policy_name().unwrap_or("").to_owned();
+            let instance = ctx.policy_instance().map(|id| id.to_string()).unwrap_or_default();
+            let histograms = self.histograms.clone();
+            let gauges = self.gauges.clone();
             return Box::pin(async move {
-                c.with_label_values(&[p, e]).inc();
+                c.with_label_values(&[p, e, &name]).inc();
+                histograms.observe(&event, &name);
+                gauges.observe(&event, &name, &instance);
                 Ok(())
             });
         }
@@ -77,3 +153,191 @@ impl tower_service::Service<PolicyEvent> for PrometheusSink {
 impl TelemetrySink for PrometheusSink {
     type SinkError = Infallible;
 }
+
+/// Bucket boundaries, in seconds, for the duration histograms of a [`PrometheusSink`].
+///
+/// Each histogram defaults to `prometheus::DEFAULT_BUCKETS` (5ms to 10s).
+#[derive(Clone, Debug)]
+pub struct PrometheusSinkBuilder {
+    request_buckets: Vec<f64>,
+    retry_delay_buckets: Vec<f64>,
+    timeout_buckets: Vec<f64>,
+}
+
+impl Default for PrometheusSinkBuilder {
+    fn default() -> Self {
+        let defaults = vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+        Self {
+            request_buckets: defaults.clone(),
+            retry_delay_buckets: defaults.clone(),
+            timeout_buckets: defaults,
+        }
+    }
+}
+
+impl PrometheusSinkBuilder {
+    /// Buckets for `ninelives_request_duration_seconds`.
+    pub fn request_buckets(mut self, buckets: Vec<f64>) -> Self {
+        self.request_buckets = buckets;
+        self
+    }
+
+    /// Buckets for `ninelives_retry_delay_seconds`.
+    pub fn retry_delay_buckets(mut self, buckets: Vec<f64>) -> Self {
+        self.retry_delay_buckets = buckets;
+        self
+    }
+
+    /// Buckets for `ninelives_timeout_seconds`.
+    pub fn timeout_buckets(mut self, buckets: Vec<f64>) -> Self {
+        self.timeout_buckets = buckets;
+        self
+    }
+
+    /// Create the sink and register its metrics on a fresh registry.
+    ///
+    /// Panics if a bucket list is not strictly increasing.
+    pub fn build(self) -> PrometheusSink {
+        #[cfg(feature = "client")]
+        {
+            let registry = prometheus::Registry::new();
+            let counter = prometheus::IntCounterVec::new(
+                prometheus::Opts::new("ninelives_events_total", "Policy events"),
+                &["policy", "event", "name"],
+            )
+            .expect("create counter");
+            registry.register(Box::new(counter.clone())).ok();
+            let histograms = Histograms::new(&registry, self);
+            let gauges = Gauges::new(&registry);
+            PrometheusSink { registry, counter, histograms, gauges }
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = self;
+            PrometheusSink {}
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+#[derive(Clone, Debug)]
+struct Histograms {
+    request: prometheus::HistogramVec,
+    retry_delay: prometheus::HistogramVec,
+    timeout: prometheus::HistogramVec,
+}
+
+#[cfg(feature = "client")]
+impl Histograms {
+    fn new(registry: &prometheus::Registry, buckets: PrometheusSinkBuilder) -> Self {
+        let histogram = |name: &str, help: &str, buckets: Vec<f64>, labels: &[&str]| {
+            let vec = prometheus::HistogramVec::new(
+                prometheus::HistogramOpts::new(name, help).buckets(buckets),
+                labels,
+            )
+            .expect("create histogram");
+            registry.register(Box::new(vec.clone())).ok();
+            vec
+        };
+        Self {
+            request: histogram(
+                "ninelives_request_duration_seconds",
+                "Duration of requests through a policy stack",
+                buckets.request_buckets,
+                &["name", "outcome"],
+            ),
+            retry_delay: histogram(
+                "ninelives_retry_delay_seconds",
+                "Backoff delay before each retry",
+                buckets.retry_delay_buckets,
+                &["name"],
+            ),
+            timeout: histogram(
+                "ninelives_timeout_seconds",
+                "Timeout threshold in force when a timeout warned or fired",
+                buckets.timeout_buckets,
+                &["name", "event"],
+            ),
+        }
+    }
+
+    fn observe(&self, event: &PolicyEvent, name: &str) {
+        use ninelives::telemetry::{RequestOutcome, RetryEvent, TimeoutEvent};
+
+        match event {
+            PolicyEvent::Request(RequestOutcome::Success { duration }) => {
+                self.request.with_label_values(&[name, "success"]).observe(duration.as_secs_f64())
+            }
+            PolicyEvent::Request(RequestOutcome::Failure { duration }) => {
+                self.request.with_label_values(&[name, "failure"]).observe(duration.as_secs_f64())
+            }
+            PolicyEvent::Retry(RetryEvent::Attempt { delay, .. }) => {
+                self.retry_delay.with_label_values(&[name]).observe(delay.as_secs_f64())
+            }
+            PolicyEvent::Timeout(
+                TimeoutEvent::Approaching { timeout, .. } | TimeoutEvent::Occurred { timeout },
+            ) => self
+                .timeout
+                .with_label_values(&[name, event.event_name()])
+                .observe(timeout.as_secs_f64()),
+            _ => {}
+        }
+    }
+}
+
+/// Live breaker and bulkhead state, one series per `Policy::named` name and instance.
+#[cfg(feature = "client")]
+#[derive(Clone, Debug)]
+struct Gauges {
+    circuit_state: prometheus::IntGaugeVec,
+    bulkhead_in_flight: prometheus::IntGaugeVec,
+    bulkhead_max: prometheus::IntGaugeVec,
+}
+
+#[cfg(feature = "client")]
+impl Gauges {
+    fn new(registry: &prometheus::Registry) -> Self {
+        let gauge = |name: &str, help: &str| {
+            let vec = prometheus::IntGaugeVec::new(
+                prometheus::Opts::new(name, help),
+                &["name", "instance"],
+            )
+            .expect("create gauge");
+            registry.register(Box::new(vec.clone())).ok();
+            vec
+        };
+        Self {
+            circuit_state: gauge(
+                "ninelives_circuit_breaker_state",
+                "Circuit breaker state: 0 closed, 1 half-open, 2 open",
+            ),
+            bulkhead_in_flight: gauge("ninelives_bulkhead_in_flight", "Bulkhead permits in use"),
+            bulkhead_max: gauge("ninelives_bulkhead_max_concurrency", "Bulkhead permit limit"),
+        }
+    }
+
+    fn observe(&self, event: &PolicyEvent, name: &str, instance: &str) {
+        use ninelives::telemetry::{BulkheadEvent, CircuitBreakerEvent};
+
+        let labels = [name, instance];
+        match event {
+            PolicyEvent::CircuitBreaker(transition) => {
+                let state = match transition {
+                    CircuitBreakerEvent::Closed { .. } => 0,
+                    CircuitBreakerEvent::HalfOpen { .. } => 1,
+                    CircuitBreakerEvent::Opened { .. } => 2,
+                };
+                self.circuit_state.with_label_values(&labels).set(state);
+            }
+            PolicyEvent::Bulkhead(
+                BulkheadEvent::Acquired { active_count, max_concurrency }
+                | BulkheadEvent::Released { active_count, max_concurrency }
+                | BulkheadEvent::Rejected { active_count, max_concurrency, .. },
+            ) => {
+                self.bulkhead_in_flight.with_label_values(&labels).set(*active_count as i64);
+                self.bulkhead_max.with_label_values(&labels).set(*max_concurrency as i64);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Google Cloud Pub/Sub telemetry sink for `ninelives`.
+//!
+//! Publishes each event as a JSON `EventEnvelope` with `layer` and `event` message attributes,
+//! so subscriptions can filter (`attributes.layer = "circuit_breaker"`) without parsing
+//! payloads. With [`PubSubSink::with_ordering_keys`], messages carry the emitting policy's
+//! identity as their ordering key, so one policy's events arrive in order.
+//!
+//! Default build is a no-op; enable the `client` feature to publish.
+//!
+//! ```rust,no_run
+//! use ninelives_pubsub::PubSubSink;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = PubSubSink::new("projects/acme/topics/policy-events").await?.with_ordering_keys();
+//! // attach with .with_sink(sink)
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Ordering key for `envelope`: `<policy_name>#<instance_id>`, or the name alone.
+///
+/// Events from outside a `Policy::named` stack have no key and are published unordered.
+pub fn ordering_key(envelope: &EventEnvelope) -> Option<String> {
+    let name = envelope.policy_name.as_deref()?;
+    Some(match envelope.instance_id {
+        Some(instance) => format!("{name}#{instance}"),
+        None => name.to_owned(),
+    })
+}
+
+/// Sink publishing policy events to a Pub/Sub topic.
+#[derive(Clone)]
+pub struct PubSubSink {
+    ordered: bool,
+    #[cfg(feature = "client")]
+    publisher: google_cloud_pubsub::publisher::Publisher,
+}
+
+impl std::fmt::Debug for PubSubSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PubSubSink").field("ordered", &self.ordered).finish_non_exhaustive()
+    }
+}
+
+impl PubSubSink {
+    /// Sink publishing to `topic` (`projects/<project>/topics/<name>` or a bare topic id), with
+    /// credentials from the environment.
+    pub async fn new<S: Into<String>>(topic: S) -> Result<Self, Box<dyn std::error::Error>> {
+        #[cfg(feature = "client")]
+        {
+            use google_cloud_pubsub::client::{Client, ClientConfig};
+
+            let client = Client::new(ClientConfig::default().with_auth().await?).await?;
+            let topic = topic.into();
+            let id = topic.rsplit('/').next().unwrap_or(&topic);
+            let publisher = client.topic(id).new_publisher(None);
+            return Ok(Self { ordered: false, publisher });
+        }
+        #[cfg(not(feature = "client"))]
+        {
+            let _ = topic;
+            Ok(Self { ordered: false })
+        }
+    }
+
+    /// Set each message's ordering key with [`ordering_key`].
+    ///
+    /// The subscription must have message ordering enabled for the keys to take effect.
+    pub fn with_ordering_keys(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
+    /// Publish any messages still batched in the client and stop its background tasks.
+    pub async fn shutdown(self) {
+        #[cfg(feature = "client")]
+        {
+            let mut publisher = self.publisher;
+            publisher.shutdown().await;
+        }
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for PubSubSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            use google_cloud_googleapis::pubsub::v1::PubsubMessage;
+
+            let envelope = EventEnvelope::capture(event);
+            let message = PubsubMessage {
+                data: envelope.to_json().into_bytes(),
+                attributes: [
+                    ("layer".to_owned(), envelope.event.layer_kind().to_owned()),
+                    ("event".to_owned(), envelope.event.event_name().to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+                ordering_key: if self.ordered { ordering_key(&envelope) } else { None }
+                    .unwrap_or_default(),
+                ..Default::default()
+            };
+            let publisher = self.publisher.clone();
+            Box::pin(async move {
+                let _ = publisher.publish(message).await.get().await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for PubSubSink {
+    type SinkError = Infallible;
+}
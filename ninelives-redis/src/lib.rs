@@ -0,0 +1,131 @@
+//! Redis Streams telemetry sink for `ninelives`.
+//!
+//! Each event is appended with `XADD <key> MAXLEN ~ <max_len> * ...`, so the stream stays a
+//! capped buffer of recent events that dashboards and CLIs can read with `XRANGE`/`XREVRANGE`
+//! or follow with `XREAD BLOCK`. Entries carry flat fields for filtering and the full JSON
+//! envelope:
+//!
+//! ```text
+//! layer=retry event=attempt policy_name=checkout instance_id=3 data={"schema_version":1,...}
+//! ```
+//!
+//! Default build is a no-op; enable the `client` feature to write to Redis.
+//!
+//! ```rust,no_run
+//! use ninelives_redis::RedisStreamSink;
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = RedisStreamSink::new("redis://127.0.0.1/", "ninelives:events")
+//!     .await?
+//!     .with_max_len(50_000);
+//! // attach with .with_sink(sink)
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Field/value pairs of the stream entry for `envelope`.
+///
+/// `policy_name` and `instance_id` are omitted outside a `Policy::named` stack.
+pub fn stream_fields(envelope: &EventEnvelope) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("layer", envelope.event.layer_kind().to_owned()),
+        ("event", envelope.event.event_name().to_owned()),
+    ];
+    if let Some(name) = &envelope.policy_name {
+        fields.push(("policy_name", name.to_string()));
+    }
+    if let Some(instance) = envelope.instance_id {
+        fields.push(("instance_id", instance.to_string()));
+    }
+    fields.push(("data", envelope.to_json()));
+    fields
+}
+
+/// Sink appending policy events to a capped Redis stream.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct RedisStreamSink {
+    key: String,
+    max_len: usize,
+    #[cfg(feature = "client")]
+    connection: redis::aio::ConnectionManager,
+}
+
+impl std::fmt::Debug for RedisStreamSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisStreamSink")
+            .field("key", &self.key)
+            .field("max_len", &self.max_len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisStreamSink {
+    /// Connect to `url` and append to the stream at `key`, trimmed to about 10,000 entries.
+    ///
+    /// The connection reconnects on its own after failures.
+    pub async fn new<S: Into<String>>(url: S, key: S) -> Result<Self, Box<dyn std::error::Error>> {
+        let key = key.into();
+        #[cfg(feature = "client")]
+        let sink = {
+            let client = redis::Client::open(url.into())?;
+            let connection = redis::aio::ConnectionManager::new(client).await?;
+            Self { key, max_len: 10_000, connection }
+        };
+
+        #[cfg(not(feature = "client"))]
+        let sink = {
+            let _ = url;
+            Self { key, max_len: 10_000 }
+        };
+
+        Ok(sink)
+    }
+
+    /// Keep roughly the last `max_len` entries (approximate trimming, `MAXLEN ~`).
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len.max(1);
+        self
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for RedisStreamSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let mut command = redis::cmd("XADD");
+            command.arg(&self.key).arg("MAXLEN").arg("~").arg(self.max_len).arg("*");
+            for (field, value) in stream_fields(&EventEnvelope::capture(event)) {
+                command.arg(field).arg(value);
+            }
+            let mut connection = self.connection.clone();
+            Box::pin(async move {
+                let _: redis::RedisResult<String> = command.query_async(&mut connection).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for RedisStreamSink {
+    type SinkError = Infallible;
+}
@@ -0,0 +1,145 @@
+//! Sentry sink for `ninelives`.
+//!
+//! Forwards failure-class events through the application's Sentry client so resilience
+//! incidents sit next to its exceptions. Routing follows `PolicyEvent::severity`:
+//!
+//! - `Severity::Error` (retries exhausted, breaker opened, bulkhead closed, ...) is captured as a
+//!   Sentry event, fingerprinted by layer, event, and policy name so each incident groups on its
+//!   own.
+//! - `Severity::Warn` and above (timeouts, rejections, ...) is added as a breadcrumb, so it shows
+//!   up in the trail of the next captured error.
+//! - Routine events are ignored.
+//!
+//! Both thresholds can be moved. Events carry `ninelives.*` tags for the layer, event,
+//! `Policy::named` name, and instance id, and the event's fields as extra data.
+//!
+//! Default build is a no-op; enable the `client` feature to report through the `sentry` crate.
+//! Initialize Sentry (`sentry::init`) as usual; the sink uses the current hub.
+//!
+//! ```rust,no_run
+//! use ninelives::telemetry::Severity;
+//! use ninelives_sentry::SentrySink;
+//! let sink = SentrySink::new().with_event_threshold(Severity::Error);
+//! // attach with .with_sink(sink)
+//! ```
+
+use ninelives::telemetry::{PolicyEvent, Severity, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Sink reporting failure-class policy events to Sentry.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct SentrySink {
+    event_threshold: Severity,
+    breadcrumb_threshold: Severity,
+}
+
+impl Default for SentrySink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SentrySink {
+    /// Capture `Error` events and leave breadcrumbs for `Warn` and above.
+    pub fn new() -> Self {
+        Self { event_threshold: Severity::Error, breadcrumb_threshold: Severity::Warn }
+    }
+
+    /// Capture a Sentry event for events at or above `severity`.
+    pub fn with_event_threshold(mut self, severity: Severity) -> Self {
+        self.event_threshold = severity;
+        self
+    }
+
+    /// Add a breadcrumb for events at or above `severity`.
+    pub fn with_breadcrumb_threshold(mut self, severity: Severity) -> Self {
+        self.breadcrumb_threshold = severity;
+        self
+    }
+
+    #[cfg(feature = "client")]
+    fn report(&self, event: PolicyEvent) {
+        use sentry::protocol::{Breadcrumb, Event, Level};
+        use std::borrow::Cow;
+
+        let severity = event.severity();
+        if severity < self.breadcrumb_threshold && severity < self.event_threshold {
+            return;
+        }
+        let level = match severity {
+            Severity::Info => Level::Info,
+            Severity::Warn => Level::Warning,
+            Severity::Error => Level::Error,
+        };
+        let envelope = ninelives::telemetry::EventEnvelope::capture(event);
+        let data: sentry::protocol::Map<String, serde_json::Value> =
+            match serde_json::to_value(&envelope.event) {
+                Ok(serde_json::Value::Object(fields)) => fields.into_iter().collect(),
+                _ => Default::default(),
+            };
+        let message = envelope.event.to_string();
+        let policy = envelope.policy_name.as_deref().unwrap_or("unnamed");
+
+        if severity >= self.breadcrumb_threshold {
+            sentry::add_breadcrumb(Breadcrumb {
+                ty: "default".into(),
+                category: Some(format!("ninelives.{}", envelope.event.layer_kind())),
+                level,
+                message: Some(message.clone()),
+                data: data.clone(),
+                ..Default::default()
+            });
+        }
+        if severity >= self.event_threshold {
+            let mut tags = sentry::protocol::Map::new();
+            tags.insert("ninelives.layer".to_owned(), envelope.event.layer_kind().to_owned());
+            tags.insert("ninelives.event".to_owned(), envelope.event.event_name().to_owned());
+            tags.insert("ninelives.policy".to_owned(), policy.to_owned());
+            if let Some(instance) = envelope.instance_id {
+                tags.insert("ninelives.instance".to_owned(), instance.to_string());
+            }
+            let fingerprint = vec![
+                Cow::Borrowed("ninelives"),
+                Cow::Borrowed(envelope.event.layer_kind()),
+                Cow::Borrowed(envelope.event.event_name()),
+                Cow::Owned(policy.to_owned()),
+            ];
+            sentry::capture_event(Event {
+                message: Some(message),
+                level,
+                logger: Some("ninelives".to_owned()),
+                tags,
+                extra: data,
+                fingerprint: Cow::Owned(fingerprint),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for SentrySink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        // The SDK queues and sends in the background, so reporting inline is cheap and keeps
+        // the caller's hub (and its scope) in play.
+        #[cfg(feature = "client")]
+        self.report(event);
+        #[cfg(not(feature = "client"))]
+        let _ = event;
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for SentrySink {
+    type SinkError = Infallible;
+}
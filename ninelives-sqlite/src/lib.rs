@@ -0,0 +1,186 @@
+//! Embedded SQLite telemetry sink for `ninelives`.
+//!
+//! A structured, queryable upgrade over the JSONL sink for desktop apps and edge agents: events
+//! go into one table of a local database opened in WAL mode, so readers (a CLI, a diagnostics
+//! screen) can query while the sink writes. Old rows are pruned by age and/or count every
+//! `prune_every` inserts.
+//!
+//! Default build is a no-op; enable the `client` feature to write to SQLite.
+//!
+//! ```rust,no_run
+//! use ninelives_sqlite::SqliteSink;
+//! use std::time::Duration;
+//! # fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = SqliteSink::open("./diagnostics.db")?
+//!     .with_max_age(Duration::from_secs(7 * 24 * 3600))
+//!     .with_max_rows(100_000);
+//! // attach with .with_sink(sink)
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Pragmas and schema applied when the database is opened.
+pub const SCHEMA: &str = "
+PRAGMA journal_mode = WAL;
+PRAGMA synchronous = NORMAL;
+CREATE TABLE IF NOT EXISTS ninelives_events (
+    id          INTEGER PRIMARY KEY,
+    ts_ms       INTEGER NOT NULL,
+    layer       TEXT    NOT NULL,
+    event       TEXT    NOT NULL,
+    policy_name TEXT,
+    instance_id INTEGER,
+    is_error    INTEGER NOT NULL,
+    payload     TEXT    NOT NULL
+);
+CREATE INDEX IF NOT EXISTS ninelives_events_ts ON ninelives_events (ts_ms);
+CREATE INDEX IF NOT EXISTS ninelives_events_policy_ts ON ninelives_events (policy_name, ts_ms);
+";
+
+/// Sink writing policy events to a local SQLite database.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct SqliteSink {
+    max_age: Option<Duration>,
+    max_rows: Option<u64>,
+    prune_every: u64,
+    inserted: Arc<AtomicU64>,
+    #[cfg(feature = "client")]
+    connection: Arc<std::sync::Mutex<rusqlite::Connection>>,
+}
+
+impl std::fmt::Debug for SqliteSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteSink")
+            .field("max_age", &self.max_age)
+            .field("max_rows", &self.max_rows)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqliteSink {
+    /// Open (or create) the database at `path`, switch it to WAL mode, and apply [`SCHEMA`].
+    ///
+    /// Rows are kept forever until [`with_max_age`](Self::with_max_age) or
+    /// [`with_max_rows`](Self::with_max_rows) is set.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        #[cfg(feature = "client")]
+        let connection = {
+            let connection = rusqlite::Connection::open(path)?;
+            connection.execute_batch(SCHEMA)?;
+            Arc::new(std::sync::Mutex::new(connection))
+        };
+        #[cfg(not(feature = "client"))]
+        let _ = path;
+        Ok(Self {
+            max_age: None,
+            max_rows: None,
+            prune_every: 1000,
+            inserted: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "client")]
+            connection,
+        })
+    }
+
+    /// Delete rows older than `max_age` when pruning.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Keep at most the newest `max_rows` rows when pruning.
+    pub fn with_max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Prune after every `inserts` inserts (default 1000, minimum 1).
+    pub fn with_prune_every(mut self, inserts: u64) -> Self {
+        self.prune_every = inserts.max(1);
+        self
+    }
+
+    #[cfg(feature = "client")]
+    fn write(&self, envelope: &ninelives::telemetry::EventEnvelope) -> rusqlite::Result<()> {
+        use rusqlite::params;
+
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let connection = self.connection.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        connection.execute(
+            "INSERT INTO ninelives_events
+                 (ts_ms, layer, event, policy_name, instance_id, is_error, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                now_ms,
+                envelope.event.layer_kind(),
+                envelope.event.event_name(),
+                envelope.policy_name.as_deref(),
+                envelope.instance_id.and_then(|id| i64::try_from(id).ok()),
+                envelope.event.is_error(),
+                envelope.to_json(),
+            ],
+        )?;
+
+        let inserted = self.inserted.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        if !inserted.is_multiple_of(self.prune_every) {
+            return Ok(());
+        }
+        if let Some(max_age) = self.max_age {
+            let cutoff = now_ms.saturating_sub(max_age.as_millis() as i64);
+            connection.execute("DELETE FROM ninelives_events WHERE ts_ms < ?1", [cutoff])?;
+        }
+        if let Some(max_rows) = self.max_rows {
+            connection.execute(
+                "DELETE FROM ninelives_events
+                 WHERE id <= (SELECT MAX(id) FROM ninelives_events) - ?1",
+                [max_rows as i64],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for SqliteSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let envelope = ninelives::telemetry::EventEnvelope::capture(event);
+            let sink = self.clone();
+            Box::pin(async move {
+                // SQLite calls block; keep them off the async worker threads.
+                let _ = tokio::task::spawn_blocking(move || sink.write(&envelope)).await;
+                Ok(())
+            })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for SqliteSink {
+    type SinkError = Infallible;
+}
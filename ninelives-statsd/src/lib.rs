@@ -0,0 +1,215 @@
+//! StatsD / DogStatsD telemetry sink for `ninelives`.
+//!
+//! Sends one counter per `PolicyEvent` (`<prefix>.<layer>.<event>:1|c`) and a timer for events
+//! that carry a duration, over UDP with no extra dependencies. Sends never block: datagrams the
+//! socket cannot take right away are dropped, like any other StatsD loss.
+//!
+//! Plain StatsD has no tags, so a `Policy::named` name becomes a metric path segment
+//! (`ninelives.checkout.retry.attempt`). With [`StatsdSink::with_datadog_tags`] the path stays
+//! fixed and the name, instance id, and `EnrichSink` attributes are sent as DogStatsD tags.
+//!
+//! ```rust,no_run
+//! use ninelives_statsd::StatsdSink;
+//! # fn demo() -> std::io::Result<()> {
+//! let sink = StatsdSink::new("127.0.0.1:8125")?.with_prefix("checkout").with_datadog_tags();
+//! // attach with .with_sink(sink) on your policies
+//! # Ok(()) }
+//! ```
+
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::fmt::Write as _;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Sink sending policy events to a StatsD agent over UDP.
+#[derive(Clone, Debug)]
+pub struct StatsdSink {
+    socket: Arc<UdpSocket>,
+    prefix: Arc<str>,
+    datadog: bool,
+}
+
+impl StatsdSink {
+    /// Sink sending to the StatsD agent at `agent`, with the metric prefix `ninelives`.
+    ///
+    /// Sends go to the first address `agent` resolves to, from a socket of the same family.
+    pub fn new(agent: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let agent = agent.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "StatsD agent has no address")
+        })?;
+        let local: SocketAddr = if agent.is_ipv6() {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(agent)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket: Arc::new(socket), prefix: Arc::from("ninelives"), datadog: false })
+    }
+
+    /// Prefix every metric path with `prefix` instead of `ninelives`.
+    pub fn with_prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        self.prefix = Arc::from(sanitize(prefix.as_ref()));
+        self
+    }
+
+    /// Send the policy name, instance id, and context attributes as DogStatsD tags.
+    pub fn with_datadog_tags(mut self) -> Self {
+        self.datadog = true;
+        self
+    }
+
+    fn lines(&self, event: &PolicyEvent) -> Vec<String> {
+        let ctx = ninelives::RequestContext::current();
+        let mut path = self.prefix.to_string();
+        let mut tags = String::new();
+        if self.datadog {
+            let mut tag = |key: &str, value: &str| {
+                let sep = if tags.is_empty() { "|#" } else { "," };
+                let _ = write!(tags, "{sep}{}:{}", sanitize(key), sanitize(value));
+            };
+            if let Some(name) = ctx.policy_name() {
+                tag("policy", name);
+            }
+            if let Some(instance) = ctx.policy_instance() {
+                tag("instance", &instance.to_string());
+            }
+            for (key, value) in ctx.attributes() {
+                tag(key, value);
+            }
+        } else if let Some(name) = ctx.policy_name() {
+            path.push('.');
+            path.push_str(&sanitize(name));
+        }
+        let _ = write!(path, ".{}", event.layer_kind());
+
+        let mut lines = vec![format!("{path}.{}:1|c{tags}", event.event_name())];
+        if let Some((metric, duration)) = timing(event) {
+            lines.push(format!("{path}.{metric}:{}|ms{tags}", duration.as_secs_f64() * 1000.0));
+        }
+        lines
+    }
+}
+
+/// The duration an event reports, if any, with the timer name it is sent under.
+fn timing(event: &PolicyEvent) -> Option<(&'static str, Duration)> {
+    use ninelives::telemetry::{
+        CircuitBreakerEvent, FallbackEvent, RequestOutcome, RetryEvent, TimeoutEvent,
+    };
+
+    match *event {
+        PolicyEvent::Request(
+            RequestOutcome::Success { duration } | RequestOutcome::Failure { duration },
+        ) => Some(("duration", duration)),
+        PolicyEvent::Retry(RetryEvent::Attempt { delay, .. }) => Some(("delay", delay)),
+        PolicyEvent::Retry(RetryEvent::Exhausted { total_duration, .. }) => {
+            Some(("total_duration", total_duration))
+        }
+        PolicyEvent::CircuitBreaker(
+            CircuitBreakerEvent::HalfOpen { open_duration }
+            | CircuitBreakerEvent::Closed { open_duration },
+        ) => Some(("open_duration", open_duration)),
+        PolicyEvent::Timeout(
+            TimeoutEvent::Approaching { timeout, .. } | TimeoutEvent::Occurred { timeout },
+        ) => Some(("threshold", timeout)),
+        PolicyEvent::Fallback(
+            FallbackEvent::Served { duration, .. } | FallbackEvent::Exhausted { duration, .. },
+        ) => Some(("duration", duration)),
+        _ => None,
+    }
+}
+
+/// Replace characters that delimit StatsD fields or DogStatsD tags.
+fn sanitize(raw: &str) -> String {
+    raw.chars()
+        .map(|c| if matches!(c, ':' | '|' | '@' | '#' | ',' | ' ') { '_' } else { c })
+        .collect()
+}
+
+impl tower_service::Service<PolicyEvent> for StatsdSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        // Lines go out while the caller's context (name, attributes) is still in scope.
+        for line in self.lines(&event) {
+            let _ = self.socket.send(line.as_bytes());
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for StatsdSink {
+    type SinkError = Infallible;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ninelives::telemetry::{BulkheadEvent, RequestOutcome};
+    use ninelives::RequestContext;
+
+    fn sink() -> StatsdSink {
+        StatsdSink::new("127.0.0.1:8125").unwrap()
+    }
+
+    #[test]
+    fn counts_every_event_and_times_durations() {
+        let success =
+            PolicyEvent::Request(RequestOutcome::Success { duration: Duration::from_micros(1500) });
+        assert_eq!(
+            sink().lines(&success),
+            ["ninelives.request.success:1|c", "ninelives.request.duration:1.5|ms"]
+        );
+        let closed = PolicyEvent::Bulkhead(BulkheadEvent::Closed);
+        assert_eq!(sink().lines(&closed), ["ninelives.bulkhead.closed:1|c"]);
+    }
+
+    #[test]
+    fn policy_name_is_a_path_segment_without_tags() {
+        let lines = RequestContext::new().with_policy_name("check out|v2").sync_scope(|| {
+            sink().with_prefix("svc:a").lines(&PolicyEvent::Bulkhead(BulkheadEvent::Closed))
+        });
+        assert_eq!(lines, ["svc_a.check_out_v2.bulkhead.closed:1|c"]);
+    }
+
+    #[test]
+    fn datadog_tags_escape_delimiters() {
+        let lines = RequestContext::new()
+            .with_policy_name("checkout")
+            .with_policy_instance(3)
+            .with_attribute("region#1", "us east,1")
+            .sync_scope(|| {
+                sink().with_datadog_tags().lines(&PolicyEvent::Bulkhead(BulkheadEvent::Closed))
+            });
+        assert_eq!(
+            lines,
+            ["ninelives.bulkhead.closed:1|c|#policy:checkout,instance:3,region_1:us_east_1"]
+        );
+    }
+
+    #[test]
+    fn sanitize_replaces_statsd_delimiters() {
+        assert_eq!(sanitize("a:b|c@d#e,f g.h-i"), "a_b_c_d_e_f_g.h-i");
+    }
+
+    #[test]
+    fn binds_a_socket_of_the_agent_address_family() {
+        let v4 = StatsdSink::new("127.0.0.1:8125").unwrap();
+        assert!(v4.socket.local_addr().unwrap().is_ipv4());
+        // Hosts without IPv6 cannot bind `[::]`; nothing to check there.
+        if let Ok(v6) = StatsdSink::new("[::1]:8125") {
+            assert!(v6.socket.local_addr().unwrap().is_ipv6());
+        }
+    }
+}
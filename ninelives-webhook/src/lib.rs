@@ -0,0 +1,457 @@
+//! Generic HTTP webhook sink for `ninelives`.
+//!
+//! Events are buffered and POSTed as one JSON document per batch:
+//!
+//! ```text
+//! {"events":[{"schema_version":1,"policy_name":"checkout","policy":"retry",...},...]}
+//! ```
+//!
+//! A batch is sent once `batch_size` events are pending or `flush_interval` after the first of
+//! them arrived. Batches are sent one at a time by a single background Tokio task: `call` only
+//! buffers the event, so a slow or failing receiver never holds up the policy that emitted it.
+//! Up to [`WebhookSink::with_queue_size`] batches wait for that task; further batches are
+//! dropped. Failed deliveries are retried with the crate's own [`RetryPolicy`]: transport
+//! errors, `429`, and `5xx` are retried, other statuses are not. Events in batches that were
+//! dropped or still fail are counted in [`WebhookSink::failures`].
+//!
+//! # Signing
+//!
+//! With [`WebhookSink::with_hmac_secret`] every request carries
+//!
+//! - `x-ninelives-timestamp`: seconds since the Unix epoch, and
+//! - `x-ninelives-signature`: `v1=<hex HMAC-SHA256 of "<timestamp>.<body>">`.
+//!
+//! Receivers recompute the HMAC over the raw body, compare in constant time, and reject old
+//! timestamps to stop replays.
+//!
+//! Default build is a no-op; enable the `client` feature to send.
+//!
+//! ```rust,no_run
+//! use ninelives_webhook::WebhookSink;
+//! let sink = WebhookSink::new("https://hooks.internal.example/resilience")
+//!     .with_bearer_token("s3cret-token")
+//!     .with_hmac_secret("shared-signing-key")
+//!     .with_batch_size(50);
+//! // attach with .with_sink(sink.clone()); call sink.flush().await on shutdown
+//! ```
+
+#[cfg(feature = "client")]
+use ninelives::telemetry::EventEnvelope;
+use ninelives::telemetry::{PolicyEvent, TelemetrySink};
+use ninelives::{Backoff, Jitter, RetryPolicy};
+use std::convert::Infallible;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Why a webhook delivery failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeliveryError {
+    /// The receiver answered with a non-success status.
+    Status(u16),
+    /// The request did not complete (connection, TLS, timeout).
+    Transport(String),
+}
+
+impl DeliveryError {
+    /// Whether retrying could help: transport errors, `429`, and `5xx`.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DeliveryError::Status(status) => *status == 429 || *status >= 500,
+            DeliveryError::Transport(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeliveryError::Status(status) => write!(f, "webhook answered {status}"),
+            DeliveryError::Transport(reason) => write!(f, "webhook request failed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DeliveryError {}
+
+/// Request body for a batch of serialized envelopes.
+pub fn batch_body(events: &[String]) -> String {
+    format!("{{\"events\":[{}]}}", events.join(","))
+}
+
+/// The string signed for `x-ninelives-signature`: `<timestamp>.<body>`.
+pub fn signing_payload(timestamp: u64, body: &str) -> String {
+    format!("{timestamp}.{body}")
+}
+
+/// Sink POSTing batches of policy events to an HTTP endpoint.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub struct WebhookSink {
+    url: Arc<str>,
+    headers: Arc<[(String, String)]>,
+    hmac_secret: Option<Arc<[u8]>>,
+    retry: RetryPolicy<DeliveryError>,
+    batch_size: usize,
+    flush_interval: Duration,
+    queue_size: usize,
+    pending: Arc<Mutex<Pending>>,
+    failures: Arc<AtomicU64>,
+    #[cfg(feature = "client")]
+    queue: Arc<std::sync::OnceLock<tokio::sync::mpsc::Sender<Job>>>,
+    #[cfg(feature = "client")]
+    client: reqwest::Client,
+}
+
+/// Buffered events; `generation` changes whenever the buffer is taken, so a flush timer armed
+/// for an earlier batch can tell that its batch is already gone.
+#[derive(Default)]
+struct Pending {
+    events: Vec<String>,
+    generation: u64,
+}
+
+impl Pending {
+    fn take(&mut self) -> Vec<String> {
+        self.generation = self.generation.wrapping_add(1);
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Work for the delivery task, handled in order.
+#[cfg(feature = "client")]
+enum Job {
+    Batch(Vec<String>),
+    /// Answered once every batch queued before it has been delivered or given up on.
+    Flush(tokio::sync::oneshot::Sender<()>),
+}
+
+impl fmt::Debug for WebhookSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookSink")
+            .field("url", &self.url)
+            .field("signed", &self.hmac_secret.is_some())
+            .field("retry", &self.retry)
+            .field("batch_size", &self.batch_size)
+            .field("flush_interval", &self.flush_interval)
+            .field("queue_size", &self.queue_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebhookSink {
+    /// Sink posting to `url` in batches of 100 events or every second.
+    ///
+    /// Deliveries are tried up to 4 times with full-jitter exponential backoff from 200ms.
+    pub fn new(url: impl Into<String>) -> Self {
+        let retry = RetryPolicy::builder()
+            .max_attempts(4)
+            .backoff(Backoff::exponential(Duration::from_millis(200)))
+            .with_jitter(Jitter::full())
+            .should_retry(DeliveryError::is_retryable)
+            .build()
+            .expect("attempts are non-zero");
+        Self {
+            url: Arc::from(url.into()),
+            headers: Arc::from(Vec::new()),
+            hmac_secret: None,
+            retry,
+            batch_size: 100,
+            flush_interval: Duration::from_secs(1),
+            queue_size: 16,
+            pending: Arc::new(Mutex::new(Pending::default())),
+            failures: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "client")]
+            queue: Arc::default(),
+            #[cfg(feature = "client")]
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Add a header to every request, e.g. an API key.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let mut headers = self.headers.to_vec();
+        headers.push((name.into(), value.into()));
+        self.headers = Arc::from(headers);
+        self
+    }
+
+    /// Send `Authorization: Bearer <token>`.
+    pub fn with_bearer_token(self, token: impl fmt::Display) -> Self {
+        self.with_header("authorization", format!("Bearer {token}"))
+    }
+
+    /// Sign every request with HMAC-SHA256 under `secret`.
+    pub fn with_hmac_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.hmac_secret = Some(Arc::from(secret.as_ref()));
+        self
+    }
+
+    /// Retry failed deliveries with `retry` instead of the default policy.
+    pub fn with_retry(mut self, retry: RetryPolicy<DeliveryError>) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send once `batch_size` events are buffered (minimum 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Send buffered events at most `interval` after the first of them arrived.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Let at most `batches` full batches wait for delivery (default 16, minimum 1); batches
+    /// beyond that are dropped and counted in [`failures`](Self::failures).
+    pub fn with_queue_size(mut self, batches: usize) -> Self {
+        self.queue_size = batches.max(1);
+        self
+    }
+
+    /// Events in batches that were dropped or could not be delivered.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Send any buffered events now and wait for deliveries already queued, e.g. before
+    /// shutdown.
+    pub async fn flush(&self) {
+        let batch = self.pending.lock().unwrap_or_else(PoisonError::into_inner).take();
+        #[cfg(feature = "client")]
+        {
+            let queue = self.queue().clone();
+            let count = batch.len() as u64;
+            if !batch.is_empty() && queue.send(Job::Batch(batch)).await.is_err() {
+                self.failures.fetch_add(count, Ordering::Relaxed);
+            }
+            let (done, delivered) = tokio::sync::oneshot::channel();
+            if queue.send(Job::Flush(done)).await.is_ok() {
+                let _ = delivered.await;
+            }
+        }
+        #[cfg(not(feature = "client"))]
+        let _ = batch;
+    }
+
+    /// The delivery queue, starting its task on first use.
+    #[cfg(feature = "client")]
+    fn queue(&self) -> &tokio::sync::mpsc::Sender<Job> {
+        self.queue.get_or_init(|| {
+            let (queue, jobs) = tokio::sync::mpsc::channel(self.queue_size);
+            tokio::spawn(self.deliverer().run(jobs));
+            queue
+        })
+    }
+
+    /// Hand `batch` to the delivery task, dropping it if the queue is full.
+    #[cfg(feature = "client")]
+    fn enqueue(&self, batch: Vec<String>) {
+        let count = batch.len() as u64;
+        if count > 0 && self.queue().try_send(Job::Batch(batch)).is_err() {
+            self.failures.fetch_add(count, Ordering::Relaxed);
+            tracing::warn!(url = %self.url, events = count, "webhook delivery queue is full; dropping batch");
+        }
+    }
+
+    /// After `flush_interval`, queue the batch that was started in `generation`, unless a full
+    /// batch or an explicit flush already took it.
+    #[cfg(feature = "client")]
+    fn arm_flush_timer(&self, generation: u64) {
+        let sink = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(sink.flush_interval).await;
+            let batch = {
+                let mut pending = sink.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                if pending.generation != generation {
+                    return;
+                }
+                pending.take()
+            };
+            sink.enqueue(batch);
+        });
+    }
+
+    /// What the delivery task needs; it holds no queue sender, so it ends with the last sink.
+    #[cfg(feature = "client")]
+    fn deliverer(&self) -> Deliverer {
+        Deliverer {
+            url: Arc::clone(&self.url),
+            headers: Arc::clone(&self.headers),
+            hmac_secret: self.hmac_secret.clone(),
+            retry: self.retry.clone(),
+            failures: Arc::clone(&self.failures),
+            client: self.client.clone(),
+        }
+    }
+}
+
+/// The delivery task: sends queued batches one at a time.
+#[cfg(feature = "client")]
+struct Deliverer {
+    url: Arc<str>,
+    headers: Arc<[(String, String)]>,
+    hmac_secret: Option<Arc<[u8]>>,
+    retry: RetryPolicy<DeliveryError>,
+    failures: Arc<AtomicU64>,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "client")]
+impl Deliverer {
+    async fn run(self, mut jobs: tokio::sync::mpsc::Receiver<Job>) {
+        while let Some(job) = jobs.recv().await {
+            match job {
+                Job::Batch(batch) => self.deliver(batch).await,
+                Job::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    }
+
+    async fn deliver(&self, batch: Vec<String>) {
+        let body = batch_body(&batch);
+        let result = self
+            .retry
+            .execute(|| {
+                let body = body.clone();
+                async move { self.post(body).await.map_err(ninelives::ResilienceError::Inner) }
+            })
+            .await;
+        if let Err(error) = result {
+            self.failures.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            tracing::warn!(url = %self.url, %error, "failed to deliver policy events to webhook");
+        }
+    }
+
+    async fn post(&self, body: String) -> Result<(), DeliveryError> {
+        let mut request =
+            self.client.post(&*self.url).header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in self.headers.iter() {
+            request = request.header(name, value);
+        }
+        if let Some(secret) = &self.hmac_secret {
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            request = request
+                .header("x-ninelives-timestamp", timestamp.to_string())
+                .header("x-ninelives-signature", signature(secret, timestamp, &body));
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| DeliveryError::Transport(error.to_string()))?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(DeliveryError::Status(response.status().as_u16()))
+        }
+    }
+}
+
+/// `v1=<hex HMAC-SHA256>` over [`signing_payload`].
+#[cfg(feature = "client")]
+fn signature(secret: &[u8], timestamp: u64, body: &str) -> String {
+    use hmac::{Hmac, Mac};
+    use std::fmt::Write as _;
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret).expect("HMAC accepts any key");
+    mac.update(signing_payload(timestamp, body).as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let mut signature = String::from("v1=");
+    for byte in digest {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+impl tower_service::Service<PolicyEvent> for WebhookSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        #[cfg(feature = "client")]
+        let fut = {
+            let json = EventEnvelope::capture(event).to_json();
+            let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+            pending.events.push(json);
+            if pending.events.len() >= self.batch_size {
+                let batch = pending.take();
+                drop(pending);
+                self.enqueue(batch);
+            } else if pending.events.len() == 1 {
+                let generation = pending.generation;
+                drop(pending);
+                self.arm_flush_timer(generation);
+            }
+            Box::pin(async move { Ok(()) })
+        };
+
+        #[cfg(not(feature = "client"))]
+        let fut = {
+            let _ = event;
+            Box::pin(async move { Ok(()) })
+        };
+
+        fut
+    }
+}
+
+impl TelemetrySink for WebhookSink {
+    type SinkError = Infallible;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_body_wraps_envelopes_in_an_events_array() {
+        assert_eq!(batch_body(&[]), r#"{"events":[]}"#);
+        let events = [r#"{"a":1}"#.to_string(), r#"{"b":2}"#.to_string()];
+        assert_eq!(batch_body(&events), r#"{"events":[{"a":1},{"b":2}]}"#);
+    }
+
+    #[test]
+    fn signing_payload_joins_timestamp_and_body() {
+        assert_eq!(
+            signing_payload(1_700_000_000, r#"{"events":[]}"#),
+            r#"1700000000.{"events":[]}"#
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn signature_is_hex_hmac_sha256_of_the_payload() {
+        assert_eq!(
+            signature(b"shared-signing-key", 1_700_000_000, r#"{"events":[]}"#),
+            "v1=733a1b5238682c8aee593cdcd8d09bde71efe43c88fe103bdd9d793fa6b7b736"
+        );
+    }
+
+    #[test]
+    fn only_throttling_server_errors_and_transport_failures_are_retried() {
+        for status in [429, 500, 502, 503, 599] {
+            assert!(DeliveryError::Status(status).is_retryable(), "{status}");
+        }
+        for status in [400, 401, 403, 404, 410, 422] {
+            assert!(!DeliveryError::Status(status).is_retryable(), "{status}");
+        }
+        assert!(DeliveryError::Transport("connection reset".into()).is_retryable());
+    }
+}
@@ -0,0 +1,137 @@
+//! WebSocket live-streaming sink for `ninelives`.
+//!
+//! [`WebSocketSink`] works like `StreamingSink`, broadcasting every event to all subscribers
+//! over a bounded channel, but it broadcasts the JSON `EventEnvelope` captured at emit time.
+//! Connection tasks run outside the request, so capturing up front is what keeps the
+//! `Policy::named` name, instance, and attributes on each event.
+//!
+//! With the `server` feature the sink serves the stream to WebSocket clients (a browser
+//! dev-tools panel, a TUI, `websocat`), one text frame per event:
+//!
+//! ```text
+//! {"schema_version":1,"policy_name":"checkout","instance_id":3,"event":{"policy":"retry",...}}
+//! ```
+//!
+//! A client that falls behind the channel capacity skips ahead and receives
+//! `{"missed":<n>}` so it can show the gap. Nothing is serialized while no client is connected.
+//!
+//! ```rust
+//! use ninelives_websocket::WebSocketSink;
+//! let sink = WebSocketSink::new(1024);
+//! let mut rx = sink.subscribe();
+//! // attach with .with_sink(sink.clone());
+//! // with `server`: tokio::spawn(async move { sink.serve(([127, 0, 0, 1], 9090).into()).await });
+//! # let _ = rx.try_recv();
+//! ```
+
+use ninelives::telemetry::{EventEnvelope, PolicyEvent, TelemetrySink};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::broadcast;
+
+/// Sink broadcasting serialized event envelopes to WebSocket clients.
+#[derive(Clone, Debug)]
+pub struct WebSocketSink {
+    sender: broadcast::Sender<Arc<str>>,
+    sent: Arc<AtomicU64>,
+}
+
+impl WebSocketSink {
+    /// Sink buffering up to `capacity` events per subscriber (minimum 1).
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender, sent: Arc::new(AtomicU64::new(0)) }
+    }
+
+    /// Subscribe to the JSON envelopes of future events.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<str>> {
+        self.sender.subscribe()
+    }
+
+    /// Connected clients and other subscribers.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Events broadcast to at least one subscriber.
+    pub fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "server")]
+impl WebSocketSink {
+    /// Router upgrading `GET /` to a WebSocket streaming events; nest it under any path.
+    pub fn router(&self) -> axum::Router {
+        axum::Router::new().route("/", axum::routing::get(upgrade)).with_state(self.clone())
+    }
+
+    /// Serve the stream on `addr` until the server fails.
+    pub async fn serve(&self, addr: std::net::SocketAddr) -> Result<(), axum::Error> {
+        axum::Server::try_bind(&addr)
+            .map_err(axum::Error::new)?
+            .serve(self.router().into_make_service())
+            .await
+            .map_err(axum::Error::new)
+    }
+}
+
+#[cfg(feature = "server")]
+async fn upgrade(
+    ws: axum::extract::ws::WebSocketUpgrade,
+    axum::extract::State(sink): axum::extract::State<WebSocketSink>,
+) -> axum::response::Response {
+    ws.on_upgrade(move |socket| stream(socket, sink.subscribe()))
+}
+
+/// Forward events to one client until it disconnects or the sink is dropped.
+#[cfg(feature = "server")]
+async fn stream(mut socket: axum::extract::ws::WebSocket, mut rx: broadcast::Receiver<Arc<str>>) {
+    use axum::extract::ws::Message;
+    use broadcast::error::RecvError;
+
+    loop {
+        let frame = tokio::select! {
+            received = rx.recv() => match received {
+                Ok(json) => json.to_string(),
+                Err(RecvError::Lagged(missed)) => format!("{{\"missed\":{missed}}}"),
+                Err(RecvError::Closed) => break,
+            },
+            // Clients only talk to close; pings are answered by the socket itself.
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        if socket.send(Message::Text(frame)).await.is_err() {
+            break;
+        }
+    }
+}
+
+impl tower_service::Service<PolicyEvent> for WebSocketSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        if self.sender.receiver_count() > 0 {
+            let json: Arc<str> = Arc::from(EventEnvelope::capture(event).to_json());
+            if self.sender.send(json).is_ok() {
+                self.sent.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for WebSocketSink {
+    type SinkError = Infallible;
+}
@@ -0,0 +1,65 @@
+//! Shared, live-tunable configuration values.
+//!
+//! Semantics
+//! - [`Adaptive<T>`] is a cloneable handle to a value behind a lock. Every clone observes the
+//!   same value, so a layer can hold one handle while operators or a control loop adjust another.
+//! - Reads clone the current value; layers read once per request, so a change takes effect on the
+//!   next request without rebuilding the stack.
+//! - A panic while holding the lock does not poison the handle; the last written value is kept.
+//!
+//! Example
+//! ```
+//! use ninelives::Adaptive;
+//!
+//! let weight = Adaptive::new(0.05_f64);
+//! let handle = weight.clone();
+//! handle.set(0.25);
+//! assert_eq!(weight.get(), 0.25);
+//! weight.update(|w| *w = (*w * 2.0).min(1.0));
+//! assert_eq!(handle.get(), 0.5);
+//! ```
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Cloneable handle to a value that can be changed at runtime.
+#[derive(Debug, Default)]
+pub struct Adaptive<T> {
+    value: Arc<RwLock<T>>,
+}
+
+impl<T> Clone for Adaptive<T> {
+    fn clone(&self) -> Self {
+        Self { value: Arc::clone(&self.value) }
+    }
+}
+
+impl<T> Adaptive<T> {
+    /// Wrap an initial value.
+    pub fn new(value: T) -> Self {
+        Self { value: Arc::new(RwLock::new(value)) }
+    }
+
+    /// Replace the current value.
+    pub fn set(&self, value: T) {
+        *self.value.write().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+
+    /// Modify the current value in place.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.write().unwrap_or_else(PoisonError::into_inner));
+    }
+}
+
+impl<T: Clone> Adaptive<T> {
+    /// Snapshot of the current value.
+    #[must_use]
+    pub fn get(&self) -> T {
+        self.value.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+impl<T> From<T> for Adaptive<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
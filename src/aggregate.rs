@@ -0,0 +1,287 @@
+//! In-process aggregation of the telemetry stream.
+//!
+//! [`AggregatingSink`] keeps running totals instead of shipping events anywhere, which gives a
+//! service basic observability (a `/debug` page, a periodic log line, a test assertion) without
+//! an external metrics backend.
+//!
+//! Semantics
+//! - Every event increments a counter keyed by its
+//!   [`layer_kind`](PolicyEvent::layer_kind) and [`event_name`](PolicyEvent::event_name).
+//! - [`RequestOutcome`] durations are recorded in two [`LatencyHistogram`]s, one for successes
+//!   and one for failures.
+//! - [`AggregatingSink::snapshot`] copies the current totals; [`AggregatingSink::reset`] clears
+//!   them, for callers that report per interval.
+//!
+//! Invariants
+//! - Clones share the same totals.
+//! - Histogram quantiles are within about 3% of the recorded value (log-linear buckets with 32
+//!   sub-buckets per power of two, microsecond resolution), and never below it.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let stats = AggregatingSink::new();
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(TimeoutLayer::new(Duration::from_secs(1))?.with_sink(stats.clone()))
+//!     .service_fn(|n: u32| async move { Ok::<_, std::io::Error>(n) });
+//! svc.ready().await?.call(7).await?;
+//!
+//! let snapshot = stats.snapshot();
+//! assert_eq!(snapshot.count("request", "success"), 1);
+//! assert_eq!(snapshot.success_latency.count(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{PolicyEvent, RequestOutcome, TelemetrySink};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower_service::Service;
+
+/// Sub-buckets per power of two; bounds the relative error at `1 / SUB_BUCKETS`.
+const SUB_BUCKETS: u64 = 32;
+
+/// Latency distribution with logarithmic buckets, in the style of an HDR histogram.
+///
+/// Values below 64µs are kept exactly; larger values share a bucket with others within about 3%.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum_micros: u128,
+    min_micros: u64,
+    max_micros: u64,
+}
+
+impl LatencyHistogram {
+    /// Empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one observation.
+    pub fn record(&mut self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let index = bucket_index(micros);
+        if self.buckets.len() <= index {
+            self.buckets.resize(index + 1, 0);
+        }
+        self.buckets[index] += 1;
+        self.min_micros = if self.count == 0 { micros } else { self.min_micros.min(micros) };
+        self.max_micros = self.max_micros.max(micros);
+        self.count += 1;
+        self.sum_micros += u128::from(micros);
+    }
+
+    /// Number of recorded observations.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Smallest observation, or `None` if empty.
+    pub fn min(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_micros(self.min_micros))
+    }
+
+    /// Largest observation, or `None` if empty.
+    pub fn max(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_micros(self.max_micros))
+    }
+
+    /// Arithmetic mean, or `None` if empty.
+    pub fn mean(&self) -> Option<Duration> {
+        let mean = self.sum_micros.checked_div(u128::from(self.count))?;
+        Some(Duration::from_micros(u64::try_from(mean).unwrap_or(u64::MAX)))
+    }
+
+    /// Value at quantile `q` (clamped to `0.0..=1.0`), or `None` if empty.
+    ///
+    /// Reported as the upper edge of the bucket holding that rank, capped at [`max`](Self::max).
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let q = if q.is_nan() { 0.0 } else { q.clamp(0.0, 1.0) };
+        let rank = ((q * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return Some(Duration::from_micros(bucket_upper(index).min(self.max_micros)));
+            }
+        }
+        self.max()
+    }
+}
+
+fn bucket_index(micros: u64) -> usize {
+    if micros < 2 * SUB_BUCKETS {
+        return micros as usize;
+    }
+    let shift = u64::from(63 - micros.leading_zeros()) - SUB_BUCKETS.trailing_zeros() as u64;
+    (2 * SUB_BUCKETS + (shift - 1) * SUB_BUCKETS + ((micros >> shift) - SUB_BUCKETS)) as usize
+}
+
+fn bucket_upper(index: usize) -> u64 {
+    let index = index as u64;
+    if index < 2 * SUB_BUCKETS {
+        return index;
+    }
+    let offset = index - 2 * SUB_BUCKETS;
+    let shift = offset / SUB_BUCKETS + 1;
+    let sub = offset % SUB_BUCKETS + SUB_BUCKETS;
+    u64::try_from(u128::from(sub + 1) << shift).map_or(u64::MAX, |end| end - 1)
+}
+
+/// Totals captured by [`AggregatingSink::snapshot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AggregateSnapshot {
+    /// Events seen, keyed by `(layer_kind, event_name)`.
+    pub counts: BTreeMap<(&'static str, &'static str), u64>,
+    /// Durations of successful requests.
+    pub success_latency: LatencyHistogram,
+    /// Durations of failed requests.
+    pub failure_latency: LatencyHistogram,
+}
+
+impl AggregateSnapshot {
+    /// Count of one event, such as `("retry", "attempt")`; zero if never seen.
+    pub fn count(&self, layer_kind: &str, event_name: &str) -> u64 {
+        self.counts.get(&(layer_kind, event_name)).copied().unwrap_or(0)
+    }
+
+    /// Count of every event from one kind of layer.
+    pub fn total(&self, layer_kind: &str) -> u64 {
+        self.counts.iter().filter(|((kind, _), _)| *kind == layer_kind).map(|(_, n)| n).sum()
+    }
+}
+
+/// Sink that aggregates events into counters and latency histograms in memory.
+#[derive(Debug, Clone, Default)]
+pub struct AggregatingSink {
+    totals: Arc<Mutex<AggregateSnapshot>>,
+}
+
+impl AggregatingSink {
+    /// Sink with empty totals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy of the current totals.
+    pub fn snapshot(&self) -> AggregateSnapshot {
+        self.totals.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Clear all totals.
+    pub fn reset(&self) {
+        *self.totals.lock().unwrap_or_else(PoisonError::into_inner) = AggregateSnapshot::default();
+    }
+
+    fn record(&self, event: &PolicyEvent) {
+        let mut totals = self.totals.lock().unwrap_or_else(PoisonError::into_inner);
+        *totals.counts.entry((event.layer_kind(), event.event_name())).or_insert(0) += 1;
+        match event {
+            PolicyEvent::Request(RequestOutcome::Success { duration }) => {
+                totals.success_latency.record(*duration)
+            }
+            PolicyEvent::Request(RequestOutcome::Failure { duration }) => {
+                totals.failure_latency.record(*duration)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Service<PolicyEvent> for AggregatingSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Ready<Result<(), Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        self.record(&event);
+        ready(Ok(()))
+    }
+}
+
+impl TelemetrySink for AggregatingSink {
+    type SinkError = Infallible;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::RetryEvent;
+    use tower::ServiceExt;
+
+    #[test]
+    fn buckets_are_contiguous_and_bound_the_error() {
+        let mut last = 0;
+        for micros in (0..5_000).chain([1 << 20, (1 << 40) + 12_345, u64::MAX]) {
+            let index = bucket_index(micros);
+            assert!(index == last || index == last + 1 || micros > 5_000, "gap at {}", micros);
+            last = index;
+            let upper = bucket_upper(index);
+            assert!(upper >= micros, "{} above its bucket {}", micros, upper);
+            assert!((upper - micros) as f64 <= micros as f64 / SUB_BUCKETS as f64);
+        }
+    }
+
+    #[test]
+    fn quantiles_track_recorded_latencies() {
+        let mut histogram = LatencyHistogram::new();
+        assert_eq!(histogram.quantile(0.5), None);
+        for ms in 1..=100 {
+            histogram.record(Duration::from_millis(ms));
+        }
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.min(), Some(Duration::from_millis(1)));
+        assert_eq!(histogram.max(), Some(Duration::from_millis(100)));
+        assert_eq!(histogram.quantile(1.0), Some(Duration::from_millis(100)));
+        let p50 = histogram.quantile(0.5).unwrap();
+        assert!(
+            p50 >= Duration::from_millis(50) && p50 <= Duration::from_micros(51_600),
+            "{:?}",
+            p50
+        );
+        let p99 = histogram.quantile(0.99).unwrap();
+        assert!(p99 >= Duration::from_millis(99), "{:?}", p99);
+    }
+
+    #[tokio::test]
+    async fn counts_events_and_splits_outcome_latencies() {
+        let sink = AggregatingSink::new();
+        let events = [
+            PolicyEvent::Retry(RetryEvent::Attempt { attempt: 1, delay: Duration::ZERO }),
+            PolicyEvent::Retry(RetryEvent::Attempt { attempt: 2, delay: Duration::ZERO }),
+            PolicyEvent::Request(RequestOutcome::Failure { duration: Duration::from_millis(30) }),
+            PolicyEvent::Request(RequestOutcome::Success { duration: Duration::from_millis(5) }),
+        ];
+        for event in events {
+            sink.clone().oneshot(event).await.unwrap();
+        }
+
+        let snapshot = sink.snapshot();
+        assert_eq!(snapshot.count("retry", "attempt"), 2);
+        assert_eq!(snapshot.total("request"), 2);
+        assert_eq!(snapshot.failure_latency.max(), Some(Duration::from_millis(30)));
+        assert_eq!(snapshot.success_latency.mean(), Some(Duration::from_millis(5)));
+
+        sink.reset();
+        assert_eq!(sink.snapshot(), AggregateSnapshot::default());
+    }
+}
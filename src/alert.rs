@@ -0,0 +1,427 @@
+//! Anomaly detection over the telemetry stream.
+//!
+//! Semantics
+//! - [`StormDetector`] wraps a telemetry sink. Every event is forwarded unchanged; in addition
+//!   the detector watches for two anomalies and emits a synthetic [`PolicyEvent::Alert`] into
+//!   the same sink when one starts or ends:
+//!   - **Retry storm**: retry attempts ([`RetryEvent::Attempt`]) exceed a fraction of requests
+//!     ([`PolicyEvent::Request`] outcomes) over a sliding window, once the window has seen a
+//!     minimum number of requests.
+//!   - **Breaker flapping**: a circuit breaker opened at least N times within a window.
+//! - An alert fires once when its condition becomes true and resolves once when it stops being
+//!   true, so a sustained storm pages once rather than on every event.
+//! - Conditions are only evaluated when events arrive; a stream that goes silent keeps its
+//!   last alert state.
+//!
+//! Invariants
+//! - Clones share detection state, so every policy reporting into a detector contributes to
+//!   the same windows.
+//! - When several layers in one stack report request outcomes, each request is counted once per
+//!   layer, which makes the retry ratio conservative.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Page when retries exceed 20% of traffic over 30s, or a breaker opens 3 times in 5 minutes.
+//! let sink = StormDetector::new(LogSink)
+//!     .retry_ratio(0.2, Duration::from_secs(30))?
+//!     .min_requests(50)
+//!     .breaker_flaps(3, Duration::from_secs(300))?;
+//! let retry = RetryPolicy::<std::io::Error>::builder().build()?.into_layer().with_sink(sink);
+//! # let _ = retry;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{AlertEvent, AlertKind, CircuitBreakerEvent, PolicyEvent, RetryEvent};
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::ServiceExt;
+use tower_service::Service;
+
+/// Sliding windows are tracked in this many buckets.
+const BUCKETS: u32 = 10;
+
+/// Errors produced while configuring a [`StormDetector`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertRuleError {
+    /// The retry ratio was not a finite number greater than zero.
+    InvalidRatio {
+        /// The rejected ratio.
+        provided: f64,
+    },
+    /// The flap threshold was zero.
+    ZeroFlaps,
+    /// A window was zero.
+    ZeroWindow,
+}
+
+impl fmt::Display for AlertRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRatio { provided } => {
+                write!(f, "retry ratio must be finite and > 0, got {}", provided)
+            }
+            Self::ZeroFlaps => write!(f, "breaker flap threshold must be > 0"),
+            Self::ZeroWindow => write!(f, "alert window must be > 0"),
+        }
+    }
+}
+
+impl std::error::Error for AlertRuleError {}
+
+/// Telemetry sink wrapper that turns retry storms and breaker flapping into alerts.
+pub struct StormDetector<S> {
+    inner: S,
+    state: Arc<Mutex<Detector>>,
+}
+
+impl<S> StormDetector<S> {
+    /// Watch the events flowing into `inner`.
+    ///
+    /// Defaults: retries above 50% of at least 20 requests over 30s, or 3 breaker opens within
+    /// 60s.
+    pub fn new(inner: S) -> Self {
+        let retries = RetryRule {
+            ratio: 0.5,
+            min_requests: 20,
+            window: Window::new(Duration::from_secs(30)),
+            firing: false,
+        };
+        let flaps = FlapRule {
+            opens: 3,
+            window: Duration::from_secs(60),
+            seen: VecDeque::new(),
+            firing: false,
+        };
+        Self { inner, state: Arc::new(Mutex::new(Detector { retries, flaps })) }
+    }
+
+    /// Alert when retry attempts exceed `ratio` × requests over `window`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AlertRuleError`] if `ratio` is not positive and finite or `window` is zero.
+    pub fn retry_ratio(self, ratio: f64, window: Duration) -> Result<Self, AlertRuleError> {
+        if !(ratio.is_finite() && ratio > 0.0) {
+            return Err(AlertRuleError::InvalidRatio { provided: ratio });
+        }
+        if window.is_zero() {
+            return Err(AlertRuleError::ZeroWindow);
+        }
+        {
+            let mut state = self.lock();
+            state.retries.ratio = ratio;
+            state.retries.window = Window::new(window);
+        }
+        Ok(self)
+    }
+
+    /// Ignore retry ratios until the window holds at least `requests` requests.
+    pub fn min_requests(self, requests: u64) -> Self {
+        self.lock().retries.min_requests = requests;
+        self
+    }
+
+    /// Alert when circuit breakers open at least `opens` times within `window`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AlertRuleError`] if `opens` or `window` is zero.
+    pub fn breaker_flaps(self, opens: usize, window: Duration) -> Result<Self, AlertRuleError> {
+        if opens == 0 {
+            return Err(AlertRuleError::ZeroFlaps);
+        }
+        if window.is_zero() {
+            return Err(AlertRuleError::ZeroWindow);
+        }
+        {
+            let mut state = self.lock();
+            state.flaps.opens = opens;
+            state.flaps.window = window;
+        }
+        Ok(self)
+    }
+
+    /// Alerts currently firing.
+    pub fn firing(&self) -> Vec<AlertKind> {
+        let state = self.lock();
+        let mut firing = Vec::new();
+        if state.retries.firing {
+            firing.push(AlertKind::RetryStorm);
+        }
+        if state.flaps.firing {
+            firing.push(AlertKind::BreakerFlapping);
+        }
+        firing
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Detector> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S: Clone> Clone for StormDetector<S> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), state: Arc::clone(&self.state) }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for StormDetector<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StormDetector").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<PolicyEvent> for StormDetector<S>
+where
+    S: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<(), S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let alerts = self.lock().observe(&event, Instant::now());
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            // Alerts are delivered even if forwarding the triggering event failed.
+            let forwarded = inner.clone().oneshot(event).await;
+            for alert in alerts {
+                inner.clone().oneshot(PolicyEvent::Alert(alert)).await?;
+            }
+            forwarded
+        })
+    }
+}
+
+impl<S> crate::telemetry::TelemetrySink for StormDetector<S>
+where
+    S: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type SinkError = S::Error;
+}
+
+struct Detector {
+    retries: RetryRule,
+    flaps: FlapRule,
+}
+
+struct RetryRule {
+    ratio: f64,
+    min_requests: u64,
+    window: Window,
+    firing: bool,
+}
+
+struct FlapRule {
+    opens: usize,
+    window: Duration,
+    seen: VecDeque<Instant>,
+    firing: bool,
+}
+
+impl Detector {
+    fn observe(&mut self, event: &PolicyEvent, now: Instant) -> Vec<AlertEvent> {
+        let mut alerts = Vec::new();
+        match event {
+            PolicyEvent::Retry(RetryEvent::Attempt { .. }) => {
+                self.retries.window.add(now, 1, 0);
+                self.check_retries(now, &mut alerts);
+            }
+            PolicyEvent::Request(_) => {
+                self.retries.window.add(now, 0, 1);
+                self.check_retries(now, &mut alerts);
+            }
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { .. }) => {
+                self.flaps.seen.push_back(now);
+                self.check_flaps(now, &mut alerts);
+            }
+            PolicyEvent::CircuitBreaker(_) => self.check_flaps(now, &mut alerts),
+            _ => {}
+        }
+        alerts
+    }
+
+    fn check_retries(&mut self, now: Instant, alerts: &mut Vec<AlertEvent>) {
+        let rule = &mut self.retries;
+        let (retries, requests) = rule.window.totals(now);
+        let storming =
+            requests >= rule.min_requests.max(1) && retries as f64 > rule.ratio * requests as f64;
+        if storming && !rule.firing {
+            alerts.push(AlertEvent::RetryStorm {
+                ratio: retries as f64 / requests as f64,
+                window: rule.window.span,
+            });
+        } else if !storming && rule.firing {
+            alerts.push(AlertEvent::Resolved { kind: AlertKind::RetryStorm });
+        }
+        rule.firing = storming;
+    }
+
+    fn check_flaps(&mut self, now: Instant, alerts: &mut Vec<AlertEvent>) {
+        let rule = &mut self.flaps;
+        while rule.seen.front().is_some_and(|t| now.duration_since(*t) >= rule.window) {
+            rule.seen.pop_front();
+        }
+        let flapping = rule.seen.len() >= rule.opens;
+        if flapping && !rule.firing {
+            alerts
+                .push(AlertEvent::BreakerFlapping { opens: rule.seen.len(), window: rule.window });
+        } else if !flapping && rule.firing {
+            alerts.push(AlertEvent::Resolved { kind: AlertKind::BreakerFlapping });
+        }
+        rule.firing = flapping;
+    }
+}
+
+/// Retry and request counts over a sliding window, kept in fixed-width buckets.
+struct Window {
+    span: Duration,
+    width: Duration,
+    /// `(bucket start, retries, requests)`, oldest first.
+    buckets: VecDeque<(Instant, u64, u64)>,
+}
+
+impl Window {
+    fn new(span: Duration) -> Self {
+        Self {
+            span,
+            width: (span / BUCKETS).max(Duration::from_millis(1)),
+            buckets: VecDeque::new(),
+        }
+    }
+
+    fn add(&mut self, now: Instant, retries: u64, requests: u64) {
+        match self.buckets.back_mut() {
+            Some((start, r, q)) if now.duration_since(*start) < self.width => {
+                *r += retries;
+                *q += requests;
+            }
+            _ => self.buckets.push_back((now, retries, requests)),
+        }
+    }
+
+    fn totals(&mut self, now: Instant) -> (u64, u64) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(start, _, _)| now.duration_since(*start) >= self.span)
+        {
+            self.buckets.pop_front();
+        }
+        self.buckets.iter().fold((0, 0), |(r, q), (_, br, bq)| (r + br, q + bq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{MemorySink, RequestOutcome};
+
+    fn attempt() -> PolicyEvent {
+        PolicyEvent::Retry(RetryEvent::Attempt { attempt: 1, delay: Duration::ZERO })
+    }
+
+    fn request() -> PolicyEvent {
+        PolicyEvent::Request(RequestOutcome::Failure { duration: Duration::ZERO })
+    }
+
+    fn alerts(sink: &MemorySink) -> Vec<AlertEvent> {
+        sink.events()
+            .into_iter()
+            .filter_map(|e| match e {
+                PolicyEvent::Alert(alert) => Some(alert),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_storm_fires_once_and_resolves() {
+        let sink = MemorySink::new();
+        let mut detector = StormDetector::new(sink.clone())
+            .retry_ratio(0.5, Duration::from_secs(10))
+            .unwrap()
+            .min_requests(4);
+
+        for _ in 0..4 {
+            detector.call(request()).await.unwrap();
+            detector.call(attempt()).await.unwrap();
+        }
+        detector.call(attempt()).await.unwrap();
+        assert_eq!(detector.firing(), vec![AlertKind::RetryStorm]);
+        assert_eq!(
+            alerts(&sink),
+            vec![AlertEvent::RetryStorm { ratio: 0.75, window: Duration::from_secs(10) }]
+        );
+
+        // Once the storm ages out of the window, healthy traffic resolves it.
+        tokio::time::advance(Duration::from_secs(11)).await;
+        for _ in 0..4 {
+            detector.call(request()).await.unwrap();
+        }
+        assert!(detector.firing().is_empty());
+        assert_eq!(
+            alerts(&sink).last(),
+            Some(&AlertEvent::Resolved { kind: AlertKind::RetryStorm })
+        );
+        assert_eq!(alerts(&sink).len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn breaker_flapping_needs_opens_within_the_window() {
+        let sink = MemorySink::new();
+        let mut detector =
+            StormDetector::new(sink.clone()).breaker_flaps(2, Duration::from_secs(60)).unwrap();
+        let opened = PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 5 });
+
+        detector.call(opened.clone()).await.unwrap();
+        tokio::time::advance(Duration::from_secs(61)).await;
+        detector.call(opened.clone()).await.unwrap();
+        assert!(alerts(&sink).is_empty());
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        detector.call(opened).await.unwrap();
+        assert_eq!(
+            alerts(&sink),
+            vec![AlertEvent::BreakerFlapping { opens: 2, window: Duration::from_secs(60) }]
+        );
+        // Every event is still forwarded.
+        assert_eq!(sink.events().len(), 4);
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let detector = || StormDetector::new(MemorySink::new());
+        assert!(matches!(
+            detector().retry_ratio(0.0, Duration::from_secs(1)),
+            Err(AlertRuleError::InvalidRatio { .. })
+        ));
+        assert!(matches!(
+            detector().retry_ratio(0.1, Duration::ZERO),
+            Err(AlertRuleError::ZeroWindow)
+        ));
+        assert!(matches!(
+            detector().breaker_flaps(0, Duration::from_secs(1)),
+            Err(AlertRuleError::ZeroFlaps)
+        ));
+    }
+}
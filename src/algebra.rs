@@ -15,6 +15,14 @@
 //!
 //! This means: `A | B + C & D` is parsed as `A | (B + (C & D))`.
 //!
+//! # Telemetry
+//!
+//! `|` and `&` stay silent unless given a sink: `(a | b).with_sink(sink)` reports which branch
+//! served each request as a [`FallbackEvent`](crate::telemetry::FallbackEvent), and
+//! `(a & b).with_sink(sink)` reports the winning branch as a
+//! [`ForkJoinEvent`](crate::telemetry::ForkJoinEvent). Each `|` branch also runs in a DEBUG
+//! `ninelives.fallback.branch` tracing span, numbered from 0 for the primary.
+//!
 //! **Example precedence:**
 //! ```text
 //! Policy(A) | Policy(B) + Policy(C)
@@ -84,10 +92,45 @@
 //! # }
 //! ```
 
+use crate::describe::{Describe, PolicyNode};
+use crate::span::in_span;
+use crate::telemetry::{emit_best_effort, FallbackEvent, ForkJoinEvent, NullSink, PolicyEvent};
 use futures::future::{select, Either};
 use std::ops::{Add, BitAnd, BitOr};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 use tower_layer::Layer;
 
+/// Errors returned when building n-ary combinators from a list of policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompositionError {
+    /// The combinator needs at least one policy.
+    Empty,
+    /// A quorum requires more successes than there are policies (or zero successes).
+    InvalidQuorum {
+        /// Successes requested.
+        required: usize,
+        /// Policies available.
+        available: usize,
+    },
+}
+
+impl std::fmt::Display for CompositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompositionError::Empty => write!(f, "combinator requires at least one policy"),
+            CompositionError::InvalidQuorum { required, available } => write!(
+                f,
+                "quorum of {} must be between 1 and the number of policies ({})",
+                required, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CompositionError {}
+
 /// Opt-in wrapper enabling algebraic composition of tower layers.
 ///
 /// The `Policy` wrapper allows layers to be combined using intuitive operators:
@@ -218,6 +261,12 @@ where
     }
 }
 
+impl<A: Describe, B: Describe> Describe for CombinedLayer<A, B> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::sequence(self.outer.describe(), self.inner.describe())
+    }
+}
+
 /// Fallback composition layer that tries `primary`, falling back to `secondary` on error.
 ///
 /// Created by the `|` operator on `Policy<L>` types:
@@ -260,35 +309,158 @@ where
 /// # Ok(())
 /// # }
 /// ```
+///
+/// # Conditional fallback
+///
+/// By default every primary failure is retried through the secondary. Use
+/// [`FallbackLayer::with_predicate`] to fall back only on failures that the secondary can
+/// plausibly fix; rejected errors are returned as [`FallbackError::Primary`].
+///
+/// ```
+/// use ninelives::prelude::*;
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let primary = Policy(TimeoutLayer::new(Duration::from_millis(100))?);
+/// let replica = Policy(TimeoutLayer::new(Duration::from_secs(2))?);
+/// // Only timeouts are worth another attempt; anything else is returned as-is.
+/// let _policy = (primary | replica)
+///     .with_predicate(|e: &ResilienceError<std::io::Error>| e.is_timeout());
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Clone, Debug)]
-pub struct FallbackLayer<A, B> {
+pub struct FallbackLayer<A, B, P = AlwaysFallback, Sink = NullSink> {
     /// The primary layer strategy (tried first)
     pub primary: A,
     /// The secondary layer strategy (fallback on primary failure)
     pub secondary: B,
+    /// Decides which primary errors are handed to the secondary
+    pub predicate: P,
+    /// Receives a [`FallbackEvent`] per request (branch 0 is the primary, 1 the secondary)
+    pub sink: Sink,
+}
+
+impl<A, B, Sink> FallbackLayer<A, B, AlwaysFallback, Sink> {
+    /// Only fall back when `predicate` returns `true` for the primary's error.
+    ///
+    /// When it returns `false` the secondary is not called and the request fails with
+    /// [`FallbackError::Primary`] carrying the primary's error unchanged.
+    pub fn with_predicate<F>(self, predicate: F) -> FallbackLayer<A, B, F, Sink> {
+        FallbackLayer {
+            primary: self.primary,
+            secondary: self.secondary,
+            predicate,
+            sink: self.sink,
+        }
+    }
+}
+
+impl<A, B, P, Sink> FallbackLayer<A, B, P, Sink> {
+    /// Attach a telemetry sink for [`FallbackEvent`]s.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> FallbackLayer<A, B, P, NewSink>
+    where
+        NewSink: Clone,
+    {
+        FallbackLayer {
+            primary: self.primary,
+            secondary: self.secondary,
+            predicate: self.predicate,
+            sink,
+        }
+    }
+}
+
+impl<A, B, Sink> Policy<FallbackLayer<A, B, AlwaysFallback, Sink>> {
+    /// Only fall back when `predicate` returns `true` for the primary's error.
+    ///
+    /// See [`FallbackLayer::with_predicate`].
+    pub fn with_predicate<F>(self, predicate: F) -> Policy<FallbackLayer<A, B, F, Sink>> {
+        Policy(self.0.with_predicate(predicate))
+    }
+}
+
+impl<A, B, P, Sink> Policy<FallbackLayer<A, B, P, Sink>> {
+    /// Attach a telemetry sink for [`FallbackEvent`]s.
+    ///
+    /// See [`FallbackLayer::with_sink`].
+    pub fn with_sink<NewSink: Clone>(
+        self,
+        sink: NewSink,
+    ) -> Policy<FallbackLayer<A, B, P, NewSink>> {
+        Policy(self.0.with_sink(sink))
+    }
+}
+
+/// Decides whether a primary failure should be retried through the secondary.
+///
+/// Implemented for [`AlwaysFallback`] and for any `Fn(&E) -> bool`.
+pub trait FallbackPredicate<E> {
+    /// Return `true` to try the secondary after the primary failed with `error`.
+    fn should_fallback(&self, error: &E) -> bool;
+}
+
+/// Default [`FallbackPredicate`]: every primary error triggers the fallback.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AlwaysFallback;
+
+impl<E> FallbackPredicate<E> for AlwaysFallback {
+    fn should_fallback(&self, _error: &E) -> bool {
+        true
+    }
+}
+
+impl<E, F> FallbackPredicate<E> for F
+where
+    F: Fn(&E) -> bool,
+{
+    fn should_fallback(&self, error: &E) -> bool {
+        self(error)
+    }
 }
 
 impl<L1, L2> BitOr<Policy<L2>> for Policy<L1> {
     type Output = Policy<FallbackLayer<L1, L2>>;
     fn bitor(self, rhs: Policy<L2>) -> Self::Output {
-        Policy(FallbackLayer { primary: self.0, secondary: rhs.0 })
+        Policy(FallbackLayer {
+            primary: self.0,
+            secondary: rhs.0,
+            predicate: AlwaysFallback,
+            sink: NullSink,
+        })
     }
 }
 
-impl<S, A, B> Layer<S> for FallbackLayer<A, B>
+impl<S, A, B, P, Sink> Layer<S> for FallbackLayer<A, B, P, Sink>
 where
     S: Clone + Send + 'static,
     A: Layer<S>,
     B: Layer<S>,
     A::Service: Send + 'static,
     B::Service: Send + 'static,
+    P: Clone,
+    Sink: Clone,
 {
-    type Service = FallbackService<A::Service, B::Service>;
+    type Service = FallbackService<A::Service, B::Service, P, Sink>;
 
     fn layer(&self, service: S) -> Self::Service {
         let primary = self.primary.layer(service.clone());
         let secondary = self.secondary.layer(service);
-        FallbackService { primary, secondary }
+        FallbackService {
+            primary,
+            secondary,
+            predicate: self.predicate.clone(),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<A: Describe, B: Describe, P, Sink> Describe for FallbackLayer<A, B, P, Sink> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::branch(
+            "Fallback",
+            [("primary", self.primary.describe()), ("secondary", self.secondary.describe())],
+        )
     }
 }
 
@@ -296,32 +468,41 @@ where
 ///
 /// This service is created by [`FallbackLayer`] and implements the actual
 /// fallback logic at the service level. On any error from the primary service,
-/// the original request is retried through the secondary service.
+/// the original request is retried through the secondary service, unless the
+/// [`Deadline`](crate::Deadline) in the current [`RequestContext`](crate::RequestContext)
+/// has already expired.
 ///
-/// Both services must have the same `Response` and `Error` types.
+/// Both services must have the same `Response` type. Failures are reported as a
+/// [`FallbackError`] carrying the primary's error and, if it ran, the secondary's.
+///
+/// Each request emits [`FallbackEvent::Served`] naming the branch that answered, or
+/// [`FallbackEvent::Exhausted`] with the number of branches that ran.
 #[derive(Clone, Debug)]
-pub struct FallbackService<S1, S2> {
+pub struct FallbackService<S1, S2, P = AlwaysFallback, Sink = NullSink> {
     primary: S1,
     secondary: S2,
+    predicate: P,
+    sink: Sink,
 }
 
-impl<S1, S2, Request> tower_service::Service<Request> for FallbackService<S1, S2>
+impl<S1, S2, P, Sink, Request> tower_service::Service<Request> for FallbackService<S1, S2, P, Sink>
 where
+    P: FallbackPredicate<S1::Error> + Clone + Send + 'static,
     Request: Clone + Send + 'static,
     S1: tower_service::Service<Request> + Clone + Send + 'static,
     S1::Future: Send + 'static,
     S1::Response: Send + 'static,
     S1::Error: Send + 'static,
-    S2: tower_service::Service<Request, Response = S1::Response, Error = S1::Error>
-        + Clone
-        + Send
-        + 'static,
+    S2: tower_service::Service<Request, Response = S1::Response> + Clone + Send + 'static,
     S2::Future: Send + 'static,
     S2::Response: Send + 'static,
     S2::Error: Send + 'static,
+    Sink: tower_service::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
 {
     type Response = S1::Response;
-    type Error = S1::Error;
+    type Error = FallbackError<S1::Error, S2::Error>;
     type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(
@@ -332,8 +513,12 @@ where
         let secondary_ready = self.secondary.poll_ready(cx);
 
         match (primary_ready, secondary_ready) {
-            (std::task::Poll::Ready(Err(e)), _) => std::task::Poll::Ready(Err(e)),
-            (_, std::task::Poll::Ready(Err(e))) => std::task::Poll::Ready(Err(e)),
+            (std::task::Poll::Ready(Err(e)), _) => {
+                std::task::Poll::Ready(Err(FallbackError::Primary(e)))
+            }
+            (_, std::task::Poll::Ready(Err(e))) => {
+                std::task::Poll::Ready(Err(FallbackError::Secondary(e)))
+            }
             (std::task::Poll::Ready(Ok(_)), std::task::Poll::Ready(Ok(_))) => {
                 std::task::Poll::Ready(Ok(()))
             }
@@ -344,26 +529,136 @@ where
     fn call(&mut self, req: Request) -> Self::Future {
         let mut primary = self.primary.clone();
         let mut secondary = self.secondary.clone();
+        let predicate = self.predicate.clone();
+        let sink = self.sink.clone();
         let req_clone = req.clone();
         Box::pin(async move {
-            match primary.call(req).await {
-                Ok(resp) => Ok(resp),
-                Err(primary_err) => match secondary.call(req_clone).await {
-                    Ok(resp) => Ok(resp),
-                    Err(_) => Err(primary_err), // preserve first failure for diagnostics
+            let start = Instant::now();
+            let branch = |n: usize| tracing::debug_span!("ninelives.fallback.branch", branch = n);
+            let (result, event) = match in_span(branch(0), || primary.call(req)).await {
+                Ok(resp) => {
+                    (Ok(resp), FallbackEvent::Served { branch: 0, duration: start.elapsed() })
+                }
+                // No point starting the secondary once the caller's deadline has passed.
+                Err(primary) if !predicate.should_fallback(&primary) || deadline_expired() => (
+                    Err(FallbackError::Primary(primary)),
+                    FallbackEvent::Exhausted { branches: 1, duration: start.elapsed() },
+                ),
+                Err(primary) => match in_span(branch(1), || secondary.call(req_clone)).await {
+                    Ok(resp) => {
+                        (Ok(resp), FallbackEvent::Served { branch: 1, duration: start.elapsed() })
+                    }
+                    Err(secondary) => (
+                        Err(FallbackError::Both { primary, secondary }),
+                        FallbackEvent::Exhausted { branches: 2, duration: start.elapsed() },
+                    ),
                 },
-            }
+            };
+            emit_best_effort(sink, PolicyEvent::Fallback(event)).await;
+            result
         })
     }
 }
 
+/// Error returned by [`FallbackService`].
+///
+/// `Both` is the usual failure: the primary failed and so did the secondary. `Primary` means the
+/// secondary was never tried: the predicate from [`FallbackLayer::with_predicate`] rejected the
+/// primary's error, the deadline had expired, or the primary failed `poll_ready`. `Secondary`
+/// means the secondary failed `poll_ready`.
+///
+/// The type parameters allow the two stacks to fail with different error types, which keeps
+/// chains such as `a | b | c` composable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FallbackError<P, S = P> {
+    /// Both the primary and the secondary failed.
+    Both {
+        /// Error from the primary.
+        primary: P,
+        /// Error from the secondary.
+        secondary: S,
+    },
+    /// Only the primary ran (or became unready). Also returned, with the primary's error
+    /// unchanged, when the fallback predicate declined to hand that error to the secondary.
+    Primary(P),
+    /// The secondary failed to become ready.
+    Secondary(S),
+}
+
+impl<P, S> FallbackError<P, S> {
+    /// Error from the primary, if it failed.
+    pub fn primary(&self) -> Option<&P> {
+        match self {
+            Self::Both { primary, .. } | Self::Primary(primary) => Some(primary),
+            Self::Secondary(_) => None,
+        }
+    }
+
+    /// Error from the secondary, if it failed.
+    pub fn secondary(&self) -> Option<&S> {
+        match self {
+            Self::Both { secondary, .. } | Self::Secondary(secondary) => Some(secondary),
+            Self::Primary(_) => None,
+        }
+    }
+}
+
+impl<E> FallbackError<E> {
+    /// Collapse to a single error, preferring the primary's; in `Both` the secondary's is dropped.
+    pub fn into_primary(self) -> E {
+        match self {
+            Self::Both { primary, .. } | Self::Primary(primary) => primary,
+            Self::Secondary(secondary) => secondary,
+        }
+    }
+}
+
+impl<P: std::fmt::Display, S: std::fmt::Display> std::fmt::Display for FallbackError<P, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Both { primary, secondary } => {
+                write!(f, "primary failed: {}; fallback failed: {}", primary, secondary)
+            }
+            Self::Primary(e) => write!(f, "primary failed (fallback not attempted): {}", e),
+            Self::Secondary(e) => write!(f, "fallback not ready: {}", e),
+        }
+    }
+}
+
+impl<P, S> std::error::Error for FallbackError<P, S>
+where
+    P: std::error::Error + 'static,
+    S: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Both { primary, .. } | Self::Primary(primary) => Some(primary),
+            Self::Secondary(secondary) => Some(secondary),
+        }
+    }
+}
+
+/// Collapses a fallback failure of resilience stacks, keeping the primary's error.
+///
+/// In the `Both` case the secondary's error is dropped; match on [`FallbackError`] before
+/// converting if it matters.
+impl<E> From<FallbackError<crate::ResilienceError<E>>> for crate::ResilienceError<E> {
+    fn from(err: FallbackError<crate::ResilienceError<E>>) -> Self {
+        err.into_primary()
+    }
+}
+
+fn deadline_expired() -> bool {
+    crate::RequestContext::current().deadline().is_some_and(|d| d.is_expired())
+}
+
 /// Fork-join composition layer that tries both `left` and `right` concurrently.
 ///
 /// Created by the `&` operator on `Policy<L>` types:
 /// `Policy(A) & Policy(B)` produces `Policy<ForkJoinLayer<A, B>>`.
 ///
 /// Both services are called concurrently, and the first successful result is returned.
-/// If both fail, an error is returned (currently the left error, but this may change).
+/// If both fail, a [`ForkJoinError::Both`] carrying both errors is returned.
 ///
 /// This implements the "happy eyeballs" pattern commonly used for IPv4/IPv6 racing,
 /// cache racing, or trying multiple backends simultaneously.
@@ -401,34 +696,65 @@ where
 /// # }
 /// ```
 #[derive(Clone, Debug)]
-pub struct ForkJoinLayer<A, B> {
+pub struct ForkJoinLayer<A, B, Sink = NullSink> {
     /// The left strategy (tried concurrently with right)
     pub left: A,
     /// The right strategy (tried concurrently with left)
     pub right: B,
+    /// Receives a [`ForkJoinEvent`] per request
+    pub sink: Sink,
+}
+
+impl<A, B, Sink> ForkJoinLayer<A, B, Sink> {
+    /// Attach a telemetry sink for [`ForkJoinEvent`]s.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> ForkJoinLayer<A, B, NewSink>
+    where
+        NewSink: Clone,
+    {
+        ForkJoinLayer { left: self.left, right: self.right, sink }
+    }
+}
+
+impl<A, B, Sink> Policy<ForkJoinLayer<A, B, Sink>> {
+    /// Attach a telemetry sink for [`ForkJoinEvent`]s.
+    ///
+    /// See [`ForkJoinLayer::with_sink`].
+    pub fn with_sink<NewSink: Clone>(self, sink: NewSink) -> Policy<ForkJoinLayer<A, B, NewSink>> {
+        Policy(self.0.with_sink(sink))
+    }
 }
 
 impl<L1, L2> BitAnd<Policy<L2>> for Policy<L1> {
     type Output = Policy<ForkJoinLayer<L1, L2>>;
     fn bitand(self, rhs: Policy<L2>) -> Self::Output {
-        Policy(ForkJoinLayer { left: self.0, right: rhs.0 })
+        Policy(ForkJoinLayer { left: self.0, right: rhs.0, sink: NullSink })
     }
 }
 
-impl<S, A, B> Layer<S> for ForkJoinLayer<A, B>
+impl<S, A, B, Sink> Layer<S> for ForkJoinLayer<A, B, Sink>
 where
     S: Clone + Send + 'static,
     A: Layer<S>,
     B: Layer<S>,
     A::Service: Send + 'static,
     B::Service: Send + 'static,
+    Sink: Clone,
 {
-    type Service = ForkJoinService<A::Service, B::Service>;
+    type Service = ForkJoinService<A::Service, B::Service, Sink>;
 
     fn layer(&self, service: S) -> Self::Service {
         let left = self.left.layer(service.clone());
         let right = self.right.layer(service);
-        ForkJoinService { left, right }
+        ForkJoinService { left, right, sink: self.sink.clone() }
+    }
+}
+
+impl<A: Describe, B: Describe, Sink> Describe for ForkJoinLayer<A, B, Sink> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::branch(
+            "ForkJoin",
+            [("left", self.left.describe()), ("right", self.right.describe())],
+        )
     }
 }
 
@@ -436,16 +762,23 @@ where
 ///
 /// This service is created by [`ForkJoinLayer`] and implements the actual
 /// fork-join logic at the service level. Both services are called concurrently,
-/// and the first `Ok` result is returned. If both fail, returns an error.
+/// and the first `Ok` result is returned. If both fail, returns [`ForkJoinError::Both`].
+///
+/// The slower service's future is dropped when the first succeeds. Each branch runs with its
+/// own [`CancellationToken`] in the [`RequestContext`](crate::RequestContext), and the loser's
+/// token is cancelled at that point, so work it spawned (a second database query, say) can watch
+/// [`RequestContext::is_cancelled`](crate::RequestContext::is_cancelled) and stop early.
 ///
-/// The slower service's future is dropped when the first succeeds.
+/// Each request emits [`ForkJoinEvent::Won`] with the winning branch and its latency, or
+/// [`ForkJoinEvent::Exhausted`] once both branches have failed.
 #[derive(Clone, Debug)]
-pub struct ForkJoinService<S1, S2> {
+pub struct ForkJoinService<S1, S2, Sink = NullSink> {
     left: S1,
     right: S2,
+    sink: Sink,
 }
 
-impl<S1, S2, Request> tower_service::Service<Request> for ForkJoinService<S1, S2>
+impl<S1, S2, Sink, Request> tower_service::Service<Request> for ForkJoinService<S1, S2, Sink>
 where
     Request: Clone + Send + 'static,
     S1: tower_service::Service<Request> + Clone + Send + 'static,
@@ -459,9 +792,12 @@ where
     S2::Future: Send + 'static,
     S2::Response: Send + 'static,
     S2::Error: Send + 'static,
+    Sink: tower_service::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
 {
     type Response = S1::Response;
-    type Error = S1::Error;
+    type Error = ForkJoinError<S1::Error>;
     type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(
@@ -474,8 +810,12 @@ where
             (std::task::Poll::Ready(Ok(_)), std::task::Poll::Ready(Ok(_))) => {
                 std::task::Poll::Ready(Ok(()))
             }
-            (std::task::Poll::Ready(Err(e)), _) => std::task::Poll::Ready(Err(e)),
-            (_, std::task::Poll::Ready(Err(e))) => std::task::Poll::Ready(Err(e)),
+            (std::task::Poll::Ready(Err(e)), _) => {
+                std::task::Poll::Ready(Err(ForkJoinError::Left(e)))
+            }
+            (_, std::task::Poll::Ready(Err(e))) => {
+                std::task::Poll::Ready(Err(ForkJoinError::Right(e)))
+            }
             _ => std::task::Poll::Pending,
         }
     }
@@ -483,40 +823,146 @@ where
     fn call(&mut self, req: Request) -> Self::Future {
         let mut left = self.left.clone();
         let mut right = self.right.clone();
+        let sink = self.sink.clone();
         let req_clone = req.clone();
 
+        // Each branch gets its own token so the loser can be told to stop; both stay children of
+        // any outer token so cancellation from above still reaches them.
+        let ctx = crate::RequestContext::current();
+        let branch_ctx = || {
+            let token = ctx
+                .cancellation_token()
+                .map_or_else(CancellationToken::new, CancellationToken::child_token);
+            (ctx.clone().with_cancellation_token(token.clone()), token)
+        };
+        let (left_ctx, left_token) = branch_ctx();
+        let (right_ctx, right_token) = branch_ctx();
+
         Box::pin(async move {
             use futures::pin_mut;
 
-            let left_fut = left.call(req);
-            let right_fut = right.call(req_clone);
+            // Dropping a guard cancels its branch: that happens to the loser, and to both
+            // branches if this future is dropped before either finishes.
+            let left_guard = left_token.drop_guard();
+            let right_guard = right_token.drop_guard();
+
+            let left_fut = left_ctx.clone().sync_scope(|| left.call(req));
+            let left_fut = left_ctx.scope(left_fut);
+            let right_fut = right_ctx.clone().sync_scope(|| right.call(req_clone));
+            let right_fut = right_ctx.scope(right_fut);
 
             pin_mut!(left_fut);
             pin_mut!(right_fut);
 
+            let start = Instant::now();
+            let won = |branch, loser_failed| ForkJoinEvent::Won {
+                branch,
+                latency: start.elapsed(),
+                loser_failed,
+            };
+            let exhausted = || ForkJoinEvent::Exhausted { duration: start.elapsed() };
+
             // Race the two futures
-            match select(left_fut, right_fut).await {
-                Either::Left((Ok(resp), _)) => Ok(resp),
-                Either::Right((Ok(resp), _)) => Ok(resp),
-                Either::Left((Err(left_err), right_fut)) => {
-                    // Left failed, try right
-                    match right_fut.await {
-                        Ok(resp) => Ok(resp),
-                        Err(_) => Err(left_err), // Both failed, return left error
-                    }
+            let (result, event) = match select(left_fut, right_fut).await {
+                Either::Left((Ok(resp), _)) => {
+                    left_guard.disarm();
+                    (Ok(resp), won(0, false))
                 }
-                Either::Right((Err(_right_err), left_fut)) => {
-                    // Right failed, try left
-                    match left_fut.await {
-                        Ok(resp) => Ok(resp),
-                        Err(left_err) => Err(left_err), // Both failed, return deterministic left error
-                    }
+                Either::Right((Ok(resp), _)) => {
+                    right_guard.disarm();
+                    (Ok(resp), won(1, false))
                 }
-            }
+                Either::Left((Err(left), right_fut)) => {
+                    left_guard.disarm();
+                    let result = right_fut.await;
+                    right_guard.disarm();
+                    let event = if result.is_ok() { won(1, true) } else { exhausted() };
+                    (result.map_err(|right| ForkJoinError::Both { left, right }), event)
+                }
+                Either::Right((Err(right), left_fut)) => {
+                    right_guard.disarm();
+                    let result = left_fut.await;
+                    left_guard.disarm();
+                    let event = if result.is_ok() { won(0, true) } else { exhausted() };
+                    (result.map_err(|left| ForkJoinError::Both { left, right }), event)
+                }
+            };
+            emit_best_effort(sink, PolicyEvent::ForkJoin(event)).await;
+            result
         })
     }
 }
 
+/// Error returned by [`ForkJoinService`].
+///
+/// `Both` is the normal failure: each branch ran and failed. `Left` / `Right` report a branch
+/// whose `poll_ready` failed before any request was dispatched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForkJoinError<E> {
+    /// Both branches failed.
+    Both {
+        /// Error from the left branch.
+        left: E,
+        /// Error from the right branch.
+        right: E,
+    },
+    /// The left branch failed to become ready.
+    Left(E),
+    /// The right branch failed to become ready.
+    Right(E),
+}
+
+impl<E> ForkJoinError<E> {
+    /// Error from the left branch, if it failed.
+    pub fn left(&self) -> Option<&E> {
+        match self {
+            Self::Both { left, .. } | Self::Left(left) => Some(left),
+            Self::Right(_) => None,
+        }
+    }
+
+    /// Error from the right branch, if it failed.
+    pub fn right(&self) -> Option<&E> {
+        match self {
+            Self::Both { right, .. } | Self::Right(right) => Some(right),
+            Self::Left(_) => None,
+        }
+    }
+
+    /// Collapse to a single error, preferring the left branch.
+    pub fn into_left(self) -> E {
+        match self {
+            Self::Both { left, .. } | Self::Left(left) => left,
+            Self::Right(right) => right,
+        }
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ForkJoinError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Both { left, right } => {
+                write!(f, "both fork-join branches failed (left: {}; right: {})", left, right)
+            }
+            Self::Left(e) => write!(f, "left fork-join branch not ready: {}", e),
+            Self::Right(e) => write!(f, "right fork-join branch not ready: {}", e),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ForkJoinError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.left().or_else(|| self.right()).map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Collapses a fork-join failure of resilience stacks, keeping the left branch's error.
+impl<E> From<ForkJoinError<crate::ResilienceError<E>>> for crate::ResilienceError<E> {
+    fn from(err: ForkJoinError<crate::ResilienceError<E>>) -> Self {
+        err.into_left()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -576,7 +1022,12 @@ mod tests {
         let primary = GateService::new();
         let secondary = GateService::new();
 
-        let mut svc = FallbackService { primary: primary.clone(), secondary: secondary.clone() };
+        let mut svc = FallbackService {
+            primary: primary.clone(),
+            secondary: secondary.clone(),
+            predicate: AlwaysFallback,
+            sink: NullSink,
+        };
 
         // Primary ready, secondary not ready => still Pending
         primary.set_ready(true);
@@ -591,9 +1042,9 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn fallback_returns_primary_error_when_both_fail() {
+    async fn fallback_returns_both_errors_when_both_fail() {
         #[derive(Clone, Debug)]
-        struct ErrSvc;
+        struct ErrSvc(&'static str);
 
         impl tower_service::Service<()> for ErrSvc {
             type Response = ();
@@ -603,17 +1054,105 @@ mod tests {
                 Poll::Ready(Ok(()))
             }
             fn call(&mut self, _req: ()) -> Self::Future {
-                futures::future::ready(Err("primary failed"))
+                futures::future::ready(Err(self.0))
             }
         }
 
-        let mut svc = FallbackService { primary: ErrSvc, secondary: ErrSvc };
+        let mut svc = FallbackService {
+            primary: ErrSvc("primary failed"),
+            secondary: ErrSvc("db down"),
+            predicate: AlwaysFallback,
+            sink: NullSink,
+        };
         let err = svc.call(()).await.unwrap_err();
-        assert_eq!(err, "primary failed");
+        assert_eq!(err, FallbackError::Both { primary: "primary failed", secondary: "db down" });
+        assert_eq!(err.to_string(), "primary failed: primary failed; fallback failed: db down");
+        assert_eq!(err.into_primary(), "primary failed");
     }
 
     #[tokio::test]
-    async fn fork_join_returns_left_error_if_both_fail() {
+    async fn fallback_skips_secondary_after_deadline_expires() {
+        use std::time::Duration;
+        tokio::time::pause();
+
+        #[derive(Clone, Debug)]
+        struct ErrSvc(&'static str);
+
+        impl tower_service::Service<()> for ErrSvc {
+            type Response = ();
+            type Error = &'static str;
+            type Future = futures::future::Ready<Result<Self::Response, Self::Error>>;
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+            fn call(&mut self, _req: ()) -> Self::Future {
+                futures::future::ready(Err(self.0))
+            }
+        }
+
+        let secondary = GateService::new();
+        secondary.set_ready(true);
+        let mut svc = FallbackService {
+            primary: ErrSvc("primary failed"),
+            secondary: secondary.clone(),
+            predicate: AlwaysFallback,
+            sink: NullSink,
+        };
+
+        let deadline = crate::Deadline::after(Duration::from_millis(1));
+        tokio::time::advance(Duration::from_millis(5)).await;
+        let err = crate::RequestContext::new()
+            .with_deadline(deadline)
+            .scope(svc.call(()))
+            .await
+            .unwrap_err();
+
+        assert_eq!(err, FallbackError::Primary("primary failed"));
+        assert_eq!(secondary.calls(), 0, "secondary must not run after the deadline");
+    }
+
+    #[tokio::test]
+    async fn fallback_predicate_skips_secondary_for_rejected_errors() {
+        #[derive(Clone, Debug)]
+        struct EchoErr;
+
+        impl tower_service::Service<&'static str> for EchoErr {
+            type Response = &'static str;
+            type Error = &'static str;
+            type Future = futures::future::Ready<Result<Self::Response, Self::Error>>;
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+            fn call(&mut self, req: &'static str) -> Self::Future {
+                futures::future::ready(Err(req))
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        struct Ok200;
+
+        impl<S> Layer<S> for Ok200 {
+            type Service = tower::util::BoxCloneService<&'static str, &'static str, &'static str>;
+            fn layer(&self, _inner: S) -> Self::Service {
+                tower::util::BoxCloneService::new(tower::service_fn(|_req: &'static str| {
+                    futures::future::ready(Ok("secondary"))
+                }))
+            }
+        }
+
+        let policy = (Policy(tower_layer::Identity::new()) | Policy(Ok200))
+            .with_predicate(|e: &&str| *e == "timeout");
+        let mut svc = policy.layer(EchoErr);
+
+        assert_eq!(svc.call("timeout").await.unwrap(), "secondary");
+        assert_eq!(
+            svc.call("invalid input").await.unwrap_err(),
+            FallbackError::Primary("invalid input")
+        );
+    }
+
+    #[tokio::test]
+    async fn fork_join_returns_both_errors_if_both_fail() {
         #[derive(Clone, Debug)]
         struct LeftErr;
         #[derive(Clone, Debug)]
@@ -643,9 +1182,52 @@ mod tests {
             }
         }
 
-        let mut svc = ForkJoinService { left: LeftErr, right: RightErr };
+        let mut svc = ForkJoinService { left: LeftErr, right: RightErr, sink: NullSink };
         let err = svc.call(()).await.unwrap_err();
-        assert_eq!(err, "left");
+        assert_eq!(err, ForkJoinError::Both { left: "left", right: "right" });
+        assert_eq!(err.to_string(), "both fork-join branches failed (left: left; right: right)");
+        assert_eq!(err.into_left(), "left");
+    }
+
+    #[test]
+    fn fork_join_error_converts_into_resilience_error() {
+        let err: ForkJoinError<crate::ResilienceError<std::io::Error>> = ForkJoinError::Both {
+            left: crate::ResilienceError::BulkheadClosed,
+            right: crate::ResilienceError::Inner(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "right",
+            )),
+        };
+        assert!(std::error::Error::source(&err).is_some());
+        let collapsed: crate::ResilienceError<std::io::Error> = err.into();
+        assert!(collapsed.is_bulkhead_closed());
+    }
+
+    #[tokio::test]
+    async fn fork_join_cancels_the_losing_branch() {
+        let (cancelled_tx, cancelled_rx) = tokio::sync::oneshot::channel::<()>();
+        let cancelled_tx = std::sync::Arc::new(std::sync::Mutex::new(Some(cancelled_tx)));
+
+        let winner = tower::service_fn(|_: ()| async { Ok::<_, &'static str>("fast") });
+        let loser = tower::service_fn(move |_: ()| {
+            let token = crate::RequestContext::current().cancellation_token().cloned().unwrap();
+            let tx = cancelled_tx.lock().unwrap().take();
+            // Work spawned by the branch outlives its future unless it watches the token.
+            tokio::spawn(async move {
+                token.cancelled().await;
+                if let Some(tx) = tx {
+                    let _ = tx.send(());
+                }
+            });
+            std::future::pending::<Result<&'static str, &'static str>>()
+        });
+
+        let mut svc = ForkJoinService { left: winner, right: loser, sink: NullSink };
+        assert_eq!(svc.call(()).await.unwrap(), "fast");
+        tokio::time::timeout(std::time::Duration::from_secs(1), cancelled_rx)
+            .await
+            .expect("losing branch was not cancelled")
+            .unwrap();
     }
 
     #[test]
@@ -653,7 +1235,7 @@ mod tests {
         let left = GateService::new();
         let right = GateService::new();
 
-        let mut svc = ForkJoinService { left: left.clone(), right: right.clone() };
+        let mut svc = ForkJoinService { left: left.clone(), right: right.clone(), sink: NullSink };
 
         left.set_ready(true);
         right.set_ready(false);
@@ -664,4 +1246,57 @@ mod tests {
         right.set_ready(true);
         assert!(matches!(Service::<()>::poll_ready(&mut svc, &mut cx), Poll::Ready(Ok(()))));
     }
+
+    #[tokio::test]
+    async fn fallback_reports_the_serving_branch() {
+        use crate::telemetry::MemorySink;
+
+        let sink = MemorySink::new();
+        let fails = tower::layer::layer_fn(|_: ()| {
+            tower::service_fn(|_: ()| async { Err::<&'static str, _>("down") })
+        });
+        let serves = tower::layer::layer_fn(|_: ()| {
+            tower::service_fn(|_: ()| async { Ok::<_, &'static str>("replica") })
+        });
+
+        let mut svc = (Policy(fails) | Policy(serves)).with_sink(sink.clone()).layer(());
+        assert_eq!(svc.call(()).await.unwrap(), "replica");
+        let mut svc = (Policy(fails) | Policy(fails)).with_sink(sink.clone()).layer(());
+        assert!(svc.call(()).await.is_err());
+
+        let events = sink.events();
+        assert!(matches!(
+            events[0],
+            PolicyEvent::Fallback(FallbackEvent::Served { branch: 1, .. })
+        ));
+        assert!(matches!(
+            events[1],
+            PolicyEvent::Fallback(FallbackEvent::Exhausted { branches: 2, .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fork_join_reports_the_winner_and_its_latency() {
+        use crate::telemetry::MemorySink;
+        use std::time::Duration;
+
+        let sink = MemorySink::new();
+        let slow = tower::service_fn(|_: ()| async {
+            tokio::time::sleep(Duration::from_millis(80)).await;
+            Ok::<_, &'static str>("right")
+        });
+        let fails = tower::service_fn(|_: ()| async { Err::<&'static str, _>("down") });
+
+        let mut svc = ForkJoinService { left: fails, right: slow, sink: sink.clone() };
+        assert_eq!(svc.call(()).await.unwrap(), "right");
+
+        match sink.events()[..] {
+            [PolicyEvent::ForkJoin(ForkJoinEvent::Won { branch, latency, loser_failed })] => {
+                assert_eq!(branch, 1);
+                assert!(loser_failed);
+                assert!(latency >= Duration::from_millis(80), "{:?}", latency);
+            }
+            ref other => panic!("unexpected events: {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,322 @@
+//! Lock-free bulkhead backed by a single atomic counter.
+//!
+//! [`BulkheadLayer`](crate::BulkheadLayer) never queues, so the waiter list and fairness machinery
+//! of `tokio::sync::Semaphore` behind it are pure overhead. [`AtomicBulkheadLayer`] enforces the
+//! same non-blocking limit with a compare-and-swap on an `AtomicUsize`: acquire increments the
+//! counter only while it is below the limit, and release is a single decrement.
+//!
+//! Semantics
+//! - Requests beyond `max_concurrent` fail immediately with [`ResilienceError::Bulkhead`].
+//! - Permits are held until the inner future completes or is dropped.
+//! - Emits the same [`BulkheadEvent::Acquired`], [`BulkheadEvent::Released`], and
+//!   [`BulkheadEvent::Rejected`] events as `BulkheadLayer`, so dashboards work unchanged;
+//!   per-request outcome events are not emitted.
+//! - There is no closed state; [`ResilienceError::BulkheadClosed`] is never returned.
+//!
+//! Invariants
+//! - Services built from the same layer (and its clones) share one counter.
+//! - The counter never exceeds `max_concurrent`, even under contention.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let bulkhead = AtomicBulkheadLayer::new(128)?;
+//! let gauge = bulkhead.clone();
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(bulkhead)
+//!     .service_fn(|n: u64| async move { Ok::<_, std::io::Error>(n * 2) });
+//! assert_eq!(svc.ready().await?.call(21).await?, 42);
+//! assert_eq!(gauge.in_flight(), 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::bulkhead::{BulkheadError, BulkheadPolicy};
+use crate::telemetry::{
+    emit_best_effort, emit_detached, BulkheadEvent, BulkheadRejectReason, NullSink, PolicyEvent,
+};
+use crate::ResilienceError;
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Non-blocking bulkhead layer using an atomic counter instead of a semaphore.
+#[derive(Debug, Clone)]
+pub struct AtomicBulkheadLayer<Sink = NullSink> {
+    counter: Arc<AtomicUsize>,
+    max_concurrent: usize,
+    sink: Sink,
+}
+
+impl AtomicBulkheadLayer<NullSink> {
+    /// Allow at most `max_concurrent` requests in flight; returns error if it is zero.
+    pub fn new(max_concurrent: usize) -> Result<Self, BulkheadError> {
+        BulkheadPolicy::new(max_concurrent)?;
+        Ok(Self { counter: Arc::new(AtomicUsize::new(0)), max_concurrent, sink: NullSink })
+    }
+}
+
+impl<Sink> AtomicBulkheadLayer<Sink> {
+    /// Attach a telemetry sink to this bulkhead layer.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> AtomicBulkheadLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        AtomicBulkheadLayer { counter: self.counter, max_concurrent: self.max_concurrent, sink }
+    }
+
+    /// Maximum configured concurrent requests.
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Requests currently holding a permit (a snapshot; may be stale under contention).
+    pub fn in_flight(&self) -> usize {
+        self.counter.load(Ordering::Relaxed)
+    }
+}
+
+impl<S, Sink: Clone> Layer<S> for AtomicBulkheadLayer<Sink> {
+    type Service = AtomicBulkheadService<S, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AtomicBulkheadService {
+            inner,
+            counter: Arc::clone(&self.counter),
+            max_concurrent: self.max_concurrent,
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<Sink> crate::Describe for AtomicBulkheadLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Bulkhead({}, atomic)", self.max_concurrent))
+    }
+}
+
+/// Service produced by [`AtomicBulkheadLayer`].
+#[derive(Debug, Clone)]
+pub struct AtomicBulkheadService<S, Sink = NullSink> {
+    inner: S,
+    counter: Arc<AtomicUsize>,
+    max_concurrent: usize,
+    sink: Sink,
+}
+
+/// Try to take a slot; on failure returns the in-flight count that was observed.
+fn try_acquire(counter: &AtomicUsize, max: usize) -> Result<(), usize> {
+    let mut current = counter.load(Ordering::Relaxed);
+    loop {
+        if current >= max {
+            return Err(current);
+        }
+        match counter.compare_exchange_weak(
+            current,
+            current + 1,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => return Ok(()),
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// A held slot; released on drop, which reports [`BulkheadEvent::Released`] whether the call
+/// finished or its future was dropped.
+struct Permit<Sink>
+where
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    counter: Arc<AtomicUsize>,
+    max: usize,
+    sink: Sink,
+}
+
+impl<Sink> Drop for Permit<Sink>
+where
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    fn drop(&mut self) {
+        let active_count = self.counter.fetch_sub(1, Ordering::Release) - 1;
+        emit_detached(
+            self.sink.clone(),
+            PolicyEvent::Bulkhead(BulkheadEvent::Released {
+                active_count,
+                max_concurrency: self.max,
+            }),
+        );
+    }
+}
+
+impl<S, Request, Sink> Service<Request> for AtomicBulkheadService<S, Sink>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let max = self.max_concurrent;
+        let sink = self.sink.clone();
+        match try_acquire(&self.counter, max) {
+            Ok(()) => {
+                let active_count = self.counter.load(Ordering::Relaxed);
+                let permit = Permit { counter: Arc::clone(&self.counter), max, sink: sink.clone() };
+                let fut = self.inner.call(req);
+                Box::pin(async move {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::Bulkhead(BulkheadEvent::Acquired {
+                            active_count,
+                            max_concurrency: max,
+                        }),
+                    )
+                    .await;
+                    let result = fut.await;
+                    drop(permit);
+                    result.map_err(ResilienceError::Inner)
+                })
+            }
+            Err(in_flight) => Box::pin(async move {
+                emit_best_effort(
+                    sink,
+                    PolicyEvent::Bulkhead(BulkheadEvent::Rejected {
+                        active_count: in_flight,
+                        max_concurrency: max,
+                        reason: BulkheadRejectReason::Saturated,
+                    }),
+                )
+                .await;
+                Err(ResilienceError::Bulkhead { in_flight, max })
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::fmt;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[test]
+    fn rejects_zero_max_concurrent() {
+        assert!(matches!(
+            AtomicBulkheadLayer::new(0),
+            Err(BulkheadError::InvalidMaxConcurrent { provided: 0 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_at_capacity_and_releases_on_completion() {
+        let sink = MemorySink::new();
+        let layer = AtomicBulkheadLayer::new(1).unwrap().with_sink(sink.clone());
+        let (release, hold) = tokio::sync::oneshot::channel::<()>();
+        let hold = Arc::new(tokio::sync::Mutex::new(Some(hold)));
+        let svc = layer.layer(tower::service_fn(move |_: ()| {
+            let hold = Arc::clone(&hold);
+            async move {
+                if let Some(rx) = hold.lock().await.take() {
+                    let _ = rx.await;
+                }
+                Ok::<_, TestError>(())
+            }
+        }));
+
+        let first = tokio::spawn(svc.clone().oneshot(()));
+        tokio::task::yield_now().await;
+        assert_eq!(layer.in_flight(), 1);
+
+        let err = svc.clone().oneshot(()).await.unwrap_err();
+        assert_eq!(err.bulkhead_capacity(), Some((1, 1)));
+
+        release.send(()).unwrap();
+        first.await.unwrap().unwrap();
+        assert_eq!(layer.in_flight(), 0);
+        svc.oneshot(()).await.unwrap();
+
+        let rejected = sink
+            .events()
+            .into_iter()
+            .filter(|e| matches!(e, PolicyEvent::Bulkhead(BulkheadEvent::Rejected { .. })))
+            .count();
+        assert_eq!(rejected, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn never_exceeds_the_limit_under_contention() {
+        let layer = AtomicBulkheadLayer::new(3).unwrap();
+        let peak = Arc::new(AtomicUsize::new(0));
+        let gauge = layer.clone();
+        let observed = Arc::clone(&peak);
+        let svc = layer.layer(tower::service_fn(move |_: ()| {
+            observed.fetch_max(gauge.in_flight(), Ordering::SeqCst);
+            async {
+                tokio::task::yield_now().await;
+                Ok::<_, TestError>(())
+            }
+        }));
+
+        let tasks: Vec<_> = (0..200).map(|_| tokio::spawn(svc.clone().oneshot(()))).collect();
+        for task in tasks {
+            let _ = task.await.unwrap();
+        }
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(layer.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn dropped_calls_release_and_report_it() {
+        let sink = MemorySink::new();
+        let layer = AtomicBulkheadLayer::new(2).unwrap().with_sink(sink.clone());
+        let svc =
+            layer.layer(tower::service_fn(|_: ()| std::future::pending::<Result<(), TestError>>()));
+
+        let call = tokio::spawn(svc.oneshot(()));
+        while layer.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+        call.abort();
+        assert!(call.await.unwrap_err().is_cancelled());
+        assert_eq!(layer.in_flight(), 0);
+
+        let released =
+            PolicyEvent::Bulkhead(BulkheadEvent::Released { active_count: 0, max_concurrency: 2 });
+        while !sink.events().contains(&released) {
+            tokio::task::yield_now().await;
+        }
+    }
+}
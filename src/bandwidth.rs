@@ -0,0 +1,431 @@
+//! Bytes-per-second limiting for bulk transfer clients.
+//!
+//! Semantics
+//! - A size extractor reports how many bytes each request will put on the wire. Bytes are drawn
+//!   from a bucket that refills at `bytes_per_second` and holds at most `burst` bytes.
+//! - A request that overdraws the bucket is delayed (never rejected) until the refill has
+//!   covered the debt, and each delay emits [`RateLimitEvent::Delayed`]. Requests larger than
+//!   `burst` are allowed; they simply wait longer.
+//! - With [`BandwidthLayer::count_responses`] response sizes are debited as well, once each
+//!   response arrives. Downloads therefore slow down the requests that follow them.
+//! - Waiting goes through a [`Sleeper`] and time is read from a [`Clock`], as in
+//!   [`ThrottleLayer`](crate::ThrottleLayer).
+//!
+//! Invariants
+//! - Services built from the same layer share one bucket.
+//! - Over any interval, bytes sent start at most `burst + bytes_per_second × interval`.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Replication uploads capped at 50 MB/s with 8 MB of burst; downloads count too.
+//! let limit = BandwidthLayer::new(50_000_000, 8_000_000, |blob: &Vec<u8>| blob.len() as u64)?
+//!     .count_responses(|ack: &usize| *ack as u64);
+//!
+//! let mut upload = ServiceBuilder::new()
+//!     .layer(limit)
+//!     .service_fn(|blob: Vec<u8>| async move { Ok::<_, std::io::Error>(blob.len()) });
+//! assert_eq!(upload.ready().await?.call(vec![0; 1024]).await?, 1024);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, RateLimitEvent};
+use crate::{Clock, MonotonicClock, Sleeper, TokioSleeper};
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::ServiceExt;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Errors produced while configuring a [`BandwidthLayer`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BandwidthError {
+    /// `bytes_per_second` was zero.
+    ZeroRate,
+    /// `burst` was zero.
+    ZeroBurst,
+}
+
+impl fmt::Display for BandwidthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroRate => write!(f, "bandwidth limit must be > 0 bytes per second"),
+            Self::ZeroBurst => write!(f, "bandwidth burst must be > 0 bytes"),
+        }
+    }
+}
+
+impl std::error::Error for BandwidthError {}
+
+/// Response size extractor used when responses are not counted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uncounted;
+
+/// How many bytes a response took; implemented for [`Uncounted`] and `Fn(&Response) -> u64`.
+pub trait ResponseSize<Response> {
+    /// Bytes to debit for `response`.
+    fn size(&self, response: &Response) -> u64;
+}
+
+impl<Response> ResponseSize<Response> for Uncounted {
+    fn size(&self, _: &Response) -> u64 {
+        0
+    }
+}
+
+impl<Response, G> ResponseSize<Response> for G
+where
+    G: Fn(&Response) -> u64,
+{
+    fn size(&self, response: &Response) -> u64 {
+        self(response)
+    }
+}
+
+/// Layer limiting the bytes per second flowing through a service.
+pub struct BandwidthLayer<F, G = Uncounted, Sink = NullSink> {
+    request_size: Arc<F>,
+    response_size: Arc<G>,
+    bucket: Arc<Bucket>,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    sink: Sink,
+}
+
+impl<F> BandwidthLayer<F, Uncounted, NullSink> {
+    /// Allow `bytes_per_second` with up to `burst` bytes at once, sizing requests with
+    /// `request_size`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BandwidthError`] if either limit is zero.
+    pub fn new<Request>(
+        bytes_per_second: u64,
+        burst: u64,
+        request_size: F,
+    ) -> Result<Self, BandwidthError>
+    where
+        F: Fn(&Request) -> u64,
+    {
+        if bytes_per_second == 0 {
+            return Err(BandwidthError::ZeroRate);
+        }
+        if burst == 0 {
+            return Err(BandwidthError::ZeroBurst);
+        }
+        let clock: Arc<dyn Clock> = Arc::new(MonotonicClock::new());
+        let bucket = Bucket {
+            bytes_per_second: bytes_per_second as f64,
+            burst: burst as f64,
+            state: Mutex::new(BucketState { available: burst as f64, updated_ms: None }),
+        };
+        Ok(Self {
+            request_size: Arc::new(request_size),
+            response_size: Arc::new(Uncounted),
+            bucket: Arc::new(bucket),
+            clock,
+            sleeper: Arc::new(TokioSleeper),
+            sink: NullSink,
+        })
+    }
+}
+
+impl<F, G, Sink> BandwidthLayer<F, G, Sink> {
+    /// Also debit response sizes reported by `response_size` once each response arrives.
+    pub fn count_responses<Response, NewG>(
+        self,
+        response_size: NewG,
+    ) -> BandwidthLayer<F, NewG, Sink>
+    where
+        NewG: Fn(&Response) -> u64,
+    {
+        BandwidthLayer {
+            request_size: self.request_size,
+            response_size: Arc::new(response_size),
+            bucket: self.bucket,
+            clock: self.clock,
+            sleeper: self.sleeper,
+            sink: self.sink,
+        }
+    }
+
+    /// Provide a custom sleeper implementation.
+    pub fn with_sleeper<S>(mut self, sleeper: S) -> Self
+    where
+        S: Sleeper + 'static,
+    {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Provide a custom clock implementation.
+    pub fn with_clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Attach a telemetry sink; delayed requests emit [`RateLimitEvent::Delayed`].
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> BandwidthLayer<F, G, NewSink>
+    where
+        NewSink: Clone,
+    {
+        BandwidthLayer {
+            request_size: self.request_size,
+            response_size: self.response_size,
+            bucket: self.bucket,
+            clock: self.clock,
+            sleeper: self.sleeper,
+            sink,
+        }
+    }
+
+    /// Bytes that could be sent right now without waiting (negative while in debt).
+    pub fn available(&self) -> i64 {
+        self.bucket.debit(self.clock.now_millis(), 0) as i64
+    }
+}
+
+impl<F, G, Sink: Clone> Clone for BandwidthLayer<F, G, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            request_size: Arc::clone(&self.request_size),
+            response_size: Arc::clone(&self.response_size),
+            bucket: Arc::clone(&self.bucket),
+            clock: Arc::clone(&self.clock),
+            sleeper: Arc::clone(&self.sleeper),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<F, G, Sink: fmt::Debug> fmt::Debug for BandwidthLayer<F, G, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BandwidthLayer")
+            .field("bytes_per_second", &self.bucket.bytes_per_second)
+            .field("burst", &self.bucket.burst)
+            .field("clock", &self.clock)
+            .field("sleeper", &self.sleeper)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, F, G, Sink: Clone> Layer<S> for BandwidthLayer<F, G, Sink> {
+    type Service = BandwidthService<S, F, G, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BandwidthService { inner, layer: self.clone() }
+    }
+}
+
+impl<F, G, Sink> crate::Describe for BandwidthLayer<F, G, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!(
+            "Bandwidth({} B/s, burst {} B)",
+            self.bucket.bytes_per_second, self.bucket.burst
+        ))
+    }
+}
+
+/// Service produced by [`BandwidthLayer`].
+pub struct BandwidthService<S, F, G = Uncounted, Sink = NullSink> {
+    inner: S,
+    layer: BandwidthLayer<F, G, Sink>,
+}
+
+impl<S: Clone, F, G, Sink: Clone> Clone for BandwidthService<S, F, G, Sink> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), layer: self.layer.clone() }
+    }
+}
+
+impl<S: fmt::Debug, F, G, Sink: fmt::Debug> fmt::Debug for BandwidthService<S, F, G, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BandwidthService")
+            .field("inner", &self.inner)
+            .field("layer", &self.layer)
+            .finish()
+    }
+}
+
+impl<S, Request, F, G, Sink> Service<Request> for BandwidthService<S, F, G, Sink>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    Request: Send + 'static,
+    F: Fn(&Request) -> u64,
+    G: ResponseSize<S::Response> + Send + Sync + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        // As with the throttle, readiness is checked after the wait.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let size = (self.layer.request_size)(&req);
+        let bucket = Arc::clone(&self.layer.bucket);
+        let wait = bucket.wait_for(bucket.debit(self.layer.clock.now_millis(), size));
+        let clock = Arc::clone(&self.layer.clock);
+        let response_size = Arc::clone(&self.layer.response_size);
+        let sleeper = Arc::clone(&self.layer.sleeper);
+        let sink = self.layer.sink.clone();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            if !wait.is_zero() {
+                emit_best_effort(sink, PolicyEvent::RateLimit(RateLimitEvent::Delayed { wait }))
+                    .await;
+                sleeper.sleep(wait).await;
+            }
+            let response = inner.oneshot(req).await?;
+            let received = response_size.size(&response);
+            if received > 0 {
+                bucket.debit(clock.now_millis(), received);
+            }
+            Ok(response)
+        })
+    }
+}
+
+struct Bucket {
+    bytes_per_second: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    /// Bytes that may be sent now; negative while paying off earlier transfers.
+    available: f64,
+    updated_ms: Option<u64>,
+}
+
+impl Bucket {
+    /// Refill up to `now_ms`, take `bytes`, and return the resulting balance.
+    fn debit(&self, now_ms: u64, bytes: u64) -> f64 {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(updated) = state.updated_ms {
+            let elapsed = now_ms.saturating_sub(updated) as f64 / 1000.0;
+            state.available = (state.available + elapsed * self.bytes_per_second).min(self.burst);
+        }
+        state.updated_ms = Some(now_ms);
+        state.available -= bytes as f64;
+        state.available
+    }
+
+    /// How long until a balance of `available` has been refilled back to zero.
+    fn wait_for(&self, available: f64) -> Duration {
+        if available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-available / self.bytes_per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use crate::TrackingSleeper;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Clock that only moves when told to.
+    #[derive(Debug, Clone, Default)]
+    struct ManualClock(Arc<Mutex<u64>>);
+
+    impl ManualClock {
+        fn advance(&self, ms: u64) {
+            *self.0.lock().unwrap() += ms;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now_millis(&self) -> u64 {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn echo() -> impl Service<u64, Response = u64, Error = TestError, Future = impl Send> + Clone {
+        tower::service_fn(|n: u64| async move { Ok::<_, TestError>(n) })
+    }
+
+    #[tokio::test]
+    async fn delays_requests_that_overdraw_the_bucket() {
+        let sleeper = TrackingSleeper::new();
+        let clock = ManualClock::default();
+        let sink = MemorySink::new();
+        let layer = BandwidthLayer::new(1_000, 500, |n: &u64| *n)
+            .unwrap()
+            .with_sleeper(sleeper.clone())
+            .with_clock(clock.clone())
+            .with_sink(sink.clone());
+        let mut svc = layer.layer(echo());
+
+        svc.ready().await.unwrap().call(500).await.unwrap();
+        assert_eq!(sleeper.calls(), 0, "burst covers the first request");
+
+        svc.ready().await.unwrap().call(250).await.unwrap();
+        assert_eq!(sleeper.call_at(0), Some(Duration::from_millis(250)));
+
+        // A second later the debt is repaid and the bucket is full again.
+        clock.advance(1_000);
+        assert_eq!(layer.available(), 500);
+        assert_eq!(
+            sink.events(),
+            vec![PolicyEvent::RateLimit(RateLimitEvent::Delayed {
+                wait: Duration::from_millis(250)
+            })]
+        );
+    }
+
+    #[tokio::test]
+    async fn counted_responses_slow_down_later_requests() {
+        let sleeper = TrackingSleeper::new();
+        let layer = BandwidthLayer::new(1_000, 1_000, |_: &u64| 0)
+            .unwrap()
+            .count_responses(|n: &u64| *n)
+            .with_sleeper(sleeper.clone())
+            .with_clock(ManualClock::default());
+        let mut svc = layer.layer(echo());
+
+        svc.ready().await.unwrap().call(3_000).await.unwrap();
+        assert_eq!(sleeper.calls(), 0);
+        assert_eq!(layer.available(), -2_000);
+
+        svc.ready().await.unwrap().call(0).await.unwrap();
+        assert_eq!(sleeper.call_at(0), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn rejects_zero_limits() {
+        let size = |n: &u64| *n;
+        assert_eq!(BandwidthLayer::new(0, 1, size).unwrap_err(), BandwidthError::ZeroRate);
+        assert_eq!(BandwidthLayer::new(1, 0, size).unwrap_err(), BandwidthError::ZeroBurst);
+    }
+}
@@ -0,0 +1,185 @@
+//! Type-erased policies.
+//!
+//! Semantics
+//! - Every composition produces a distinct nested type (`CombinedLayer<FallbackLayer<..>, ..>`),
+//!   which makes stacks hard to store in collections, choose between at runtime, or return from
+//!   functions. [`Policy::boxed`] erases the layer behind a trait object, leaving only the
+//!   service, request, response, and error types in the signature.
+//! - Services produced by a [`BoxLayer`] are [`BoxCloneService`]s, so they can still be cloned
+//!   and composed further with `+`, `|`, and `&`.
+//! - Erasure costs one allocation per `layer` call and one boxed future per request.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::collections::HashMap;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceExt};
+//! use tower_layer::Layer;
+//!
+//! type Svc = tower::util::BoxCloneService<u32, u32, std::io::Error>;
+//! type Err = ResilienceError<std::io::Error>;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut policies: HashMap<&str, BoxPolicy<Svc, u32, u32, Err>> = HashMap::new();
+//! policies.insert("fast", Policy(TimeoutLayer::new(Duration::from_millis(50))?).boxed());
+//! let patient = Policy::fallback_chain([
+//!     Policy(TimeoutLayer::new(Duration::from_millis(50))?),
+//!     Policy(TimeoutLayer::new(Duration::from_secs(2))?),
+//! ])?;
+//! policies.insert("patient", patient.boxed());
+//!
+//! let inner = Svc::new(tower::service_fn(|n: u32| async move { Ok(n * 2) }));
+//! let mut svc = policies["patient"].layer(inner);
+//! assert_eq!(svc.ready().await?.call(21).await?, 42);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::Policy;
+use crate::describe::{Describe, PolicyNode};
+use std::fmt;
+use std::sync::Arc;
+use tower::util::BoxCloneService;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// A [`Policy`] whose layer type has been erased; see [`Policy::boxed`].
+pub type BoxPolicy<S, Request, Response, Error> = Policy<BoxLayer<S, Request, Response, Error>>;
+
+type ResponseOf<L, S, Request> = <<L as Layer<S>>::Service as Service<Request>>::Response;
+type ErrorOf<L, S, Request> = <<L as Layer<S>>::Service as Service<Request>>::Error;
+type BoxedFrom<L, S, Request> =
+    BoxPolicy<S, Request, ResponseOf<L, S, Request>, ErrorOf<L, S, Request>>;
+
+type DynLayer<S, Request, Response, Error> =
+    dyn Layer<S, Service = BoxCloneService<Request, Response, Error>> + Send + Sync;
+
+/// Layer wrapping any other layer behind a trait object.
+///
+/// `S` is the service being wrapped; the produced service handles `Request` and yields
+/// `Result<Response, Error>`.
+pub struct BoxLayer<S, Request, Response, Error> {
+    inner: Arc<DynLayer<S, Request, Response, Error>>,
+}
+
+impl<S, Request, Response, Error> BoxLayer<S, Request, Response, Error> {
+    /// Erase the type of `layer`.
+    pub fn new<L>(layer: L) -> Self
+    where
+        L: Layer<S> + Send + Sync + 'static,
+        L::Service: Service<Request, Response = Response, Error = Error> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+        Request: 'static,
+    {
+        let erased =
+            tower::layer::layer_fn(move |service: S| BoxCloneService::new(layer.layer(service)));
+        Self { inner: Arc::new(erased) }
+    }
+}
+
+impl<S, Request, Response, Error> Clone for BoxLayer<S, Request, Response, Error> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<S, Request, Response, Error> fmt::Debug for BoxLayer<S, Request, Response, Error> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxLayer").finish_non_exhaustive()
+    }
+}
+
+impl<S, Request, Response, Error> Layer<S> for BoxLayer<S, Request, Response, Error> {
+    type Service = BoxCloneService<Request, Response, Error>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        self.inner.layer(service)
+    }
+}
+
+/// Boxed layers have erased their structure and describe themselves as `Boxed`.
+impl<S, Request, Response, Error> Describe for BoxLayer<S, Request, Response, Error> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::layer("Boxed")
+    }
+}
+
+impl<L> Policy<L> {
+    /// Erase this policy's layer type so it can be stored, selected at runtime, or returned
+    /// without spelling out the composed type.
+    pub fn boxed<S, Request>(self) -> BoxedFrom<L, S, Request>
+    where
+        L: Layer<S> + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+        Request: 'static,
+    {
+        Policy(BoxLayer::new(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ResilienceError, TimeoutLayer};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    type Inner = BoxCloneService<&'static str, &'static str, TestError>;
+    type Erased = BoxPolicy<Inner, &'static str, &'static str, ResilienceError<TestError>>;
+
+    fn build(strict: bool) -> Erased {
+        let timeout = Policy(TimeoutLayer::new(Duration::from_millis(10)).unwrap());
+        if strict {
+            timeout.boxed()
+        } else {
+            Policy::fallback_chain([
+                timeout,
+                Policy(TimeoutLayer::new(Duration::from_secs(1)).unwrap()),
+            ])
+            .unwrap()
+            .boxed()
+        }
+    }
+
+    fn echo() -> Inner {
+        BoxCloneService::new(tower::service_fn(|req: &'static str| async move {
+            if req == "fail" {
+                Err(TestError("boom".into()))
+            } else {
+                Ok(req)
+            }
+        }))
+    }
+
+    #[tokio::test]
+    async fn runtime_selected_stacks_share_a_type() {
+        let stacks = vec![build(true), build(false)];
+        for stack in &stacks {
+            let mut svc = stack.layer(echo());
+            assert_eq!(svc.ready().await.unwrap().call("ok").await.unwrap(), "ok");
+        }
+    }
+
+    #[tokio::test]
+    async fn boxed_policies_still_compose() {
+        let erased = build(true);
+        let mut svc = (erased.clone() | erased).layer(echo());
+        let err: ResilienceError<TestError> =
+            svc.ready().await.unwrap().call("fail").await.unwrap_err().into();
+        assert!(matches!(err, ResilienceError::Inner(TestError(ref m)) if m == "boom"));
+    }
+}
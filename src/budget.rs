@@ -0,0 +1,248 @@
+//! Total latency budget for everything beneath a layer.
+//!
+//! Semantics
+//! - [`BudgetLayer`] starts a budget when a request arrives and publishes it as a
+//!   [`Deadline`] in the [`RequestContext`], so it is debited by wall time as inner layers run.
+//!   Layers underneath read what is left through [`RequestContext::remaining_budget`]:
+//!   `TimeoutLayer` shortens each attempt to the remainder, `RetryLayer` stops once the next
+//!   backoff would overrun it, and `FallbackLayer` skips a secondary once it is spent.
+//! - The budget is also enforced: when it runs out the inner future is dropped and the request
+//!   fails with [`ResilienceError::Timeout`], whose `timeout` is the configured budget.
+//! - Budgets nest like deadlines. An inner `BudgetLayer`, or a caller that already scoped a
+//!   deadline, can only tighten the effective budget, never extend it.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // 500ms end to end, however many attempts the retry makes.
+//! let retry = RetryPolicy::<ResilienceError<std::io::Error>>::builder()
+//!     .max_attempts(5)
+//!     .build()?
+//!     .into_layer();
+//! let policy = Policy(BudgetLayer::new(Duration::from_millis(500))?)
+//!     + Policy(retry)
+//!     + Policy(TimeoutLayer::new(Duration::from_millis(200))?);
+//!
+//! let mut svc = ServiceBuilder::new().layer(policy).service_fn(|_: ()| async {
+//!     let left = RequestContext::current().remaining_budget().expect("budget in scope");
+//!     Ok::<_, std::io::Error>(left)
+//! });
+//! assert!(svc.ready().await?.call(()).await? <= Duration::from_millis(500));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, TimeoutEvent};
+use crate::timeout::{TimeoutError, TimeoutPolicy};
+use crate::{Deadline, RequestContext, ResilienceError};
+use futures::future::BoxFuture;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer attaching and enforcing a total latency budget for the stack beneath it.
+#[derive(Debug, Clone)]
+pub struct BudgetLayer<Sink = NullSink> {
+    budget: Duration,
+    sink: Sink,
+}
+
+impl BudgetLayer<NullSink> {
+    /// Give each request `budget` in total.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimeoutError`] if `budget` is zero or exceeds [`MAX_TIMEOUT`](crate::MAX_TIMEOUT).
+    pub fn new(budget: Duration) -> Result<Self, TimeoutError> {
+        Ok(Self { budget: TimeoutPolicy::new(budget)?.duration(), sink: NullSink })
+    }
+}
+
+impl<Sink> BudgetLayer<Sink> {
+    /// The configured budget per request.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Attach a telemetry sink; an exhausted budget emits [`TimeoutEvent::Occurred`].
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> BudgetLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        BudgetLayer { budget: self.budget, sink }
+    }
+}
+
+impl<S, Sink: Clone> Layer<S> for BudgetLayer<Sink> {
+    type Service = BudgetService<S, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BudgetService { inner, budget: self.budget, sink: self.sink.clone() }
+    }
+}
+
+impl<Sink> crate::Describe for BudgetLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Budget({:?})", self.budget))
+    }
+}
+
+/// Service produced by [`BudgetLayer`].
+#[derive(Debug, Clone)]
+pub struct BudgetService<S, Sink = NullSink> {
+    inner: S,
+    budget: Duration,
+    sink: Sink,
+}
+
+impl<S, Request, Sink> Service<Request> for BudgetService<S, Sink>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let start = Instant::now();
+        let ctx = RequestContext::current().with_deadline(Deadline::after(self.budget));
+        let deadline = ctx.deadline().expect("deadline was just attached");
+        let timeout = self.budget;
+        let sink = self.sink.clone();
+        let inner = &mut self.inner;
+        let fut = ctx.clone().sync_scope(|| inner.call(req));
+        let fut = ctx.scope(fut);
+
+        Box::pin(async move {
+            match tokio::time::timeout_at(deadline.instant(), fut).await {
+                Ok(result) => result.map_err(ResilienceError::Inner),
+                Err(_) => {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout }),
+                    )
+                    .await;
+                    Err(ResilienceError::Timeout { elapsed: start.elapsed(), timeout })
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::fmt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    #[tokio::test(start_paused = true)]
+    async fn inner_layers_see_the_budget_being_debited() {
+        let mut svc = BudgetLayer::new(Duration::from_millis(300)).unwrap().layer(
+            tower::service_fn(|_: ()| async {
+                let before = RequestContext::current().remaining_budget().unwrap();
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let after = RequestContext::current().remaining_budget().unwrap();
+                Ok::<_, TestError>((before, after))
+            }),
+        );
+
+        let (before, after) = svc.ready().await.unwrap().call(()).await.unwrap();
+        assert_eq!(before, Duration::from_millis(300));
+        assert_eq!(after, Duration::from_millis(200));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_and_timeouts_beneath_share_the_budget() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        let retry = crate::RetryPolicy::<ResilienceError<TestError>>::builder()
+            .max_attempts(10)
+            .backoff(crate::Backoff::constant(Duration::from_millis(10)))
+            .build()
+            .unwrap()
+            .into_layer();
+        let policy = crate::Policy(BudgetLayer::new(Duration::from_millis(250)).unwrap())
+            + crate::Policy(retry)
+            + crate::Policy(crate::TimeoutLayer::new(Duration::from_millis(100)).unwrap());
+        let mut svc = policy.layer(tower::service_fn(move |_: ()| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok::<_, TestError>(())
+            }
+        }));
+
+        let start = Instant::now();
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert!(start.elapsed() <= Duration::from_millis(250));
+        // 100ms + 10ms backoff + 100ms + 10ms backoff + the last 30ms of budget.
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(err.flatten().is_retry_exhausted());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fails_with_timeout_when_exhausted() {
+        let sink = MemorySink::new();
+        let mut svc = BudgetLayer::new(Duration::from_millis(100))
+            .unwrap()
+            .with_sink(sink.clone())
+            .layer(tower::service_fn(|_: ()| async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok::<_, TestError>(())
+            }));
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(
+            sink.events(),
+            vec![PolicyEvent::Timeout(TimeoutEvent::Occurred {
+                timeout: Duration::from_millis(100)
+            })]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cannot_extend_an_outer_deadline() {
+        let mut svc = BudgetLayer::new(Duration::from_secs(10)).unwrap().layer(tower::service_fn(
+            |_: ()| async {
+                Ok::<_, TestError>(RequestContext::current().remaining_budget().unwrap())
+            },
+        ));
+        let left = RequestContext::new()
+            .with_deadline(Deadline::after(Duration::from_millis(50)))
+            .scope(async { svc.ready().await.unwrap().call(()).await.unwrap() })
+            .await;
+        assert_eq!(left, Duration::from_millis(50));
+    }
+}
@@ -3,7 +3,7 @@
 //! A bulkhead caps concurrent operations to protect downstream services and bound resource usage.
 //! This implementation is non-blocking: when no permits are available it rejects immediately
 //! (`ResilienceError::Bulkhead`) rather than queuing. Permits are released when the wrapped
-//! operation finishes.
+//! operation finishes or its future is dropped.
 //!
 //! Example (brief):
 //! ```rust
@@ -44,7 +44,7 @@ use crate::ResilienceError;
 use futures::future::BoxFuture;
 use std::future::Future;
 use std::sync::Arc;
-use tokio::sync::{Semaphore, TryAcquireError};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
 use tower_layer::Layer;
 use tower_service::Service;
 
@@ -140,8 +140,10 @@ impl BulkheadPolicy {
     }
 }
 
+use crate::policy_metrics::{BulkheadStats, InFlight, MetricsSnapshot, PolicyMetrics};
 use crate::telemetry::{
-    emit_best_effort, BulkheadEvent, BulkheadRejectReason, NullSink, PolicyEvent, RequestOutcome,
+    emit_best_effort, emit_detached, BulkheadEvent, BulkheadRejectReason, NullSink, PolicyEvent,
+    RequestOutcome,
 };
 use std::time::Instant as StdInstant;
 
@@ -153,6 +155,7 @@ use std::time::Instant as StdInstant;
 #[derive(Clone)]
 pub struct BulkheadLayer<Sink = NullSink> {
     max_concurrent: usize,
+    stats: Arc<BulkheadStats>,
     sink: Sink,
 }
 
@@ -160,7 +163,7 @@ impl BulkheadLayer<NullSink> {
     /// Create a bulkhead layer with no telemetry; returns error if `max_concurrent` is zero.
     pub fn new(max_concurrent: usize) -> Result<Self, BulkheadError> {
         BulkheadPolicy::new(max_concurrent)?;
-        Ok(Self { max_concurrent, sink: NullSink })
+        Ok(Self { max_concurrent, stats: Arc::default(), sink: NullSink })
     }
 }
 
@@ -173,7 +176,7 @@ where
     where
         NewSink: Clone,
     {
-        BulkheadLayer { max_concurrent: self.max_concurrent, sink }
+        BulkheadLayer { max_concurrent: self.max_concurrent, stats: self.stats, sink }
     }
 }
 
@@ -182,13 +185,64 @@ where
 pub struct BulkheadService<S, Sink = NullSink> {
     semaphore: Arc<Semaphore>,
     max_concurrent: usize,
+    stats: Arc<BulkheadStats>,
     inner: S,
     sink: Sink,
 }
 
 impl<S, Sink> BulkheadService<S, Sink> {
-    fn new(inner: S, max_concurrent: usize, sink: Sink) -> Self {
-        Self { semaphore: Arc::new(Semaphore::new(max_concurrent)), max_concurrent, inner, sink }
+    fn new(inner: S, max_concurrent: usize, stats: Arc<BulkheadStats>, sink: Sink) -> Self {
+        let semaphore = Arc::new(Semaphore::new(max_concurrent));
+        stats.register(&semaphore);
+        Self { semaphore, max_concurrent, stats, inner, sink }
+    }
+}
+
+/// A held permit that reports [`BulkheadEvent::Released`] when it is returned, including when
+/// the call's future is dropped before the inner service finishes.
+struct Release<Sink>
+where
+    Sink: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    held: Option<(InFlight, OwnedSemaphorePermit)>,
+    semaphore: Arc<Semaphore>,
+    max: usize,
+    sink: Sink,
+}
+
+impl<Sink> Release<Sink>
+where
+    Sink: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    fn release(&mut self) -> Option<PolicyEvent> {
+        drop(self.held.take()?);
+        Some(PolicyEvent::Bulkhead(BulkheadEvent::Released {
+            active_count: self.max.saturating_sub(self.semaphore.available_permits()),
+            max_concurrency: self.max,
+        }))
+    }
+
+    async fn finish(mut self) {
+        if let Some(event) = self.release() {
+            emit_best_effort(self.sink.clone(), event).await;
+        }
+    }
+}
+
+impl<Sink> Drop for Release<Sink>
+where
+    Sink: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    fn drop(&mut self) {
+        if let Some(event) = self.release() {
+            emit_detached(self.sink.clone(), event);
+        }
     }
 }
 
@@ -219,6 +273,7 @@ where
         let semaphore = self.semaphore.clone();
         let mut inner = self.inner.clone();
         let max = self.max_concurrent;
+        let stats = Arc::clone(&self.stats);
         let sink = self.sink.clone();
 
         Box::pin(async move {
@@ -237,6 +292,7 @@ where
                     p
                 }
                 Err(TryAcquireError::NoPermits) => {
+                    stats.rejected();
                     let available_after = semaphore.available_permits();
                     let active_count = max.saturating_sub(available_after);
                     emit_best_effort(
@@ -251,6 +307,7 @@ where
                     return Err(ResilienceError::Bulkhead { in_flight: active_count, max });
                 }
                 Err(TryAcquireError::Closed) => {
+                    stats.rejected();
                     emit_best_effort(
                         sink.clone(),
                         PolicyEvent::Bulkhead(BulkheadEvent::Rejected {
@@ -266,8 +323,14 @@ where
                 }
             };
 
+            let release =
+                Release { held: Some((stats.enter(), permit)), semaphore, max, sink: sink.clone() };
             let result = inner.call(req).await;
-            drop(permit);
+            release.finish().await;
+            match &result {
+                Ok(_) => stats.success(),
+                Err(_) => stats.failure(),
+            }
 
             let duration = start.elapsed();
             match &result {
@@ -298,7 +361,24 @@ where
 {
     type Service = BulkheadService<S, Sink>;
     fn layer(&self, service: S) -> Self::Service {
-        BulkheadService::new(service, self.max_concurrent, self.sink.clone())
+        BulkheadService::new(
+            service,
+            self.max_concurrent,
+            Arc::clone(&self.stats),
+            self.sink.clone(),
+        )
+    }
+}
+
+impl<Sink> crate::Describe for BulkheadLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Bulkhead({})", self.max_concurrent))
+    }
+}
+
+impl<Sink> PolicyMetrics for BulkheadLayer<Sink> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.stats.snapshot(self.max_concurrent)
     }
 }
 
@@ -576,7 +656,8 @@ mod tests {
     #[tokio::test]
     async fn service_fails_fast_when_permits_exhausted() {
         let (tx, rx) = oneshot::channel();
-        let mut svc = BulkheadService::new(HoldService::with_block(rx), 1, NullSink);
+        let mut svc =
+            BulkheadService::new(HoldService::with_block(rx), 1, Arc::default(), NullSink);
 
         // First call acquires the single permit and blocks until `tx` fires
         let first = tokio::spawn({
@@ -597,4 +678,51 @@ mod tests {
         let _ = tx.send(());
         assert!(first.await.unwrap().is_ok());
     }
+
+    #[tokio::test]
+    async fn release_reports_remaining_in_flight() {
+        let sink = crate::telemetry::MemorySink::new();
+        let layer = BulkheadLayer::new(2).unwrap().with_sink(sink.clone());
+        let svc = layer.layer(tower::service_fn(|_: ()| async { Ok::<_, TestError>(()) }));
+
+        tower::ServiceExt::oneshot(svc, ()).await.unwrap();
+
+        let bulkhead: Vec<_> =
+            sink.events().into_iter().filter(|e| matches!(e, PolicyEvent::Bulkhead(_))).collect();
+        assert_eq!(
+            bulkhead,
+            vec![
+                PolicyEvent::Bulkhead(BulkheadEvent::Acquired {
+                    active_count: 1,
+                    max_concurrency: 2
+                }),
+                PolicyEvent::Bulkhead(BulkheadEvent::Released {
+                    active_count: 0,
+                    max_concurrency: 2
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn dropped_calls_release_and_report_it() {
+        let sink = crate::telemetry::MemorySink::new();
+        let layer = BulkheadLayer::new(2).unwrap().with_sink(sink.clone());
+        let svc =
+            layer.layer(tower::service_fn(|_: ()| std::future::pending::<Result<(), TestError>>()));
+
+        let call = tokio::spawn(tower::ServiceExt::oneshot(svc, ()));
+        while layer.metrics().bulkhead().unwrap().in_flight == 0 {
+            tokio::task::yield_now().await;
+        }
+        call.abort();
+        assert!(call.await.unwrap_err().is_cancelled());
+        assert_eq!(layer.metrics().bulkhead().unwrap().in_flight, 0);
+
+        let released =
+            PolicyEvent::Bulkhead(BulkheadEvent::Released { active_count: 0, max_concurrency: 2 });
+        while !sink.events().contains(&released) {
+            tokio::task::yield_now().await;
+        }
+    }
 }
@@ -24,7 +24,7 @@ pub enum CircuitState {
 }
 
 impl CircuitState {
-    fn to_u8(self) -> u8 {
+    pub(crate) fn to_u8(self) -> u8 {
         match self {
             CircuitState::Closed => STATE_CLOSED,
             CircuitState::Open => STATE_OPEN,
@@ -32,7 +32,7 @@ impl CircuitState {
         }
     }
 
-    fn from_u8(v: u8) -> CircuitState {
+    pub(crate) fn from_u8(v: u8) -> CircuitState {
         match v {
             STATE_CLOSED => CircuitState::Closed,
             STATE_OPEN => CircuitState::Open,
@@ -125,10 +125,12 @@ impl CircuitBreakerConfig {
 }
 
 #[derive(Debug)]
-struct CircuitBreakerState {
+pub(crate) struct CircuitBreakerState {
     state: AtomicU8,
     failure_count: AtomicUsize,
     opened_at_millis: AtomicU64,
+    /// When the circuit last left `Closed`; unlike `opened_at_millis`, failed probes keep it.
+    tripped_at_millis: AtomicU64,
     half_open_calls: AtomicUsize,
 }
 
@@ -138,11 +140,17 @@ impl CircuitBreakerState {
             state: AtomicU8::new(CircuitState::Closed.to_u8()),
             failure_count: AtomicUsize::new(0),
             opened_at_millis: AtomicU64::new(0),
+            tripped_at_millis: AtomicU64::new(0),
             half_open_calls: AtomicUsize::new(0),
         }
     }
+
+    pub(crate) fn current(&self) -> CircuitState {
+        CircuitState::from_u8(self.state.load(Ordering::Acquire))
+    }
 }
 
+use crate::policy_metrics::{CircuitBreakerStats, MetricsSnapshot, PolicyMetrics};
 use crate::telemetry::{
     emit_best_effort, CircuitBreakerEvent, NullSink, PolicyEvent, RequestOutcome,
 };
@@ -153,6 +161,7 @@ use std::time::Instant as StdInstant;
 pub struct CircuitBreakerLayer<Sink = NullSink> {
     config: CircuitBreakerConfig,
     clock: Arc<dyn Clock>,
+    stats: Arc<CircuitBreakerStats>,
     sink: Sink,
 }
 
@@ -166,7 +175,12 @@ impl CircuitBreakerLayer<NullSink> {
     /// Returns error if the configuration is invalid.
     pub fn new(config: CircuitBreakerConfig) -> Result<Self, CircuitBreakerError> {
         config.validate()?;
-        Ok(Self { config, clock: Arc::new(MonotonicClock::default()), sink: NullSink })
+        Ok(Self {
+            config,
+            clock: Arc::new(MonotonicClock::default()),
+            stats: Arc::default(),
+            sink: NullSink,
+        })
     }
 
     /// Create a circuit breaker layer with a custom clock implementation and no telemetry.
@@ -181,7 +195,7 @@ impl CircuitBreakerLayer<NullSink> {
         clock: C,
     ) -> Result<Self, CircuitBreakerError> {
         config.validate()?;
-        Ok(Self { config, clock: Arc::new(clock), sink: NullSink })
+        Ok(Self { config, clock: Arc::new(clock), stats: Arc::default(), sink: NullSink })
     }
 }
 
@@ -194,7 +208,7 @@ where
     where
         NewSink: Clone,
     {
-        CircuitBreakerLayer { config: self.config, clock: self.clock, sink }
+        CircuitBreakerLayer { config: self.config, clock: self.clock, stats: self.stats, sink }
     }
 }
 
@@ -205,12 +219,21 @@ pub struct CircuitBreakerService<S, Sink = NullSink> {
     state: Arc<CircuitBreakerState>,
     config: CircuitBreakerConfig,
     clock: Arc<dyn Clock>,
+    stats: Arc<CircuitBreakerStats>,
     sink: Sink,
 }
 
 impl<S, Sink> CircuitBreakerService<S, Sink> {
-    fn new(inner: S, config: CircuitBreakerConfig, clock: Arc<dyn Clock>, sink: Sink) -> Self {
-        Self { inner, state: Arc::new(CircuitBreakerState::new()), config, clock, sink }
+    fn new(
+        inner: S,
+        config: CircuitBreakerConfig,
+        clock: Arc<dyn Clock>,
+        stats: Arc<CircuitBreakerStats>,
+        sink: Sink,
+    ) -> Self {
+        let state = Arc::new(CircuitBreakerState::new());
+        stats.register(&state);
+        Self { inner, state, config, clock, stats, sink }
     }
 }
 
@@ -241,6 +264,7 @@ where
         let state = self.state.clone();
         let config = self.config.clone();
         let clock = self.clock.clone();
+        let stats = self.stats.clone();
         let sink = self.sink.clone();
 
         Box::pin(async move {
@@ -252,6 +276,7 @@ where
                 CircuitState::Open => {
                     let opened_at = state.opened_at_millis.load(Ordering::Acquire);
                     if now.saturating_sub(opened_at) < config.recovery_timeout.as_millis() as u64 {
+                        stats.rejected();
                         return Err(ResilienceError::CircuitOpen {
                             failure_count: state.failure_count.load(Ordering::Acquire),
                             open_duration: Duration::from_millis(now.saturating_sub(opened_at)),
@@ -265,9 +290,12 @@ where
                         Ordering::Acquire,
                     );
                     if prev.is_ok() {
+                        let open_duration = Duration::from_millis(now.saturating_sub(opened_at));
                         emit_best_effort(
                             sink.clone(),
-                            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::HalfOpen),
+                            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::HalfOpen {
+                                open_duration,
+                            }),
                         )
                         .await;
                     }
@@ -277,6 +305,7 @@ where
                 CircuitState::HalfOpen => {
                     let calls = state.half_open_calls.fetch_add(1, Ordering::AcqRel) + 1;
                     if calls > config.half_open_max_calls {
+                        stats.rejected();
                         return Err(ResilienceError::CircuitOpen {
                             failure_count: state.failure_count.load(Ordering::Acquire),
                             open_duration: Duration::ZERO,
@@ -288,15 +317,21 @@ where
 
             match inner.call(req).await {
                 Ok(resp) => {
+                    stats.success();
                     let prev_state = CircuitState::from_u8(state.state.load(Ordering::Acquire));
                     state.state.store(CircuitState::Closed.to_u8(), Ordering::Release);
                     state.failure_count.store(0, Ordering::Release);
 
                     // Emit closed event if transitioning from non-closed state
                     if prev_state != CircuitState::Closed {
+                        let tripped_at = state.tripped_at_millis.load(Ordering::Acquire);
+                        let open_duration =
+                            Duration::from_millis(clock.now_millis().saturating_sub(tripped_at));
                         emit_best_effort(
                             sink.clone(),
-                            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Closed),
+                            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Closed {
+                                open_duration,
+                            }),
                         )
                         .await;
                     }
@@ -312,6 +347,7 @@ where
                     Ok(resp)
                 }
                 Err(err) => {
+                    stats.failure();
                     let failures = state.failure_count.fetch_add(1, Ordering::AcqRel) + 1;
                     match CircuitState::from_u8(state.state.load(Ordering::Acquire)) {
                         CircuitState::Closed => {
@@ -324,6 +360,10 @@ where
                                     Ordering::Acquire,
                                 );
                                 if prev.is_ok() {
+                                    stats.opened();
+                                    state
+                                        .tripped_at_millis
+                                        .store(clock.now_millis(), Ordering::Release);
                                     emit_best_effort(
                                         sink.clone(),
                                         PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened {
@@ -337,12 +377,15 @@ where
                             }
                         }
                         CircuitState::HalfOpen => {
-                            let _ = state.state.compare_exchange(
+                            let reopened = state.state.compare_exchange(
                                 CircuitState::HalfOpen.to_u8(),
                                 CircuitState::Open.to_u8(),
                                 Ordering::AcqRel,
                                 Ordering::Acquire,
                             );
+                            if reopened.is_ok() {
+                                stats.opened();
+                            }
                             state.half_open_calls.store(0, Ordering::Release);
                             state.opened_at_millis.store(clock.now_millis(), Ordering::Release);
                         }
@@ -365,7 +408,103 @@ where
             service,
             self.config.clone(),
             self.clock.clone(),
+            self.stats.clone(),
             self.sink.clone(),
         )
     }
 }
+
+impl<Sink> crate::Describe for CircuitBreakerLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!(
+            "CircuitBreaker({} failures, {:?})",
+            self.config.failure_threshold, self.config.recovery_timeout
+        ))
+    }
+}
+
+impl<Sink> PolicyMetrics for CircuitBreakerLayer<Sink> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.stats.snapshot()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::fmt;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    #[derive(Debug, Clone, Default)]
+    struct ManualClock(Arc<AtomicU64>);
+
+    impl ManualClock {
+        fn advance(&self, millis: u64) {
+            self.0.fetch_add(millis, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now_millis(&self) -> u64 {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn transition_events_report_time_in_state() {
+        let clock = ManualClock::default();
+        let sink = MemorySink::new();
+        let config = CircuitBreakerConfig::new(1, Duration::from_secs(10), 1).unwrap();
+        let layer = CircuitBreakerLayer::with_clock(config, clock.clone()).unwrap();
+        let fail = Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let failing = Arc::clone(&fail);
+        let svc = layer.with_sink(sink.clone()).layer(tower::service_fn(move |_: ()| {
+            let fail = failing.load(Ordering::SeqCst);
+            async move {
+                if fail {
+                    Err(TestError("down".into()))
+                } else {
+                    Ok(())
+                }
+            }
+        }));
+
+        // Trip at t=0, fail the first probe at t=12s, recover on the second at t=25s.
+        assert!(svc.clone().oneshot(()).await.is_err());
+        clock.advance(12_000);
+        assert!(svc.clone().oneshot(()).await.is_err());
+        clock.advance(13_000);
+        fail.store(false, Ordering::SeqCst);
+        svc.oneshot(()).await.unwrap();
+
+        let transitions: Vec<_> = sink
+            .events()
+            .into_iter()
+            .filter_map(|e| match e {
+                PolicyEvent::CircuitBreaker(e) => Some(e),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                CircuitBreakerEvent::Opened { failure_count: 1 },
+                CircuitBreakerEvent::HalfOpen { open_duration: Duration::from_secs(12) },
+                CircuitBreakerEvent::HalfOpen { open_duration: Duration::from_secs(13) },
+                CircuitBreakerEvent::Closed { open_duration: Duration::from_secs(25) },
+            ]
+        );
+    }
+}
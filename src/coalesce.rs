@@ -0,0 +1,461 @@
+//! Request coalescing ("singleflight"): one inner call per key at a time.
+//!
+//! Semantics
+//! - Each request is mapped to a key by a closure. While a call for a key is in flight, further
+//!   requests with the same key do not reach the inner service; they wait for that call and
+//!   receive a clone of its result, success or failure.
+//! - Once the call completes the key is released, so the next request starts a new call.
+//!   Nothing is cached; pair with [`StaleCacheLayer`](crate::StaleCacheLayer) for that.
+//! - Every request that joins an existing call emits [`CoalesceEvent::Joined`] with the number
+//!   of requests currently waiting on it; requests that were dropped (for example by an outer
+//!   timeout) no longer count.
+//! - A request that joins a call never reaches the inner service; the readiness reserved by
+//!   `poll_ready` stays with the service for its next call.
+//!
+//! Invariants
+//! - Services built from the same layer share the in-flight table.
+//! - A call keeps running while any waiter remains. If every waiter is dropped, the call is
+//!   cancelled and the next request for the key starts a new one.
+//! - Responses and errors must be `Clone` to be fanned out; wrap a non-`Clone` error with
+//!   `map_err(Arc::new)` below this layer.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(CoalesceLayer::new(|path: &String| path.clone()))
+//!     .service_fn(|path: String| async move { Ok::<_, String>(path.len()) });
+//!
+//! let (a, b) = futures::join!(
+//!     svc.clone().oneshot("/config".to_string()),
+//!     svc.ready().await?.call("/config".to_string()),
+//! );
+//! assert_eq!((a?, b?), (7, 7));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, CoalesceEvent, NullSink, PolicyEvent};
+use futures::channel::oneshot;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+type Flights<K, V, E> = Arc<Mutex<HashMap<K, Flight<V, E>>>>;
+
+/// Source of [`Flight::id`]s.
+static FLIGHT_IDS: AtomicU64 = AtomicU64::new(0);
+
+struct Flight<V, E> {
+    /// Tells this call apart from later calls for the same key.
+    id: u64,
+    call: Shared<BoxFuture<'static, Result<V, E>>>,
+    waiters: usize,
+}
+
+/// Counts one request as waiting on flight `id` until the request's future is dropped.
+struct Waiter<K: Eq + Hash, V, E> {
+    flights: Flights<K, V, E>,
+    key: K,
+    id: u64,
+}
+
+impl<K: Eq + Hash, V, E> Drop for Waiter<K, V, E> {
+    fn drop(&mut self) {
+        let mut flights = self.flights.lock().unwrap_or_else(PoisonError::into_inner);
+        let abandoned = match flights.get_mut(&self.key) {
+            Some(flight) if flight.id == self.id => {
+                flight.waiters = flight.waiters.saturating_sub(1);
+                flight.waiters == 0
+            }
+            _ => false,
+        };
+        // With nobody left waiting, cancel the call so the next request starts a fresh one.
+        // Dropping the call may take the lock, so it happens after unlocking.
+        let flight = if abandoned { flights.remove(&self.key) } else { None };
+        drop(flights);
+        drop(flight);
+    }
+}
+
+/// Releases the key of flight `id` once its call finishes (or is dropped).
+struct Landed<K: Eq + Hash, V, E> {
+    flights: Flights<K, V, E>,
+    key: K,
+    id: u64,
+}
+
+impl<K: Eq + Hash, V, E> Drop for Landed<K, V, E> {
+    fn drop(&mut self) {
+        let mut flights = self.flights.lock().unwrap_or_else(PoisonError::into_inner);
+        let flight = match flights.get(&self.key) {
+            Some(flight) if flight.id == self.id => flights.remove(&self.key),
+            _ => None,
+        };
+        drop(flights);
+        drop(flight);
+    }
+}
+
+/// Layer merging concurrent requests with the same key into a single inner call.
+///
+/// `F` derives a key of type `K` from each request; `V` and `E` are the inner service's response
+/// and error types.
+pub struct CoalesceLayer<K, V, E, F, Sink = NullSink> {
+    key: Arc<F>,
+    flights: Flights<K, V, E>,
+    sink: Sink,
+}
+
+impl<K, V, E, F> CoalesceLayer<K, V, E, F, NullSink> {
+    /// Coalesce requests for which `key(&request)` is equal.
+    pub fn new<Request>(key: F) -> Self
+    where
+        F: Fn(&Request) -> K,
+    {
+        Self { key: Arc::new(key), flights: Arc::new(Mutex::new(HashMap::new())), sink: NullSink }
+    }
+}
+
+impl<K, V, E, F, Sink> CoalesceLayer<K, V, E, F, Sink> {
+    /// Attach a telemetry sink to this layer.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> CoalesceLayer<K, V, E, F, NewSink>
+    where
+        NewSink: Clone,
+    {
+        CoalesceLayer { key: self.key, flights: self.flights, sink }
+    }
+}
+
+impl<K, V, E, F, Sink: Clone> Clone for CoalesceLayer<K, V, E, F, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            key: Arc::clone(&self.key),
+            flights: Arc::clone(&self.flights),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<K, V, E, F, Sink: fmt::Debug> fmt::Debug for CoalesceLayer<K, V, E, F, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoalesceLayer").field("sink", &self.sink).finish_non_exhaustive()
+    }
+}
+
+impl<S, K, V, E, F, Sink: Clone> Layer<S> for CoalesceLayer<K, V, E, F, Sink> {
+    type Service = CoalesceService<S, K, V, E, F, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CoalesceService {
+            inner,
+            key: Arc::clone(&self.key),
+            flights: Arc::clone(&self.flights),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<K, V, E, F, Sink> crate::Describe for CoalesceLayer<K, V, E, F, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer("Coalesce")
+    }
+}
+
+/// Service produced by [`CoalesceLayer`].
+pub struct CoalesceService<S, K, V, E, F, Sink = NullSink> {
+    inner: S,
+    key: Arc<F>,
+    flights: Flights<K, V, E>,
+    sink: Sink,
+}
+
+impl<S: Clone, K, V, E, F, Sink: Clone> Clone for CoalesceService<S, K, V, E, F, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            key: Arc::clone(&self.key),
+            flights: Arc::clone(&self.flights),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<S, K, V, E, F, Sink> fmt::Debug for CoalesceService<S, K, V, E, F, Sink>
+where
+    S: fmt::Debug,
+    Sink: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoalesceService")
+            .field("inner", &self.inner)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, Request, K, V, E, F, Sink> Service<Request> for CoalesceService<S, K, V, E, F, Sink>
+where
+    S: Service<Request, Response = V, Error = E>,
+    S::Future: Send + 'static,
+    F: Fn(&Request) -> K,
+    K: Eq + Hash + Clone + Send + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = V;
+    type Error = E;
+    type Future = BoxFuture<'static, Result<V, E>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let key = (self.key)(&req);
+        let mut flights = self.flights.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(flight) = flights.get_mut(&key) {
+            flight.waiters += 1;
+            let waiters = flight.waiters;
+            let call = flight.call.clone();
+            let waiter = Waiter { flights: Arc::clone(&self.flights), key, id: flight.id };
+            drop(flights);
+            // This request never reaches `inner`; the readiness `poll_ready` reserved stays
+            // with this service for its next call.
+            let sink = self.sink.clone();
+            return Box::pin(async move {
+                let _waiter = waiter;
+                emit_best_effort(sink, PolicyEvent::Coalesce(CoalesceEvent::Joined { waiters }))
+                    .await;
+                call.await
+            });
+        }
+
+        // Publish the flight first so identical requests join it, then start the inner call
+        // once the table is unlocked.
+        let id = FLIGHT_IDS.fetch_add(1, Ordering::Relaxed);
+        let (start, started) = oneshot::channel::<S::Future>();
+        let landed = Landed { flights: Arc::clone(&self.flights), key: key.clone(), id };
+        let call = async move {
+            let _landed = landed;
+            // The sender is only dropped unsent if `inner.call` panicked.
+            let fut = started.await.expect("inner service panicked starting a coalesced call");
+            fut.await
+        }
+        .boxed()
+        .shared();
+        flights.insert(key.clone(), Flight { id, call: call.clone(), waiters: 1 });
+        drop(flights);
+        let waiter = Waiter { flights: Arc::clone(&self.flights), key, id };
+        let _ = start.send(self.inner.call(req));
+        Box::pin(async move {
+            let _waiter = waiter;
+            call.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Backend that counts calls and blocks until `release` is notified.
+    fn backend(
+        calls: Arc<AtomicUsize>,
+        release: Arc<Notify>,
+        fail: bool,
+    ) -> impl Service<
+        u32,
+        Response = u32,
+        Error = TestError,
+        Future = BoxFuture<'static, Result<u32, TestError>>,
+    > + Clone {
+        tower::service_fn(move |req: u32| {
+            let (calls, release) = (Arc::clone(&calls), Arc::clone(&release));
+            Box::pin(async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                release.notified().await;
+                if fail {
+                    Err(TestError(format!("failed {}", req)))
+                } else {
+                    Ok(req * 10)
+                }
+            }) as BoxFuture<'static, _>
+        })
+    }
+
+    /// Wait until `n` requests are attached to the in-flight call for `key`.
+    async fn joined<S, V, E, F, Sink>(
+        svc: &CoalesceService<S, u32, V, E, F, Sink>,
+        key: u32,
+        n: usize,
+    ) {
+        let count = || svc.flights.lock().unwrap().get(&key).map_or(0, |f| f.waiters);
+        while count() < n {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_identical_requests_share_one_call() {
+        let (calls, release) = (Arc::new(AtomicUsize::new(0)), Arc::new(Notify::new()));
+        let sink = MemorySink::new();
+        let svc = CoalesceLayer::new(|req: &u32| *req).with_sink(sink.clone()).layer(backend(
+            Arc::clone(&calls),
+            Arc::clone(&release),
+            false,
+        ));
+
+        let waiters: Vec<_> = (0..3).map(|_| tokio::spawn(svc.clone().oneshot(4))).collect();
+        let other = tokio::spawn(svc.clone().oneshot(5));
+        joined(&svc, 4, 3).await;
+        joined(&svc, 5, 1).await;
+        release.notify_waiters();
+
+        for waiter in waiters {
+            assert_eq!(waiter.await.unwrap(), Ok(40));
+        }
+        assert_eq!(other.await.unwrap(), Ok(50));
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "one call per distinct key");
+        assert_eq!(
+            sink.events().last(),
+            Some(&PolicyEvent::Coalesce(CoalesceEvent::Joined { waiters: 3 }))
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_fan_out_and_release_the_key() {
+        let (calls, release) = (Arc::new(AtomicUsize::new(0)), Arc::new(Notify::new()));
+        let svc = CoalesceLayer::new(|req: &u32| *req).layer(backend(
+            Arc::clone(&calls),
+            Arc::clone(&release),
+            true,
+        ));
+
+        let a = tokio::spawn(svc.clone().oneshot(1));
+        let b = tokio::spawn(svc.clone().oneshot(1));
+        joined(&svc, 1, 2).await;
+        release.notify_waiters();
+        let expected = Err(TestError("failed 1".into()));
+        assert_eq!(a.await.unwrap(), expected);
+        assert_eq!(b.await.unwrap(), expected);
+
+        let c = tokio::spawn(svc.clone().oneshot(1));
+        joined(&svc, 1, 1).await;
+        release.notify_waiters();
+        assert_eq!(c.await.unwrap(), expected);
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "completed calls are not reused");
+    }
+
+    #[tokio::test]
+    async fn dropped_joiners_stop_counting_as_waiters() {
+        let (calls, release) = (Arc::new(AtomicUsize::new(0)), Arc::new(Notify::new()));
+        let sink = MemorySink::new();
+        let svc = CoalesceLayer::new(|req: &u32| *req).with_sink(sink.clone()).layer(backend(
+            Arc::clone(&calls),
+            Arc::clone(&release),
+            false,
+        ));
+
+        let leader = tokio::spawn(svc.clone().oneshot(7));
+        let impatient = tokio::spawn(svc.clone().oneshot(7));
+        joined(&svc, 7, 2).await;
+        impatient.abort();
+        assert!(impatient.await.unwrap_err().is_cancelled());
+        assert_eq!(svc.flights.lock().unwrap()[&7].waiters, 1);
+
+        let patient = tokio::spawn(svc.clone().oneshot(7));
+        joined(&svc, 7, 2).await;
+        release.notify_waiters();
+        assert_eq!(leader.await.unwrap(), Ok(70));
+        assert_eq!(patient.await.unwrap(), Ok(70));
+        assert_eq!(
+            sink.events().last(),
+            Some(&PolicyEvent::Coalesce(CoalesceEvent::Joined { waiters: 2 }))
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn joiners_keep_inner_readiness_for_the_next_call() {
+        let (calls, release) = (Arc::new(AtomicUsize::new(0)), Arc::new(Notify::new()));
+        let limited = tower::limit::ConcurrencyLimit::new(
+            backend(Arc::clone(&calls), Arc::clone(&release), false),
+            2,
+        );
+        let mut svc = CoalesceLayer::new(|req: &u32| *req).layer(limited);
+
+        let leader = tokio::spawn(svc.clone().oneshot(1));
+        joined(&svc, 1, 1).await;
+        let joiner = svc.ready().await.unwrap().call(1);
+
+        // The joiner left the second permit reserved in `svc`; its next call uses it.
+        assert!(svc.ready().now_or_never().is_some(), "reserved permit was lost");
+        let next = tokio::spawn(svc.call(2));
+        while calls.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        release.notify_waiters();
+        assert_eq!(leader.await.unwrap(), Ok(10));
+        assert_eq!(joiner.await, Ok(10));
+        assert_eq!(next.await.unwrap(), Ok(20));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn abandoned_calls_are_cancelled_and_restarted() {
+        let (calls, release) = (Arc::new(AtomicUsize::new(0)), Arc::new(Notify::new()));
+        let svc = CoalesceLayer::new(|req: &u32| *req).layer(backend(
+            Arc::clone(&calls),
+            Arc::clone(&release),
+            false,
+        ));
+
+        let first = tokio::spawn(svc.clone().oneshot(3));
+        let second = tokio::spawn(svc.clone().oneshot(3));
+        joined(&svc, 3, 2).await;
+        while calls.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        first.abort();
+        second.abort();
+        let _ = (first.await, second.await);
+        assert!(svc.flights.lock().unwrap().is_empty(), "abandoned flight still listed");
+
+        let fresh = tokio::spawn(svc.clone().oneshot(3));
+        while calls.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+        release.notify_waiters();
+        assert_eq!(fresh.await.unwrap(), Ok(30));
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "the fresh request started a new call");
+    }
+}
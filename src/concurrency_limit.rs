@@ -0,0 +1,425 @@
+//! Adaptive concurrency limiting from latency gradients.
+//!
+//! Semantics
+//! - Works like a bulkhead whose permit count is discovered at runtime: requests beyond the
+//!   current limit are rejected with [`ResilienceError::Bulkhead`] (reporting the live limit as
+//!   `max`) and emit [`ConcurrencyEvent::Rejected`].
+//! - Every successful response contributes an RTT sample. A slow-moving average of those samples
+//!   is the baseline; the gradient `baseline / sample` (clamped to `[0.5, 1.0]`) shrinks the limit
+//!   when latency rises above the baseline, and a `sqrt(limit)` headroom term lets it probe
+//!   upward while latency stays flat. This follows the gradient approach of Netflix's
+//!   `concurrency-limits`.
+//! - The limit only grows while at least half of it is in use, so an idle service does not
+//!   inflate its limit without evidence.
+//! - Failed requests do not produce samples; fast failures would otherwise look like spare
+//!   capacity.
+//!
+//! Invariants
+//! - The limit stays within the configured bounds and is published, rounded, through
+//!   [`AdaptiveConcurrencyLayer::limit`]; [`ConcurrencyEvent::LimitChanged`] fires whenever the
+//!   rounded value moves.
+//! - Services built from the same layer share one limit and in-flight count.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let layer = AdaptiveConcurrencyLayer::new(16)?.bounds(4, 256)?;
+//! let limit = layer.limit().clone();
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(layer)
+//!     .service_fn(|req: u32| async move { Ok::<_, std::io::Error>(req) });
+//! assert_eq!(svc.ready().await?.call(1).await?, 1);
+//! assert!((4..=256).contains(&limit.get()));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::adaptive::Adaptive;
+use crate::telemetry::{emit_best_effort, ConcurrencyEvent, NullSink, PolicyEvent};
+use crate::ResilienceError;
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Weight of each new sample in the baseline RTT average.
+const BASELINE_ALPHA: f64 = 0.05;
+
+/// Errors produced while configuring an adaptive concurrency limiter.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConcurrencyLimitError {
+    /// A limit or bound was zero.
+    ZeroLimit,
+    /// `min` was greater than `max`, or the initial limit was outside them.
+    InvalidBounds {
+        /// Lower bound supplied.
+        min: usize,
+        /// Upper bound supplied.
+        max: usize,
+        /// Initial limit.
+        initial: usize,
+    },
+    /// `smoothing` was outside `(0.0, 1.0]`.
+    InvalidSmoothing {
+        /// The rejected value.
+        provided: f64,
+    },
+}
+
+impl fmt::Display for ConcurrencyLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroLimit => write!(f, "concurrency limits must be > 0"),
+            Self::InvalidBounds { min, max, initial } => write!(
+                f,
+                "concurrency limit bounds must satisfy min <= initial <= max (got {} <= {} <= {})",
+                min, initial, max
+            ),
+            Self::InvalidSmoothing { provided } => {
+                write!(f, "concurrency limit smoothing must be within (0, 1] (got {})", provided)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConcurrencyLimitError {}
+
+/// Tower layer enforcing a concurrency limit estimated from response latency.
+#[derive(Clone, Debug)]
+pub struct AdaptiveConcurrencyLayer<Sink = NullSink> {
+    limiter: Arc<Limiter>,
+    sink: Sink,
+}
+
+impl AdaptiveConcurrencyLayer<NullSink> {
+    /// Start at `initial` in-flight requests, bounded to `[1, 1000]` with smoothing 0.2.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `initial` is zero or above the default upper bound.
+    pub fn new(initial: usize) -> Result<Self, ConcurrencyLimitError> {
+        let config = Config { min: 1, max: 1000, smoothing: 0.2 };
+        Ok(Self { limiter: Arc::new(Limiter::new(initial, config)?), sink: NullSink })
+    }
+}
+
+impl<Sink> AdaptiveConcurrencyLayer<Sink> {
+    /// Keep the estimated limit within `[min, max]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a bound is zero, `min > max`, or the initial limit lies outside.
+    pub fn bounds(self, min: usize, max: usize) -> Result<Self, ConcurrencyLimitError> {
+        let config = Config { min, max, ..self.limiter.config };
+        self.rebuild(config)
+    }
+
+    /// Fraction of each new estimate blended into the limit; lower values adapt more slowly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConcurrencyLimitError::InvalidSmoothing`] unless within `(0.0, 1.0]`.
+    pub fn smoothing(self, smoothing: f64) -> Result<Self, ConcurrencyLimitError> {
+        let config = Config { smoothing, ..self.limiter.config };
+        self.rebuild(config)
+    }
+
+    /// Attach a telemetry sink to this limiter.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> AdaptiveConcurrencyLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        AdaptiveConcurrencyLayer { limiter: self.limiter, sink }
+    }
+
+    /// Live handle to the current limit.
+    pub fn limit(&self) -> &Adaptive<usize> {
+        &self.limiter.published
+    }
+
+    fn rebuild(self, config: Config) -> Result<Self, ConcurrencyLimitError> {
+        let initial = self.limiter.published.get();
+        Ok(Self { limiter: Arc::new(Limiter::new(initial, config)?), sink: self.sink })
+    }
+}
+
+impl<S, Sink: Clone> Layer<S> for AdaptiveConcurrencyLayer<Sink> {
+    type Service = AdaptiveConcurrencyService<S, Sink>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        AdaptiveConcurrencyService {
+            inner: service,
+            limiter: Arc::clone(&self.limiter),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<Sink> crate::Describe for AdaptiveConcurrencyLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        let Config { min, max, .. } = self.limiter.config;
+        crate::PolicyNode::layer(format!(
+            "AdaptiveConcurrency({} in {}..={})",
+            self.limiter.published.get(),
+            min,
+            max
+        ))
+    }
+}
+
+/// Service produced by [`AdaptiveConcurrencyLayer`].
+#[derive(Clone, Debug)]
+pub struct AdaptiveConcurrencyService<S, Sink = NullSink> {
+    inner: S,
+    limiter: Arc<Limiter>,
+    sink: Sink,
+}
+
+impl<S, Request, Sink> Service<Request> for AdaptiveConcurrencyService<S, Sink>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let limiter = Arc::clone(&self.limiter);
+        let sink = self.sink.clone();
+
+        let Some(permit) = Permit::try_acquire(&limiter) else {
+            let in_flight = limiter.in_flight.load(Ordering::SeqCst);
+            let limit = limiter.published.get();
+            return Box::pin(async move {
+                emit_best_effort(
+                    sink,
+                    PolicyEvent::Concurrency(ConcurrencyEvent::Rejected { in_flight, limit }),
+                )
+                .await;
+                Err(ResilienceError::Bulkhead { in_flight, max: limit })
+            });
+        };
+
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = fut.await;
+            let in_flight = permit.release();
+            if result.is_ok() {
+                if let Some(limit) = limiter.sample(start.elapsed(), in_flight) {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::Concurrency(ConcurrencyEvent::LimitChanged { limit }),
+                    )
+                    .await;
+                }
+            }
+            result.map_err(ResilienceError::Inner)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    min: usize,
+    max: usize,
+    smoothing: f64,
+}
+
+#[derive(Debug)]
+struct Limiter {
+    config: Config,
+    in_flight: AtomicUsize,
+    published: Adaptive<usize>,
+    estimate: Mutex<Estimate>,
+}
+
+#[derive(Debug)]
+struct Estimate {
+    limit: f64,
+    baseline: Option<f64>,
+}
+
+impl Limiter {
+    fn new(initial: usize, config: Config) -> Result<Self, ConcurrencyLimitError> {
+        let Config { min, max, smoothing } = config;
+        if initial == 0 || min == 0 || max == 0 {
+            return Err(ConcurrencyLimitError::ZeroLimit);
+        }
+        if !(min <= initial && initial <= max) {
+            return Err(ConcurrencyLimitError::InvalidBounds { min, max, initial });
+        }
+        if !(smoothing > 0.0 && smoothing <= 1.0) {
+            return Err(ConcurrencyLimitError::InvalidSmoothing { provided: smoothing });
+        }
+        Ok(Self {
+            config,
+            in_flight: AtomicUsize::new(0),
+            published: Adaptive::new(initial),
+            estimate: Mutex::new(Estimate { limit: initial as f64, baseline: None }),
+        })
+    }
+
+    /// Fold in an RTT sample taken with `in_flight` requests outstanding; returns the new
+    /// rounded limit if it changed.
+    fn sample(&self, rtt: Duration, in_flight: usize) -> Option<usize> {
+        let rtt = rtt.as_secs_f64().max(f64::EPSILON);
+        let mut est = self.estimate.lock().unwrap_or_else(PoisonError::into_inner);
+        let baseline = match est.baseline {
+            Some(b) => b + BASELINE_ALPHA * (rtt - b),
+            None => rtt,
+        };
+        est.baseline = Some(baseline);
+
+        let gradient = (baseline / rtt).clamp(0.5, 1.0);
+        let headroom = est.limit.sqrt();
+        let mut target = est.limit * gradient + headroom;
+        if (in_flight as f64) < est.limit / 2.0 {
+            // Not enough load to justify growth; still allow shrinking.
+            target = target.min(est.limit);
+        }
+        let Config { min, max, smoothing } = self.config;
+        est.limit = (est.limit + smoothing * (target - est.limit)).clamp(min as f64, max as f64);
+
+        let rounded = est.limit.round() as usize;
+        if rounded == self.published.get() {
+            None
+        } else {
+            self.published.set(rounded);
+            Some(rounded)
+        }
+    }
+}
+
+/// In-flight slot; released explicitly on completion or implicitly on drop.
+struct Permit {
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl Permit {
+    fn try_acquire(limiter: &Arc<Limiter>) -> Option<Self> {
+        let limit = limiter.published.get();
+        limiter
+            .in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < limit).then_some(n + 1))
+            .ok()
+            .map(|_| Self { limiter: Some(Arc::clone(limiter)) })
+    }
+
+    /// Release the slot, returning how many requests were in flight including this one.
+    fn release(mut self) -> usize {
+        self.limiter.take().map_or(0, |l| l.in_flight.fetch_sub(1, Ordering::SeqCst))
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    fn config() -> Config {
+        Config { min: 1, max: 100, smoothing: 1.0 }
+    }
+
+    #[test]
+    fn grows_under_flat_latency_when_busy() {
+        let limiter = Limiter::new(16, config()).unwrap();
+        let rtt = Duration::from_millis(10);
+        assert_eq!(limiter.sample(rtt, 16), Some(20), "16 + sqrt(16) headroom");
+        assert_eq!(limiter.sample(rtt, 20), Some(24));
+    }
+
+    #[test]
+    fn does_not_grow_when_mostly_idle() {
+        let limiter = Limiter::new(16, config()).unwrap();
+        assert_eq!(limiter.sample(Duration::from_millis(10), 2), None);
+        assert_eq!(limiter.published.get(), 16);
+    }
+
+    #[test]
+    fn shrinks_when_latency_rises() {
+        let limiter = Limiter::new(64, config()).unwrap();
+        limiter.sample(Duration::from_millis(10), 64);
+        let grown = limiter.published.get();
+        // Latency jumps 4x: the gradient bottoms out at 0.5.
+        let shrunk = limiter.sample(Duration::from_millis(40), grown).unwrap();
+        assert!(shrunk < grown, "{} should be below {}", shrunk, grown);
+    }
+
+    #[tokio::test]
+    async fn rejects_beyond_the_current_limit() {
+        let sink = MemorySink::new();
+        let layer = AdaptiveConcurrencyLayer::new(1).unwrap().with_sink(sink.clone());
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let release_rx = Arc::new(Mutex::new(Some(release_rx)));
+        let mut svc = layer.layer(tower::service_fn(move |req: u32| {
+            let rx = release_rx.lock().unwrap().take();
+            async move {
+                if let Some(rx) = rx {
+                    let _ = rx.await;
+                }
+                Ok::<_, TestError>(req)
+            }
+        }));
+
+        let first = tokio::spawn(svc.ready().await.unwrap().call(1));
+        let err = svc.ready().await.unwrap().call(2).await.unwrap_err();
+        assert_eq!(err.bulkhead_capacity(), Some((1, 1)));
+        assert_eq!(
+            sink.events()[0],
+            PolicyEvent::Concurrency(ConcurrencyEvent::Rejected { in_flight: 1, limit: 1 })
+        );
+
+        release_tx.send(()).unwrap();
+        assert_eq!(first.await.unwrap().unwrap(), 1);
+        assert_eq!(svc.ready().await.unwrap().call(3).await.unwrap(), 3);
+    }
+
+    #[test]
+    fn validates_configuration() {
+        assert_eq!(AdaptiveConcurrencyLayer::new(0).unwrap_err(), ConcurrencyLimitError::ZeroLimit);
+        assert_eq!(
+            AdaptiveConcurrencyLayer::new(8).unwrap().bounds(10, 20).unwrap_err(),
+            ConcurrencyLimitError::InvalidBounds { min: 10, max: 20, initial: 8 }
+        );
+    }
+}
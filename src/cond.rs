@@ -0,0 +1,158 @@
+//! Conditional routing: pick one of two stacks per request.
+//!
+//! Semantics
+//! - The predicate sees each request before it is dispatched; `true` routes it through the
+//!   `then` stack, `false` through the `otherwise` stack.
+//! - Both stacks wrap clones of the same inner service and must agree on response and error types.
+//! - The service is ready only when both branches are ready, since the branch is not known until
+//!   the request arrives.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Reads get a tight timeout; writes are given more room.
+//! let reads = Policy(TimeoutLayer::new(Duration::from_millis(100))?);
+//! let writes = Policy(TimeoutLayer::new(Duration::from_secs(2))?);
+//! let policy = Policy::when(|req: &(&str, u32)| req.0 == "GET", reads, writes);
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(policy)
+//!     .service_fn(|req: (&'static str, u32)| async move { Ok::<_, std::io::Error>(req.1) });
+//! assert_eq!(svc.ready().await?.call(("GET", 7)).await?, 7);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::Policy;
+use crate::describe::{Describe, PolicyNode};
+use futures::future::Either;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer routing each request to `then` or `otherwise` based on a predicate.
+#[derive(Clone, Debug)]
+pub struct CondLayer<P, A, B> {
+    predicate: P,
+    then: A,
+    otherwise: B,
+}
+
+impl<P, A, B> CondLayer<P, A, B> {
+    /// Route requests matching `predicate` through `then`, everything else through `otherwise`.
+    pub fn new(predicate: P, then: A, otherwise: B) -> Self {
+        Self { predicate, then, otherwise }
+    }
+}
+
+impl<P, A, B> Policy<CondLayer<P, A, B>> {
+    /// Route requests matching `predicate` through `then`, everything else through `otherwise`.
+    pub fn when(predicate: P, then: Policy<A>, otherwise: Policy<B>) -> Self {
+        Policy(CondLayer::new(predicate, then.0, otherwise.0))
+    }
+}
+
+impl<S, P, A, B> Layer<S> for CondLayer<P, A, B>
+where
+    S: Clone,
+    P: Clone,
+    A: Layer<S>,
+    B: Layer<S>,
+{
+    type Service = CondService<P, A::Service, B::Service>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        CondService {
+            predicate: self.predicate.clone(),
+            then: self.then.layer(service.clone()),
+            otherwise: self.otherwise.layer(service),
+        }
+    }
+}
+
+impl<P, A: Describe, B: Describe> Describe for CondLayer<P, A, B> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::branch(
+            "When",
+            [("then", self.then.describe()), ("otherwise", self.otherwise.describe())],
+        )
+    }
+}
+
+/// Service produced by [`CondLayer`].
+#[derive(Clone, Debug)]
+pub struct CondService<P, S1, S2> {
+    predicate: P,
+    then: S1,
+    otherwise: S2,
+}
+
+impl<P, S1, S2, Request> Service<Request> for CondService<P, S1, S2>
+where
+    P: Fn(&Request) -> bool,
+    S1: Service<Request>,
+    S2: Service<Request, Response = S1::Response, Error = S1::Error>,
+{
+    type Response = S1::Response;
+    type Error = S1::Error;
+    type Future = Either<S1::Future, S2::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match (self.then.poll_ready(cx), self.otherwise.poll_ready(cx)) {
+            (Poll::Ready(Err(e)), _) | (_, Poll::Ready(Err(e))) => Poll::Ready(Err(e)),
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => Poll::Ready(Ok(())),
+            _ => Poll::Pending,
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if (self.predicate)(&req) {
+            Either::Left(self.then.call(req))
+        } else {
+            Either::Right(self.otherwise.call(req))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Tag(&'static str);
+
+    impl<S> Layer<S> for Tag {
+        type Service = tower::util::BoxCloneService<u32, String, std::convert::Infallible>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let tag = self.0;
+            tower::util::BoxCloneService::new(tower::service_fn(move |req: u32| async move {
+                Ok(format!("{}:{}", tag, req))
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_by_predicate() {
+        let mut svc =
+            Policy::when(|req: &u32| req % 2 == 0, Policy(Tag("even")), Policy(Tag("odd")))
+                .layer(());
+
+        assert_eq!(svc.ready().await.unwrap().call(4).await.unwrap(), "even:4");
+        assert_eq!(svc.ready().await.unwrap().call(7).await.unwrap(), "odd:7");
+    }
+
+    #[tokio::test]
+    async fn composes_with_other_operators() {
+        let routed = Policy::when(|req: &u32| *req > 10, Policy(Tag("big")), Policy(Tag("small")));
+        let mut svc = (routed | Policy(Tag("fallback"))).layer(());
+
+        assert_eq!(svc.ready().await.unwrap().call(11).await.unwrap(), "big:11");
+    }
+}
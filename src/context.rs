@@ -0,0 +1,234 @@
+//! Request-scoped context visible to every layer in a stack.
+//!
+//! Semantics
+//! - [`RequestContext`] is stored in a Tokio task-local. Wrap the future that drives a request in
+//!   [`RequestContext::scope`] and every layer polled inside it can read the context through
+//!   [`RequestContext::current`].
+//! - Layers read the context when their futures are polled, so the scope must enclose the
+//!   `.await`. Work moved onto another task with `tokio::spawn` does not inherit the context;
+//!   re-scope it explicitly if needed.
+//! - Outside of any scope, [`RequestContext::current`] returns an empty context and layers behave
+//!   exactly as they do without deadlines.
+//! - A [`CancellationToken`] in the context lets inner services notice when an outer layer has
+//!   given up on the request (for example `TimeoutLayer::with_cancellation`, or `&` once the
+//!   other branch has won) and release resources cooperatively.
+//! - The policy name and instance id set by `Policy::named` are visible to telemetry sinks while
+//!   they handle events, so a sink shared by several stacks can attribute each event.
+//! - Attributes are free-form key/value pairs for telemetry (region, pod, build); sinks that
+//!   capture an `EventEnvelope` ship them with every event.
+//!
+//! Invariants
+//! - Nested scopes can only tighten a deadline: [`RequestContext::with_deadline`] keeps the
+//!   earlier of the existing and the new deadline.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(TimeoutLayer::new(Duration::from_secs(5))?)
+//!     .service_fn(|req: &'static str| async move { Ok::<_, std::io::Error>(req) });
+//!
+//! // The 5s timeout is shortened to whatever is left of the caller's 200ms budget.
+//! let ctx = RequestContext::new().with_deadline(Deadline::after(Duration::from_millis(200)));
+//! let resp = ctx.scope(async { svc.ready().await?.call("hello").await }).await?;
+//! assert_eq!(resp, "hello");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Deadline, TimeoutProfile};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::futures::TaskLocalFuture;
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static CURRENT: RequestContext;
+}
+
+/// Per-request state propagated implicitly through a layer stack.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    deadline: Option<Deadline>,
+    cancellation: Option<CancellationToken>,
+    timeout_profile: Option<TimeoutProfile>,
+    policy_name: Option<Arc<str>>,
+    policy_instance: Option<u64>,
+    attributes: Arc<BTreeMap<Arc<str>, Arc<str>>>,
+}
+
+impl RequestContext {
+    /// Empty context with no deadline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of the context for the current task, or an empty context outside any scope.
+    pub fn current() -> Self {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// Deadline attached to this context, if any.
+    #[must_use]
+    pub fn deadline(&self) -> Option<Deadline> {
+        self.deadline
+    }
+
+    /// Time left before the deadline in scope, if any; shrinks as the request runs.
+    ///
+    /// Set at the edge with `BudgetLayer` (or [`with_deadline`](Self::with_deadline)) and consult
+    /// it before starting work that cannot finish in time.
+    #[must_use]
+    pub fn remaining_budget(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline.remaining())
+    }
+
+    /// Attach a deadline, keeping the earlier one if a deadline is already present.
+    #[must_use]
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(match self.deadline {
+            Some(existing) => existing.min(deadline),
+            None => deadline,
+        });
+        self
+    }
+
+    /// Cancellation token attached to this context, if any.
+    #[must_use]
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    /// Whether an outer layer has cancelled this request.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Attach a cancellation token, replacing any existing one.
+    ///
+    /// Derive the token from [`cancellation_token`](Self::cancellation_token) with
+    /// [`CancellationToken::child_token`] to keep outer cancellation flowing inward.
+    #[must_use]
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Phase timeouts published by an outer `TimeoutLayer::from_profile`, if any.
+    #[must_use]
+    pub fn timeout_profile(&self) -> Option<TimeoutProfile> {
+        self.timeout_profile
+    }
+
+    /// Attach phase timeouts for transports beneath this scope.
+    #[must_use]
+    pub fn with_timeout_profile(mut self, profile: TimeoutProfile) -> Self {
+        self.timeout_profile = Some(profile);
+        self
+    }
+
+    /// Name of the innermost `Policy::named` stack handling the request, if any.
+    #[must_use]
+    pub fn policy_name(&self) -> Option<&str> {
+        self.policy_name.as_deref()
+    }
+
+    /// Attribute work in this scope to the named policy, replacing any outer name.
+    #[must_use]
+    pub fn with_policy_name(mut self, name: impl Into<Arc<str>>) -> Self {
+        self.policy_name = Some(name.into());
+        self
+    }
+
+    /// Instance id of the innermost `Policy::named` stack handling the request, if any.
+    ///
+    /// Distinguishes stacks that share a name, such as one per downstream host.
+    #[must_use]
+    pub fn policy_instance(&self) -> Option<u64> {
+        self.policy_instance
+    }
+
+    /// Attribute work in this scope to a specific policy instance, replacing any outer id.
+    #[must_use]
+    pub fn with_policy_instance(mut self, instance_id: u64) -> Self {
+        self.policy_instance = Some(instance_id);
+        self
+    }
+
+    /// Value of the telemetry attribute `key`, if set.
+    #[must_use]
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|value| &**value)
+    }
+
+    /// Telemetry attributes in scope, ordered by key.
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().map(|(key, value)| (&**key, &**value))
+    }
+
+    /// Attach a telemetry attribute, replacing any value already set for `key`.
+    #[must_use]
+    pub fn with_attribute(mut self, key: impl Into<Arc<str>>, value: impl Into<Arc<str>>) -> Self {
+        Arc::make_mut(&mut self.attributes).insert(key.into(), value.into());
+        self
+    }
+
+    /// Run `fut` with this context installed as the current context.
+    pub fn scope<F>(self, fut: F) -> TaskLocalFuture<RequestContext, F>
+    where
+        F: Future,
+    {
+        CURRENT.scope(self, fut)
+    }
+
+    /// Run a synchronous closure with this context installed as the current context.
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        CURRENT.sync_scope(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn current_is_empty_outside_scope() {
+        assert!(RequestContext::current().deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn nested_scopes_keep_earliest_deadline() {
+        tokio::time::pause();
+        let outer = Deadline::after(Duration::from_millis(100));
+        let looser = Deadline::after(Duration::from_secs(10));
+
+        let seen = RequestContext::new()
+            .with_deadline(outer)
+            .scope(async {
+                RequestContext::current()
+                    .with_deadline(looser)
+                    .scope(async { RequestContext::current().deadline() })
+                    .await
+            })
+            .await;
+
+        assert_eq!(seen, Some(outer));
+    }
+
+    #[tokio::test]
+    async fn cancellation_token_is_visible_in_scope() {
+        let token = CancellationToken::new();
+        let ctx = RequestContext::new().with_cancellation_token(token.clone());
+        token.cancel();
+        assert!(ctx.scope(async { RequestContext::current().is_cancelled() }).await);
+    }
+}
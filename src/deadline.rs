@@ -0,0 +1,229 @@
+//! Request deadlines shared across layers.
+//!
+//! Semantics
+//! - A [`Deadline`] is an absolute instant on Tokio's clock. It is carried to layers through the
+//!   task-local [`RequestContext`](crate::RequestContext), so a budget set at the edge applies to
+//!   every layer underneath it.
+//! - `TimeoutLayer` shortens its configured duration to the remaining budget, `RetryLayer` stops
+//!   retrying once the next backoff would overrun the deadline, and `FallbackLayer` skips the
+//!   secondary when the deadline has already passed.
+//! - Deadlines can be imported from and exported to the gRPC `grpc-timeout` header and the
+//!   [`TIMEOUT_HEADER`] HTTP header (relative milliseconds). Both formats are relative, so the
+//!   absolute deadline is re-derived from "now" on import.
+//!
+//! Invariants
+//! - [`Deadline::remaining`] saturates at zero; it never underflows once the deadline passes.
+//! - Exported values never exceed the remaining budget (they are rounded down).
+//!
+//! Example
+//! ```
+//! use ninelives::{Deadline, RequestContext};
+//! use std::time::Duration;
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let deadline = Deadline::from_grpc_timeout("250m").unwrap();
+//! let budget = RequestContext::new()
+//!     .with_deadline(deadline)
+//!     .scope(async { RequestContext::current().deadline().unwrap().remaining() })
+//!     .await;
+//! assert!(budget <= Duration::from_millis(250));
+//! # });
+//! ```
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Name of the gRPC header carrying a relative deadline.
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Name of the HTTP header carrying a relative deadline in whole milliseconds.
+pub const TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+
+/// Maximum number of digits permitted in a `grpc-timeout` value by the gRPC wire spec.
+const GRPC_MAX_DIGITS: usize = 8;
+
+/// Errors returned when parsing a deadline from a header value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeadlineParseError {
+    /// The header value was empty.
+    Empty,
+    /// The value portion was not a valid unsigned integer.
+    InvalidValue(String),
+    /// The `grpc-timeout` value carried more than eight digits.
+    TooManyDigits(usize),
+    /// The `grpc-timeout` unit was not one of `H`, `M`, `S`, `m`, `u`, `n`.
+    InvalidUnit(char),
+}
+
+impl std::fmt::Display for DeadlineParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeadlineParseError::Empty => write!(f, "deadline header value is empty"),
+            DeadlineParseError::InvalidValue(v) => {
+                write!(f, "deadline value {:?} is not an unsigned integer", v)
+            }
+            DeadlineParseError::TooManyDigits(n) => {
+                write!(f, "grpc-timeout allows at most {} digits (got {})", GRPC_MAX_DIGITS, n)
+            }
+            DeadlineParseError::InvalidUnit(c) => write!(f, "unknown grpc-timeout unit {:?}", c),
+        }
+    }
+}
+
+impl std::error::Error for DeadlineParseError {}
+
+/// Absolute point in time by which a request must complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// Deadline expiring at the given instant.
+    pub fn at(at: Instant) -> Self {
+        Self { at }
+    }
+
+    /// Deadline expiring `budget` from now.
+    pub fn after(budget: Duration) -> Self {
+        let now = Instant::now();
+        Self { at: now.checked_add(budget).unwrap_or(now + crate::MAX_TIMEOUT) }
+    }
+
+    /// Instant at which this deadline expires.
+    #[must_use]
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// Time left before the deadline; zero once it has passed.
+    #[must_use]
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has passed.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Parse a gRPC `grpc-timeout` header value (e.g. `"100m"`, `"5S"`).
+    pub fn from_grpc_timeout(value: &str) -> Result<Self, DeadlineParseError> {
+        let value = value.trim();
+        let unit = value.chars().last().ok_or(DeadlineParseError::Empty)?;
+        let digits = &value[..value.len() - unit.len_utf8()];
+        if digits.is_empty() {
+            return Err(DeadlineParseError::InvalidValue(value.to_string()));
+        }
+        if digits.len() > GRPC_MAX_DIGITS {
+            return Err(DeadlineParseError::TooManyDigits(digits.len()));
+        }
+        let amount: u64 =
+            digits.parse().map_err(|_| DeadlineParseError::InvalidValue(digits.to_string()))?;
+        let budget = match unit {
+            'H' => Duration::from_secs(amount * 3600),
+            'M' => Duration::from_secs(amount * 60),
+            'S' => Duration::from_secs(amount),
+            'm' => Duration::from_millis(amount),
+            'u' => Duration::from_micros(amount),
+            'n' => Duration::from_nanos(amount),
+            other => return Err(DeadlineParseError::InvalidUnit(other)),
+        };
+        Ok(Self::after(budget))
+    }
+
+    /// Encode the remaining budget as a `grpc-timeout` header value.
+    ///
+    /// Picks the finest unit that fits in eight digits, rounding down.
+    #[must_use]
+    pub fn to_grpc_timeout(&self) -> String {
+        let nanos = self.remaining().as_nanos();
+        let limit = 10u128.pow(GRPC_MAX_DIGITS as u32);
+        let units: [(u128, char); 6] = [
+            (1, 'n'),
+            (1_000, 'u'),
+            (1_000_000, 'm'),
+            (1_000_000_000, 'S'),
+            (60_000_000_000, 'M'),
+            (3_600_000_000_000, 'H'),
+        ];
+        for (scale, unit) in units {
+            let amount = nanos / scale;
+            if amount < limit {
+                return format!("{}{}", amount, unit);
+            }
+        }
+        format!("{}H", limit - 1)
+    }
+
+    /// Parse the [`TIMEOUT_HEADER`] value (relative whole milliseconds).
+    pub fn from_timeout_header(value: &str) -> Result<Self, DeadlineParseError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(DeadlineParseError::Empty);
+        }
+        let millis: u64 =
+            value.parse().map_err(|_| DeadlineParseError::InvalidValue(value.to_string()))?;
+        Ok(Self::after(Duration::from_millis(millis)))
+    }
+
+    /// Encode the remaining budget as a [`TIMEOUT_HEADER`] value.
+    #[must_use]
+    pub fn to_timeout_header(&self) -> String {
+        self.remaining().as_millis().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn grpc_timeout_round_trips() {
+        tokio::time::pause();
+        let deadline = Deadline::from_grpc_timeout("250m").unwrap();
+        assert_eq!(deadline.remaining(), Duration::from_millis(250));
+        assert_eq!(deadline.to_grpc_timeout(), "250000u");
+
+        let long = Deadline::from_grpc_timeout("2H").unwrap();
+        assert_eq!(long.to_grpc_timeout(), "7200000m");
+    }
+
+    #[test]
+    fn grpc_timeout_rejects_malformed_values() {
+        assert_eq!(Deadline::from_grpc_timeout("").unwrap_err(), DeadlineParseError::Empty);
+        assert_eq!(
+            Deadline::from_grpc_timeout("10x").unwrap_err(),
+            DeadlineParseError::InvalidUnit('x')
+        );
+        assert_eq!(
+            Deadline::from_grpc_timeout("123456789S").unwrap_err(),
+            DeadlineParseError::TooManyDigits(9)
+        );
+        assert!(matches!(
+            Deadline::from_grpc_timeout("m").unwrap_err(),
+            DeadlineParseError::InvalidValue(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn timeout_header_round_trips() {
+        tokio::time::pause();
+        let deadline = Deadline::from_timeout_header("1500").unwrap();
+        assert_eq!(deadline.to_timeout_header(), "1500");
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert_eq!(deadline.to_timeout_header(), "1000");
+        assert!(Deadline::from_timeout_header("soon").is_err());
+    }
+
+    #[tokio::test]
+    async fn remaining_saturates_after_expiry() {
+        tokio::time::pause();
+        let deadline = Deadline::after(Duration::from_millis(10));
+        assert!(!deadline.is_expired());
+        tokio::time::advance(Duration::from_millis(20)).await;
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,321 @@
+//! Inspect composed policies as a tree and render them as DOT or Mermaid diagrams.
+//!
+//! Semantics
+//! - Every layer in this crate implements [`Describe`]. Calling [`Describe::describe`] on a
+//!   composed policy walks the composition and returns a [`PolicyNode`] tree.
+//! - `+` becomes a [`PolicyNode::Sequence`] (outermost first, nested sequences flattened);
+//!   combinators such as `|`, `&`, quorum, and hedging become [`PolicyNode::Branch`] nodes whose
+//!   edges carry the branch role (`primary`, `secondary`, `left`, ...).
+//! - `Display` gives a one-line summary; [`PolicyNode::to_dot`] and [`PolicyNode::to_mermaid`]
+//!   produce diagrams where an edge means "dispatches to".
+//! - Adaptive values (for example a canary weight) are rendered with their value at the time
+//!   `describe` is called.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let fast = Policy(TimeoutLayer::new(Duration::from_millis(100))?);
+//! let sturdy = Policy(TimeoutLayer::new(Duration::from_secs(2))?)
+//!     + Policy(BulkheadLayer::new(64)?);
+//! let policy = fast | sturdy;
+//!
+//! assert_eq!(
+//!     policy.describe().to_string(),
+//!     "Fallback(primary: Timeout(100ms), secondary: Timeout(2s) -> Bulkhead(64))"
+//! );
+//! assert!(policy.describe().to_mermaid().starts_with("flowchart TD"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::Policy;
+use std::fmt::{self, Write as _};
+
+/// Layers that can report their configuration and structure.
+pub trait Describe {
+    /// Describe this layer, including any layers it composes.
+    fn describe(&self) -> PolicyNode;
+
+    /// Check the composition for known-bad layer orderings; see [`PolicyNode::lint`].
+    fn validate(&self) -> Vec<crate::CompositionWarning> {
+        self.describe().lint()
+    }
+}
+
+impl<L: Describe> Describe for Policy<L> {
+    fn describe(&self) -> PolicyNode {
+        self.0.describe()
+    }
+}
+
+/// Tree describing a composed policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyNode {
+    /// A single layer, e.g. `Timeout(300ms)`.
+    Layer(String),
+    /// Layers applied in order, outermost first.
+    Sequence(Vec<PolicyNode>),
+    /// A combinator dispatching to several branches.
+    Branch {
+        /// Combinator label, e.g. `Fallback` or `Quorum(2 of 3)`.
+        label: String,
+        /// Branches with the role of each edge.
+        branches: Vec<(String, PolicyNode)>,
+    },
+    /// A policy given a name with `Policy::named`.
+    Named {
+        /// The policy name.
+        name: String,
+        /// The named policy.
+        inner: Box<PolicyNode>,
+    },
+}
+
+impl PolicyNode {
+    /// Leaf node for a single layer.
+    pub fn layer(label: impl Into<String>) -> Self {
+        PolicyNode::Layer(label.into())
+    }
+
+    /// `outer` wrapping `inner`, flattening nested sequences.
+    pub fn sequence(outer: PolicyNode, inner: PolicyNode) -> Self {
+        let mut items = Vec::new();
+        for node in [outer, inner] {
+            match node {
+                PolicyNode::Sequence(nodes) => items.extend(nodes),
+                other => items.push(other),
+            }
+        }
+        PolicyNode::Sequence(items)
+    }
+
+    /// Combinator node with labelled branches.
+    pub fn branch<I, K>(label: impl Into<String>, branches: I) -> Self
+    where
+        I: IntoIterator<Item = (K, PolicyNode)>,
+        K: Into<String>,
+    {
+        PolicyNode::Branch {
+            label: label.into(),
+            branches: branches.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        }
+    }
+
+    /// Combinator node whose branches are numbered from 1.
+    pub fn numbered<I>(label: impl Into<String>, branches: I) -> Self
+    where
+        I: IntoIterator<Item = PolicyNode>,
+    {
+        Self::branch(label, branches.into_iter().enumerate().map(|(i, n)| ((i + 1).to_string(), n)))
+    }
+
+    /// Render as a Graphviz DOT digraph.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut r = Renderer::new(Format::Dot);
+        r.out.push_str("digraph policy {\n    rankdir=LR;\n    node [shape=box];\n");
+        r.render(self, 1);
+        r.out.push_str("}\n");
+        r.out
+    }
+
+    /// Render as a Mermaid flowchart.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        let mut r = Renderer::new(Format::Mermaid);
+        r.out.push_str("flowchart TD\n");
+        r.render(self, 1);
+        r.out
+    }
+}
+
+impl fmt::Display for PolicyNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyNode::Layer(label) => f.write_str(label),
+            PolicyNode::Sequence(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" -> ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                Ok(())
+            }
+            PolicyNode::Branch { label, branches } => {
+                write!(f, "{}(", label)?;
+                for (i, (role, node)) in branches.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", role, node)?;
+                }
+                f.write_str(")")
+            }
+            PolicyNode::Named { name, inner } => write!(f, "{}: {{ {} }}", name, inner),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Dot,
+    Mermaid,
+}
+
+struct Renderer {
+    format: Format,
+    out: String,
+    next_id: usize,
+}
+
+impl Renderer {
+    fn new(format: Format) -> Self {
+        Self { format, out: String::new(), next_id: 0 }
+    }
+
+    /// Emit `node` and return the id of its entry point.
+    fn render(&mut self, node: &PolicyNode, depth: usize) -> String {
+        match node {
+            PolicyNode::Layer(label) => self.node(label, depth),
+            PolicyNode::Sequence(items) => {
+                let ids: Vec<String> = items.iter().map(|n| self.render(n, depth)).collect();
+                for pair in ids.windows(2) {
+                    self.edge(&pair[0], &pair[1], None, depth);
+                }
+                ids.into_iter().next().unwrap_or_else(|| self.node("(empty)", depth))
+            }
+            PolicyNode::Branch { label, branches } => {
+                let id = self.node(label, depth);
+                for (role, child) in branches {
+                    let child_id = self.render(child, depth);
+                    self.edge(&id, &child_id, Some(role), depth);
+                }
+                id
+            }
+            PolicyNode::Named { name, inner } => {
+                let cluster = format!("cluster_{}", self.next_id);
+                self.next_id += 1;
+                let pad = "    ".repeat(depth);
+                match self.format {
+                    Format::Dot => {
+                        let _ = writeln!(self.out, "{}subgraph {} {{", pad, cluster);
+                        let _ = writeln!(self.out, "{}    label=\"{}\";", pad, escape_dot(name));
+                    }
+                    Format::Mermaid => {
+                        let _ = writeln!(
+                            self.out,
+                            "{}subgraph {}[\"{}\"]",
+                            pad,
+                            cluster,
+                            escape_mermaid(name)
+                        );
+                    }
+                }
+                let id = self.render(inner, depth + 1);
+                let _ = match self.format {
+                    Format::Dot => writeln!(self.out, "{}}}", pad),
+                    Format::Mermaid => writeln!(self.out, "{}end", pad),
+                };
+                id
+            }
+        }
+    }
+
+    fn node(&mut self, label: &str, depth: usize) -> String {
+        let id = format!("n{}", self.next_id);
+        self.next_id += 1;
+        let pad = "    ".repeat(depth);
+        let _ = match self.format {
+            Format::Dot => writeln!(self.out, "{}{} [label=\"{}\"];", pad, id, escape_dot(label)),
+            Format::Mermaid => writeln!(self.out, "{}{}[\"{}\"]", pad, id, escape_mermaid(label)),
+        };
+        id
+    }
+
+    fn edge(&mut self, from: &str, to: &str, role: Option<&str>, depth: usize) {
+        let pad = "    ".repeat(depth);
+        let _ = match (self.format, role) {
+            (Format::Dot, None) => writeln!(self.out, "{}{} -> {};", pad, from, to),
+            (Format::Dot, Some(role)) => {
+                writeln!(self.out, "{}{} -> {} [label=\"{}\"];", pad, from, to, escape_dot(role))
+            }
+            (Format::Mermaid, None) => writeln!(self.out, "{}{} --> {}", pad, from, to),
+            (Format::Mermaid, Some(role)) => {
+                writeln!(self.out, "{}{} -->|{}| {}", pad, from, escape_mermaid(role), to)
+            }
+        };
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;").replace('|', "#124;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PolicyNode {
+        PolicyNode::branch(
+            "Fallback",
+            [
+                (
+                    "primary",
+                    PolicyNode::sequence(
+                        PolicyNode::layer("Timeout(300ms)"),
+                        PolicyNode::sequence(
+                            PolicyNode::layer("Retry(x3)"),
+                            PolicyNode::layer("Bulkhead(64)"),
+                        ),
+                    ),
+                ),
+                (
+                    "secondary",
+                    PolicyNode::Named {
+                        name: "replica".into(),
+                        inner: Box::new(PolicyNode::layer("Timeout(2s)")),
+                    },
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn display_flattens_sequences() {
+        assert_eq!(
+            sample().to_string(),
+            "Fallback(primary: Timeout(300ms) -> Retry(x3) -> Bulkhead(64), \
+             secondary: replica: { Timeout(2s) })"
+        );
+    }
+
+    #[test]
+    fn renders_dot() {
+        let dot = sample().to_dot();
+        assert!(dot.starts_with("digraph policy {"));
+        assert!(dot.contains("n0 [label=\"Fallback\"];"));
+        assert!(dot.contains("n1 -> n2;"));
+        assert!(dot.contains("n0 -> n1 [label=\"primary\"];"));
+        assert!(dot.contains("subgraph cluster_4 {"));
+        assert!(dot.contains("n0 -> n5 [label=\"secondary\"];"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn renders_mermaid() {
+        let mermaid = sample().to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("n1[\"Timeout(300ms)\"]"));
+        assert!(mermaid.contains("n2 --> n3"));
+        assert!(mermaid.contains("n0 -->|secondary| n5"));
+        assert!(mermaid.contains("subgraph cluster_4[\"replica\"]"));
+    }
+}
@@ -37,6 +37,16 @@ pub enum ResilienceError<E> {
         /// Recorded failures (up to MAX_RETRY_FAILURES).
         failures: Arc<Vec<E>>,
     },
+    /// A rate limiter rejected the operation.
+    RateLimited {
+        /// Suggested wait before the next request would be admitted.
+        retry_after: Duration,
+    },
+    /// A load shedder dropped the operation to relieve an overloaded backend.
+    Shed {
+        /// Fraction of requests being shed when this one was dropped.
+        probability: f64,
+    },
     /// The underlying operation failed
     Inner(E),
 }
@@ -78,6 +88,12 @@ impl<E: fmt::Display> fmt::Display for ResilienceError<E> {
                     )
                 }
             }
+            Self::RateLimited { retry_after } => {
+                write!(f, "rate limit exceeded (retry after {:?})", retry_after)
+            }
+            Self::Shed { probability } => {
+                write!(f, "request shed under load (shedding {:.0}%)", probability * 100.0)
+            }
             Self::Inner(e) => write!(f, "{}", e),
         }
     }
@@ -120,6 +136,14 @@ impl<E> ResilienceError<E> {
     pub fn is_bulkhead_closed(&self) -> bool {
         matches!(self, Self::BulkheadClosed)
     }
+    /// Check if this error is due to rate limiting
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::RateLimited { .. })
+    }
+    /// Check if this error is due to load shedding
+    pub fn is_shed(&self) -> bool {
+        matches!(self, Self::Shed { .. })
+    }
     /// Check if this error is due to retry exhaustion
     pub fn is_retry_exhausted(&self) -> bool {
         matches!(self, Self::RetryExhausted { .. })
@@ -186,6 +210,32 @@ impl<E> ResilienceError<E> {
         }
     }
 }
+impl<E> ResilienceError<ResilienceError<E>> {
+    /// Collapse an error from stacked policies into a single level.
+    ///
+    /// Outer variants are kept as-is and `Inner` unwraps to the nested error. For
+    /// `RetryExhausted`, only failures that were `Inner` survive in the recorded list since the
+    /// rest have no `E` to hold; the attempt count is unchanged.
+    pub fn flatten(self) -> ResilienceError<E> {
+        match self {
+            Self::Inner(inner) => inner,
+            Self::Timeout { elapsed, timeout } => ResilienceError::Timeout { elapsed, timeout },
+            Self::Bulkhead { in_flight, max } => ResilienceError::Bulkhead { in_flight, max },
+            Self::BulkheadClosed => ResilienceError::BulkheadClosed,
+            Self::CircuitOpen { failure_count, open_duration } => {
+                ResilienceError::CircuitOpen { failure_count, open_duration }
+            }
+            Self::RateLimited { retry_after } => ResilienceError::RateLimited { retry_after },
+            Self::Shed { probability } => ResilienceError::Shed { probability },
+            Self::RetryExhausted { attempts, failures } => {
+                let failures = Arc::try_unwrap(failures)
+                    .map(|fs| fs.into_iter().filter_map(ResilienceError::into_inner).collect())
+                    .unwrap_or_default();
+                ResilienceError::RetryExhausted { attempts, failures: Arc::new(failures) }
+            }
+        }
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +251,29 @@ mod tests {
     }
     impl std::error::Error for DummyError {}
     #[test]
+    fn flatten_unwraps_nested_inner_and_keeps_outer_variants() {
+        let nested: ResilienceError<ResilienceError<DummyError>> =
+            ResilienceError::Inner(ResilienceError::Inner(DummyError("boom")));
+        assert_eq!(nested.flatten().into_inner(), Some(DummyError("boom")));
+        let outer: ResilienceError<ResilienceError<DummyError>> = ResilienceError::BulkheadClosed;
+        assert!(outer.flatten().is_bulkhead_closed());
+        let retried: ResilienceError<ResilienceError<DummyError>> =
+            ResilienceError::retry_exhausted(
+                3,
+                vec![
+                    ResilienceError::Inner(DummyError("a")),
+                    ResilienceError::Timeout {
+                        elapsed: Duration::from_millis(5),
+                        timeout: Duration::from_millis(5),
+                    },
+                    ResilienceError::Inner(DummyError("c")),
+                ],
+            );
+        let flat = retried.flatten();
+        assert_eq!(flat.retry_exhausted_info(), Some((3, 2)));
+        assert_eq!(flat.failures().unwrap(), &[DummyError("a"), DummyError("c")]);
+    }
+    #[test]
     fn timeout_error_display() {
         let err: ResilienceError<io::Error> = ResilienceError::Timeout {
             elapsed: Duration::from_millis(5100),
@@ -0,0 +1,348 @@
+//! Ordered fallback across any number of homogeneous policy stacks.
+//!
+//! Semantics
+//! - Branches are tried in order with a clone of the original request; the first success is
+//!   returned. This is the n-ary form of `Policy(A) | Policy(B)` without nesting
+//!   `A | (B | (C | D))` types.
+//! - All branches share one layer type, so heterogeneous stacks must be made uniform first (for
+//!   example by boxing them).
+//! - When every branch fails, the primary (first) error is returned, matching `FallbackService`.
+//! - Once the [`Deadline`](crate::Deadline) in scope has expired no further branches are started.
+//! - Emits [`FallbackEvent::Served`] with the index of the serving branch, or
+//!   [`FallbackEvent::Exhausted`] when all branches fail.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let chain = Policy::fallback_chain(vec![
+//!     Policy(TimeoutLayer::new(Duration::from_millis(50))?),
+//!     Policy(TimeoutLayer::new(Duration::from_millis(500))?),
+//!     Policy(TimeoutLayer::new(Duration::from_secs(5))?),
+//! ])?;
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(chain)
+//!     .service_fn(|req: &'static str| async move { Ok::<_, std::io::Error>(req) });
+//! assert_eq!(svc.ready().await?.call("hi").await?, "hi");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Runtime-sized chains
+//!
+//! The branch list does not have to be known at compile time. Box stacks of different shapes
+//! into [`BoxPolicy`](crate::BoxPolicy) and collect however many the configuration describes:
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceExt};
+//! use tower_layer::Layer;
+//!
+//! type Svc = tower::util::BoxCloneService<u32, u32, std::io::Error>;
+//! type Err = ResilienceError<std::io::Error>;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // One entry per configured replica region: (name, timeout in ms).
+//! let regions = [("eu-west", 100), ("us-east", 250), ("ap-south", 400)];
+//!
+//! let mut stacks: Vec<BoxPolicy<Svc, u32, u32, Err>> = Vec::new();
+//! for (i, (name, timeout_ms)) in regions.into_iter().enumerate() {
+//!     let timeout = Policy(TimeoutLayer::new(Duration::from_millis(timeout_ms))?);
+//!     // Remote regions get a second, more patient attempt; the stacks differ in type until boxed.
+//!     let stack = if i == 0 {
+//!         timeout.named(name).boxed()
+//!     } else {
+//!         let patient = Policy(TimeoutLayer::new(Duration::from_millis(timeout_ms * 2))?);
+//!         Policy::fallback_chain([timeout, patient])?.named(name).boxed()
+//!     };
+//!     stacks.push(stack);
+//! }
+//! let chain = Policy::fallback_chain(stacks)?;
+//!
+//! let mut svc = chain.layer(Svc::new(tower::service_fn(|n: u32| async move { Ok(n + 1) })));
+//! assert_eq!(svc.ready().await?.call(41).await?, 42);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{CompositionError, Policy};
+use crate::describe::{Describe, PolicyNode};
+use crate::telemetry::{emit_best_effort, FallbackEvent, NullSink, PolicyEvent};
+use crate::RequestContext;
+use futures::future::BoxFuture;
+use std::task::{Context, Poll};
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer that tries an ordered list of homogeneous layers until one succeeds.
+#[derive(Clone, Debug)]
+pub struct FallbackChainLayer<L, Sink = NullSink> {
+    layers: Vec<L>,
+    sink: Sink,
+}
+
+impl<L> FallbackChainLayer<L, NullSink> {
+    /// Build a chain from `layers`, tried in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompositionError::Empty`] if no layers are given.
+    pub fn new<I>(layers: I) -> Result<Self, CompositionError>
+    where
+        I: IntoIterator<Item = L>,
+    {
+        let layers: Vec<L> = layers.into_iter().collect();
+        if layers.is_empty() {
+            return Err(CompositionError::Empty);
+        }
+        Ok(Self { layers, sink: NullSink })
+    }
+}
+
+impl<L, Sink> FallbackChainLayer<L, Sink> {
+    /// Attach a telemetry sink reporting which branch served each request.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> FallbackChainLayer<L, NewSink>
+    where
+        NewSink: Clone,
+    {
+        FallbackChainLayer { layers: self.layers, sink }
+    }
+
+    /// Number of branches in the chain.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Always `false`; chains are validated to be non-empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl<L> Policy<FallbackChainLayer<L>> {
+    /// Try each policy in order until one succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompositionError::Empty`] if `policies` is empty.
+    pub fn fallback_chain<I>(policies: I) -> Result<Self, CompositionError>
+    where
+        I: IntoIterator<Item = Policy<L>>,
+    {
+        FallbackChainLayer::new(policies.into_iter().map(|p| p.0)).map(Policy)
+    }
+}
+
+impl<S, L, Sink> Layer<S> for FallbackChainLayer<L, Sink>
+where
+    S: Clone,
+    L: Layer<S>,
+    Sink: Clone,
+{
+    type Service = FallbackChainService<L::Service, Sink>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        let services = self.layers.iter().map(|l| l.layer(service.clone())).collect();
+        FallbackChainService { services, sink: self.sink.clone() }
+    }
+}
+
+impl<L: Describe, Sink> Describe for FallbackChainLayer<L, Sink> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::numbered("FallbackChain", self.layers.iter().map(Describe::describe))
+    }
+}
+
+/// Service produced by [`FallbackChainLayer`].
+#[derive(Clone, Debug)]
+pub struct FallbackChainService<S, Sink = NullSink> {
+    services: Vec<S>,
+    sink: Sink,
+}
+
+impl<S, Request, Sink> Service<Request> for FallbackChainService<S, Sink>
+where
+    Request: Clone + Send + 'static,
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut pending = false;
+        for svc in &mut self.services {
+            match svc.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let services = self.services.clone();
+        let sink = self.sink.clone();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let mut first_err = None;
+            let mut tried = 0;
+            for (branch, mut svc) in services.into_iter().enumerate() {
+                if first_err.is_some() && deadline_expired() {
+                    break;
+                }
+                tried += 1;
+                match svc.call(req.clone()).await {
+                    Ok(resp) => {
+                        let duration = start.elapsed();
+                        emit_best_effort(
+                            sink,
+                            PolicyEvent::Fallback(FallbackEvent::Served { branch, duration }),
+                        )
+                        .await;
+                        return Ok(resp);
+                    }
+                    Err(e) => {
+                        first_err.get_or_insert(e);
+                    }
+                }
+            }
+            let duration = start.elapsed();
+            emit_best_effort(
+                sink,
+                PolicyEvent::Fallback(FallbackEvent::Exhausted { branches: tried, duration }),
+            )
+            .await;
+            Err(first_err.expect("fallback chain is never empty"))
+        })
+    }
+}
+
+fn deadline_expired() -> bool {
+    RequestContext::current().deadline().is_some_and(|d| d.is_expired())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// Layer that makes its service fail unless it is the configured "healthy" branch.
+    #[derive(Clone, Debug)]
+    struct Branch {
+        id: usize,
+        healthy: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl<S> Layer<S> for Branch {
+        type Service = tower::util::BoxCloneService<(), usize, &'static str>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let (id, healthy, calls) = (self.id, self.healthy, self.calls.clone());
+            tower::util::BoxCloneService::new(tower::service_fn(move |_req: ()| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if healthy {
+                        Ok(id)
+                    } else if id == 0 {
+                        Err("primary failed")
+                    } else {
+                        Err("backup failed")
+                    }
+                }
+            }))
+        }
+    }
+
+    fn branches(healthy: &[bool], calls: &Arc<AtomicUsize>) -> Vec<Policy<Branch>> {
+        healthy
+            .iter()
+            .enumerate()
+            .map(|(id, &healthy)| Policy(Branch { id, healthy, calls: calls.clone() }))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn first_healthy_branch_serves_and_is_reported() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let sink = MemorySink::new();
+        let layer = Policy::fallback_chain(branches(&[false, false, true, true], &calls))
+            .unwrap()
+            .0
+            .with_sink(sink.clone());
+        let mut svc = layer.layer(());
+
+        let resp = svc.ready().await.unwrap().call(()).await.unwrap();
+        assert_eq!(resp, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3, "stops at the serving branch");
+        assert!(matches!(
+            sink.events()[0],
+            PolicyEvent::Fallback(FallbackEvent::Served { branch: 2, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn returns_primary_error_when_all_fail() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let sink = MemorySink::new();
+        let layer = Policy::fallback_chain(branches(&[false, false, false], &calls))
+            .unwrap()
+            .0
+            .with_sink(sink.clone());
+        let mut svc = layer.layer(());
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert_eq!(err, "primary failed");
+        assert!(matches!(
+            sink.events()[0],
+            PolicyEvent::Fallback(FallbackEvent::Exhausted { branches: 3, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn chains_runtime_list_of_boxed_policies() {
+        type Svc = tower::util::BoxCloneService<(), usize, &'static str>;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let healthy = [false, false, false, true];
+        let policies: Vec<crate::BoxPolicy<Svc, (), usize, &'static str>> =
+            branches(&healthy, &calls).into_iter().map(Policy::boxed).collect();
+        let mut svc = Policy::fallback_chain(policies)
+            .unwrap()
+            .layer(Svc::new(tower::service_fn(|_: ()| async { Ok(usize::MAX) })));
+
+        assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn empty_chain_is_rejected() {
+        let err = Policy::fallback_chain(Vec::<Policy<Branch>>::new()).unwrap_err();
+        assert_eq!(err, CompositionError::Empty);
+    }
+}
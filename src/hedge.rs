@@ -0,0 +1,325 @@
+//! Hedged requests: send a backup request only when the primary is slow.
+//!
+//! Semantics
+//! - The request goes to the `primary` stack first. If no response has arrived after `delay`,
+//!   a clone is sent to the `secondary` stack and the first success of the two is returned.
+//! - If the primary fails before `delay`, the secondary is started immediately.
+//! - Compared with `&`, which always doubles load, hedging only adds load for the slow tail
+//!   (choose `delay` around the primary's p95 latency).
+//! - When both fail the error is a [`ForkJoinError`]; `left` is the primary's error and `right`
+//!   the secondary's. If the primary succeeds the secondary is never called.
+//! - With a sink attached, [`HedgeEvent::Launched`] reports each backup request, followed by
+//!   [`HedgeEvent::Won`] (branch 0 is the primary, 1 the hedge) or [`HedgeEvent::Exhausted`].
+//!   Comparing launches with hedge wins shows whether `delay` is paying for itself.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let replica = || Policy(TimeoutLayer::new(Duration::from_millis(500)).unwrap());
+//! let hedged = Policy::hedge(replica(), replica(), Duration::from_millis(50));
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(hedged)
+//!     .service_fn(|key: &'static str| async move { Ok::<_, std::io::Error>(key.len()) });
+//! assert_eq!(svc.ready().await?.call("user:42").await?, 7);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{ForkJoinError, Policy};
+use crate::describe::{Describe, PolicyNode};
+use crate::telemetry::{emit_best_effort, HedgeEvent, NullSink, PolicyEvent};
+use futures::future::{select, BoxFuture, Either};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer issuing a delayed backup request to `secondary` when `primary` is slow.
+#[derive(Clone, Debug)]
+pub struct HedgeLayer<A, B, Sink = NullSink> {
+    primary: A,
+    secondary: B,
+    delay: Duration,
+    sink: Sink,
+}
+
+impl<A, B> HedgeLayer<A, B> {
+    /// Hedge `primary` with `secondary`, sending the backup after `delay`.
+    pub fn new(primary: A, secondary: B, delay: Duration) -> Self {
+        Self { primary, secondary, delay, sink: NullSink }
+    }
+}
+
+impl<A, B, Sink> HedgeLayer<A, B, Sink> {
+    /// Attach a telemetry sink for [`HedgeEvent`]s.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> HedgeLayer<A, B, NewSink>
+    where
+        NewSink: Clone,
+    {
+        HedgeLayer { primary: self.primary, secondary: self.secondary, delay: self.delay, sink }
+    }
+
+    /// Delay before the backup request is sent.
+    #[must_use]
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+impl<A, B> Policy<HedgeLayer<A, B>> {
+    /// Hedge `primary` with `secondary`, sending the backup after `delay`.
+    pub fn hedge(primary: Policy<A>, secondary: Policy<B>, delay: Duration) -> Self {
+        Policy(HedgeLayer::new(primary.0, secondary.0, delay))
+    }
+}
+
+impl<A, B, Sink> Policy<HedgeLayer<A, B, Sink>> {
+    /// Attach a telemetry sink for [`HedgeEvent`]s.
+    ///
+    /// See [`HedgeLayer::with_sink`].
+    pub fn with_sink<NewSink: Clone>(self, sink: NewSink) -> Policy<HedgeLayer<A, B, NewSink>> {
+        Policy(self.0.with_sink(sink))
+    }
+}
+
+impl<S, A, B, Sink> Layer<S> for HedgeLayer<A, B, Sink>
+where
+    S: Clone,
+    A: Layer<S>,
+    B: Layer<S>,
+    Sink: Clone,
+{
+    type Service = HedgeService<A::Service, B::Service, Sink>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        HedgeService {
+            primary: self.primary.layer(service.clone()),
+            secondary: self.secondary.layer(service),
+            delay: self.delay,
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<A: Describe, B: Describe, Sink> Describe for HedgeLayer<A, B, Sink> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::branch(
+            format!("Hedge({:?})", self.delay),
+            [("primary", self.primary.describe()), ("hedge", self.secondary.describe())],
+        )
+    }
+}
+
+/// Service produced by [`HedgeLayer`].
+#[derive(Clone, Debug)]
+pub struct HedgeService<S1, S2, Sink = NullSink> {
+    primary: S1,
+    secondary: S2,
+    delay: Duration,
+    sink: Sink,
+}
+
+impl<S1, S2, Sink, Request> Service<Request> for HedgeService<S1, S2, Sink>
+where
+    Request: Clone + Send + 'static,
+    S1: Service<Request> + Clone + Send + 'static,
+    S1::Future: Send + 'static,
+    S1::Response: Send + 'static,
+    S1::Error: Send + 'static,
+    S2: Service<Request, Response = S1::Response, Error = S1::Error> + Clone + Send + 'static,
+    S2::Future: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S1::Response;
+    type Error = ForkJoinError<S1::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match (self.primary.poll_ready(cx), self.secondary.poll_ready(cx)) {
+            (Poll::Ready(Err(e)), _) => Poll::Ready(Err(ForkJoinError::Left(e))),
+            (_, Poll::Ready(Err(e))) => Poll::Ready(Err(ForkJoinError::Right(e))),
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => Poll::Ready(Ok(())),
+            _ => Poll::Pending,
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let primary_fut = self.primary.call(req.clone());
+        let mut secondary = self.secondary.clone();
+        let delay = self.delay;
+        let sink = self.sink.clone();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let sleep = tokio::time::sleep(delay);
+            futures::pin_mut!(primary_fut);
+            futures::pin_mut!(sleep);
+            let launched = |primary_failed| {
+                PolicyEvent::Hedge(HedgeEvent::Launched {
+                    elapsed: start.elapsed(),
+                    primary_failed,
+                })
+            };
+            let outcome = |branch: usize, ok: bool| {
+                let event = if ok {
+                    HedgeEvent::Won { branch, latency: start.elapsed() }
+                } else {
+                    HedgeEvent::Exhausted { duration: start.elapsed() }
+                };
+                PolicyEvent::Hedge(event)
+            };
+
+            let primary_fut = match select(primary_fut, sleep).await {
+                Either::Left((Ok(resp), _)) => {
+                    emit_best_effort(sink, outcome(0, true)).await;
+                    return Ok(resp);
+                }
+                Either::Left((Err(left), _)) => {
+                    emit_best_effort(sink.clone(), launched(true)).await;
+                    let result = secondary.call(req).await;
+                    emit_best_effort(sink, outcome(1, result.is_ok())).await;
+                    return result.map_err(|right| ForkJoinError::Both { left, right });
+                }
+                Either::Right(((), primary_fut)) => primary_fut,
+            };
+
+            emit_best_effort(sink.clone(), launched(false)).await;
+            let secondary_fut = secondary.call(req);
+            futures::pin_mut!(secondary_fut);
+            let (result, branch) = match select(primary_fut, secondary_fut).await {
+                Either::Left((Ok(resp), _)) => (Ok(resp), 0),
+                Either::Right((Ok(resp), _)) => (Ok(resp), 1),
+                Either::Left((Err(left), secondary_fut)) => {
+                    (secondary_fut.await.map_err(|right| ForkJoinError::Both { left, right }), 1)
+                }
+                Either::Right((Err(right), primary_fut)) => {
+                    (primary_fut.await.map_err(|left| ForkJoinError::Both { left, right }), 0)
+                }
+            };
+            emit_best_effort(sink, outcome(branch, result.is_ok())).await;
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Backend {
+        delay_ms: u64,
+        result: Result<&'static str, &'static str>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Backend {
+        fn new(delay_ms: u64, result: Result<&'static str, &'static str>) -> Self {
+            Self { delay_ms, result, calls: Arc::new(AtomicUsize::new(0)) }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    impl<S> Layer<S> for Backend {
+        type Service = tower::util::BoxCloneService<(), &'static str, &'static str>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let backend = self.clone();
+            tower::util::BoxCloneService::new(tower::service_fn(move |_req: ()| {
+                let backend = backend.clone();
+                async move {
+                    backend.calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(backend.delay_ms)).await;
+                    backend.result
+                }
+            }))
+        }
+    }
+
+    async fn run(
+        primary: &Backend,
+        secondary: &Backend,
+    ) -> Result<&'static str, ForkJoinError<&'static str>> {
+        let mut svc = Policy::hedge(
+            Policy(primary.clone()),
+            Policy(secondary.clone()),
+            Duration::from_millis(50),
+        )
+        .layer(());
+        svc.ready().await?.call(()).await
+    }
+
+    #[tokio::test]
+    async fn fast_primary_never_hedges() {
+        tokio::time::pause();
+        let primary = Backend::new(10, Ok("primary"));
+        let secondary = Backend::new(10, Ok("secondary"));
+
+        assert_eq!(run(&primary, &secondary).await.unwrap(), "primary");
+        assert_eq!(secondary.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn slow_primary_is_hedged_after_delay() {
+        tokio::time::pause();
+        let primary = Backend::new(500, Ok("primary"));
+        let secondary = Backend::new(10, Ok("secondary"));
+
+        let start = tokio::time::Instant::now();
+        assert_eq!(run(&primary, &secondary).await.unwrap(), "secondary");
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(60) && elapsed < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn early_primary_failure_starts_secondary_immediately() {
+        tokio::time::pause();
+        let primary = Backend::new(5, Err("down"));
+        let secondary = Backend::new(10, Err("also down"));
+
+        let start = tokio::time::Instant::now();
+        let err = run(&primary, &secondary).await.unwrap_err();
+        assert_eq!(err, ForkJoinError::Both { left: "down", right: "also down" });
+        assert!(start.elapsed() < Duration::from_millis(50), "should not wait for the hedge delay");
+    }
+
+    #[tokio::test]
+    async fn hedge_launches_and_winners_are_reported() {
+        tokio::time::pause();
+        let sink = crate::telemetry::MemorySink::new();
+        let primary = Backend::new(500, Ok("primary"));
+        let secondary = Backend::new(10, Ok("secondary"));
+
+        let mut svc = Policy::hedge(
+            Policy(primary.clone()),
+            Policy(secondary.clone()),
+            Duration::from_millis(50),
+        )
+        .with_sink(sink.clone())
+        .layer(());
+        assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), "secondary");
+
+        let events = sink.events();
+        assert!(matches!(
+            events[0],
+            PolicyEvent::Hedge(HedgeEvent::Launched { primary_failed: false, elapsed })
+                if elapsed >= Duration::from_millis(50)
+        ));
+        assert!(matches!(events[1], PolicyEvent::Hedge(HedgeEvent::Won { branch: 1, .. })));
+    }
+}
@@ -0,0 +1,469 @@
+//! Duplicate suppression by idempotency key.
+//!
+//! Semantics
+//! - A closure extracts an optional idempotency key from each request; requests without one pass
+//!   straight through.
+//! - The first request for a key runs normally. If it succeeds, its response is remembered for
+//!   `ttl` and later requests with the same key get a clone of it without reaching the inner
+//!   service, emitting [`IdempotencyEvent::Replayed`].
+//! - A duplicate arriving while the original is still running waits for it. If the original
+//!   fails (or is dropped), nothing is remembered and the waiting duplicate runs in its place,
+//!   so a retry after a failure still gets a real attempt.
+//!
+//! Invariants
+//! - Only successful responses are stored; errors never need to be `Clone`.
+//! - At most `max_entries` keys are tracked, counting requests still running. Making room drops
+//!   expired responses first, then the oldest stored one; running requests are never evicted.
+//! - Services built from the same layer share the store, so retries issued by an outer
+//!   `RetryLayer` through clones of the service are deduplicated too.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! struct Charge {
+//!     idempotency_key: Option<String>,
+//!     cents: u64,
+//! }
+//!
+//! let charges = Arc::new(AtomicUsize::new(0));
+//! let counter = charges.clone();
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(IdempotencyLayer::new(Duration::from_secs(600), |c: &Charge| {
+//!         c.idempotency_key.clone()
+//!     }))
+//!     .service_fn(move |c: Charge| {
+//!         let n = counter.fetch_add(1, Ordering::SeqCst);
+//!         async move { Ok::<_, std::io::Error>(format!("receipt-{}-{}", n, c.cents)) }
+//!     });
+//!
+//! let charge = || Charge { idempotency_key: Some("order-42".into()), cents: 999 };
+//! let first = svc.ready().await?.call(charge()).await?;
+//! let retried = svc.ready().await?.call(charge()).await?;
+//! assert_eq!(first, retried);
+//! assert_eq!(charges.load(Ordering::SeqCst), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, IdempotencyEvent, NullSink, PolicyEvent};
+use futures::future::BoxFuture;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+type SharedStore<K, V> = Arc<Mutex<Store<K, V>>>;
+
+/// Layer replaying the stored response for requests whose idempotency key was already served.
+///
+/// `F` extracts an optional key of type `K` from each request; `V` is the response type.
+pub struct IdempotencyLayer<K, V, F, Sink = NullSink> {
+    key: Arc<F>,
+    ttl: Duration,
+    max_entries: usize,
+    store: SharedStore<K, V>,
+    sink: Sink,
+}
+
+impl<K, V, F> IdempotencyLayer<K, V, F, NullSink> {
+    /// Remember successful responses for `ttl`, keyed by `key(&request)`.
+    pub fn new<Request>(ttl: Duration, key: F) -> Self
+    where
+        F: Fn(&Request) -> Option<K>,
+    {
+        Self {
+            key: Arc::new(key),
+            ttl,
+            max_entries: 10_000,
+            store: Arc::new(Mutex::new(Store { entries: HashMap::new(), stored: VecDeque::new() })),
+            sink: NullSink,
+        }
+    }
+}
+
+impl<K, V, F, Sink> IdempotencyLayer<K, V, F, Sink> {
+    /// Track at most `max_entries` keys (default 10,000), including requests still running.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Attach a telemetry sink to this layer.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> IdempotencyLayer<K, V, F, NewSink>
+    where
+        NewSink: Clone,
+    {
+        IdempotencyLayer {
+            key: self.key,
+            ttl: self.ttl,
+            max_entries: self.max_entries,
+            store: self.store,
+            sink,
+        }
+    }
+}
+
+impl<K, V, F, Sink: Clone> Clone for IdempotencyLayer<K, V, F, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            key: Arc::clone(&self.key),
+            ttl: self.ttl,
+            max_entries: self.max_entries,
+            store: Arc::clone(&self.store),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<K, V, F, Sink: fmt::Debug> fmt::Debug for IdempotencyLayer<K, V, F, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdempotencyLayer")
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, K, V, F, Sink: Clone> Layer<S> for IdempotencyLayer<K, V, F, Sink> {
+    type Service = IdempotencyService<S, K, V, F, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService { inner, layer: self.clone() }
+    }
+}
+
+impl<K, V, F, Sink> crate::Describe for IdempotencyLayer<K, V, F, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Idempotency(ttl={:?})", self.ttl))
+    }
+}
+
+/// Service produced by [`IdempotencyLayer`].
+pub struct IdempotencyService<S, K, V, F, Sink = NullSink> {
+    inner: S,
+    layer: IdempotencyLayer<K, V, F, Sink>,
+}
+
+impl<S: Clone, K, V, F, Sink: Clone> Clone for IdempotencyService<S, K, V, F, Sink> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), layer: self.layer.clone() }
+    }
+}
+
+impl<S, K, V, F, Sink> fmt::Debug for IdempotencyService<S, K, V, F, Sink>
+where
+    S: fmt::Debug,
+    Sink: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdempotencyService")
+            .field("inner", &self.inner)
+            .field("layer", &self.layer)
+            .finish()
+    }
+}
+
+impl<S, Request, K, V, F, Sink> Service<Request> for IdempotencyService<S, K, V, F, Sink>
+where
+    S: Service<Request, Response = V> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    Request: Send + 'static,
+    F: Fn(&Request) -> Option<K>,
+    K: Eq + Hash + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = V;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<V, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let Some(key) = (self.layer.key)(&req) else {
+            return Box::pin(self.inner.call(req));
+        };
+        // Keep the service that was driven to readiness; a duplicate may call it after waiting.
+        let clone = self.inner.clone();
+        let mut ready = std::mem::replace(&mut self.inner, clone);
+        let store = Arc::clone(&self.layer.store);
+        let (ttl, max_entries) = (self.layer.ttl, self.layer.max_entries);
+        let sink = self.layer.sink.clone();
+
+        Box::pin(async move {
+            loop {
+                let claim = store.lock().unwrap_or_else(PoisonError::into_inner).claim(
+                    &key,
+                    ttl,
+                    max_entries,
+                );
+                match claim {
+                    Claim::Replay { value, age } => {
+                        emit_best_effort(
+                            sink,
+                            PolicyEvent::Idempotency(IdempotencyEvent::Replayed { age }),
+                        )
+                        .await;
+                        return Ok(value);
+                    }
+                    Claim::Wait(mut done) => {
+                        // Resolves once the original finishes and drops its sender.
+                        let _ = done.changed().await;
+                    }
+                    Claim::Lead(done) => {
+                        let mut guard = LeadGuard { store, key: Some(key), _done: done };
+                        let result = ready.call(req).await;
+                        if let Ok(value) = &result {
+                            guard.complete(value.clone(), ttl, max_entries);
+                        }
+                        return result;
+                    }
+                }
+            }
+        })
+    }
+}
+
+struct Store<K, V> {
+    entries: HashMap<K, Slot<V>>,
+    /// Stored responses, oldest first. Entries whose slot has since been replaced are skipped.
+    stored: VecDeque<(Instant, K)>,
+}
+
+enum Slot<V> {
+    /// The original request is still running; its sender is dropped when it finishes.
+    Pending(watch::Receiver<()>),
+    Done {
+        value: V,
+        stored: Instant,
+    },
+}
+
+enum Claim<V> {
+    Replay { value: V, age: Duration },
+    Wait(watch::Receiver<()>),
+    Lead(watch::Sender<()>),
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Store<K, V> {
+    fn claim(&mut self, key: &K, ttl: Duration, max_entries: usize) -> Claim<V> {
+        match self.entries.get(key) {
+            Some(Slot::Done { value, stored }) if stored.elapsed() < ttl => {
+                return Claim::Replay { value: value.clone(), age: stored.elapsed() };
+            }
+            Some(Slot::Pending(done)) => return Claim::Wait(done.clone()),
+            Some(Slot::Done { .. }) => {}
+            None => self.make_room(ttl, max_entries),
+        }
+        let (tx, rx) = watch::channel(());
+        self.entries.insert(key.clone(), Slot::Pending(rx));
+        Claim::Lead(tx)
+    }
+
+    fn insert(&mut self, key: K, value: V, ttl: Duration, max_entries: usize) {
+        // Normally this replaces the key's own pending slot, which already counts.
+        if !self.entries.contains_key(&key) {
+            self.make_room(ttl, max_entries);
+        }
+        let stored = Instant::now();
+        self.stored.push_back((stored, key.clone()));
+        self.entries.insert(key, Slot::Done { value, stored });
+    }
+
+    /// Drop expired responses, then the oldest stored ones until a new key fits.
+    fn make_room(&mut self, ttl: Duration, max_entries: usize) {
+        while let Some((stored, _)) = self.stored.front() {
+            if stored.elapsed() < ttl && self.entries.len() < max_entries {
+                break;
+            }
+            let Some((stored, key)) = self.stored.pop_front() else { break };
+            if matches!(self.entries.get(&key), Some(Slot::Done { stored: s, .. }) if *s == stored)
+            {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
+
+/// Releases a pending key if the original request fails or is dropped before completing.
+struct LeadGuard<K: Eq + Hash + Clone, V: Clone> {
+    store: SharedStore<K, V>,
+    key: Option<K>,
+    _done: watch::Sender<()>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> LeadGuard<K, V> {
+    fn complete(&mut self, value: V, ttl: Duration, max_entries: usize) {
+        if let Some(key) = self.key.take() {
+            let mut store = self.store.lock().unwrap_or_else(PoisonError::into_inner);
+            store.insert(key, value, ttl, max_entries);
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Drop for LeadGuard<K, V> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.store.lock().unwrap_or_else(PoisonError::into_inner).entries.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Payments {
+        calls: Arc<AtomicUsize>,
+        failing: Arc<AtomicBool>,
+    }
+
+    impl Service<(Option<&'static str>, u32)> for Payments {
+        type Response = String;
+        type Error = TestError;
+        type Future = BoxFuture<'static, Result<String, TestError>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), TestError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, (_, amount): (Option<&'static str>, u32)) -> Self::Future {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let failing = self.failing.load(Ordering::SeqCst);
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                if failing {
+                    Err(TestError("declined".into()))
+                } else {
+                    Ok(format!("charge#{} {}", n, amount))
+                }
+            })
+        }
+    }
+
+    type Key = fn(&(Option<&'static str>, u32)) -> Option<&'static str>;
+
+    fn layer() -> IdempotencyLayer<&'static str, String, Key> {
+        IdempotencyLayer::new(Duration::from_secs(60), |req: &(Option<&'static str>, u32)| req.0)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replays_the_original_response_until_ttl() {
+        let payments = Payments::default();
+        let sink = MemorySink::new();
+        let mut svc = layer().with_sink(sink.clone()).layer(payments.clone());
+
+        let first = svc.ready().await.unwrap().call((Some("k1"), 5)).await.unwrap();
+        let dup = svc.ready().await.unwrap().call((Some("k1"), 5)).await.unwrap();
+        assert_eq!(first, dup);
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            sink.events()[0],
+            PolicyEvent::Idempotency(IdempotencyEvent::Replayed { .. })
+        ));
+
+        svc.ready().await.unwrap().call((None, 5)).await.unwrap();
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 2, "keyless requests pass through");
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        let fresh = svc.ready().await.unwrap().call((Some("k1"), 5)).await.unwrap();
+        assert_ne!(fresh, first);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_duplicates_wait_for_the_original() {
+        let payments = Payments::default();
+        let svc = layer().layer(payments.clone());
+
+        let (a, b) =
+            tokio::join!(svc.clone().oneshot((Some("k"), 1)), svc.clone().oneshot((Some("k"), 1)));
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failures_are_not_remembered() {
+        let payments = Payments::default();
+        payments.failing.store(true, Ordering::SeqCst);
+        let svc = layer().layer(payments.clone());
+
+        let (a, b) =
+            tokio::join!(svc.clone().oneshot((Some("k"), 1)), svc.clone().oneshot((Some("k"), 1)));
+        assert!(a.is_err() && b.is_err());
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 2, "the duplicate ran after the failure");
+
+        payments.failing.store(false, Ordering::SeqCst);
+        assert!(svc.clone().oneshot((Some("k"), 1)).await.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expired_responses_are_purged_before_live_ones() {
+        let payments = Payments::default();
+        let layer = layer().max_entries(2);
+        let mut svc = layer.clone().layer(payments.clone());
+
+        svc.ready().await.unwrap().call((Some("old"), 1)).await.unwrap();
+        tokio::time::advance(Duration::from_secs(30)).await;
+        svc.ready().await.unwrap().call((Some("live"), 2)).await.unwrap();
+        tokio::time::advance(Duration::from_secs(31)).await;
+        svc.ready().await.unwrap().call((Some("new"), 3)).await.unwrap();
+
+        let mut keys: Vec<_> = layer.store.lock().unwrap().entries.keys().copied().collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["live", "new"]);
+        svc.ready().await.unwrap().call((Some("live"), 2)).await.unwrap();
+        assert_eq!(payments.calls.load(Ordering::SeqCst), 3, "the live response was replayed");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_requests_count_against_the_bound() {
+        let payments = Payments::default();
+        let layer = layer().max_entries(2);
+        let mut svc = layer.clone().layer(payments.clone());
+
+        svc.ready().await.unwrap().call((Some("a"), 1)).await.unwrap();
+        svc.ready().await.unwrap().call((Some("b"), 2)).await.unwrap();
+        let running = tokio::spawn(svc.clone().oneshot((Some("c"), 3)));
+        while payments.calls.load(Ordering::SeqCst) < 3 {
+            tokio::task::yield_now().await;
+        }
+        {
+            let store = layer.store.lock().unwrap();
+            assert_eq!(store.entries.len(), 2);
+            assert!(!store.entries.contains_key("a"), "the oldest response made room");
+        }
+        running.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,328 @@
+//! All-of composition: call two stacks and succeed only if both do.
+//!
+//! Semantics
+//! - Every request is cloned and sent to both stacks concurrently; the service waits for both
+//!   to finish, even if one has already failed, so a dual write is never abandoned half way.
+//! - On success the two responses are returned as a tuple, or merged with the combiner given to
+//!   [`JoinLayer::with_combiner`].
+//! - Any failure is reported as a [`JoinError`] saying which side failed; the stacks may have
+//!   different response and error types.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Dual write: the new store and the legacy store must both accept the record.
+//! let new_store = Policy(TimeoutLayer::new(Duration::from_millis(200))?);
+//! let legacy_store = Policy(TimeoutLayer::new(Duration::from_secs(1))?);
+//! let dual_write = Policy::join(new_store, legacy_store).with_combiner(|a: u64, b: u64| a.max(b));
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(dual_write)
+//!     .service_fn(|version: u64| async move { Ok::<_, std::io::Error>(version) });
+//! assert_eq!(svc.ready().await?.call(7).await?, 7);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::Policy;
+use crate::describe::{Describe, PolicyNode};
+use futures::future::BoxFuture;
+use std::fmt;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Merges the two responses of a [`JoinService`].
+///
+/// Implemented for [`Pair`] and for any `Fn(L, R) -> T`.
+pub trait Combine<L, R> {
+    /// The merged response.
+    type Output;
+
+    /// Merge the left and right responses.
+    fn combine(&self, left: L, right: R) -> Self::Output;
+}
+
+/// Default [`Combine`]: returns both responses as a `(left, right)` tuple.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pair;
+
+impl<L, R> Combine<L, R> for Pair {
+    type Output = (L, R);
+
+    fn combine(&self, left: L, right: R) -> (L, R) {
+        (left, right)
+    }
+}
+
+impl<L, R, T, F> Combine<L, R> for F
+where
+    F: Fn(L, R) -> T,
+{
+    type Output = T;
+
+    fn combine(&self, left: L, right: R) -> T {
+        self(left, right)
+    }
+}
+
+/// Layer sending each request to both `left` and `right`, requiring both to succeed.
+#[derive(Clone, Debug)]
+pub struct JoinLayer<A, B, C = Pair> {
+    left: A,
+    right: B,
+    combiner: C,
+}
+
+impl<A, B> JoinLayer<A, B> {
+    /// Require both `left` and `right` to succeed, returning both responses.
+    pub fn new(left: A, right: B) -> Self {
+        Self { left, right, combiner: Pair }
+    }
+
+    /// Merge the two responses with `combiner` instead of returning a tuple.
+    pub fn with_combiner<C>(self, combiner: C) -> JoinLayer<A, B, C> {
+        JoinLayer { left: self.left, right: self.right, combiner }
+    }
+}
+
+impl<A, B> Policy<JoinLayer<A, B>> {
+    /// Require both `left` and `right` to succeed, returning both responses.
+    pub fn join(left: Policy<A>, right: Policy<B>) -> Self {
+        Policy(JoinLayer::new(left.0, right.0))
+    }
+
+    /// Merge the two responses with `combiner`; see [`JoinLayer::with_combiner`].
+    pub fn with_combiner<C>(self, combiner: C) -> Policy<JoinLayer<A, B, C>> {
+        Policy(self.0.with_combiner(combiner))
+    }
+}
+
+impl<S, A, B, C> Layer<S> for JoinLayer<A, B, C>
+where
+    S: Clone,
+    A: Layer<S>,
+    B: Layer<S>,
+    C: Clone,
+{
+    type Service = JoinService<A::Service, B::Service, C>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        JoinService {
+            left: self.left.layer(service.clone()),
+            right: self.right.layer(service),
+            combiner: self.combiner.clone(),
+        }
+    }
+}
+
+impl<A: Describe, B: Describe, C> Describe for JoinLayer<A, B, C> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::branch(
+            "Join",
+            [("left", self.left.describe()), ("right", self.right.describe())],
+        )
+    }
+}
+
+/// Service produced by [`JoinLayer`].
+#[derive(Clone, Debug)]
+pub struct JoinService<S1, S2, C = Pair> {
+    left: S1,
+    right: S2,
+    combiner: C,
+}
+
+impl<S1, S2, C, Request> Service<Request> for JoinService<S1, S2, C>
+where
+    Request: Clone,
+    S1: Service<Request>,
+    S2: Service<Request>,
+    S1::Future: Send + 'static,
+    S2::Future: Send + 'static,
+    S1::Response: Send + 'static,
+    S2::Response: Send + 'static,
+    S1::Error: Send + 'static,
+    S2::Error: Send + 'static,
+    C: Combine<S1::Response, S2::Response> + Clone + Send + 'static,
+{
+    type Response = C::Output;
+    type Error = JoinError<S1::Error, S2::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match (self.left.poll_ready(cx), self.right.poll_ready(cx)) {
+            (Poll::Ready(Err(e)), _) => Poll::Ready(Err(JoinError::Left(e))),
+            (_, Poll::Ready(Err(e))) => Poll::Ready(Err(JoinError::Right(e))),
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => Poll::Ready(Ok(())),
+            _ => Poll::Pending,
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let left = self.left.call(req.clone());
+        let right = self.right.call(req);
+        let combiner = self.combiner.clone();
+        Box::pin(async move {
+            match futures::future::join(left, right).await {
+                (Ok(l), Ok(r)) => Ok(combiner.combine(l, r)),
+                (Err(left), Err(right)) => Err(JoinError::Both { left, right }),
+                (Err(left), Ok(_)) => Err(JoinError::Left(left)),
+                (Ok(_), Err(right)) => Err(JoinError::Right(right)),
+            }
+        })
+    }
+}
+
+/// Error returned by [`JoinService`] when either side fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinError<L, R = L> {
+    /// Only the left stack failed.
+    Left(L),
+    /// Only the right stack failed.
+    Right(R),
+    /// Both stacks failed.
+    Both {
+        /// Error from the left stack.
+        left: L,
+        /// Error from the right stack.
+        right: R,
+    },
+}
+
+impl<L, R> JoinError<L, R> {
+    /// Error from the left stack, if it failed.
+    pub fn left(&self) -> Option<&L> {
+        match self {
+            Self::Left(left) | Self::Both { left, .. } => Some(left),
+            Self::Right(_) => None,
+        }
+    }
+
+    /// Error from the right stack, if it failed.
+    pub fn right(&self) -> Option<&R> {
+        match self {
+            Self::Right(right) | Self::Both { right, .. } => Some(right),
+            Self::Left(_) => None,
+        }
+    }
+}
+
+impl<E> JoinError<E> {
+    /// Collapse to a single error, preferring the left stack's.
+    pub fn into_left(self) -> E {
+        match self {
+            Self::Left(left) | Self::Both { left, .. } => left,
+            Self::Right(right) => right,
+        }
+    }
+}
+
+impl<L: fmt::Display, R: fmt::Display> fmt::Display for JoinError<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left(e) => write!(f, "left join branch failed: {}", e),
+            Self::Right(e) => write!(f, "right join branch failed: {}", e),
+            Self::Both { left, right } => {
+                write!(f, "both join branches failed (left: {}; right: {})", left, right)
+            }
+        }
+    }
+}
+
+impl<L, R> std::error::Error for JoinError<L, R>
+where
+    L: std::error::Error + 'static,
+    R: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Left(left) | Self::Both { left, .. } => Some(left),
+            Self::Right(right) => Some(right),
+        }
+    }
+}
+
+/// Collapses a join failure of resilience stacks, keeping the left stack's error.
+impl<E> From<JoinError<crate::ResilienceError<E>>> for crate::ResilienceError<E> {
+    fn from(err: JoinError<crate::ResilienceError<E>>) -> Self {
+        err.into_left()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Store {
+        name: &'static str,
+        fail: bool,
+        writes: Arc<AtomicUsize>,
+    }
+
+    impl<S> Layer<S> for Store {
+        type Service = tower::util::BoxCloneService<u32, String, String>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let store = self.clone();
+            tower::util::BoxCloneService::new(tower::service_fn(move |req: u32| {
+                let store = store.clone();
+                async move {
+                    tokio::task::yield_now().await;
+                    store.writes.fetch_add(1, Ordering::SeqCst);
+                    if store.fail {
+                        Err(format!("{} rejected {}", store.name, req))
+                    } else {
+                        Ok(format!("{}:{}", store.name, req))
+                    }
+                }
+            }))
+        }
+    }
+
+    fn store(name: &'static str, fail: bool, writes: &Arc<AtomicUsize>) -> Policy<Store> {
+        Policy(Store { name, fail, writes: Arc::clone(writes) })
+    }
+
+    #[tokio::test]
+    async fn returns_both_responses() {
+        let writes = Arc::new(AtomicUsize::new(0));
+        let mut svc =
+            Policy::join(store("new", false, &writes), store("legacy", false, &writes)).layer(());
+
+        let resp = svc.ready().await.unwrap().call(1).await.unwrap();
+        assert_eq!(resp, ("new:1".to_string(), "legacy:1".to_string()));
+        assert_eq!(writes.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn combiner_merges_responses() {
+        let writes = Arc::new(AtomicUsize::new(0));
+        let mut svc = Policy::join(store("a", false, &writes), store("b", false, &writes))
+            .with_combiner(|a: String, b: String| format!("{}+{}", a, b))
+            .layer(());
+
+        assert_eq!(svc.ready().await.unwrap().call(2).await.unwrap(), "a:2+b:2");
+    }
+
+    #[tokio::test]
+    async fn fails_if_either_side_fails_but_still_runs_both() {
+        let writes = Arc::new(AtomicUsize::new(0));
+        let mut svc =
+            Policy::join(store("new", true, &writes), store("legacy", false, &writes)).layer(());
+
+        let err = svc.ready().await.unwrap().call(3).await.unwrap_err();
+        assert_eq!(err, JoinError::Left("new rejected 3".to_string()));
+        assert_eq!(err.to_string(), "left join branch failed: new rejected 3");
+        assert_eq!(writes.load(Ordering::SeqCst), 2, "the legacy write must still complete");
+    }
+}
@@ -0,0 +1,361 @@
+//! Artificial latency for resilience testing.
+//!
+//! Semantics
+//! - [`LatencyInjectionLayer`] delays a configurable share of requests before they reach the
+//!   inner service, so timeouts, hedging, and budgets can be exercised against a slow backend
+//!   without one.
+//! - Delays are drawn from a [`LatencyDistribution`]: fixed, uniform over a range, or Pareto
+//!   (heavy-tailed, the usual shape of real tail latency) capped at a maximum.
+//! - The delay is applied through a [`Sleeper`], so `tokio::time::pause()` tests advance through
+//!   it virtually and a `TrackingSleeper` records each injected delay.
+//!
+//! Invariants
+//! - Requests not selected are forwarded immediately; with `probability(0.0)` the layer is inert.
+//! - Sampled delays never exceed the distribution's upper bound (`max` or `cap`).
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // 10% of requests get a heavy-tailed delay of at least 50ms, capped at 2s.
+//! let slow = LatencyInjectionLayer::new(LatencyDistribution::Pareto {
+//!     scale: Duration::from_millis(50),
+//!     shape: 1.5,
+//!     cap: Duration::from_secs(2),
+//! })?
+//! .probability(0.1)?
+//! .with_sleeper(InstantSleeper);
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(slow)
+//!     .service_fn(|req: u32| async move { Ok::<_, std::io::Error>(req) });
+//! assert_eq!(svc.ready().await?.call(1).await?, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Sleeper, TokioSleeper};
+use futures::future::BoxFuture;
+use rand::Rng;
+use std::fmt;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Errors produced while configuring latency injection.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LatencyInjectionError {
+    /// Probability must be within `[0.0, 1.0]`.
+    InvalidProbability {
+        /// The rejected value.
+        provided: f64,
+    },
+    /// A uniform range had `min > max`.
+    InvalidRange {
+        /// Lower bound supplied.
+        min: Duration,
+        /// Upper bound supplied.
+        max: Duration,
+    },
+    /// Pareto shape must be finite and > 0, with a non-zero scale no larger than the cap.
+    InvalidPareto {
+        /// Shape supplied.
+        shape: f64,
+    },
+}
+
+impl fmt::Display for LatencyInjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidProbability { provided } => {
+                write!(f, "latency injection probability must be within [0, 1] (got {})", provided)
+            }
+            Self::InvalidRange { min, max } => {
+                write!(
+                    f,
+                    "uniform latency range must satisfy min <= max (got {:?}..{:?})",
+                    min, max
+                )
+            }
+            Self::InvalidPareto { shape } => write!(
+                f,
+                "pareto latency needs shape > 0 and 0 < scale <= cap (got shape {})",
+                shape
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LatencyInjectionError {}
+
+/// Distribution artificial delays are drawn from.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyDistribution {
+    /// Always the same delay.
+    Fixed(Duration),
+    /// Uniformly distributed in `[min, max]`.
+    Uniform {
+        /// Shortest delay.
+        min: Duration,
+        /// Longest delay.
+        max: Duration,
+    },
+    /// Pareto-distributed: at least `scale`, with a tail whose weight falls as `shape` grows
+    /// (`1.0..=2.0` is typical of service latency), truncated at `cap`.
+    Pareto {
+        /// Minimum delay.
+        scale: Duration,
+        /// Tail index; smaller values give heavier tails.
+        shape: f64,
+        /// Longest delay ever injected.
+        cap: Duration,
+    },
+}
+
+impl LatencyDistribution {
+    /// Check that the parameters describe a valid distribution.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LatencyInjectionError`] for an inverted range or invalid Pareto parameters.
+    pub fn validate(&self) -> Result<(), LatencyInjectionError> {
+        match *self {
+            Self::Fixed(_) => Ok(()),
+            Self::Uniform { min, max } if min > max => {
+                Err(LatencyInjectionError::InvalidRange { min, max })
+            }
+            Self::Uniform { .. } => Ok(()),
+            Self::Pareto { scale, shape, cap } => {
+                if shape.is_finite() && shape > 0.0 && !scale.is_zero() && scale <= cap {
+                    Ok(())
+                } else {
+                    Err(LatencyInjectionError::InvalidPareto { shape })
+                }
+            }
+        }
+    }
+
+    /// Draw a delay using `rng` (inject a seeded RNG for reproducible tests).
+    pub fn sample_with_rng<R: Rng>(&self, rng: &mut R) -> Duration {
+        match *self {
+            Self::Fixed(delay) => delay,
+            Self::Uniform { min, max } => {
+                Duration::from_secs_f64(rng.random_range(min.as_secs_f64()..=max.as_secs_f64()))
+            }
+            Self::Pareto { scale, shape, cap } => {
+                // Inverse CDF: scale / U^(1/shape) with U in (0, 1].
+                let u: f64 = 1.0 - rng.random::<f64>();
+                let secs = scale.as_secs_f64() / u.powf(1.0 / shape);
+                Duration::from_secs_f64(secs.min(cap.as_secs_f64()))
+            }
+        }
+    }
+}
+
+/// Layer delaying a share of requests by a randomly drawn latency.
+#[derive(Clone)]
+pub struct LatencyInjectionLayer {
+    distribution: LatencyDistribution,
+    probability: f64,
+    sleeper: Arc<dyn Sleeper>,
+}
+
+impl LatencyInjectionLayer {
+    /// Delay every request by a sample from `distribution`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LatencyInjectionError`] if the distribution is invalid.
+    pub fn new(distribution: LatencyDistribution) -> Result<Self, LatencyInjectionError> {
+        distribution.validate()?;
+        Ok(Self { distribution, probability: 1.0, sleeper: Arc::new(TokioSleeper) })
+    }
+
+    /// Only delay this fraction of requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LatencyInjectionError::InvalidProbability`] unless within `[0.0, 1.0]`.
+    pub fn probability(mut self, probability: f64) -> Result<Self, LatencyInjectionError> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(LatencyInjectionError::InvalidProbability { provided: probability });
+        }
+        self.probability = probability;
+        Ok(self)
+    }
+
+    /// Provide a custom sleeper implementation.
+    pub fn with_sleeper<S>(mut self, sleeper: S) -> Self
+    where
+        S: Sleeper + 'static,
+    {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    fn draw(&self) -> Option<Duration> {
+        let mut rng = rand::rng();
+        rng.random_bool(self.probability).then(|| self.distribution.sample_with_rng(&mut rng))
+    }
+}
+
+impl fmt::Debug for LatencyInjectionLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyInjectionLayer")
+            .field("distribution", &self.distribution)
+            .field("probability", &self.probability)
+            .field("sleeper", &self.sleeper)
+            .finish()
+    }
+}
+
+impl<S> Layer<S> for LatencyInjectionLayer {
+    type Service = LatencyInjectionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LatencyInjectionService { inner, layer: self.clone() }
+    }
+}
+
+impl crate::Describe for LatencyInjectionLayer {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!(
+            "LatencyInjection({:.0}%, {:?})",
+            self.probability * 100.0,
+            self.distribution
+        ))
+    }
+}
+
+/// Service produced by [`LatencyInjectionLayer`].
+#[derive(Clone, Debug)]
+pub struct LatencyInjectionService<S> {
+    inner: S,
+    layer: LatencyInjectionLayer,
+}
+
+impl<S, Request> Service<Request> for LatencyInjectionService<S>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let delay = self.layer.draw();
+        let sleeper = Arc::clone(&self.layer.sleeper);
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            if let Some(delay) = delay {
+                sleeper.sleep(delay).await;
+            }
+            fut.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackingSleeper;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use tokio::time::Instant;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    fn echo() -> impl Service<u32, Response = u32, Error = TestError, Future = impl Send> {
+        tower::service_fn(|req: u32| async move { Ok::<_, TestError>(req) })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fixed_delay_advances_virtual_time() {
+        let mut svc =
+            LatencyInjectionLayer::new(LatencyDistribution::Fixed(Duration::from_millis(250)))
+                .unwrap()
+                .layer(echo());
+        let start = Instant::now();
+        assert_eq!(svc.ready().await.unwrap().call(1).await.unwrap(), 1);
+        assert_eq!(start.elapsed(), Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn probability_selects_requests() {
+        let sleeper = TrackingSleeper::new();
+        let dist = LatencyDistribution::Fixed(Duration::from_millis(5));
+        let mut never = LatencyInjectionLayer::new(dist)
+            .unwrap()
+            .probability(0.0)
+            .unwrap()
+            .with_sleeper(sleeper.clone())
+            .layer(echo());
+        for i in 0..20 {
+            never.ready().await.unwrap().call(i).await.unwrap();
+        }
+        assert_eq!(sleeper.calls(), 0);
+
+        let mut always =
+            LatencyInjectionLayer::new(dist).unwrap().with_sleeper(sleeper.clone()).layer(echo());
+        always.ready().await.unwrap().call(0).await.unwrap();
+        assert_eq!(sleeper.all_calls(), vec![Duration::from_millis(5)]);
+    }
+
+    #[test]
+    fn samples_stay_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (min, max) = (Duration::from_millis(10), Duration::from_millis(20));
+        let uniform = LatencyDistribution::Uniform { min, max };
+        let pareto = LatencyDistribution::Pareto { scale: min, shape: 1.2, cap: max };
+        for _ in 0..1000 {
+            let u = uniform.sample_with_rng(&mut rng);
+            let p = pareto.sample_with_rng(&mut rng);
+            assert!(u >= min && u <= max, "{:?}", u);
+            assert!(p >= min && p <= max, "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        let fixed = LatencyDistribution::Fixed(Duration::from_millis(1));
+        assert!(matches!(
+            LatencyInjectionLayer::new(fixed).unwrap().probability(1.5),
+            Err(LatencyInjectionError::InvalidProbability { .. })
+        ));
+        let inverted = LatencyDistribution::Uniform {
+            min: Duration::from_secs(2),
+            max: Duration::from_secs(1),
+        };
+        assert!(matches!(
+            LatencyInjectionLayer::new(inverted),
+            Err(LatencyInjectionError::InvalidRange { .. })
+        ));
+        let zero_shape = LatencyDistribution::Pareto {
+            scale: Duration::from_millis(1),
+            shape: 0.0,
+            cap: Duration::from_secs(1),
+        };
+        assert_eq!(zero_shape.validate(), Err(LatencyInjectionError::InvalidPareto { shape: 0.0 }));
+    }
+}
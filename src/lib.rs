@@ -110,37 +110,125 @@
 //!
 //! For more examples, see the algebra module documentation.
 
+mod adaptive;
+mod aggregate;
+mod alert;
 mod algebra;
+mod atomic_bulkhead;
 mod backoff;
+mod bandwidth;
+mod boxed;
+mod budget;
 mod bulkhead;
 mod circuit_breaker;
 mod clock;
+mod coalesce;
+mod concurrency_limit;
+mod cond;
+mod context;
+mod deadline;
+mod describe;
 mod error;
+mod fallback_chain;
+mod hedge;
+mod idempotency;
 mod jitter;
+mod join;
+mod latency;
+mod lint;
+mod load_shed;
+#[cfg(feature = "metrics")]
+mod metrics_sink;
+mod named;
+mod policy_metrics;
+mod priority;
+mod quorum;
+mod race;
+mod rate_limit;
 mod retry;
+mod router;
 mod sleeper;
+mod span;
+mod spec;
+mod spillover;
+mod stale_cache;
+mod summary;
 // stack module removed in favor of tower-native algebra
 pub mod telemetry;
+mod throttle;
 mod timeout;
+mod value_fallback;
+mod watchdog;
+mod weighted;
 
 // Re-exports
+pub use adaptive::Adaptive;
+pub use aggregate::{AggregateSnapshot, AggregatingSink, LatencyHistogram};
+pub use alert::{AlertRuleError, StormDetector};
 pub use algebra::{
-    CombinedLayer, FallbackLayer, FallbackService, ForkJoinLayer, ForkJoinService, Policy,
+    AlwaysFallback, CombinedLayer, CompositionError, FallbackError, FallbackLayer,
+    FallbackPredicate, FallbackService, ForkJoinError, ForkJoinLayer, ForkJoinService, Policy,
 };
+pub use atomic_bulkhead::{AtomicBulkheadLayer, AtomicBulkheadService};
 pub use backoff::{
     Backoff, BackoffError, BackoffStrategy, ConstantBackoff, ExponentialBackoff, LinearBackoff,
     MAX_BACKOFF,
 };
+pub use bandwidth::{BandwidthError, BandwidthLayer, BandwidthService, ResponseSize, Uncounted};
+pub use boxed::{BoxLayer, BoxPolicy};
+pub use budget::{BudgetLayer, BudgetService};
 pub use bulkhead::BulkheadLayer;
 pub use bulkhead::{BulkheadError, BulkheadPolicy};
 pub use circuit_breaker::{
     CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerLayer, CircuitState,
 };
 pub use clock::{Clock, MonotonicClock};
+pub use coalesce::{CoalesceLayer, CoalesceService};
+pub use concurrency_limit::{
+    AdaptiveConcurrencyLayer, AdaptiveConcurrencyService, ConcurrencyLimitError,
+};
+pub use cond::{CondLayer, CondService};
+pub use context::RequestContext;
+pub use deadline::{Deadline, DeadlineParseError, GRPC_TIMEOUT_HEADER, TIMEOUT_HEADER};
+pub use describe::{Describe, PolicyNode};
 pub use error::ResilienceError;
+pub use fallback_chain::{FallbackChainLayer, FallbackChainService};
+pub use hedge::{HedgeLayer, HedgeService};
+pub use idempotency::{IdempotencyLayer, IdempotencyService};
 pub use jitter::Jitter;
+pub use join::{Combine, JoinError, JoinLayer, JoinService, Pair};
+pub use latency::{
+    LatencyDistribution, LatencyInjectionError, LatencyInjectionLayer, LatencyInjectionService,
+};
+pub use lint::CompositionWarning;
+pub use load_shed::{LoadShedError, LoadShedLayer, LoadShedService, ShedSignal};
+#[cfg(feature = "metrics")]
+pub use metrics_sink::MetricsSink;
+pub use named::{NamedLayer, NamedService};
+pub use policy_metrics::{
+    BulkheadMetrics, CircuitBreakerMetrics, LayerMetrics, MetricsSnapshot, PolicyMetrics,
+    RetryMetrics, TimeoutMetrics,
+};
+pub use priority::{Priority, PriorityError, PriorityLayer, PriorityService};
+pub use quorum::{QuorumError, QuorumLayer, QuorumService};
+pub use race::{RaceError, RaceLayer, RaceService};
+pub use rate_limit::{RateLimitAlgorithm, RateLimitError, RateLimitLayer, RateLimitService};
 pub use retry::{BuildError, RetryLayer, RetryPolicy, RetryPolicyBuilder, RetryService};
+pub use router::{RouterLayer, RouterService};
 pub use sleeper::{InstantSleeper, Sleeper, TokioSleeper, TrackingSleeper};
-pub use timeout::{TimeoutError, TimeoutLayer, TimeoutPolicy, MAX_TIMEOUT};
+pub use span::{TracingLayer, TracingService};
+pub use spec::{BackoffSpec, JitterSpec, PolicySpec, PolicySpecError};
+pub use spillover::{SpilloverError, SpilloverLayer, SpilloverService};
+pub use stale_cache::{StaleCacheLayer, StaleCacheService};
+pub use summary::{SummaryError, SummaryReporter};
+pub use throttle::{DebounceLayer, DebounceService, ThrottleLayer, ThrottleService};
+pub use timeout::{
+    GraceOutcome, TimeoutError, TimeoutLayer, TimeoutPhase, TimeoutPolicy, TimeoutProfile,
+    MAX_TIMEOUT,
+};
+pub use tokio_util::sync::CancellationToken;
+pub use value_fallback::{ValueFallbackLayer, ValueFallbackService};
+pub use watchdog::{WatchdogError, WatchdogLayer, WatchdogService};
+pub use weighted::{WeightedLayer, WeightedService};
 
 pub mod prelude;
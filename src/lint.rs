@@ -0,0 +1,214 @@
+//! Composition lint: flag layer orderings that are known to behave badly.
+//!
+//! Semantics
+//! - [`PolicyNode::lint`] walks a described policy and returns a [`CompositionWarning`] for each
+//!   problem it finds; [`Describe::validate`](crate::Describe::validate) is shorthand for
+//!   `policy.describe().lint()`.
+//! - Warnings are advisory. Nothing is rejected, so call `validate` where the stack is built
+//!   (startup, a test) and log or assert on the result.
+//! - Checks follow the layer's position in the composition: a layer "wraps" everything after it
+//!   in a `+` sequence and everything inside its combinator branches.
+//!
+//! Checks
+//! - Retry wrapping a circuit breaker: once the breaker opens, every retry attempt is rejected
+//!   immediately, burning the retry budget (and backoff sleeps) without reaching the backend.
+//! - Retry without an enclosing timeout or budget: per-attempt timeouts inside the retry, or none
+//!   at all, leave the total time spent on a request unbounded by the policy.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let retry = RetryPolicy::<std::io::Error>::builder().max_attempts(3).build()?.into_layer();
+//! let per_attempt = TimeoutLayer::new(Duration::from_millis(200))?;
+//!
+//! let inverted = Policy(retry.clone()) + Policy(per_attempt.clone());
+//! assert!(matches!(
+//!     inverted.validate().as_slice(),
+//!     [CompositionWarning::RetryWithoutTimeout { .. }]
+//! ));
+//!
+//! let bounded = Policy(TimeoutLayer::new(Duration::from_secs(1))?)
+//!     + Policy(retry)
+//!     + Policy(per_attempt);
+//! assert!(bounded.validate().is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::describe::PolicyNode;
+use std::fmt;
+
+/// A problem found by [`PolicyNode::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompositionWarning {
+    /// A retry layer wraps a circuit breaker, so retries hammer an open breaker.
+    RetryWrapsCircuitBreaker {
+        /// Location of the circuit breaker, e.g. `Retry(x3) > CircuitBreaker(5 failures, 30s)`.
+        path: String,
+    },
+    /// A retry layer is not enclosed by any timeout, so total time is unbounded.
+    RetryWithoutTimeout {
+        /// Location of the retry layer.
+        path: String,
+    },
+}
+
+impl CompositionWarning {
+    /// Location of the offending layer within the composition.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::RetryWrapsCircuitBreaker { path } | Self::RetryWithoutTimeout { path } => path,
+        }
+    }
+}
+
+impl fmt::Display for CompositionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RetryWrapsCircuitBreaker { path } => write!(
+                f,
+                "retry wraps a circuit breaker at {}; retries will be rejected while it is open \
+                 (move the breaker outside the retry, or skip retrying CircuitOpen errors)",
+                path
+            ),
+            Self::RetryWithoutTimeout { path } => write!(
+                f,
+                "retry at {} has no enclosing timeout; total request time is unbounded \
+                 (add a timeout outside the retry)",
+                path
+            ),
+        }
+    }
+}
+
+impl PolicyNode {
+    /// Check this composition for known-bad layer orderings.
+    #[must_use]
+    pub fn lint(&self) -> Vec<CompositionWarning> {
+        let mut walker = Walker::default();
+        walker.walk(self);
+        walker.warnings
+    }
+}
+
+#[derive(Default)]
+struct Walker<'a> {
+    /// Kinds of the layers wrapping the current node, outermost first.
+    enclosing: Vec<&'a str>,
+    /// Human-readable route to the current node.
+    path: Vec<String>,
+    warnings: Vec<CompositionWarning>,
+}
+
+impl<'a> Walker<'a> {
+    fn walk(&mut self, node: &'a PolicyNode) {
+        match node {
+            PolicyNode::Layer(label) => self.check(label),
+            PolicyNode::Sequence(items) => {
+                let (depth, path_len) = (self.enclosing.len(), self.path.len());
+                for item in items {
+                    self.walk(item);
+                    if let PolicyNode::Layer(label) = item {
+                        self.enclosing.push(kind(label));
+                        self.path.push(label.clone());
+                    }
+                }
+                self.enclosing.truncate(depth);
+                self.path.truncate(path_len);
+            }
+            PolicyNode::Branch { label, branches } => {
+                for (role, child) in branches {
+                    self.path.push(format!("{}[{}]", label, role));
+                    self.walk(child);
+                    self.path.pop();
+                }
+            }
+            PolicyNode::Named { name, inner } => {
+                self.path.push(name.clone());
+                self.walk(inner);
+                self.path.pop();
+            }
+        }
+    }
+
+    fn check(&mut self, label: &str) {
+        let wrapped_by = |k: &str| self.enclosing.contains(&k);
+        let warning = match kind(label) {
+            "CircuitBreaker" if wrapped_by("Retry") => {
+                Some(CompositionWarning::RetryWrapsCircuitBreaker { path: self.location(label) })
+            }
+            "Retry" if !wrapped_by("Timeout") && !wrapped_by("Budget") => {
+                Some(CompositionWarning::RetryWithoutTimeout { path: self.location(label) })
+            }
+            _ => None,
+        };
+        self.warnings.extend(warning);
+    }
+
+    fn location(&self, label: &str) -> String {
+        self.path.iter().map(String::as_str).chain([label]).collect::<Vec<_>>().join(" > ")
+    }
+}
+
+/// Layer kind from a label such as `Retry(x3)`.
+fn kind(label: &str) -> &str {
+    label.split('(').next().unwrap_or(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seq(nodes: &[&str]) -> PolicyNode {
+        PolicyNode::Sequence(nodes.iter().map(|l| PolicyNode::layer(*l)).collect())
+    }
+
+    #[test]
+    fn flags_retry_around_circuit_breaker() {
+        let node = seq(&["Timeout(1s)", "Retry(x3)", "CircuitBreaker(5 failures, 30s)"]);
+        assert_eq!(
+            node.lint(),
+            vec![CompositionWarning::RetryWrapsCircuitBreaker {
+                path: "Timeout(1s) > Retry(x3) > CircuitBreaker(5 failures, 30s)".into()
+            }]
+        );
+
+        let fixed = seq(&["Timeout(1s)", "CircuitBreaker(5 failures, 30s)", "Retry(x3)"]);
+        assert!(fixed.lint().is_empty());
+
+        let budgeted = seq(&["Budget(1s)", "CircuitBreaker(5 failures, 30s)", "Retry(x3)"]);
+        assert!(budgeted.lint().is_empty(), "a budget bounds the retry like a timeout");
+    }
+
+    #[test]
+    fn flags_retry_without_outer_timeout_inside_branches() {
+        let node = PolicyNode::branch(
+            "Fallback",
+            [
+                ("primary", seq(&["Retry(x3)", "Timeout(100ms)"])),
+                ("secondary", seq(&["Timeout(2s)"])),
+            ],
+        );
+        let warnings = node.lint();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path(), "Fallback[primary] > Retry(x3)");
+        assert!(warnings[0].to_string().contains("no enclosing timeout"));
+    }
+
+    #[test]
+    fn outer_timeout_covers_nested_branches() {
+        let node = PolicyNode::sequence(
+            PolicyNode::layer("Timeout(1s)"),
+            PolicyNode::Named {
+                name: "reads".into(),
+                inner: Box::new(PolicyNode::numbered("Race", [seq(&["Retry(x2)"])])),
+            },
+        );
+        assert!(node.lint().is_empty());
+    }
+}
@@ -0,0 +1,474 @@
+//! Probabilistic load shedding driven by a health signal.
+//!
+//! Semantics
+//! - Each request reads a load ratio from the configured [`ShedSignal`]: observed p99 latency
+//!   over the target, in-flight requests over the target, or a caller-supplied callback. `1.0`
+//!   means "at target".
+//! - At or below target nothing is shed. Above it the desired shed probability grows linearly,
+//!   reaching [`LoadShedLayer::max_shed`] at twice the target.
+//! - The applied probability moves toward the desired one by a [`LoadShedLayer::smoothing`]
+//!   fraction per request, so shedding ramps up and recovers gradually instead of flapping.
+//! - Shed requests fail fast with [`ResilienceError::Shed`] and emit [`LoadShedEvent::Shed`]; the
+//!   inner service is not called.
+//! - Where a bulkhead is a hard cap, this is a brown-out: some traffic keeps flowing while the
+//!   backend recovers. Services built from the same layer share the signal and the probability.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let shed = LoadShedLayer::new(ShedSignal::Latency { target: Duration::from_millis(250) })?
+//!     .max_shed(0.8)?
+//!     .smoothing(0.05)?;
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(shed)
+//!     .service_fn(|req: u32| async move { Ok::<_, std::io::Error>(req) });
+//! assert_eq!(svc.ready().await?.call(1).await?, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, LoadShedEvent, NullSink, PolicyEvent};
+use crate::ResilienceError;
+use futures::future::BoxFuture;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Latency samples kept for the p99 estimate.
+const LATENCY_WINDOW: usize = 128;
+/// Below this many samples the latency signal reports no load.
+const MIN_LATENCY_SAMPLES: usize = 16;
+
+/// Health signal a [`LoadShedLayer`] sheds on.
+#[derive(Clone)]
+pub enum ShedSignal {
+    /// p99 of recent request latencies compared with `target`.
+    Latency {
+        /// Latency considered healthy.
+        target: Duration,
+    },
+    /// Requests currently in flight through this layer compared with `target`.
+    InFlight {
+        /// In-flight count considered healthy.
+        target: usize,
+    },
+    /// Load ratio supplied by the caller, e.g. from queue depth or CPU; `1.0` is at target.
+    Custom(Arc<dyn Fn() -> f64 + Send + Sync>),
+}
+
+impl ShedSignal {
+    /// Shed on a load ratio computed by `f`.
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn() -> f64 + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for ShedSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latency { target } => f.debug_struct("Latency").field("target", target).finish(),
+            Self::InFlight { target } => {
+                f.debug_struct("InFlight").field("target", target).finish()
+            }
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl fmt::Display for ShedSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latency { target } => write!(f, "p99 > {:?}", target),
+            Self::InFlight { target } => write!(f, "in-flight > {}", target),
+            Self::Custom(_) => f.write_str("custom"),
+        }
+    }
+}
+
+/// Errors produced while configuring a load shedder.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadShedError {
+    /// The latency or in-flight target was zero.
+    ZeroTarget,
+    /// `max_shed` was outside `[0.0, 1.0]`.
+    InvalidMaxShed {
+        /// The rejected value.
+        provided: f64,
+    },
+    /// `smoothing` was outside `(0.0, 1.0]`.
+    InvalidSmoothing {
+        /// The rejected value.
+        provided: f64,
+    },
+}
+
+impl fmt::Display for LoadShedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroTarget => write!(f, "load shed target must be > 0"),
+            Self::InvalidMaxShed { provided } => {
+                write!(f, "load shed max_shed must be within [0, 1] (got {})", provided)
+            }
+            Self::InvalidSmoothing { provided } => {
+                write!(f, "load shed smoothing must be within (0, 1] (got {})", provided)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadShedError {}
+
+/// Tower layer shedding a growing share of requests as the health signal degrades.
+#[derive(Clone, Debug)]
+pub struct LoadShedLayer<Sink = NullSink> {
+    shedder: Shedder,
+    sink: Sink,
+}
+
+impl LoadShedLayer<NullSink> {
+    /// Shed on `signal`, with `max_shed` 0.9 and `smoothing` 0.1.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoadShedError::ZeroTarget`] if the signal's target is zero.
+    pub fn new(signal: ShedSignal) -> Result<Self, LoadShedError> {
+        match signal {
+            ShedSignal::Latency { target } if target.is_zero() => {
+                return Err(LoadShedError::ZeroTarget)
+            }
+            ShedSignal::InFlight { target: 0 } => return Err(LoadShedError::ZeroTarget),
+            _ => {}
+        }
+        let shedder = Shedder {
+            signal,
+            max_shed: 0.9,
+            smoothing: 0.1,
+            state: Arc::new(ShedState::default()),
+        };
+        Ok(Self { shedder, sink: NullSink })
+    }
+}
+
+impl<Sink> LoadShedLayer<Sink> {
+    /// Highest fraction of requests ever shed, reached at twice the target load.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoadShedError::InvalidMaxShed`] unless `max_shed` is within `[0.0, 1.0]`.
+    pub fn max_shed(mut self, max_shed: f64) -> Result<Self, LoadShedError> {
+        if !(0.0..=1.0).contains(&max_shed) {
+            return Err(LoadShedError::InvalidMaxShed { provided: max_shed });
+        }
+        self.shedder.max_shed = max_shed;
+        Ok(self)
+    }
+
+    /// Fraction of the gap to the desired probability closed on each request; `1.0` disables
+    /// smoothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoadShedError::InvalidSmoothing`] unless `smoothing` is within `(0.0, 1.0]`.
+    pub fn smoothing(mut self, smoothing: f64) -> Result<Self, LoadShedError> {
+        if !(smoothing > 0.0 && smoothing <= 1.0) {
+            return Err(LoadShedError::InvalidSmoothing { provided: smoothing });
+        }
+        self.shedder.smoothing = smoothing;
+        Ok(self)
+    }
+
+    /// Attach a telemetry sink to this load shedder.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> LoadShedLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        LoadShedLayer { shedder: self.shedder, sink }
+    }
+
+    /// Fraction of requests currently being shed.
+    #[must_use]
+    pub fn shed_probability(&self) -> f64 {
+        self.shedder.state.lock().probability
+    }
+}
+
+impl<S, Sink: Clone> Layer<S> for LoadShedLayer<Sink> {
+    type Service = LoadShedService<S, Sink>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        LoadShedService { inner: service, shedder: self.shedder.clone(), sink: self.sink.clone() }
+    }
+}
+
+impl<Sink> crate::Describe for LoadShedLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("LoadShed({})", self.shedder.signal))
+    }
+}
+
+/// Service produced by [`LoadShedLayer`].
+#[derive(Clone, Debug)]
+pub struct LoadShedService<S, Sink = NullSink> {
+    inner: S,
+    shedder: Shedder,
+    sink: Sink,
+}
+
+impl<S, Request, Sink> Service<Request> for LoadShedService<S, Sink>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let (load, probability) = self.shedder.update();
+
+        if probability > 0.0 && rand::rng().random_bool(probability.min(1.0)) {
+            let sink = self.sink.clone();
+            return Box::pin(async move {
+                emit_best_effort(
+                    sink,
+                    PolicyEvent::LoadShed(LoadShedEvent::Shed { probability, load }),
+                )
+                .await;
+                Err(ResilienceError::Shed { probability })
+            });
+        }
+
+        let state = Arc::clone(&self.shedder.state);
+        let track_latency = matches!(self.shedder.signal, ShedSignal::Latency { .. });
+        state.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard(Arc::clone(&state));
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = fut.await;
+            drop(guard);
+            if track_latency {
+                state.record_latency(start.elapsed());
+            }
+            result.map_err(ResilienceError::Inner)
+        })
+    }
+}
+
+/// Configuration plus the state shared by every service built from one layer.
+#[derive(Clone, Debug)]
+struct Shedder {
+    signal: ShedSignal,
+    max_shed: f64,
+    smoothing: f64,
+    state: Arc<ShedState>,
+}
+
+impl Shedder {
+    /// Read the signal and step the shed probability toward its target; returns (load, p).
+    fn update(&self) -> (f64, f64) {
+        let mut state = self.state.lock();
+        let load = match &self.signal {
+            ShedSignal::Latency { target } => {
+                p99(&state.latencies).map_or(0.0, |p| p.as_secs_f64() / target.as_secs_f64())
+            }
+            ShedSignal::InFlight { target } => {
+                self.state.in_flight.load(Ordering::SeqCst) as f64 / *target as f64
+            }
+            ShedSignal::Custom(f) => f(),
+        };
+        let desired = if load.is_nan() || load <= 1.0 {
+            0.0
+        } else {
+            ((load - 1.0) * self.max_shed).min(self.max_shed)
+        };
+        state.probability += self.smoothing * (desired - state.probability);
+        (load, state.probability)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ShedState {
+    in_flight: AtomicUsize,
+    inner: Mutex<Window>,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    probability: f64,
+    latencies: VecDeque<Duration>,
+}
+
+impl ShedState {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Window> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record_latency(&self, latency: Duration) {
+        let mut window = self.lock();
+        if window.latencies.len() == LATENCY_WINDOW {
+            window.latencies.pop_front();
+        }
+        window.latencies.push_back(latency);
+    }
+}
+
+fn p99(samples: &VecDeque<Duration>) -> Option<Duration> {
+    if samples.len() < MIN_LATENCY_SAMPLES {
+        return None;
+    }
+    let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+    sorted.sort_unstable();
+    let idx = ((sorted.len() as f64) * 0.99).ceil() as usize - 1;
+    sorted.get(idx).copied()
+}
+
+struct InFlightGuard(Arc<ShedState>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::sync::atomic::AtomicU64;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    fn echo() -> tower::util::BoxCloneService<u32, u32, TestError> {
+        tower::util::BoxCloneService::new(tower::service_fn(|req: u32| async move {
+            Ok::<_, TestError>(req)
+        }))
+    }
+
+    #[tokio::test]
+    async fn sheds_when_custom_signal_is_overloaded() {
+        let load = Arc::new(AtomicU64::new(1.0_f64.to_bits()));
+        let reading = Arc::clone(&load);
+        let sink = MemorySink::new();
+        let layer = LoadShedLayer::new(ShedSignal::custom(move || {
+            f64::from_bits(reading.load(Ordering::SeqCst))
+        }))
+        .unwrap()
+        .max_shed(1.0)
+        .unwrap()
+        .smoothing(1.0)
+        .unwrap()
+        .with_sink(sink.clone());
+        let mut svc = layer.layer(echo());
+
+        assert_eq!(svc.ready().await.unwrap().call(1).await.unwrap(), 1);
+
+        load.store(3.0_f64.to_bits(), Ordering::SeqCst);
+        let err = svc.ready().await.unwrap().call(2).await.unwrap_err();
+        assert!(matches!(err, ResilienceError::Shed { probability } if probability == 1.0));
+        assert_eq!(
+            sink.events(),
+            vec![PolicyEvent::LoadShed(LoadShedEvent::Shed { probability: 1.0, load: 3.0 })]
+        );
+
+        load.store(0.5_f64.to_bits(), Ordering::SeqCst);
+        assert_eq!(svc.ready().await.unwrap().call(3).await.unwrap(), 3);
+        assert_eq!(layer.shed_probability(), 0.0);
+    }
+
+    #[test]
+    fn probability_ramps_smoothly() {
+        let layer = LoadShedLayer::new(ShedSignal::custom(|| 1.5)).unwrap().smoothing(0.5).unwrap();
+        // 50% over target with max_shed 0.9 aims for 0.45; each step closes half the gap.
+        let (_, first) = layer.shedder.update();
+        let (_, second) = layer.shedder.update();
+        assert!((first - 0.225).abs() < 1e-9, "first step: {}", first);
+        assert!((second - 0.3375).abs() < 1e-9, "second step: {}", second);
+        assert_eq!(layer.shed_probability(), second);
+    }
+
+    #[tokio::test]
+    async fn in_flight_signal_counts_outstanding_requests() {
+        let layer = LoadShedLayer::new(ShedSignal::InFlight { target: 1 })
+            .unwrap()
+            .max_shed(1.0)
+            .unwrap()
+            .smoothing(1.0)
+            .unwrap();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let release_rx = Arc::new(Mutex::new(Some(release_rx)));
+        let mut svc = layer.layer(tower::service_fn(move |req: u32| {
+            let rx = release_rx.lock().unwrap().take();
+            async move {
+                if let Some(rx) = rx {
+                    let _ = rx.await;
+                }
+                Ok::<_, TestError>(req)
+            }
+        }));
+
+        // The first request is held; the second sees one in flight (at target) and is admitted.
+        let first = tokio::spawn(svc.ready().await.unwrap().call(1));
+        let second = svc.ready().await.unwrap().call(2);
+        // With two outstanding the load is twice the target, so everything is shed.
+        let third = svc.ready().await.unwrap().call(3);
+        assert!(matches!(third.await, Err(ResilienceError::Shed { .. })));
+        assert_eq!(second.await.unwrap(), 2);
+
+        release_tx.send(()).unwrap();
+        assert_eq!(first.await.unwrap().unwrap(), 1);
+    }
+
+    #[test]
+    fn p99_needs_enough_samples() {
+        let few: VecDeque<Duration> = (0..8).map(Duration::from_millis).collect();
+        assert_eq!(p99(&few), None);
+        let many: VecDeque<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(p99(&many), Some(Duration::from_millis(99)));
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        assert_eq!(
+            LoadShedLayer::new(ShedSignal::InFlight { target: 0 }).unwrap_err(),
+            LoadShedError::ZeroTarget
+        );
+        let layer = LoadShedLayer::new(ShedSignal::InFlight { target: 4 }).unwrap();
+        assert_eq!(
+            layer.smoothing(0.0).unwrap_err(),
+            LoadShedError::InvalidSmoothing { provided: 0.0 }
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Publishing policy events through the [`metrics`](https://docs.rs/metrics) facade.
+//!
+//! [`MetricsSink`] turns events into counters, gauges, and histograms on whatever recorder the
+//! application installed (`metrics-exporter-prometheus`, a statsd exporter, ...), so no
+//! ninelives companion crate is needed. Requires the `metrics` feature.
+//!
+//! Semantics
+//! - `<prefix>_events_total{layer, event, policy}` counts every event, keyed like
+//!   [`AggregatingSink`](crate::AggregatingSink).
+//! - `<prefix>_request_duration_seconds{outcome, policy}` records
+//!   [`RequestOutcome`] durations, with `outcome` `success` or `failure`.
+//! - `<prefix>_retry_delay_seconds{policy}` records each retry backoff.
+//! - Gauges track the latest reported state: `<prefix>_bulkhead_in_flight`,
+//!   `<prefix>_concurrency_limit`, and `<prefix>_circuit_state` (0 closed, 1 half-open, 2 open).
+//! - `policy` is the name from `Policy::named`, or empty outside any named stack.
+//!
+//! Invariants
+//! - With no recorder installed every call is a no-op.
+//! - Metric names are fixed for a given prefix (default `ninelives`); only label values vary.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Install a recorder first, e.g. `metrics_exporter_prometheus::PrometheusBuilder`.
+//! let sink = MetricsSink::new().with_prefix("checkout");
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(TimeoutLayer::new(Duration::from_secs(1))?.with_sink(sink))
+//!     .service_fn(|n: u32| async move { Ok::<_, std::io::Error>(n) });
+//! svc.ready().await?.call(7).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{
+    BulkheadEvent, CircuitBreakerEvent, ConcurrencyEvent, PolicyEvent, RequestOutcome, RetryEvent,
+    TelemetrySink,
+};
+use crate::RequestContext;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+#[derive(Debug)]
+struct MetricNames {
+    events: String,
+    request_duration: String,
+    retry_delay: String,
+    bulkhead_in_flight: String,
+    concurrency_limit: String,
+    circuit_state: String,
+}
+
+impl MetricNames {
+    fn new(prefix: &str) -> Self {
+        Self {
+            events: format!("{}_events_total", prefix),
+            request_duration: format!("{}_request_duration_seconds", prefix),
+            retry_delay: format!("{}_retry_delay_seconds", prefix),
+            bulkhead_in_flight: format!("{}_bulkhead_in_flight", prefix),
+            concurrency_limit: format!("{}_concurrency_limit", prefix),
+            circuit_state: format!("{}_circuit_state", prefix),
+        }
+    }
+}
+
+/// Sink that records events as `metrics` counters, gauges, and histograms.
+#[derive(Debug, Clone)]
+pub struct MetricsSink {
+    names: Arc<MetricNames>,
+}
+
+impl Default for MetricsSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsSink {
+    /// Sink publishing metrics under the `ninelives` prefix.
+    pub fn new() -> Self {
+        Self { names: Arc::new(MetricNames::new("ninelives")) }
+    }
+
+    /// Publish under `prefix` instead, as in `<prefix>_events_total`.
+    pub fn with_prefix(self, prefix: impl AsRef<str>) -> Self {
+        Self { names: Arc::new(MetricNames::new(prefix.as_ref())) }
+    }
+
+    fn record(&self, event: &PolicyEvent) {
+        let names = &self.names;
+        let policy = RequestContext::current().policy_name().unwrap_or_default().to_owned();
+        metrics::counter!(
+            names.events.clone(),
+            "layer" => event.layer_kind(),
+            "event" => event.event_name(),
+            "policy" => policy.clone(),
+        )
+        .increment(1);
+
+        match event {
+            PolicyEvent::Request(outcome) => {
+                let (outcome, duration) = match outcome {
+                    RequestOutcome::Success { duration } => ("success", duration),
+                    RequestOutcome::Failure { duration } => ("failure", duration),
+                };
+                metrics::histogram!(
+                    names.request_duration.clone(),
+                    "outcome" => outcome,
+                    "policy" => policy,
+                )
+                .record(duration.as_secs_f64());
+            }
+            PolicyEvent::Retry(RetryEvent::Attempt { delay, .. }) => {
+                metrics::histogram!(names.retry_delay.clone(), "policy" => policy)
+                    .record(delay.as_secs_f64());
+            }
+            PolicyEvent::Bulkhead(
+                BulkheadEvent::Acquired { active_count, .. }
+                | BulkheadEvent::Released { active_count, .. }
+                | BulkheadEvent::Rejected { active_count, .. },
+            ) => {
+                metrics::gauge!(names.bulkhead_in_flight.clone(), "policy" => policy)
+                    .set(*active_count as f64);
+            }
+            PolicyEvent::Concurrency(
+                ConcurrencyEvent::LimitChanged { limit } | ConcurrencyEvent::Rejected { limit, .. },
+            ) => {
+                metrics::gauge!(names.concurrency_limit.clone(), "policy" => policy)
+                    .set(*limit as f64);
+            }
+            PolicyEvent::CircuitBreaker(transition) => {
+                let state = match transition {
+                    CircuitBreakerEvent::Closed { .. } => 0.0,
+                    CircuitBreakerEvent::HalfOpen { .. } => 1.0,
+                    CircuitBreakerEvent::Opened { .. } => 2.0,
+                };
+                metrics::gauge!(names.circuit_state.clone(), "policy" => policy).set(state);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Service<PolicyEvent> for MetricsSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Ready<Result<(), Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        self.record(&event);
+        ready(Ok(()))
+    }
+}
+
+impl TelemetrySink for MetricsSink {
+    type SinkError = Infallible;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{
+        Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata,
+        Recorder, SharedString, Unit,
+    };
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Recorder keeping the last value written to each `name{labels}` series.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<BTreeMap<String, f64>>>);
+
+    struct Series(Capture, String);
+
+    impl Capture {
+        fn series(&self, key: &Key) -> Arc<Series> {
+            let labels: Vec<_> =
+                key.labels().map(|label| format!("{}={}", label.key(), label.value())).collect();
+            Arc::new(Series(self.clone(), format!("{}{{{}}}", key.name(), labels.join(","))))
+        }
+
+        fn get(&self, series: &str) -> Option<f64> {
+            self.0.lock().unwrap().get(series).copied()
+        }
+    }
+
+    impl Series {
+        fn update(&self, f: impl FnOnce(f64) -> f64) {
+            let mut values = self.0 .0.lock().unwrap();
+            let value = values.entry(self.1.clone()).or_insert(0.0);
+            *value = f(*value);
+        }
+    }
+
+    impl CounterFn for Series {
+        fn increment(&self, n: u64) {
+            self.update(|v| v + n as f64);
+        }
+
+        fn absolute(&self, n: u64) {
+            self.update(|_| n as f64);
+        }
+    }
+
+    impl GaugeFn for Series {
+        fn increment(&self, n: f64) {
+            self.update(|v| v + n);
+        }
+
+        fn decrement(&self, n: f64) {
+            self.update(|v| v - n);
+        }
+
+        fn set(&self, n: f64) {
+            self.update(|_| n);
+        }
+    }
+
+    impl HistogramFn for Series {
+        fn record(&self, n: f64) {
+            self.update(|_| n);
+        }
+    }
+
+    impl Recorder for Capture {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.series(key))
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.series(key))
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.series(key))
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_counters_gauges_and_histograms() {
+        let capture = Capture::default();
+        let sink = MetricsSink::new().with_prefix("app");
+        let events = [
+            PolicyEvent::Retry(RetryEvent::Attempt {
+                attempt: 1,
+                delay: Duration::from_millis(250),
+            }),
+            PolicyEvent::Retry(RetryEvent::Attempt {
+                attempt: 2,
+                delay: Duration::from_millis(500),
+            }),
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 5 }),
+            PolicyEvent::Request(RequestOutcome::Failure { duration: Duration::from_secs(2) }),
+        ];
+        let ctx = RequestContext::new().with_policy_name("db-read");
+        for event in events {
+            // The sink records synchronously in `call`, while the recorder and context are set.
+            let fut = ctx
+                .clone()
+                .sync_scope(|| metrics::with_local_recorder(&capture, || sink.clone().call(event)));
+            fut.await.unwrap();
+        }
+
+        let get = |series: &str| capture.get(series);
+        assert_eq!(get("app_events_total{layer=retry,event=attempt,policy=db-read}"), Some(2.0));
+        assert_eq!(get("app_retry_delay_seconds{policy=db-read}"), Some(0.5));
+        assert_eq!(get("app_circuit_state{policy=db-read}"), Some(2.0));
+        assert_eq!(get("app_request_duration_seconds{outcome=failure,policy=db-read}"), Some(2.0));
+    }
+}
@@ -0,0 +1,274 @@
+//! Named policies for telemetry attribution.
+//!
+//! Semantics
+//! - `policy.named("db-read")` wraps a policy so that every request passing through it runs with
+//!   the name installed in the [`RequestContext`]. Telemetry sinks read it with
+//!   `RequestContext::current().policy_name()` while handling an event.
+//! - Names nest: the innermost named policy wins, so `(a.named("reads") + b).named("api")`
+//!   attributes events from `a` to `"reads"` and events from `b` to `"api"`.
+//! - Each `NamedLayer` also carries a process-unique instance id (shared by its clones), so two
+//!   stacks given the same name can still be told apart. Pin it with
+//!   [`with_instance_id`](NamedLayer::with_instance_id) when ids must match across restarts.
+//! - [`EventEnvelope::capture`](crate::telemetry::EventEnvelope::capture) bundles an event with
+//!   the name, instance id, and layer kind in scope; [`LogSink`](crate::telemetry::LogSink)
+//!   records the same attribution as fields, and `NonBlockingSink` carries the context across to
+//!   its worker task.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let reads = Policy(TimeoutLayer::new(Duration::from_millis(200))?.with_sink(LogSink))
+//!     .named("db-read");
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(reads)
+//!     .service_fn(|_: ()| async { Ok::<_, std::io::Error>(RequestContext::current()) });
+//! let ctx = svc.ready().await?.call(()).await?;
+//! assert_eq!(ctx.policy_name(), Some("db-read"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::Policy;
+use crate::describe::{Describe, PolicyNode};
+use crate::policy_metrics::{MetricsSnapshot, PolicyMetrics};
+use crate::RequestContext;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::futures::TaskLocalFuture;
+use tower_layer::Layer;
+use tower_service::Service;
+
+static NEXT_INSTANCE: AtomicU64 = AtomicU64::new(1);
+
+/// Layer attributing everything beneath it to a policy name.
+#[derive(Clone, Debug)]
+pub struct NamedLayer<L> {
+    name: Arc<str>,
+    instance_id: u64,
+    inner: L,
+}
+
+impl<L> NamedLayer<L> {
+    /// Attribute `inner` to `name` under a fresh instance id.
+    pub fn new(name: impl Into<Arc<str>>, inner: L) -> Self {
+        let instance_id = NEXT_INSTANCE.fetch_add(1, Ordering::Relaxed);
+        Self { name: name.into(), instance_id, inner }
+    }
+
+    /// Use `instance_id` instead of the id assigned at construction.
+    #[must_use]
+    pub fn with_instance_id(mut self, instance_id: u64) -> Self {
+        self.instance_id = instance_id;
+        self
+    }
+
+    /// The policy name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Instance id reported alongside the name.
+    #[must_use]
+    pub fn instance_id(&self) -> u64 {
+        self.instance_id
+    }
+}
+
+impl<L> Policy<L> {
+    /// Attribute events emitted beneath this policy to `name`.
+    pub fn named(self, name: impl Into<Arc<str>>) -> Policy<NamedLayer<L>> {
+        Policy(NamedLayer::new(name, self.0))
+    }
+}
+
+impl<S, L> Layer<S> for NamedLayer<L>
+where
+    L: Layer<S>,
+{
+    type Service = NamedService<L::Service>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        NamedService {
+            name: Arc::clone(&self.name),
+            instance_id: self.instance_id,
+            inner: self.inner.layer(service),
+        }
+    }
+}
+
+impl<L: Describe> Describe for NamedLayer<L> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::Named { name: self.name.to_string(), inner: Box::new(self.inner.describe()) }
+    }
+}
+
+impl<L: PolicyMetrics> PolicyMetrics for NamedLayer<L> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.inner.metrics()
+    }
+}
+
+/// Service produced by [`NamedLayer`].
+#[derive(Clone, Debug)]
+pub struct NamedService<S> {
+    name: Arc<str>,
+    instance_id: u64,
+    inner: S,
+}
+
+impl<S, Request> Service<Request> for NamedService<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<RequestContext, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let ctx = RequestContext::current()
+            .with_policy_name(Arc::clone(&self.name))
+            .with_policy_instance(self.instance_id);
+        // Layers may read the context while building their futures, not only when polling.
+        let fut = ctx.clone().sync_scope(|| self.inner.call(req));
+        ctx.scope(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{EventEnvelope, NonBlockingSink, PolicyEvent};
+    use crate::TimeoutLayer;
+    use std::convert::Infallible;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    /// Records the policy name visible while each event is handled.
+    #[derive(Clone, Default)]
+    struct AttributingSink(Arc<Mutex<Vec<Option<String>>>>);
+
+    impl AttributingSink {
+        fn names(&self) -> Vec<Option<String>> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    impl Service<PolicyEvent> for AttributingSink {
+        type Response = ();
+        type Error = Infallible;
+        type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Infallible>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _event: PolicyEvent) -> Self::Future {
+            let name = RequestContext::current().policy_name().map(str::to_owned);
+            self.0.lock().unwrap().push(name);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn slow() -> tower::util::BoxCloneService<(), (), std::io::Error> {
+        tower::util::BoxCloneService::new(tower::service_fn(|_: ()| async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        }))
+    }
+
+    async fn call_once<S: Service<()>>(mut svc: S) {
+        if let Ok(ready) = svc.ready().await {
+            let _ = ready.call(()).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn events_are_attributed_to_their_stack() {
+        tokio::time::pause();
+        let sink = AttributingSink::default();
+        let timeout =
+            || TimeoutLayer::new(Duration::from_millis(10)).unwrap().with_sink(sink.clone());
+
+        call_once(Policy(timeout()).named("db-read").layer(slow())).await;
+        call_once(Policy(timeout()).named("db-write").layer(slow())).await;
+
+        let names = sink.names();
+        assert!(!names.is_empty());
+        let reads = names.iter().filter(|n| n.as_deref() == Some("db-read")).count();
+        let writes = names.iter().filter(|n| n.as_deref() == Some("db-write")).count();
+        assert_eq!(reads + writes, names.len(), "every event carries a name: {:?}", names);
+        assert_eq!(reads, writes);
+    }
+
+    #[tokio::test]
+    async fn envelopes_capture_name_instance_and_kind() {
+        let timeout = || TimeoutLayer::new(Duration::from_secs(1)).unwrap();
+        let fresh = NamedLayer::new("db-read", timeout());
+        let pinned = NamedLayer::new("db-read", timeout()).with_instance_id(42);
+        assert_ne!(fresh.instance_id(), NamedLayer::new("db-read", timeout()).instance_id());
+
+        let svc = pinned.layer(tower::service_fn(|_: ()| async {
+            let event = PolicyEvent::Timeout(crate::telemetry::TimeoutEvent::Occurred {
+                timeout: Duration::from_secs(1),
+            });
+            Ok::<_, std::io::Error>(EventEnvelope::capture(event))
+        }));
+        let envelope = svc.oneshot(()).await.unwrap();
+        assert_eq!(envelope.policy_name.as_deref(), Some("db-read"));
+        assert_eq!(envelope.instance_id, Some(42));
+        assert_eq!(envelope.layer_kind(), "timeout");
+
+        let outside = EventEnvelope::capture(envelope.event.clone());
+        assert_eq!((outside.policy_name, outside.instance_id), (None, None));
+    }
+
+    #[tokio::test]
+    async fn innermost_name_wins() {
+        let seen = RequestContext::new()
+            .with_policy_name("outer")
+            .scope(async {
+                RequestContext::current()
+                    .with_policy_name("inner")
+                    .scope(async { RequestContext::current().policy_name().map(str::to_owned) })
+                    .await
+            })
+            .await;
+        assert_eq!(seen.as_deref(), Some("inner"));
+    }
+
+    #[tokio::test]
+    async fn non_blocking_sink_preserves_attribution() {
+        let recorder = AttributingSink::default();
+        let mut sink = NonBlockingSink::with_capacity(recorder.clone(), 8);
+        let event = PolicyEvent::Timeout(crate::telemetry::TimeoutEvent::Occurred {
+            timeout: Duration::from_millis(1),
+        });
+
+        RequestContext::new()
+            .with_policy_name("cache")
+            .scope(async { sink.call(event).await })
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            if !recorder.names().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(recorder.names(), vec![Some("cache".to_string())]);
+    }
+}
@@ -0,0 +1,636 @@
+//! Typed counters read straight from policy layers.
+//!
+//! Sinks see every event but need a pipeline to turn them into numbers. [`PolicyMetrics`] asks
+//! the layers themselves: each keeps a few atomic counters that are updated whether or not a
+//! sink is attached, so a health or `/metrics` handler can report on a stack it already holds.
+//!
+//! Semantics
+//! - [`RetryLayer`](crate::RetryLayer), [`CircuitBreakerLayer`](crate::CircuitBreakerLayer),
+//!   [`BulkheadLayer`](crate::BulkheadLayer), and [`TimeoutLayer`](crate::TimeoutLayer)
+//!   implement [`PolicyMetrics`]; `Policy`, `+`, and `Policy::named` pass it through, so a
+//!   composed stack reports one [`LayerMetrics`] per layer, outermost first.
+//! - Counts start at zero when the layer is built and only grow. Calls are counted when they
+//!   finish; requests still running show up only in [`BulkheadMetrics::in_flight`].
+//! - Rates are ratios over the layer's lifetime (for example timeouts per finished call) and
+//!   are `0.0` before the first call.
+//!
+//! Invariants
+//! - Clones of a layer, and every service built from it, share one set of counters.
+//! - Each service keeps its own breaker or permits. A circuit breaker reports the most open
+//!   breaker among its live services; a bulkhead sums in-flight calls and limits across them.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let policy = Policy(TimeoutLayer::new(Duration::from_secs(1))?) + Policy(BulkheadLayer::new(8)?);
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(policy.clone())
+//!     .service_fn(|n: u32| async move { Ok::<_, std::io::Error>(n) });
+//! svc.ready().await?.call(7).await?;
+//!
+//! let snapshot = policy.metrics();
+//! assert_eq!(snapshot.layers.len(), 2);
+//! assert_eq!(snapshot.timeout().map(TimeoutMetrics::calls), Some(1));
+//! assert_eq!(snapshot.rejected(), 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{CombinedLayer, Policy};
+use crate::circuit_breaker::CircuitBreakerState;
+use crate::circuit_breaker::CircuitState;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Layers that can report counters describing the traffic they have handled.
+pub trait PolicyMetrics {
+    /// Current counters of this layer and any layers it composes.
+    fn metrics(&self) -> MetricsSnapshot;
+}
+
+impl<L: PolicyMetrics> PolicyMetrics for Policy<L> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.0.metrics()
+    }
+}
+
+impl<A: PolicyMetrics, B: PolicyMetrics> PolicyMetrics for CombinedLayer<A, B> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.outer.metrics().merge(self.inner.metrics())
+    }
+}
+
+/// Counters of every layer in a stack, outermost first.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct MetricsSnapshot {
+    /// One entry per reporting layer.
+    pub layers: Vec<LayerMetrics>,
+}
+
+impl MetricsSnapshot {
+    /// Snapshot of a single layer.
+    pub fn single(layer: LayerMetrics) -> Self {
+        Self { layers: vec![layer] }
+    }
+
+    /// Append `inner`'s layers after this snapshot's.
+    pub fn merge(mut self, inner: MetricsSnapshot) -> Self {
+        self.layers.extend(inner.layers);
+        self
+    }
+
+    /// The outermost retry layer, if any.
+    pub fn retry(&self) -> Option<&RetryMetrics> {
+        self.layers.iter().find_map(|layer| match layer {
+            LayerMetrics::Retry(metrics) => Some(metrics),
+            _ => None,
+        })
+    }
+
+    /// The outermost circuit breaker, if any.
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreakerMetrics> {
+        self.layers.iter().find_map(|layer| match layer {
+            LayerMetrics::CircuitBreaker(metrics) => Some(metrics),
+            _ => None,
+        })
+    }
+
+    /// The outermost bulkhead, if any.
+    pub fn bulkhead(&self) -> Option<&BulkheadMetrics> {
+        self.layers.iter().find_map(|layer| match layer {
+            LayerMetrics::Bulkhead(metrics) => Some(metrics),
+            _ => None,
+        })
+    }
+
+    /// The outermost timeout, if any.
+    pub fn timeout(&self) -> Option<&TimeoutMetrics> {
+        self.layers.iter().find_map(|layer| match layer {
+            LayerMetrics::Timeout(metrics) => Some(metrics),
+            _ => None,
+        })
+    }
+
+    /// Calls refused across the stack: open circuits plus saturated or closed bulkheads.
+    pub fn rejected(&self) -> u64 {
+        self.layers
+            .iter()
+            .map(|layer| match layer {
+                LayerMetrics::CircuitBreaker(metrics) => metrics.rejected,
+                LayerMetrics::Bulkhead(metrics) => metrics.rejected,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Retries scheduled across the stack.
+    pub fn retries(&self) -> u64 {
+        self.layers
+            .iter()
+            .map(|layer| match layer {
+                LayerMetrics::Retry(metrics) => metrics.retries,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Timeouts fired across the stack.
+    pub fn timeouts(&self) -> u64 {
+        self.layers
+            .iter()
+            .map(|layer| match layer {
+                LayerMetrics::Timeout(metrics) => metrics.timeouts,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+/// Counters of one layer.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LayerMetrics {
+    /// A retry layer.
+    Retry(RetryMetrics),
+    /// A circuit breaker.
+    CircuitBreaker(CircuitBreakerMetrics),
+    /// A bulkhead.
+    Bulkhead(BulkheadMetrics),
+    /// A timeout.
+    Timeout(TimeoutMetrics),
+}
+
+/// Counters of a [`RetryLayer`](crate::RetryLayer).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RetryMetrics {
+    /// Calls that eventually succeeded.
+    pub successes: u64,
+    /// Calls that failed, including those that ran out of attempts.
+    pub failures: u64,
+    /// Retries scheduled after a failed attempt.
+    pub retries: u64,
+    /// Calls that failed because attempts or the deadline ran out.
+    pub exhausted: u64,
+}
+
+impl RetryMetrics {
+    /// Finished calls.
+    pub fn calls(&self) -> u64 {
+        self.successes + self.failures
+    }
+
+    /// Retries per finished call.
+    pub fn retry_rate(&self) -> f64 {
+        ratio(self.retries, self.calls())
+    }
+
+    /// Share of finished calls that failed.
+    pub fn failure_rate(&self) -> f64 {
+        ratio(self.failures, self.calls())
+    }
+}
+
+/// Counters and state of a [`CircuitBreakerLayer`](crate::CircuitBreakerLayer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CircuitBreakerMetrics {
+    /// Current state; with several services built from the layer, the most open of their breakers.
+    pub state: CircuitState,
+    /// Calls the inner service completed successfully.
+    pub successes: u64,
+    /// Calls the inner service failed.
+    pub failures: u64,
+    /// Calls refused without reaching the inner service.
+    pub rejected: u64,
+    /// Times the circuit opened.
+    pub opens: u64,
+}
+
+impl CircuitBreakerMetrics {
+    /// Finished calls, including rejections.
+    pub fn calls(&self) -> u64 {
+        self.successes + self.failures + self.rejected
+    }
+
+    /// Share of finished calls refused by the breaker.
+    pub fn rejection_rate(&self) -> f64 {
+        ratio(self.rejected, self.calls())
+    }
+
+    /// Share of calls reaching the inner service that failed.
+    pub fn failure_rate(&self) -> f64 {
+        ratio(self.failures, self.successes + self.failures)
+    }
+}
+
+/// Counters and occupancy of a [`BulkheadLayer`](crate::BulkheadLayer).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BulkheadMetrics {
+    /// Concurrency limit: the configured per-service limit times the live services built from
+    /// the layer, or the configured limit before any are built.
+    pub max_concurrency: usize,
+    /// Calls holding a permit right now, across all services built from the layer.
+    pub in_flight: usize,
+    /// Calls that got a permit and succeeded.
+    pub successes: u64,
+    /// Calls that got a permit and failed.
+    pub failures: u64,
+    /// Calls refused because the bulkhead was full or closed.
+    pub rejected: u64,
+}
+
+impl BulkheadMetrics {
+    /// Finished calls, including rejections.
+    pub fn calls(&self) -> u64 {
+        self.successes + self.failures + self.rejected
+    }
+
+    /// Share of finished calls refused by the bulkhead.
+    pub fn rejection_rate(&self) -> f64 {
+        ratio(self.rejected, self.calls())
+    }
+
+    /// Share of the limit in use right now.
+    pub fn utilization(&self) -> f64 {
+        ratio(self.in_flight as u64, self.max_concurrency as u64)
+    }
+}
+
+/// Counters of a [`TimeoutLayer`](crate::TimeoutLayer).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TimeoutMetrics {
+    /// Configured timeout, before jitter.
+    pub timeout: Duration,
+    /// Calls that finished in time and succeeded.
+    pub successes: u64,
+    /// Calls that finished in time and failed.
+    pub failures: u64,
+    /// Calls cut off by the timeout.
+    pub timeouts: u64,
+}
+
+impl TimeoutMetrics {
+    /// Finished calls, including timeouts.
+    pub fn calls(&self) -> u64 {
+        self.successes + self.failures + self.timeouts
+    }
+
+    /// Share of finished calls that timed out.
+    pub fn timeout_rate(&self) -> f64 {
+        ratio(self.timeouts, self.calls())
+    }
+}
+
+fn ratio(n: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        n as f64 / total as f64
+    }
+}
+
+fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+fn read(counter: &AtomicU64) -> u64 {
+    counter.load(Ordering::Relaxed)
+}
+
+/// Counters shared by a [`RetryLayer`](crate::RetryLayer) and its services.
+#[derive(Debug, Default)]
+pub(crate) struct RetryStats {
+    successes: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    exhausted: AtomicU64,
+}
+
+impl RetryStats {
+    pub(crate) fn success(&self) {
+        bump(&self.successes);
+    }
+
+    pub(crate) fn failure(&self) {
+        bump(&self.failures);
+    }
+
+    pub(crate) fn retry(&self) {
+        bump(&self.retries);
+    }
+
+    pub(crate) fn exhausted(&self) {
+        bump(&self.exhausted);
+        bump(&self.failures);
+    }
+
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot::single(LayerMetrics::Retry(RetryMetrics {
+            successes: read(&self.successes),
+            failures: read(&self.failures),
+            retries: read(&self.retries),
+            exhausted: read(&self.exhausted),
+        }))
+    }
+}
+
+/// Counters shared by a [`CircuitBreakerLayer`](crate::CircuitBreakerLayer) and its services.
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreakerStats {
+    breakers: Registry<CircuitBreakerState>,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    rejected: AtomicU64,
+    opens: AtomicU64,
+}
+
+impl CircuitBreakerStats {
+    /// Track a service's breaker so snapshots can report its state.
+    pub(crate) fn register(&self, breaker: &Arc<CircuitBreakerState>) {
+        self.breakers.register(breaker);
+    }
+
+    pub(crate) fn success(&self) {
+        bump(&self.successes);
+    }
+
+    pub(crate) fn failure(&self) {
+        bump(&self.failures);
+    }
+
+    pub(crate) fn rejected(&self) {
+        bump(&self.rejected);
+    }
+
+    pub(crate) fn opened(&self) {
+        bump(&self.opens);
+    }
+
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        let state = self
+            .breakers
+            .live()
+            .iter()
+            .map(|breaker| breaker.current())
+            .max_by_key(|state| match state {
+                CircuitState::Closed => 0,
+                CircuitState::HalfOpen => 1,
+                CircuitState::Open => 2,
+            })
+            .unwrap_or(CircuitState::Closed);
+        MetricsSnapshot::single(LayerMetrics::CircuitBreaker(CircuitBreakerMetrics {
+            state,
+            successes: read(&self.successes),
+            failures: read(&self.failures),
+            rejected: read(&self.rejected),
+            opens: read(&self.opens),
+        }))
+    }
+}
+
+/// Counters shared by a [`BulkheadLayer`](crate::BulkheadLayer) and its services.
+#[derive(Debug, Default)]
+pub(crate) struct BulkheadStats {
+    semaphores: Registry<Semaphore>,
+    in_flight: AtomicUsize,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl BulkheadStats {
+    /// Track a service's semaphore so snapshots can count its permits.
+    pub(crate) fn register(&self, semaphore: &Arc<Semaphore>) {
+        self.semaphores.register(semaphore);
+    }
+
+    /// Count a call as in flight until the returned guard drops.
+    pub(crate) fn enter(self: &Arc<Self>) -> InFlight {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlight(Arc::clone(self))
+    }
+
+    pub(crate) fn success(&self) {
+        bump(&self.successes);
+    }
+
+    pub(crate) fn failure(&self) {
+        bump(&self.failures);
+    }
+
+    pub(crate) fn rejected(&self) {
+        bump(&self.rejected);
+    }
+
+    pub(crate) fn snapshot(&self, max_concurrency: usize) -> MetricsSnapshot {
+        let services = self.semaphores.live().len().max(1);
+        MetricsSnapshot::single(LayerMetrics::Bulkhead(BulkheadMetrics {
+            max_concurrency: max_concurrency.saturating_mul(services),
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            successes: read(&self.successes),
+            failures: read(&self.failures),
+            rejected: read(&self.rejected),
+        }))
+    }
+}
+
+/// Weak handles to the per-service state of every service built from a layer.
+#[derive(Debug)]
+struct Registry<T>(Mutex<Vec<Weak<T>>>);
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self(Mutex::new(Vec::new()))
+    }
+}
+
+impl<T> Registry<T> {
+    fn register(&self, item: &Arc<T>) {
+        let mut items = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        items.retain(|item| item.strong_count() > 0);
+        items.push(Arc::downgrade(item));
+    }
+
+    fn live(&self) -> Vec<Arc<T>> {
+        let mut items = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        items.retain(|item| item.strong_count() > 0);
+        items.iter().filter_map(Weak::upgrade).collect()
+    }
+}
+
+/// Guard returned by [`BulkheadStats::enter`].
+pub(crate) struct InFlight(Arc<BulkheadStats>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Counters shared by a [`TimeoutLayer`](crate::TimeoutLayer) and its services.
+#[derive(Debug, Default)]
+pub(crate) struct TimeoutStats {
+    successes: AtomicU64,
+    failures: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+impl TimeoutStats {
+    pub(crate) fn success(&self) {
+        bump(&self.successes);
+    }
+
+    pub(crate) fn failure(&self) {
+        bump(&self.failures);
+    }
+
+    pub(crate) fn timeout(&self) {
+        bump(&self.timeouts);
+    }
+
+    pub(crate) fn snapshot(&self, timeout: Duration) -> MetricsSnapshot {
+        MetricsSnapshot::single(LayerMetrics::Timeout(TimeoutMetrics {
+            timeout,
+            successes: read(&self.successes),
+            failures: read(&self.failures),
+            timeouts: read(&self.timeouts),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Backoff, BulkheadLayer, CircuitBreakerConfig, CircuitBreakerLayer, Jitter, ResilienceError,
+        RetryLayer, RetryPolicy, TimeoutLayer,
+    };
+    use std::fmt;
+    use tower::{Layer, ServiceExt};
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    #[tokio::test]
+    async fn stack_reports_each_layer_outermost_first() {
+        let retry: RetryLayer<ResilienceError<ResilienceError<TestError>>> = RetryPolicy::builder()
+            .max_attempts(3)
+            .backoff(Backoff::constant(Duration::ZERO))
+            .with_jitter(Jitter::None)
+            .build()
+            .unwrap()
+            .into_layer();
+        let breaker = CircuitBreakerLayer::new(
+            CircuitBreakerConfig::new(2, Duration::from_secs(60), 1).unwrap(),
+        )
+        .unwrap();
+        let timeout = TimeoutLayer::new(Duration::from_secs(1)).unwrap();
+        let policy = (Policy(retry) + Policy(breaker) + Policy(timeout)).named("db-read");
+
+        let svc = policy
+            .layer(tower::service_fn(|_: ()| async { Err::<(), _>(TestError("down".into())) }));
+        assert!(svc.clone().oneshot(()).await.is_err());
+
+        let snapshot = policy.metrics();
+        assert_eq!(snapshot.layers.len(), 3);
+        let retry = snapshot.retry().unwrap();
+        assert_eq!((retry.calls(), retry.retries, retry.exhausted), (1, 2, 1));
+        assert_eq!(retry.failure_rate(), 1.0);
+        let breaker = snapshot.circuit_breaker().unwrap();
+        assert_eq!(breaker.state, CircuitState::Open);
+        assert_eq!((breaker.failures, breaker.rejected, breaker.opens), (2, 1, 1));
+        assert!((breaker.rejection_rate() - 1.0 / 3.0).abs() < 1e-9);
+        let timeout = snapshot.timeout().unwrap();
+        assert_eq!((timeout.failures, timeout.timeouts, timeout.timeout_rate()), (2, 0, 0.0));
+        assert_eq!((snapshot.rejected(), snapshot.retries(), snapshot.timeouts()), (1, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn bulkhead_tracks_calls_in_flight() {
+        let bulkhead = BulkheadLayer::new(2).unwrap();
+        let gate = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::clone(&gate);
+        let svc = bulkhead.layer(tower::service_fn(move |_: ()| {
+            let release = Arc::clone(&release);
+            async move {
+                release.notified().await;
+                Ok::<_, TestError>(())
+            }
+        }));
+
+        let call = tokio::spawn(svc.clone().oneshot(()));
+        while bulkhead.metrics().bulkhead().unwrap().in_flight == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(bulkhead.metrics().bulkhead().unwrap().utilization(), 0.5);
+
+        gate.notify_one();
+        call.await.unwrap().unwrap();
+        let metrics = *bulkhead.metrics().bulkhead().unwrap();
+        assert_eq!((metrics.in_flight, metrics.successes, metrics.calls()), (0, 1, 1));
+        assert_eq!(MetricsSnapshot::default().bulkhead(), None);
+    }
+
+    #[tokio::test]
+    async fn services_built_from_one_layer_keep_their_own_state() {
+        let breaker = CircuitBreakerLayer::new(
+            CircuitBreakerConfig::new(1, Duration::from_secs(60), 1).unwrap(),
+        )
+        .unwrap();
+        let failing = breaker
+            .layer(tower::service_fn(|_: ()| async { Err::<(), _>(TestError("down".into())) }));
+        let healthy = breaker.layer(tower::service_fn(|_: ()| async { Ok::<_, TestError>(()) }));
+
+        assert!(failing.clone().oneshot(()).await.is_err());
+        assert!(failing.clone().oneshot(()).await.unwrap_err().is_circuit_open());
+        healthy.clone().oneshot(()).await.unwrap();
+        let metrics = *breaker.metrics().circuit_breaker().unwrap();
+        assert_eq!(metrics.state, CircuitState::Open);
+        assert_eq!((metrics.successes, metrics.failures, metrics.rejected), (1, 1, 1));
+
+        let bulkhead = BulkheadLayer::new(1).unwrap();
+        let gate = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::clone(&gate);
+        let held = bulkhead.layer(tower::service_fn(move |_: ()| {
+            let release = Arc::clone(&release);
+            async move {
+                release.notified().await;
+                Ok::<_, TestError>(())
+            }
+        }));
+        let other = bulkhead.layer(tower::service_fn(|_: ()| async { Ok::<_, TestError>(()) }));
+
+        let call = tokio::spawn(held.oneshot(()));
+        while bulkhead.metrics().bulkhead().unwrap().in_flight == 0 {
+            tokio::task::yield_now().await;
+        }
+        let metrics = *bulkhead.metrics().bulkhead().unwrap();
+        assert_eq!((metrics.in_flight, metrics.max_concurrency), (1, 2));
+        other.oneshot(()).await.unwrap();
+
+        gate.notify_one();
+        call.await.unwrap().unwrap();
+        let metrics = *bulkhead.metrics().bulkhead().unwrap();
+        assert_eq!((metrics.in_flight, metrics.successes, metrics.rejected), (0, 2, 0));
+        assert_eq!(metrics.max_concurrency, 1);
+    }
+}
@@ -1,21 +1,75 @@
 //! Convenient re-exports for common Nine Lives types.
 pub use crate::{
-    algebra::{CombinedLayer, FallbackLayer, ForkJoinLayer, Policy},
+    adaptive::Adaptive,
+    aggregate::{AggregateSnapshot, AggregatingSink, LatencyHistogram},
+    alert::{AlertRuleError, StormDetector},
+    algebra::{
+        AlwaysFallback, CombinedLayer, CompositionError, FallbackError, FallbackLayer,
+        FallbackPredicate, ForkJoinError, ForkJoinLayer, Policy,
+    },
+    atomic_bulkhead::AtomicBulkheadLayer,
     backoff::{
         Backoff, BackoffError, BackoffStrategy, ConstantBackoff, ExponentialBackoff, LinearBackoff,
         MAX_BACKOFF,
     },
+    bandwidth::{BandwidthError, BandwidthLayer},
+    boxed::{BoxLayer, BoxPolicy},
+    budget::BudgetLayer,
     bulkhead::BulkheadLayer,
     circuit_breaker::{CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerLayer},
     clock::{Clock, MonotonicClock},
+    coalesce::CoalesceLayer,
+    concurrency_limit::{AdaptiveConcurrencyLayer, ConcurrencyLimitError},
+    cond::CondLayer,
+    context::RequestContext,
+    deadline::Deadline,
+    describe::{Describe, PolicyNode},
+    fallback_chain::FallbackChainLayer,
+    hedge::HedgeLayer,
+    idempotency::IdempotencyLayer,
     jitter::Jitter,
+    join::{JoinError, JoinLayer},
+    latency::{LatencyDistribution, LatencyInjectionError, LatencyInjectionLayer},
+    lint::CompositionWarning,
+    load_shed::{LoadShedError, LoadShedLayer, ShedSignal},
+    named::NamedLayer,
+    policy_metrics::{
+        BulkheadMetrics, CircuitBreakerMetrics, LayerMetrics, MetricsSnapshot, PolicyMetrics,
+        RetryMetrics, TimeoutMetrics,
+    },
+    priority::{Priority, PriorityError, PriorityLayer},
+    quorum::{QuorumError, QuorumLayer},
+    race::{RaceError, RaceLayer},
+    rate_limit::{RateLimitAlgorithm, RateLimitError, RateLimitLayer},
     retry::{BuildError, RetryLayer, RetryPolicy, RetryPolicyBuilder},
+    router::RouterLayer,
     sleeper::{InstantSleeper, Sleeper, TokioSleeper, TrackingSleeper},
+    span::TracingLayer,
+    spec::{PolicySpec, PolicySpecError},
+    spillover::{SpilloverError, SpilloverLayer},
+    stale_cache::StaleCacheLayer,
+    summary::{SummaryError, SummaryReporter},
     telemetry::{
-        BulkheadEvent, CircuitBreakerEvent, FallbackSink, LogSink, MemorySink, MulticastSink,
-        NullSink, PolicyEvent, RequestOutcome, RetryEvent, StreamingSink, TelemetrySink,
-        TimeoutEvent,
+        async_sink_fn, sink_fn, AlertEvent, AlertKind, AsyncFnSink, BatchingSink, BulkheadEvent,
+        CacheEvent, CircuitBreakerEvent, CoalesceEvent, ConcurrencyEvent, ConsoleSink, EnrichSink,
+        EventEnvelope, EventStream, FallbackEvent, FallbackSink, FilterSink, FnSink, ForkJoinEvent,
+        HedgeEvent, IdempotencyEvent, LoadShedEvent, LogSink, MemorySink, MulticastSink, NullSink,
+        PerEventSink, PolicyEvent, PriorityEvent, RateLimitEvent, RequestOutcome, RetryEvent,
+        Severity, SinkBuilder, StaleReason, StreamingSink, SummaryEvent, TelemetrySink,
+        ThrottleEvent, TimeoutEvent, TracingSink, WatchdogEvent,
+    },
+    throttle::{DebounceLayer, ThrottleLayer},
+    timeout::{
+        TimeoutError, TimeoutLayer, TimeoutPhase, TimeoutPolicy, TimeoutProfile, MAX_TIMEOUT,
     },
-    timeout::{TimeoutError, TimeoutLayer, TimeoutPolicy, MAX_TIMEOUT},
+    value_fallback::ValueFallbackLayer,
+    watchdog::{WatchdogError, WatchdogLayer},
+    weighted::WeightedLayer,
     BulkheadPolicy, ResilienceError,
 };
+
+#[cfg(feature = "serde")]
+pub use crate::telemetry::{DeadLetterSink, ReplayReport};
+
+#[cfg(feature = "metrics")]
+pub use crate::metrics_sink::MetricsSink;
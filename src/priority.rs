@@ -0,0 +1,504 @@
+//! Priority-aware admission for concurrency-limited services.
+//!
+//! Semantics
+//! - A closure classifies each request into a [`Priority`]. Up to `max_concurrent` requests run
+//!   at once; the rest wait in a queue shared by all classes.
+//! - When a slot frees up it goes to the oldest waiter of the highest class, so critical traffic
+//!   overtakes bulk traffic under load while order within a class is preserved.
+//! - The queue holds at most `queue_capacity` waiters. When it is full, an arriving request evicts
+//!   the newest waiter of a strictly lower class, or is itself rejected if there is none. Both
+//!   rejections fail with [`ResilienceError::Bulkhead`].
+//! - Queueing and shedding emit [`PriorityEvent`]s carrying the queue depth of every class;
+//!   [`PriorityLayer::queue_depths`] reads the same numbers on demand.
+//!
+//! Invariants
+//! - Services built from the same layer share the slots and the queue.
+//! - A waiter that is dropped (e.g. by an outer timeout) gives up its place; the slot it would
+//!   have received goes to the next waiter.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let admission = PriorityLayer::new(32, 256, |path: &String| {
+//!     if path.starts_with("/checkout") {
+//!         Priority::Critical
+//!     } else if path.starts_with("/reports") {
+//!         Priority::Low
+//!     } else {
+//!         Priority::Normal
+//!     }
+//! })?;
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(admission)
+//!     .service_fn(|path: String| async move { Ok::<_, std::io::Error>(path.len()) });
+//! assert_eq!(svc.ready().await?.call("/checkout/pay".to_string()).await?, 13);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, PriorityEvent};
+use crate::ResilienceError;
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Admission class of a request; earlier variants are admitted first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Priority {
+    /// Must not be shed while anything else is waiting (e.g. payments, health checks).
+    Critical,
+    /// User-facing, latency-sensitive traffic.
+    High,
+    /// Default class.
+    Normal,
+    /// Background or bulk work; shed first.
+    Low,
+}
+
+impl Priority {
+    /// Number of priority classes.
+    pub const COUNT: usize = 4;
+
+    /// All classes, highest first.
+    pub const ALL: [Priority; Self::COUNT] =
+        [Priority::Critical, Priority::High, Priority::Normal, Priority::Low];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Priority::Critical => "critical",
+            Priority::High => "high",
+            Priority::Normal => "normal",
+            Priority::Low => "low",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Errors produced while configuring a [`PriorityLayer`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriorityError {
+    /// `max_concurrent` was zero.
+    ZeroConcurrency,
+}
+
+impl fmt::Display for PriorityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroConcurrency => write!(f, "priority admission needs max_concurrent > 0"),
+        }
+    }
+}
+
+impl std::error::Error for PriorityError {}
+
+/// Layer admitting requests by priority class when concurrency is constrained.
+pub struct PriorityLayer<F, Sink = NullSink> {
+    classify: Arc<F>,
+    admission: Arc<Admission>,
+    sink: Sink,
+}
+
+impl<F> PriorityLayer<F, NullSink> {
+    /// Run up to `max_concurrent` requests, queueing up to `queue_capacity` more by priority.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PriorityError::ZeroConcurrency`] if `max_concurrent` is zero.
+    pub fn new<Request>(
+        max_concurrent: usize,
+        queue_capacity: usize,
+        classify: F,
+    ) -> Result<Self, PriorityError>
+    where
+        F: Fn(&Request) -> Priority,
+    {
+        if max_concurrent == 0 {
+            return Err(PriorityError::ZeroConcurrency);
+        }
+        let admission = Admission {
+            max_concurrent,
+            queue_capacity,
+            state: Mutex::new(State { in_flight: 0, queues: Default::default() }),
+        };
+        Ok(Self { classify: Arc::new(classify), admission: Arc::new(admission), sink: NullSink })
+    }
+}
+
+impl<F, Sink> PriorityLayer<F, Sink> {
+    /// Attach a telemetry sink to this layer.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> PriorityLayer<F, NewSink>
+    where
+        NewSink: Clone,
+    {
+        PriorityLayer { classify: self.classify, admission: self.admission, sink }
+    }
+
+    /// Current number of waiting requests per class, highest class first.
+    pub fn queue_depths(&self) -> [usize; Priority::COUNT] {
+        self.admission.lock().depths()
+    }
+}
+
+impl<F, Sink: Clone> Clone for PriorityLayer<F, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            classify: Arc::clone(&self.classify),
+            admission: Arc::clone(&self.admission),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<F, Sink: fmt::Debug> fmt::Debug for PriorityLayer<F, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriorityLayer")
+            .field("max_concurrent", &self.admission.max_concurrent)
+            .field("queue_capacity", &self.admission.queue_capacity)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, F, Sink: Clone> Layer<S> for PriorityLayer<F, Sink> {
+    type Service = PriorityService<S, F, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PriorityService { inner, layer: self.clone() }
+    }
+}
+
+impl<F, Sink> crate::Describe for PriorityLayer<F, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!(
+            "Priority({} slots, queue {})",
+            self.admission.max_concurrent, self.admission.queue_capacity
+        ))
+    }
+}
+
+/// Service produced by [`PriorityLayer`].
+pub struct PriorityService<S, F, Sink = NullSink> {
+    inner: S,
+    layer: PriorityLayer<F, Sink>,
+}
+
+impl<S: Clone, F, Sink: Clone> Clone for PriorityService<S, F, Sink> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), layer: self.layer.clone() }
+    }
+}
+
+impl<S: fmt::Debug, F, Sink: fmt::Debug> fmt::Debug for PriorityService<S, F, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriorityService")
+            .field("inner", &self.inner)
+            .field("layer", &self.layer)
+            .finish()
+    }
+}
+
+impl<S, Request, F, Sink> Service<Request> for PriorityService<S, F, Sink>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    Request: Send + 'static,
+    F: Fn(&Request) -> Priority,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let priority = (self.layer.classify)(&req);
+        let admission = Arc::clone(&self.layer.admission);
+        let sink = self.layer.sink.clone();
+        // Keep the service that was driven to readiness; the request may run after queueing.
+        let clone = self.inner.clone();
+        let mut ready = std::mem::replace(&mut self.inner, clone);
+
+        let entry = admission.enter(priority);
+        Box::pin(async move {
+            let permit = match entry {
+                Entry::Admitted => Permit(Arc::clone(&admission)),
+                Entry::Queued { grant, depths, evicted } => {
+                    if let Some(victim) = evicted {
+                        emit_best_effort(
+                            sink.clone(),
+                            PolicyEvent::Priority(PriorityEvent::Shed { priority: victim, depths }),
+                        )
+                        .await;
+                    }
+                    emit_best_effort(
+                        sink.clone(),
+                        PolicyEvent::Priority(PriorityEvent::Queued { priority, depths }),
+                    )
+                    .await;
+                    match grant.await {
+                        Ok(true) => Permit(Arc::clone(&admission)),
+                        // Evicted by a higher class (or the layer went away).
+                        _ => return Err(admission.rejection()),
+                    }
+                }
+                Entry::Rejected { depths } => {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::Priority(PriorityEvent::Shed { priority, depths }),
+                    )
+                    .await;
+                    return Err(admission.rejection());
+                }
+            };
+            let result = ready.call(req).await;
+            drop(permit);
+            result.map_err(ResilienceError::Inner)
+        })
+    }
+}
+
+struct Admission {
+    max_concurrent: usize,
+    queue_capacity: usize,
+    state: Mutex<State>,
+}
+
+struct State {
+    in_flight: usize,
+    queues: [VecDeque<oneshot::Sender<bool>>; Priority::COUNT],
+}
+
+enum Entry {
+    Admitted,
+    Queued {
+        grant: oneshot::Receiver<bool>,
+        depths: [usize; Priority::COUNT],
+        evicted: Option<Priority>,
+    },
+    Rejected {
+        depths: [usize; Priority::COUNT],
+    },
+}
+
+impl Admission {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn enter(&self, priority: Priority) -> Entry {
+        let mut state = self.lock();
+        state.prune();
+        if state.in_flight < self.max_concurrent {
+            state.in_flight += 1;
+            return Entry::Admitted;
+        }
+        let mut evicted = None;
+        if state.queued() >= self.queue_capacity {
+            let victim = Priority::ALL
+                .into_iter()
+                .rev()
+                .take_while(|p| *p > priority)
+                .find(|p| !state.queues[p.index()].is_empty());
+            match victim {
+                Some(victim) => {
+                    if let Some(tx) = state.queues[victim.index()].pop_back() {
+                        let _ = tx.send(false);
+                    }
+                    evicted = Some(victim);
+                }
+                None => return Entry::Rejected { depths: state.depths() },
+            }
+        }
+        let (tx, rx) = oneshot::channel();
+        state.queues[priority.index()].push_back(tx);
+        Entry::Queued { grant: rx, depths: state.depths(), evicted }
+    }
+
+    /// Hand a finished request's slot to the best live waiter, or free it.
+    fn release(&self) {
+        let mut state = self.lock();
+        for queue in state.queues.iter_mut() {
+            while let Some(tx) = queue.pop_front() {
+                if tx.send(true).is_ok() {
+                    return;
+                }
+            }
+        }
+        state.in_flight -= 1;
+    }
+
+    fn rejection<E>(&self) -> ResilienceError<E> {
+        let in_flight = self.lock().in_flight;
+        ResilienceError::Bulkhead { in_flight, max: self.max_concurrent }
+    }
+}
+
+impl State {
+    fn prune(&mut self) {
+        for queue in self.queues.iter_mut() {
+            queue.retain(|tx| !tx.is_closed());
+        }
+    }
+
+    fn queued(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    fn depths(&self) -> [usize; Priority::COUNT] {
+        let mut depths = [0; Priority::COUNT];
+        for (depth, queue) in depths.iter_mut().zip(&self.queues) {
+            *depth = queue.len();
+        }
+        depths
+    }
+}
+
+/// A running request's slot, released (or handed over) on drop.
+struct Permit(Arc<Admission>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Requests are `(priority, label)`; each waits for `gate` before finishing and records its
+    /// label in completion order.
+    #[derive(Clone)]
+    struct Gated {
+        gate: Arc<Notify>,
+        order: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Service<(Priority, &'static str)> for Gated {
+        type Response = ();
+        type Error = TestError;
+        type Future = BoxFuture<'static, Result<(), TestError>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), TestError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, (_, label): (Priority, &'static str)) -> Self::Future {
+            let (gate, order) = (Arc::clone(&self.gate), Arc::clone(&self.order));
+            Box::pin(async move {
+                order.lock().unwrap().push(label);
+                gate.notified().await;
+                Ok(())
+            })
+        }
+    }
+
+    type Classify = fn(&(Priority, &'static str)) -> Priority;
+
+    fn setup(queue: usize) -> (PriorityLayer<Classify, MemorySink>, Gated, MemorySink) {
+        let sink = MemorySink::new();
+        let layer = PriorityLayer::new(1, queue, (|r: &(Priority, &'static str)| r.0) as Classify)
+            .unwrap()
+            .with_sink(sink.clone());
+        let svc = Gated { gate: Arc::new(Notify::new()), order: Arc::new(Mutex::new(Vec::new())) };
+        (layer, svc, sink)
+    }
+
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn higher_classes_are_admitted_first() {
+        let (layer, backend, _) = setup(8);
+        let svc = layer.layer(backend.clone());
+        let mut tasks = Vec::new();
+        for req in [
+            (Priority::Normal, "running"),
+            (Priority::Low, "low"),
+            (Priority::Normal, "normal"),
+            (Priority::Critical, "critical"),
+        ] {
+            tasks.push(tokio::spawn(svc.clone().oneshot(req)));
+            settle().await;
+        }
+        assert_eq!(layer.queue_depths(), [1, 0, 1, 1]);
+
+        for _ in 0..4 {
+            backend.gate.notify_one();
+            settle().await;
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(*backend.order.lock().unwrap(), vec!["running", "critical", "normal", "low"]);
+    }
+
+    #[tokio::test]
+    async fn full_queue_evicts_lower_classes_then_rejects() {
+        let (layer, backend, sink) = setup(1);
+        let svc = layer.layer(backend.clone());
+
+        let running = tokio::spawn(svc.clone().oneshot((Priority::Normal, "running")));
+        settle().await;
+        let low = tokio::spawn(svc.clone().oneshot((Priority::Low, "low")));
+        settle().await;
+        let high = tokio::spawn(svc.clone().oneshot((Priority::High, "high")));
+        settle().await;
+
+        let err = low.await.unwrap().unwrap_err();
+        assert_eq!(err.bulkhead_capacity(), Some((1, 1)));
+        assert!(sink.events().contains(&PolicyEvent::Priority(PriorityEvent::Shed {
+            priority: Priority::Low,
+            depths: [0, 1, 0, 0],
+        })));
+
+        let normal = svc.clone().oneshot((Priority::Normal, "normal")).await;
+        assert!(normal.unwrap_err().is_bulkhead(), "nothing lower to evict");
+
+        backend.gate.notify_one();
+        settle().await;
+        backend.gate.notify_one();
+        running.await.unwrap().unwrap();
+        high.await.unwrap().unwrap();
+    }
+}
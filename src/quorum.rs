@@ -0,0 +1,284 @@
+//! Quorum combinator: fan out to N stacks, succeed once k of them succeed.
+//!
+//! Semantics
+//! - Every branch receives a clone of the request concurrently.
+//! - As soon as `required` branches succeed, their responses are returned (in completion order)
+//!   and the remaining branches are dropped.
+//! - As soon as enough branches have failed that `required` successes are no longer possible,
+//!   the call fails with [`QuorumError`] carrying every failure observed so far.
+//! - Comparing the returned values (read-repair, majority vote) is left to the caller.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let replica = || Policy(TimeoutLayer::new(Duration::from_millis(100)).unwrap());
+//! let quorum = Policy::quorum(vec![replica(), replica(), replica()], 2)?;
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(quorum)
+//!     .service_fn(|key: &'static str| async move { Ok::<_, std::io::Error>(key.len()) });
+//! let answers = svc.ready().await?.call("user:42").await?;
+//! assert_eq!(answers, vec![7, 7]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{CompositionError, Policy};
+use crate::describe::{Describe, PolicyNode};
+use futures::future::BoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::fmt;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Error returned when a quorum can no longer be reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumError<E> {
+    /// Successes that were required.
+    pub required: usize,
+    /// Successes observed before the quorum became impossible.
+    pub successes: usize,
+    /// Failures observed, in completion order.
+    pub failures: Vec<E>,
+}
+
+impl<E: fmt::Display> fmt::Display for QuorumError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "quorum not reached: {} of {} required successes ({} failures",
+            self.successes,
+            self.required,
+            self.failures.len()
+        )?;
+        match self.failures.last() {
+            Some(last) => write!(f, "; last: {})", last),
+            None => write!(f, ")"),
+        }
+    }
+}
+
+impl<E> std::error::Error for QuorumError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.failures.last().map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Layer fanning requests out to several stacks and requiring `required` successes.
+#[derive(Clone, Debug)]
+pub struct QuorumLayer<L> {
+    layers: Vec<L>,
+    required: usize,
+}
+
+impl<L> QuorumLayer<L> {
+    /// Require `required` successes out of `layers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompositionError::Empty`] for no layers and
+    /// [`CompositionError::InvalidQuorum`] unless `1 <= required <= layers.len()`.
+    pub fn new<I>(layers: I, required: usize) -> Result<Self, CompositionError>
+    where
+        I: IntoIterator<Item = L>,
+    {
+        let layers: Vec<L> = layers.into_iter().collect();
+        if layers.is_empty() {
+            return Err(CompositionError::Empty);
+        }
+        if required == 0 || required > layers.len() {
+            return Err(CompositionError::InvalidQuorum { required, available: layers.len() });
+        }
+        Ok(Self { layers, required })
+    }
+
+    /// Number of successes required.
+    #[must_use]
+    pub fn required(&self) -> usize {
+        self.required
+    }
+}
+
+impl<L> Policy<QuorumLayer<L>> {
+    /// Fan out to every policy and succeed once `required` of them succeed.
+    ///
+    /// # Errors
+    ///
+    /// See [`QuorumLayer::new`].
+    pub fn quorum<I>(policies: I, required: usize) -> Result<Self, CompositionError>
+    where
+        I: IntoIterator<Item = Policy<L>>,
+    {
+        QuorumLayer::new(policies.into_iter().map(|p| p.0), required).map(Policy)
+    }
+}
+
+impl<S, L> Layer<S> for QuorumLayer<L>
+where
+    S: Clone,
+    L: Layer<S>,
+{
+    type Service = QuorumService<L::Service>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        let services = self.layers.iter().map(|l| l.layer(service.clone())).collect();
+        QuorumService { services, required: self.required }
+    }
+}
+
+impl<L: Describe> Describe for QuorumLayer<L> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::numbered(
+            format!("Quorum({} of {})", self.required, self.layers.len()),
+            self.layers.iter().map(Describe::describe),
+        )
+    }
+}
+
+/// Service produced by [`QuorumLayer`].
+#[derive(Clone, Debug)]
+pub struct QuorumService<S> {
+    services: Vec<S>,
+    required: usize,
+}
+
+impl<S, Request> Service<Request> for QuorumService<S>
+where
+    Request: Clone + Send + 'static,
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = Vec<S::Response>;
+    type Error = QuorumError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut pending = false;
+        for svc in &mut self.services {
+            match svc.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => {
+                    return Poll::Ready(Err(QuorumError {
+                        required: self.required,
+                        successes: 0,
+                        failures: vec![e],
+                    }))
+                }
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let required = self.required;
+        let total = self.services.len();
+        let mut pending: FuturesUnordered<_> =
+            self.services.iter_mut().map(|svc| svc.call(req.clone())).collect();
+
+        Box::pin(async move {
+            let mut responses = Vec::with_capacity(required);
+            let mut failures = Vec::new();
+            while let Some(result) = pending.next().await {
+                match result {
+                    Ok(resp) => {
+                        responses.push(resp);
+                        if responses.len() == required {
+                            return Ok(responses);
+                        }
+                    }
+                    Err(e) => {
+                        failures.push(e);
+                        if total - failures.len() < required {
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(QuorumError { required, successes: responses.len(), failures })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Replica {
+        delay_ms: u64,
+        ok: bool,
+    }
+
+    impl<S> Layer<S> for Replica {
+        type Service = tower::util::BoxCloneService<(), u64, &'static str>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let Replica { delay_ms, ok } = self.clone();
+            tower::util::BoxCloneService::new(tower::service_fn(move |_req: ()| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                if ok {
+                    Ok(delay_ms)
+                } else {
+                    Err("replica down")
+                }
+            }))
+        }
+    }
+
+    fn replicas(spec: &[(u64, bool)]) -> Vec<Policy<Replica>> {
+        spec.iter().map(|&(delay_ms, ok)| Policy(Replica { delay_ms, ok })).collect()
+    }
+
+    #[tokio::test]
+    async fn returns_first_k_successes() {
+        tokio::time::pause();
+        let mut svc =
+            Policy::quorum(replicas(&[(30, true), (10, true), (20, false), (50, true)]), 2)
+                .unwrap()
+                .layer(());
+
+        let resp = svc.ready().await.unwrap().call(()).await.unwrap();
+        assert_eq!(resp, vec![10, 30]);
+    }
+
+    #[tokio::test]
+    async fn fails_as_soon_as_quorum_is_impossible() {
+        tokio::time::pause();
+        let mut svc = Policy::quorum(replicas(&[(10, false), (20, false), (1_000, true)]), 2)
+            .unwrap()
+            .layer(());
+
+        let start = tokio::time::Instant::now();
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert_eq!(err.failures, vec!["replica down", "replica down"]);
+        assert_eq!(err.successes, 0);
+        assert!(start.elapsed() < Duration::from_millis(1_000), "should not wait for stragglers");
+    }
+
+    #[test]
+    fn rejects_invalid_quorum_sizes() {
+        assert_eq!(
+            Policy::quorum(replicas(&[(1, true)]), 2).unwrap_err(),
+            CompositionError::InvalidQuorum { required: 2, available: 1 }
+        );
+        assert!(Policy::quorum(replicas(&[(1, true)]), 0).is_err());
+    }
+}
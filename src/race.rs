@@ -0,0 +1,253 @@
+//! N-way racing: call every stack concurrently and return the first success.
+//!
+//! Semantics
+//! - The generalisation of `&` to any number of branches: every branch receives a clone of the
+//!   request at the same time, the first `Ok` wins, and the remaining futures are dropped.
+//! - If every branch fails, [`RaceError`] carries all errors in branch order (not completion
+//!   order), so `failures[i]` always belongs to the i-th policy.
+//! - Unlike nesting `(a & b) & c`, the response and error types stay flat regardless of the
+//!   number of branches.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let replica = || Policy(TimeoutLayer::new(Duration::from_millis(200)).unwrap());
+//! let race = Policy::race(vec![replica(), replica(), replica()])?;
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(race)
+//!     .service_fn(|key: &'static str| async move { Ok::<_, std::io::Error>(key.len()) });
+//! assert_eq!(svc.ready().await?.call("user:42").await?, 7);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{CompositionError, Policy};
+use crate::describe::{Describe, PolicyNode};
+use futures::future::BoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::fmt;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Error returned when no branch of a race succeeds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceError<E> {
+    /// Branch errors, indexed like the policies passed to [`RaceLayer::new`].
+    ///
+    /// When a branch fails `poll_ready`, only that branch's error is reported.
+    pub failures: Vec<E>,
+}
+
+impl<E: fmt::Display> fmt::Display for RaceError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "all {} raced branches failed", self.failures.len())?;
+        for (i, e) in self.failures.iter().enumerate() {
+            write!(f, "{} [{}] {}", if i == 0 { ":" } else { ";" }, i, e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> std::error::Error for RaceError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.failures.first().map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Layer racing a request across several stacks.
+#[derive(Clone, Debug)]
+pub struct RaceLayer<L> {
+    layers: Vec<L>,
+}
+
+impl<L> RaceLayer<L> {
+    /// Race across `layers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompositionError::Empty`] if no layers are supplied.
+    pub fn new<I>(layers: I) -> Result<Self, CompositionError>
+    where
+        I: IntoIterator<Item = L>,
+    {
+        let layers: Vec<L> = layers.into_iter().collect();
+        if layers.is_empty() {
+            return Err(CompositionError::Empty);
+        }
+        Ok(Self { layers })
+    }
+
+    /// Number of branches.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Always `false`; construction rejects empty races.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl<L> Policy<RaceLayer<L>> {
+    /// Call every policy concurrently and return the first success.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompositionError::Empty`] if no policies are supplied.
+    pub fn race<I>(policies: I) -> Result<Self, CompositionError>
+    where
+        I: IntoIterator<Item = Policy<L>>,
+    {
+        RaceLayer::new(policies.into_iter().map(|p| p.0)).map(Policy)
+    }
+}
+
+impl<S, L> Layer<S> for RaceLayer<L>
+where
+    S: Clone,
+    L: Layer<S>,
+{
+    type Service = RaceService<L::Service>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RaceService { services: self.layers.iter().map(|l| l.layer(service.clone())).collect() }
+    }
+}
+
+impl<L: Describe> Describe for RaceLayer<L> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::numbered("Race", self.layers.iter().map(Describe::describe))
+    }
+}
+
+/// Service produced by [`RaceLayer`].
+#[derive(Clone, Debug)]
+pub struct RaceService<S> {
+    services: Vec<S>,
+}
+
+impl<S, Request> Service<Request> for RaceService<S>
+where
+    Request: Clone + Send + 'static,
+    S: Service<Request> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = RaceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut pending = false;
+        for svc in &mut self.services {
+            match svc.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(RaceError { failures: vec![e] })),
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let total = self.services.len();
+        let mut pending: FuturesUnordered<_> = self
+            .services
+            .iter_mut()
+            .enumerate()
+            .map(|(i, svc)| {
+                let fut = svc.call(req.clone());
+                async move { (i, fut.await) }
+            })
+            .collect();
+
+        Box::pin(async move {
+            let mut failures: Vec<Option<S::Error>> = (0..total).map(|_| None).collect();
+            while let Some((i, result)) = pending.next().await {
+                match result {
+                    Ok(resp) => return Ok(resp),
+                    Err(e) => failures[i] = Some(e),
+                }
+            }
+            Err(RaceError { failures: failures.into_iter().flatten().collect() })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Replica {
+        delay_ms: u64,
+        result: Result<&'static str, &'static str>,
+    }
+
+    impl<S> Layer<S> for Replica {
+        type Service = tower::util::BoxCloneService<(), &'static str, &'static str>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let Replica { delay_ms, result } = self.clone();
+            tower::util::BoxCloneService::new(tower::service_fn(move |_req: ()| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                result
+            }))
+        }
+    }
+
+    fn policy(delay_ms: u64, result: Result<&'static str, &'static str>) -> Policy<Replica> {
+        Policy(Replica { delay_ms, result })
+    }
+
+    #[tokio::test]
+    async fn first_success_wins_over_earlier_failures() {
+        tokio::time::pause();
+        let mut svc =
+            Policy::race(vec![policy(10, Err("a")), policy(30, Ok("b")), policy(20, Ok("c"))])
+                .unwrap()
+                .layer(());
+
+        assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), "c");
+    }
+
+    #[tokio::test]
+    async fn reports_all_failures_in_branch_order() {
+        tokio::time::pause();
+        let mut svc =
+            Policy::race(vec![policy(30, Err("a")), policy(10, Err("b")), policy(20, Err("c"))])
+                .unwrap()
+                .layer(());
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert_eq!(err.failures, vec!["a", "b", "c"]);
+        assert_eq!(err.to_string(), "all 3 raced branches failed: [0] a; [1] b; [2] c");
+    }
+
+    #[test]
+    fn empty_race_is_rejected() {
+        assert_eq!(
+            Policy::race(Vec::<Policy<Replica>>::new()).unwrap_err(),
+            CompositionError::Empty
+        );
+    }
+}
@@ -0,0 +1,507 @@
+//! Rate limiting with selectable admission algorithms.
+//!
+//! Semantics
+//! - [`RateLimitAlgorithm::TokenBucket`] admits bursts of up to `burst` requests and refills at
+//!   `per_second`; requests beyond the bucket are rejected.
+//! - [`RateLimitAlgorithm::SlidingWindowLog`] admits at most `limit` requests in any trailing
+//!   `window`, with no burst allowance at window edges.
+//! - [`RateLimitAlgorithm::LeakyBucket`] smooths admission: requests leave at an even
+//!   `1 / per_second` spacing, waiting their turn, and up to `queue` requests may wait at once.
+//! - Rejections fail with [`ResilienceError::RateLimited`] carrying a `retry_after` hint and emit
+//!   [`RateLimitEvent::Rejected`]; smoothing delays emit [`RateLimitEvent::Delayed`].
+//! - Services built from the same layer (and its clones) share one limiter, since a rate limit
+//!   usually protects a single upstream.
+//!
+//! Invariants
+//! - The algorithm lives in an [`Adaptive`], read once per request. Changing it (including
+//!   switching algorithm) resets the limiter state on the next request.
+//! - [`RateLimitLayer::new`] validates the initial algorithm. Values written later through the
+//!   handle are not validated; an invalid algorithm rejects every request until it is corrected,
+//!   so check with [`RateLimitAlgorithm::validate`] before setting.
+//! - A leaky-bucket slot is reserved when the request is called; dropping the request future
+//!   while it waits does not give the slot back.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let limiter =
+//!     RateLimitLayer::new(RateLimitAlgorithm::TokenBucket { per_second: 100.0, burst: 1 })?;
+//! let tuning = limiter.algorithm().clone();
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(limiter)
+//!     .service_fn(|req: u32| async move { Ok::<_, std::io::Error>(req) });
+//! assert_eq!(svc.ready().await?.call(1).await?, 1);
+//! assert!(svc.ready().await?.call(2).await.unwrap_err().is_rate_limited());
+//!
+//! // Switch to a smoothed limiter for an upstream that cannot absorb bursts.
+//! tuning.set(RateLimitAlgorithm::LeakyBucket { per_second: 100.0, queue: 16 });
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::adaptive::Adaptive;
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, RateLimitEvent};
+use crate::ResilienceError;
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Admission algorithm used by a [`RateLimitLayer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum RateLimitAlgorithm {
+    /// Allow bursts up to `burst`, refilling at `per_second` tokens per second.
+    TokenBucket {
+        /// Sustained rate in requests per second.
+        per_second: f64,
+        /// Bucket capacity: the largest burst admitted at once.
+        burst: u32,
+    },
+    /// Allow at most `limit` requests in any trailing `window`.
+    SlidingWindowLog {
+        /// Requests allowed per window.
+        limit: u32,
+        /// Length of the trailing window.
+        window: Duration,
+    },
+    /// Space requests evenly at `per_second`, letting up to `queue` requests wait for a slot.
+    LeakyBucket {
+        /// Outgoing rate in requests per second.
+        per_second: f64,
+        /// Requests allowed to wait for a slot; `0` rejects instead of waiting.
+        queue: u32,
+    },
+}
+
+impl RateLimitAlgorithm {
+    /// Check that the parameters describe a usable limiter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RateLimitError`] naming the first invalid parameter.
+    pub fn validate(&self) -> Result<(), RateLimitError> {
+        match *self {
+            Self::TokenBucket { per_second, .. } | Self::LeakyBucket { per_second, .. }
+                if !(per_second.is_finite() && per_second > 0.0) =>
+            {
+                Err(RateLimitError::InvalidRate { provided: per_second })
+            }
+            Self::TokenBucket { burst: 0, .. } => Err(RateLimitError::ZeroCapacity),
+            Self::SlidingWindowLog { limit: 0, .. } => Err(RateLimitError::ZeroCapacity),
+            Self::SlidingWindowLog { window, .. } if window.is_zero() => {
+                Err(RateLimitError::ZeroWindow)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for RateLimitAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TokenBucket { per_second, burst } => {
+                write!(f, "token bucket {}/s, burst {}", per_second, burst)
+            }
+            Self::SlidingWindowLog { limit, window } => {
+                write!(f, "sliding window {} per {:?}", limit, window)
+            }
+            Self::LeakyBucket { per_second, queue } => {
+                write!(f, "leaky bucket {}/s, queue {}", per_second, queue)
+            }
+        }
+    }
+}
+
+/// Errors produced while configuring a rate limiter.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitError {
+    /// `per_second` was zero, negative, or not finite.
+    InvalidRate {
+        /// The rejected rate.
+        provided: f64,
+    },
+    /// `burst` or `limit` was zero, so nothing could ever be admitted.
+    ZeroCapacity,
+    /// The sliding window had zero length.
+    ZeroWindow,
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRate { provided } => {
+                write!(f, "rate limit per_second must be finite and > 0 (got {})", provided)
+            }
+            Self::ZeroCapacity => write!(f, "rate limit burst/limit must be > 0"),
+            Self::ZeroWindow => write!(f, "rate limit window must be > 0"),
+        }
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+/// Tower layer limiting the rate of requests reaching the inner service.
+#[derive(Clone)]
+pub struct RateLimitLayer<Sink = NullSink> {
+    algorithm: Adaptive<RateLimitAlgorithm>,
+    state: Arc<Mutex<Limiter>>,
+    sink: Sink,
+}
+
+impl RateLimitLayer<NullSink> {
+    /// Create a rate limiter; accepts a plain algorithm or a shared [`Adaptive`] handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RateLimitError`] if the initial algorithm is invalid.
+    pub fn new(algorithm: impl Into<Adaptive<RateLimitAlgorithm>>) -> Result<Self, RateLimitError> {
+        let algorithm = algorithm.into();
+        let current = algorithm.get();
+        current.validate()?;
+        let state = Arc::new(Mutex::new(Limiter::new(current, Instant::now())));
+        Ok(Self { algorithm, state, sink: NullSink })
+    }
+}
+
+impl<Sink> RateLimitLayer<Sink> {
+    /// Attach a telemetry sink to this rate limiter.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> RateLimitLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        RateLimitLayer { algorithm: self.algorithm, state: self.state, sink }
+    }
+
+    /// Handle to the live algorithm; changes apply to the next request.
+    pub fn algorithm(&self) -> &Adaptive<RateLimitAlgorithm> {
+        &self.algorithm
+    }
+}
+
+impl<Sink: fmt::Debug> fmt::Debug for RateLimitLayer<Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitLayer")
+            .field("algorithm", &self.algorithm.get())
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, Sink> Layer<S> for RateLimitLayer<Sink>
+where
+    Sink: Clone,
+{
+    type Service = RateLimitService<S, Sink>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimitService {
+            inner: service,
+            algorithm: self.algorithm.clone(),
+            state: Arc::clone(&self.state),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<Sink> crate::Describe for RateLimitLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("RateLimit({})", self.algorithm.get()))
+    }
+}
+
+/// Service produced by [`RateLimitLayer`].
+#[derive(Clone)]
+pub struct RateLimitService<S, Sink = NullSink> {
+    inner: S,
+    algorithm: Adaptive<RateLimitAlgorithm>,
+    state: Arc<Mutex<Limiter>>,
+    sink: Sink,
+}
+
+impl<S: fmt::Debug, Sink> fmt::Debug for RateLimitService<S, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitService")
+            .field("inner", &self.inner)
+            .field("algorithm", &self.algorithm.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, Request, Sink> Service<Request> for RateLimitService<S, Sink>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    Request: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let algorithm = self.algorithm.get();
+        let decision = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .acquire(algorithm, Instant::now());
+        let mut inner = self.inner.clone();
+        let sink = self.sink.clone();
+
+        Box::pin(async move {
+            match decision {
+                Decision::Admit => {}
+                Decision::Wait(wait) => {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::RateLimit(RateLimitEvent::Delayed { wait }),
+                    )
+                    .await;
+                    tokio::time::sleep(wait).await;
+                }
+                Decision::Reject { retry_after } => {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::RateLimit(RateLimitEvent::Rejected { retry_after }),
+                    )
+                    .await;
+                    return Err(ResilienceError::RateLimited { retry_after });
+                }
+            }
+            inner.call(req).await.map_err(ResilienceError::Inner)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Admit,
+    Wait(Duration),
+    Reject { retry_after: Duration },
+}
+
+/// Limiter state for the algorithm it was built for.
+#[derive(Debug)]
+struct Limiter {
+    algorithm: RateLimitAlgorithm,
+    state: State,
+}
+
+#[derive(Debug)]
+enum State {
+    TokenBucket { tokens: f64, refilled_at: Instant },
+    SlidingWindowLog { admitted: VecDeque<Instant> },
+    LeakyBucket { next_slot: Instant },
+}
+
+impl Limiter {
+    fn new(algorithm: RateLimitAlgorithm, now: Instant) -> Self {
+        let state = match algorithm {
+            RateLimitAlgorithm::TokenBucket { burst, .. } => {
+                State::TokenBucket { tokens: f64::from(burst), refilled_at: now }
+            }
+            RateLimitAlgorithm::SlidingWindowLog { .. } => {
+                State::SlidingWindowLog { admitted: VecDeque::new() }
+            }
+            RateLimitAlgorithm::LeakyBucket { .. } => State::LeakyBucket { next_slot: now },
+        };
+        Self { algorithm, state }
+    }
+
+    fn acquire(&mut self, algorithm: RateLimitAlgorithm, now: Instant) -> Decision {
+        if algorithm.validate().is_err() {
+            return Decision::Reject { retry_after: Duration::ZERO };
+        }
+        if algorithm != self.algorithm {
+            *self = Self::new(algorithm, now);
+        }
+        match (algorithm, &mut self.state) {
+            (
+                RateLimitAlgorithm::TokenBucket { per_second, burst },
+                State::TokenBucket { tokens, refilled_at },
+            ) => {
+                let refill = now.saturating_duration_since(*refilled_at).as_secs_f64() * per_second;
+                *tokens = (*tokens + refill).min(f64::from(burst));
+                *refilled_at = now;
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    Decision::Admit
+                } else {
+                    Decision::Reject {
+                        retry_after: Duration::from_secs_f64((1.0 - *tokens) / per_second),
+                    }
+                }
+            }
+            (
+                RateLimitAlgorithm::SlidingWindowLog { limit, window },
+                State::SlidingWindowLog { admitted },
+            ) => {
+                while admitted.front().is_some_and(|&t| t + window <= now) {
+                    admitted.pop_front();
+                }
+                if admitted.len() < limit as usize {
+                    admitted.push_back(now);
+                    Decision::Admit
+                } else {
+                    let oldest = admitted.front().copied().unwrap_or(now);
+                    Decision::Reject { retry_after: (oldest + window) - now }
+                }
+            }
+            (
+                RateLimitAlgorithm::LeakyBucket { per_second, queue },
+                State::LeakyBucket { next_slot },
+            ) => {
+                let interval = Duration::from_secs_f64(1.0 / per_second);
+                let slot = (*next_slot).max(now);
+                let wait = slot - now;
+                let max_wait = interval * queue;
+                if wait > max_wait {
+                    return Decision::Reject { retry_after: wait - max_wait };
+                }
+                *next_slot = slot + interval;
+                if wait.is_zero() {
+                    Decision::Admit
+                } else {
+                    Decision::Wait(wait)
+                }
+            }
+            _ => unreachable!("limiter state is rebuilt whenever the algorithm changes"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    fn echo() -> tower::util::BoxCloneService<u32, u32, TestError> {
+        tower::util::BoxCloneService::new(tower::service_fn(|req: u32| async move {
+            Ok::<_, TestError>(req)
+        }))
+    }
+
+    #[tokio::test]
+    async fn token_bucket_allows_burst_then_refills() {
+        tokio::time::pause();
+        let layer =
+            RateLimitLayer::new(RateLimitAlgorithm::TokenBucket { per_second: 10.0, burst: 2 })
+                .unwrap();
+        let mut svc = layer.layer(echo());
+
+        assert_eq!(svc.ready().await.unwrap().call(1).await.unwrap(), 1);
+        assert_eq!(svc.ready().await.unwrap().call(2).await.unwrap(), 2);
+        let err = svc.ready().await.unwrap().call(3).await.unwrap_err();
+        assert_eq!(err.to_string(), "rate limit exceeded (retry after 100ms)");
+
+        tokio::time::advance(Duration::from_millis(100)).await;
+        assert_eq!(svc.ready().await.unwrap().call(4).await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn sliding_window_counts_trailing_requests() {
+        tokio::time::pause();
+        let layer = RateLimitLayer::new(RateLimitAlgorithm::SlidingWindowLog {
+            limit: 2,
+            window: Duration::from_secs(1),
+        })
+        .unwrap();
+        let mut svc = layer.layer(echo());
+
+        svc.ready().await.unwrap().call(1).await.unwrap();
+        tokio::time::advance(Duration::from_millis(600)).await;
+        svc.ready().await.unwrap().call(2).await.unwrap();
+
+        let err = svc.ready().await.unwrap().call(3).await.unwrap_err();
+        assert!(matches!(err, ResilienceError::RateLimited { retry_after }
+            if retry_after == Duration::from_millis(400)));
+
+        tokio::time::advance(Duration::from_millis(400)).await;
+        svc.ready().await.unwrap().call(4).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn leaky_bucket_spaces_requests_and_bounds_the_queue() {
+        tokio::time::pause();
+        let sink = MemorySink::new();
+        let layer =
+            RateLimitLayer::new(RateLimitAlgorithm::LeakyBucket { per_second: 10.0, queue: 1 })
+                .unwrap()
+                .with_sink(sink.clone());
+        let mut svc = layer.layer(echo());
+
+        let start = Instant::now();
+        let first = svc.ready().await.unwrap().call(1);
+        let second = svc.ready().await.unwrap().call(2);
+        let third = svc.ready().await.unwrap().call(3);
+
+        assert!(third.await.unwrap_err().is_rate_limited());
+        assert_eq!(first.await.unwrap(), 1);
+        assert_eq!(second.await.unwrap(), 2);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert!(sink.events().contains(&PolicyEvent::RateLimit(RateLimitEvent::Delayed {
+            wait: Duration::from_millis(100)
+        })));
+    }
+
+    #[tokio::test]
+    async fn adaptive_changes_apply_to_the_next_request() {
+        tokio::time::pause();
+        let algorithm =
+            Adaptive::new(RateLimitAlgorithm::TokenBucket { per_second: 1.0, burst: 1 });
+        let layer = RateLimitLayer::new(algorithm.clone()).unwrap();
+        let mut svc = layer.layer(echo());
+
+        svc.ready().await.unwrap().call(1).await.unwrap();
+        assert!(svc.ready().await.unwrap().call(2).await.unwrap_err().is_rate_limited());
+
+        algorithm.set(RateLimitAlgorithm::TokenBucket { per_second: 1.0, burst: 5 });
+        svc.ready().await.unwrap().call(3).await.unwrap();
+
+        algorithm.set(RateLimitAlgorithm::TokenBucket { per_second: f64::NAN, burst: 5 });
+        assert!(svc.ready().await.unwrap().call(4).await.unwrap_err().is_rate_limited());
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        let err =
+            RateLimitLayer::new(RateLimitAlgorithm::LeakyBucket { per_second: 0.0, queue: 1 })
+                .unwrap_err();
+        assert_eq!(err, RateLimitError::InvalidRate { provided: 0.0 });
+        assert_eq!(
+            RateLimitAlgorithm::SlidingWindowLog { limit: 3, window: Duration::ZERO }.validate(),
+            Err(RateLimitError::ZeroWindow)
+        );
+    }
+}
@@ -9,6 +9,10 @@
 //! - `should_retry` predicate decides whether an `Inner` error is retryable.
 //! - Backoff calculates delay per retry attempt; jitter randomizes the delay to avoid thundering
 //!   herds.
+//! - When a [`Deadline`](crate::Deadline) is in scope (see [`RequestContext`]), retrying stops
+//!   early with `RetryExhausted` once the next backoff delay would consume the remaining budget.
+//! - Each attempt runs in a DEBUG `ninelives.retry.attempt` span (see
+//!   [`TracingLayer`](crate::TracingLayer)).
 //! - Sleeper controls how delays are applied (production uses `TokioSleeper`; tests can inject
 //!   `InstantSleeper`/`TrackingSleeper`).
 //!
@@ -42,7 +46,7 @@
 //! ```
 
 use crate::error::MAX_RETRY_FAILURES;
-use crate::{Backoff, Jitter, ResilienceError, Sleeper, TokioSleeper};
+use crate::{Backoff, Jitter, RequestContext, ResilienceError, Sleeper, TokioSleeper};
 use futures::future::BoxFuture;
 use std::collections::VecDeque;
 use std::future::Future;
@@ -50,6 +54,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tower_layer::Layer;
 use tower_service::Service;
+use tracing::Instrument;
 
 /// Retry policy combining backoff, jitter, predicate, and sleeper.
 #[derive(Clone)]
@@ -90,6 +95,7 @@ where
             jitter: self.jitter,
             should_retry: self.should_retry,
             sleeper: self.sleeper,
+            stats: Arc::default(),
             sink: NullSink,
         }
     }
@@ -250,6 +256,7 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
     use crate::{InstantSleeper, TrackingSleeper};
@@ -612,11 +619,39 @@ mod tests {
         assert_eq!(attempts.load(Ordering::SeqCst), 1, "should not retry");
     }
 
+    #[tokio::test]
+    async fn stops_retrying_when_deadline_budget_is_spent() {
+        tokio::time::pause();
+        let sleeper = TrackingSleeper::new();
+        let policy = RetryPolicy::builder()
+            .max_attempts(10)
+            .backoff(Backoff::constant(Duration::from_millis(100)))
+            .with_jitter(Jitter::None)
+            .with_sleeper(sleeper.clone())
+            .build()
+            .expect("builder");
+
+        let deadline = crate::Deadline::after(Duration::from_millis(50));
+        let result = RequestContext::new()
+            .with_deadline(deadline)
+            .scope(policy.execute(|| async {
+                Err::<(), _>(ResilienceError::Inner(TestError("fail".into())))
+            }))
+            .await;
+
+        match result.unwrap_err() {
+            ResilienceError::RetryExhausted { attempts, .. } => assert_eq!(attempts, 1),
+            e => panic!("Expected RetryExhausted, got {:?}", e),
+        }
+        assert_eq!(sleeper.calls(), 0, "should not sleep past the deadline");
+    }
+
     // end of tests module
 }
 
 // end of file
 
+use crate::policy_metrics::{MetricsSnapshot, PolicyMetrics, RetryStats};
 use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, RetryEvent};
 use std::time::Instant;
 
@@ -627,6 +662,7 @@ pub struct RetryLayer<E, Sink = NullSink> {
     jitter: Jitter,
     should_retry: Arc<dyn Fn(&E) -> bool + Send + Sync>,
     sleeper: Arc<dyn Sleeper>,
+    stats: Arc<RetryStats>,
     sink: Sink,
 }
 
@@ -651,7 +687,15 @@ where
         if max_attempts == 0 {
             return Err(BuildError::InvalidMaxAttempts(0));
         }
-        Ok(Self { max_attempts, backoff, jitter, should_retry, sleeper, sink: NullSink })
+        Ok(Self {
+            max_attempts,
+            backoff,
+            jitter,
+            should_retry,
+            sleeper,
+            stats: Arc::default(),
+            sink: NullSink,
+        })
     }
 }
 
@@ -671,6 +715,7 @@ where
             jitter: self.jitter,
             should_retry: self.should_retry,
             sleeper: self.sleeper,
+            stats: self.stats,
             sink,
         }
     }
@@ -687,18 +732,24 @@ where
             jitter: self.jitter.clone(),
             should_retry: self.should_retry.clone(),
             sleeper: self.sleeper.clone(),
+            stats: self.stats.clone(),
             sink: self.sink.clone(),
         }
     }
 }
 
 /// Retry service produced by `RetryLayer`.
-#[derive(Clone)]
 pub struct RetryService<S, E, Sink = NullSink> {
     inner: S,
     layer: RetryLayer<E, Sink>,
 }
 
+impl<S: Clone, E, Sink: Clone> Clone for RetryService<S, E, Sink> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), layer: self.layer.clone() }
+    }
+}
+
 impl<S, E, Sink> RetryService<S, E, Sink> {
     fn new(inner: S, layer: RetryLayer<E, Sink>) -> Self {
         Self { inner, layer }
@@ -734,20 +785,32 @@ where
 
         Box::pin(async move {
             let start = Instant::now();
-            run_retry_loop(
+            let stats = Arc::clone(&layer.stats);
+            let mut first = true;
+            let result = run_retry_loop(
                 layer.max_attempts,
                 &layer.backoff,
                 &layer.jitter,
                 &layer.should_retry,
                 &layer.sleeper,
                 move || {
+                    // Every attempt after the first was scheduled as a retry.
+                    if !std::mem::take(&mut first) {
+                        stats.retry();
+                    }
                     let req_clone = req.clone();
                     let mut inner_clone = inner.clone();
                     async move { inner_clone.call(req_clone).await.map_err(ResilienceError::Inner) }
                 },
                 Some((sink, start)),
             )
-            .await
+            .await;
+            match &result {
+                Ok(_) => layer.stats.success(),
+                Err(ResilienceError::RetryExhausted { .. }) => layer.stats.exhausted(),
+                Err(_) => layer.stats.failure(),
+            }
+            result
         })
     }
 }
@@ -773,7 +836,8 @@ where
     let mut failures: VecDeque<E> = VecDeque::new();
 
     for attempt_idx in 0..max_attempts {
-        match attempt().await {
+        let span = tracing::debug_span!("ninelives.retry.attempt", attempt = attempt_idx + 1);
+        match attempt().instrument(span).await {
             Ok(value) => {
                 if let Some((sink, start)) = telemetry.as_ref() {
                     let duration = start.elapsed();
@@ -807,36 +871,46 @@ where
                     failures.pop_front();
                 }
 
-                if attempt_idx + 1 >= max_attempts {
-                    if let Some((sink, start)) = telemetry.as_ref() {
-                        let total_duration = start.elapsed();
-                        emit_best_effort(
-                            sink.clone(),
-                            PolicyEvent::Retry(RetryEvent::Exhausted {
-                                total_attempts: max_attempts,
-                                total_duration,
-                            }),
-                        )
-                        .await;
-                        emit_best_effort(
-                            sink.clone(),
-                            PolicyEvent::Request(crate::telemetry::RequestOutcome::Failure {
-                                duration: total_duration,
-                            }),
-                        )
-                        .await;
+                let attempts_made = attempt_idx + 1;
+                let delay = (attempts_made < max_attempts)
+                    .then(|| jitter.apply_with_state(backoff.delay(attempts_made)));
+                let budget_spent = match (delay, RequestContext::current().deadline()) {
+                    (Some(delay), Some(deadline)) => delay >= deadline.remaining(),
+                    _ => false,
+                };
+
+                let delay = match delay {
+                    Some(delay) if !budget_spent => delay,
+                    _ => {
+                        if let Some((sink, start)) = telemetry.as_ref() {
+                            let total_duration = start.elapsed();
+                            emit_best_effort(
+                                sink.clone(),
+                                PolicyEvent::Retry(RetryEvent::Exhausted {
+                                    total_attempts: attempts_made,
+                                    total_duration,
+                                }),
+                            )
+                            .await;
+                            emit_best_effort(
+                                sink.clone(),
+                                PolicyEvent::Request(crate::telemetry::RequestOutcome::Failure {
+                                    duration: total_duration,
+                                }),
+                            )
+                            .await;
+                        }
+                        return Err(ResilienceError::retry_exhausted(
+                            attempts_made,
+                            failures.into_iter().collect(),
+                        ));
                     }
-                    return Err(ResilienceError::retry_exhausted(
-                        max_attempts,
-                        failures.into_iter().collect(),
-                    ));
-                }
+                };
 
-                let delay = jitter.apply_with_state(backoff.delay(attempt_idx + 1));
                 if let Some((sink, _)) = telemetry.as_ref() {
                     emit_best_effort(
                         sink.clone(),
-                        PolicyEvent::Retry(RetryEvent::Attempt { attempt: attempt_idx + 1, delay }),
+                        PolicyEvent::Retry(RetryEvent::Attempt { attempt: attempts_made, delay }),
                     )
                     .await;
                 }
@@ -859,3 +933,15 @@ where
         RetryService::new(service, self.clone())
     }
 }
+
+impl<E, Sink> crate::Describe for RetryLayer<E, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Retry(x{})", self.max_attempts))
+    }
+}
+
+impl<E, Sink> PolicyMetrics for RetryLayer<E, Sink> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.stats.snapshot()
+    }
+}
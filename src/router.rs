@@ -0,0 +1,269 @@
+//! Per-endpoint routing: pick a policy stack per request key.
+//!
+//! Semantics
+//! - A key function maps each request to a route key (a path, an HTTP method, an RPC name). The
+//!   request is dispatched through the stack registered for that key, or through the default
+//!   stack when the key is not registered.
+//! - Every stack wraps a clone of the same inner service. Stacks share one layer type; box them
+//!   with [`BoxPolicy`](crate::BoxPolicy) to register differently shaped stacks.
+//! - As with [`CondLayer`](crate::CondLayer), the service is ready only when every route is
+//!   ready, since the route is not known until the request arrives.
+//! - Registered keys and their stacks can be listed at runtime with [`RouterLayer::keys`] and
+//!   [`RouterLayer::route_for`], and [`Describe`] renders one branch per key.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceExt};
+//! use tower_layer::Layer;
+//!
+//! type Svc = tower::util::BoxCloneService<(&'static str, u32), u32, std::io::Error>;
+//! type Err = ResilienceError<std::io::Error>;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Reads get two quick attempts; writes a single patient one. The stacks differ until boxed.
+//! let reads: BoxPolicy<Svc, _, _, Err> = Policy::fallback_chain([
+//!     Policy(TimeoutLayer::new(Duration::from_millis(100))?),
+//!     Policy(TimeoutLayer::new(Duration::from_millis(100))?),
+//! ])?
+//! .boxed();
+//! let writes: BoxPolicy<Svc, _, _, Err> =
+//!     Policy(TimeoutLayer::new(Duration::from_secs(2))?).boxed();
+//!
+//! let router = RouterLayer::new(|req: &(&'static str, u32)| req.0, writes.0.clone())
+//!     .route("GET", reads.0)
+//!     .route("POST", writes.0);
+//! assert_eq!(router.keys().copied().collect::<Vec<_>>(), ["GET", "POST"]);
+//!
+//! let inner = Svc::new(tower::service_fn(|req: (&'static str, u32)| async move { Ok(req.1) }));
+//! let mut svc = router.layer(inner);
+//! assert_eq!(svc.ready().await?.call(("GET", 7)).await?, 7);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::describe::{Describe, PolicyNode};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer dispatching each request through the stack registered for its key.
+pub struct RouterLayer<K, L, F> {
+    key: Arc<F>,
+    routes: BTreeMap<K, L>,
+    default: L,
+}
+
+impl<K: Ord, L, F> RouterLayer<K, L, F> {
+    /// Route by `key`, sending requests whose key has no stack through `default`.
+    pub fn new<Request>(key: F, default: L) -> Self
+    where
+        F: Fn(&Request) -> K,
+    {
+        Self { key: Arc::new(key), routes: BTreeMap::new(), default }
+    }
+
+    /// Register `layer` for requests whose key equals `key`, replacing any earlier stack.
+    pub fn route(mut self, key: K, layer: L) -> Self {
+        self.routes.insert(key, layer);
+        self
+    }
+
+    /// Registered keys in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.routes.keys()
+    }
+
+    /// The stack registered for `key`, if any.
+    pub fn route_for(&self, key: &K) -> Option<&L> {
+        self.routes.get(key)
+    }
+
+    /// The stack used for unregistered keys.
+    pub fn default_route(&self) -> &L {
+        &self.default
+    }
+}
+
+impl<K: Clone, L: Clone, F> Clone for RouterLayer<K, L, F> {
+    fn clone(&self) -> Self {
+        Self {
+            key: Arc::clone(&self.key),
+            routes: self.routes.clone(),
+            default: self.default.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, L: fmt::Debug, F> fmt::Debug for RouterLayer<K, L, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouterLayer")
+            .field("routes", &self.routes)
+            .field("default", &self.default)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, K, L, F> Layer<S> for RouterLayer<K, L, F>
+where
+    S: Clone,
+    K: Ord + Clone,
+    L: Layer<S>,
+{
+    type Service = RouterService<K, L::Service, F>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RouterService {
+            key: Arc::clone(&self.key),
+            routes: self
+                .routes
+                .iter()
+                .map(|(key, layer)| (key.clone(), layer.layer(service.clone())))
+                .collect(),
+            default: self.default.layer(service),
+        }
+    }
+}
+
+impl<K: fmt::Display, L: Describe, F> Describe for RouterLayer<K, L, F> {
+    fn describe(&self) -> PolicyNode {
+        let routes = self.routes.iter().map(|(key, layer)| (key.to_string(), layer.describe()));
+        PolicyNode::branch(
+            "Router",
+            routes.chain(std::iter::once(("default".to_string(), self.default.describe()))),
+        )
+    }
+}
+
+/// Service produced by [`RouterLayer`].
+pub struct RouterService<K, S, F> {
+    key: Arc<F>,
+    routes: BTreeMap<K, S>,
+    default: S,
+}
+
+impl<K, S, F> RouterService<K, S, F> {
+    /// Registered keys in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.routes.keys()
+    }
+}
+
+impl<K: Clone, S: Clone, F> Clone for RouterService<K, S, F> {
+    fn clone(&self) -> Self {
+        Self {
+            key: Arc::clone(&self.key),
+            routes: self.routes.clone(),
+            default: self.default.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, S: fmt::Debug, F> fmt::Debug for RouterService<K, S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouterService")
+            .field("routes", &self.routes)
+            .field("default", &self.default)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, S, F, Request> Service<Request> for RouterService<K, S, F>
+where
+    K: Ord,
+    F: Fn(&Request) -> K,
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut pending = false;
+        for route in self.routes.values_mut().chain(std::iter::once(&mut self.default)) {
+            match route.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let key = (self.key)(&req);
+        match self.routes.get_mut(&key) {
+            Some(route) => route.call(req),
+            None => self.default.call(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Tag(&'static str);
+
+    impl<S> Layer<S> for Tag {
+        type Service = tower::util::BoxCloneService<&'static str, String, std::convert::Infallible>;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let tag = self.0;
+            tower::util::BoxCloneService::new(tower::service_fn(
+                move |req: &'static str| async move { Ok(format!("{}:{}", tag, req)) },
+            ))
+        }
+    }
+
+    impl Describe for Tag {
+        fn describe(&self) -> PolicyNode {
+            PolicyNode::layer(self.0)
+        }
+    }
+
+    fn method(req: &&'static str) -> &'static str {
+        req.split(' ').next().unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn dispatches_by_key_with_default() {
+        let router = RouterLayer::new(method, Tag("no-retry"))
+            .route("GET", Tag("aggressive"))
+            .route("HEAD", Tag("aggressive"));
+        let mut svc = router.layer(());
+
+        assert_eq!(svc.ready().await.unwrap().call("GET /a").await.unwrap(), "aggressive:GET /a");
+        assert_eq!(svc.ready().await.unwrap().call("POST /a").await.unwrap(), "no-retry:POST /a");
+        assert_eq!(svc.keys().copied().collect::<Vec<_>>(), ["GET", "HEAD"]);
+    }
+
+    #[test]
+    fn routes_are_inspectable() {
+        let router = RouterLayer::new(method, Tag("no-retry"))
+            .route("GET", Tag("aggressive"))
+            .route("GET", Tag("cached"));
+
+        assert_eq!(router.route_for(&"GET").map(|t| t.0), Some("cached"));
+        assert!(router.route_for(&"PUT").is_none());
+        assert_eq!(router.default_route().0, "no-retry");
+        assert_eq!(
+            router.describe(),
+            PolicyNode::branch(
+                "Router",
+                [("GET", PolicyNode::layer("cached")), ("default", PolicyNode::layer("no-retry"))]
+            )
+        );
+    }
+}
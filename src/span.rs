@@ -0,0 +1,208 @@
+//! `tracing` spans around policy execution.
+//!
+//! [`LogSink`](crate::telemetry::LogSink) writes each event as a standalone log line, so a
+//! subscriber cannot tell which request, attempt, or fallback branch it belongs to.
+//! [`TracingLayer`] opens a span per request, and [`TracingSink`](crate::telemetry::TracingSink)
+//! records events as structured span events inside whatever span is current when they are
+//! emitted.
+//!
+//! Semantics
+//! - `TracingLayer` opens an INFO span named `ninelives.policy` around each call, with fields
+//!   `policy` and `instance` taken from the [`RequestContext`] (see `Policy::named`).
+//! - Retry opens a DEBUG child span `ninelives.retry.attempt` (field `attempt`, starting at 1)
+//!   around every attempt; fallback opens `ninelives.fallback.branch` (field `branch`, 0 for the
+//!   primary) around each branch. These are always on and cost nothing without a subscriber.
+//! - Policy events are emitted between attempts, so they land in the request span rather than in
+//!   an attempt span.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let timeout = TimeoutLayer::new(Duration::from_secs(1))?.with_sink(TracingSink::new());
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(Policy(TracingLayer::new()).named("db-read"))
+//!     .layer(timeout)
+//!     .service_fn(|n: u32| async move { Ok::<_, std::io::Error>(n) });
+//! assert_eq!(svc.ready().await?.call(7).await?, 7);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::RequestContext;
+use std::future::Future;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+use tracing::instrument::{Instrument, Instrumented};
+use tracing::Span;
+
+/// Layer that runs each request inside a `ninelives.policy` span.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingLayer;
+
+impl TracingLayer {
+    /// Create the layer.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for TracingLayer {
+    type Service = TracingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracingService { inner }
+    }
+}
+
+impl crate::Describe for TracingLayer {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer("Tracing")
+    }
+}
+
+/// Service produced by [`TracingLayer`].
+#[derive(Debug, Clone)]
+pub struct TracingService<S> {
+    inner: S,
+}
+
+impl<S, Request> Service<Request> for TracingService<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let ctx = RequestContext::current();
+        let span = tracing::info_span!(
+            "ninelives.policy",
+            policy = ctx.policy_name(),
+            instance = ctx.policy_instance(),
+        );
+        in_span(span, || self.inner.call(req))
+    }
+}
+
+/// Build a future inside `span` and keep it there while it runs.
+pub(crate) fn in_span<F: Future>(span: Span, make: impl FnOnce() -> F) -> Instrumented<F> {
+    span.in_scope(make).instrument(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::TracingSink;
+    use crate::{Backoff, Jitter, Policy, RetryLayer, RetryPolicy};
+    use std::fmt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tower::ServiceExt;
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    /// An event's message (or `event` field) and the names of its enclosing spans, innermost first.
+    type Recorded = (String, Vec<&'static str>);
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+    struct Message(String);
+
+    impl Visit for Message {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "event" {
+                self.0 = value.to_owned();
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" && self.0.is_empty() {
+                self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for Recorder
+    where
+        S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_event(&self, event: &tracing::Event<'_>, ctx: LayerContext<'_, S>) {
+            let mut message = Message(String::new());
+            event.record(&mut message);
+            let spans = ctx
+                .event_scope(event)
+                .map(|scope| scope.map(|span| span.name()).collect())
+                .unwrap_or_default();
+            self.0.lock().unwrap().push((message.0, spans));
+        }
+    }
+
+    #[tokio::test]
+    async fn attempts_and_events_nest_under_the_request_span() {
+        let recorder = Recorder::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+        let retry: RetryLayer<TestError, _> = RetryPolicy::builder()
+            .max_attempts(2)
+            .backoff(Backoff::constant(Duration::ZERO))
+            .with_jitter(Jitter::None)
+            .build()
+            .unwrap()
+            .into_layer()
+            .with_sink(TracingSink::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let svc = tower::ServiceBuilder::new()
+            .layer(Policy(TracingLayer::new()).named("db-read"))
+            .layer(retry)
+            .service_fn(move |_: ()| {
+                let first = calls.fetch_add(1, Ordering::SeqCst) == 0;
+                async move {
+                    tracing::info!("inner");
+                    if first {
+                        Err(TestError("flaky".into()))
+                    } else {
+                        Ok(())
+                    }
+                }
+            });
+        svc.oneshot(()).await.unwrap();
+
+        let attempt = vec!["ninelives.retry.attempt", "ninelives.policy"];
+        let request = vec!["ninelives.policy"];
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                ("inner".to_string(), attempt.clone()),
+                ("attempt".to_string(), request.clone()),
+                ("inner".to_string(), attempt),
+                ("success".to_string(), request),
+            ]
+        );
+    }
+}
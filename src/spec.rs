@@ -0,0 +1,491 @@
+//! Declarative policy construction.
+//!
+//! Semantics
+//! - [`PolicySpec`] is a plain data model of a policy stack: leaf layers (timeout, retry,
+//!   circuit breaker, bulkhead) combined with the composition operators (`+` as `sequence`,
+//!   `|` as `fallback`, `&` as `fork_join`) and `named` for telemetry attribution.
+//! - With the `serde` feature the model derives `Serialize`/`Deserialize`, internally tagged by
+//!   `kind` with snake_case names, so it can be loaded from JSON, YAML, or TOML. Durations are
+//!   whole milliseconds (`*_ms` fields).
+//! - [`PolicySpec::build`] validates the spec and compiles it into a [`BoxPolicy`]. Every layer
+//!   in a compiled stack reports `ResilienceError<E>` for the wrapped service's error `E`; errors
+//!   from nested layers are collapsed with [`ResilienceError::flatten`] and fork-join failures
+//!   keep the left branch's error.
+//! - Compiled retries retry every error and sleep on Tokio; compiled layers emit no telemetry.
+//!
+//! A spec in JSON:
+//! ```text
+//! { "kind": "fallback", "policies": [
+//!     { "kind": "timeout", "timeout_ms": 100 },
+//!     { "kind": "sequence", "policies": [
+//!         { "kind": "timeout", "timeout_ms": 2000 },
+//!         { "kind": "retry", "max_attempts": 3,
+//!           "backoff": { "kind": "exponential", "base_ms": 50, "max_ms": 1000 } },
+//!         { "kind": "bulkhead", "max_concurrent": 32 } ] } ] }
+//! ```
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use tower::{Service, ServiceExt};
+//! use tower_layer::Layer;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let spec = PolicySpec::Sequence {
+//!     policies: vec![
+//!         PolicySpec::Timeout { timeout_ms: 500 },
+//!         PolicySpec::Bulkhead { max_concurrent: 8 },
+//!     ],
+//! };
+//! let policy = spec.build()?;
+//! assert_eq!(policy.describe().to_string(), "Boxed");
+//!
+//! let inner = tower::service_fn(|n: u32| async move { Ok::<_, std::io::Error>(n + 1) });
+//! let mut svc = policy.layer(inner);
+//! assert_eq!(svc.ready().await?.call(41).await?, 42);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{CombinedLayer, CompositionError};
+use crate::{
+    Backoff, BackoffError, BoxLayer, BoxPolicy, BuildError, BulkheadError, BulkheadLayer,
+    CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerLayer, FallbackChainLayer, Jitter,
+    NamedLayer, Policy, ResilienceError, RetryLayer, TimeoutError, TimeoutLayer, TokioSleeper,
+};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::util::{BoxCloneService, MapErr};
+use tower_layer::{layer_fn, Layer};
+use tower_service::Service;
+
+/// Declarative description of a policy stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum PolicySpec {
+    /// [`TimeoutLayer`] with the given limit.
+    Timeout {
+        /// Timeout in milliseconds.
+        timeout_ms: u64,
+    },
+    /// [`RetryLayer`] retrying every error.
+    Retry {
+        /// Total attempts, including the first.
+        max_attempts: usize,
+        /// Delay between attempts.
+        backoff: BackoffSpec,
+        /// Randomisation applied to each delay.
+        #[cfg_attr(feature = "serde", serde(default))]
+        jitter: JitterSpec,
+    },
+    /// [`CircuitBreakerLayer`].
+    CircuitBreaker {
+        /// Consecutive failures that open the breaker.
+        failure_threshold: usize,
+        /// Time the breaker stays open before probing, in milliseconds.
+        recovery_timeout_ms: u64,
+        /// Probe calls allowed while half-open.
+        half_open_max_calls: usize,
+    },
+    /// [`BulkheadLayer`].
+    Bulkhead {
+        /// Maximum concurrent requests.
+        max_concurrent: usize,
+    },
+    /// Policies applied in order, outermost first (`a + b + c`).
+    Sequence {
+        /// The policies, outermost first.
+        policies: Vec<PolicySpec>,
+    },
+    /// Policies tried in order until one succeeds (`a | b | c`).
+    Fallback {
+        /// The policies, in priority order.
+        policies: Vec<PolicySpec>,
+    },
+    /// Policies raced concurrently, first success wins (`a & b & c`).
+    ForkJoin {
+        /// The policies to race.
+        policies: Vec<PolicySpec>,
+    },
+    /// A policy attributed to a name in telemetry (`Policy::named`).
+    Named {
+        /// Name reported in `RequestContext::policy_name`.
+        name: String,
+        /// The named policy.
+        policy: Box<PolicySpec>,
+    },
+}
+
+/// Declarative backoff configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum BackoffSpec {
+    /// Same delay before every retry.
+    Constant {
+        /// Delay in milliseconds.
+        delay_ms: u64,
+    },
+    /// Delay grows by `base_ms` per attempt.
+    Linear {
+        /// Base delay in milliseconds.
+        base_ms: u64,
+        /// Optional cap in milliseconds.
+        #[cfg_attr(feature = "serde", serde(default))]
+        max_ms: Option<u64>,
+    },
+    /// Delay doubles per attempt.
+    Exponential {
+        /// Base delay in milliseconds.
+        base_ms: u64,
+        /// Optional cap in milliseconds.
+        #[cfg_attr(feature = "serde", serde(default))]
+        max_ms: Option<u64>,
+    },
+}
+
+/// Declarative jitter configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum JitterSpec {
+    /// Exact backoff delays.
+    #[default]
+    None,
+    /// Uniform in `[0, delay]`.
+    Full,
+    /// Uniform in `[delay / 2, delay]`.
+    Equal,
+    /// AWS-style decorrelated jitter.
+    Decorrelated {
+        /// Lower bound in milliseconds.
+        base_ms: u64,
+        /// Upper bound in milliseconds.
+        max_ms: u64,
+    },
+}
+
+/// Errors returned when a [`PolicySpec`] does not describe a valid policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PolicySpecError {
+    /// Invalid timeout.
+    Timeout(TimeoutError),
+    /// Invalid retry configuration.
+    Retry(BuildError),
+    /// Invalid backoff configuration.
+    Backoff(BackoffError),
+    /// Invalid jitter configuration.
+    Jitter(&'static str),
+    /// Invalid circuit breaker configuration.
+    CircuitBreaker(CircuitBreakerError),
+    /// Invalid bulkhead configuration.
+    Bulkhead(BulkheadError),
+    /// A combinator was given no policies.
+    Composition(CompositionError),
+}
+
+impl fmt::Display for PolicySpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicySpecError::Timeout(e) => write!(f, "invalid timeout: {}", e),
+            PolicySpecError::Retry(e) => write!(f, "invalid retry: {}", e),
+            PolicySpecError::Backoff(e) => write!(f, "invalid backoff: {}", e),
+            PolicySpecError::Jitter(e) => write!(f, "invalid jitter: {}", e),
+            PolicySpecError::CircuitBreaker(e) => write!(f, "invalid circuit breaker: {}", e),
+            PolicySpecError::Bulkhead(e) => write!(f, "invalid bulkhead: {}", e),
+            PolicySpecError::Composition(e) => write!(f, "invalid composition: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PolicySpecError {}
+
+/// Uniform service type every compiled layer wraps and produces.
+type Stage<Req, Resp, E> = BoxCloneService<Req, Resp, ResilienceError<E>>;
+/// A compiled spec node.
+type Node<Req, Resp, E> = BoxLayer<Stage<Req, Resp, E>, Req, Resp, ResilienceError<E>>;
+
+impl PolicySpec {
+    /// Validate the spec and compile it into a layer for services failing with `E`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid setting found, e.g. a zero timeout or an empty `fallback`.
+    pub fn build<S, Req, Resp, E>(
+        &self,
+    ) -> Result<BoxPolicy<S, Req, Resp, ResilienceError<E>>, PolicySpecError>
+    where
+        S: Service<Req, Response = Resp, Error = E> + Clone + Send + 'static,
+        S::Future: Send + 'static,
+        Req: Send + Clone + 'static,
+        Resp: Send + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let node = self.compile::<Req, Resp, E>()?;
+        let lift = layer_fn(move |service: S| {
+            node.layer(BoxCloneService::new(MapErr::new(
+                service,
+                ResilienceError::Inner as fn(E) -> ResilienceError<E>,
+            )))
+        });
+        Ok(Policy(BoxLayer::new(lift)))
+    }
+
+    fn compile<Req, Resp, E>(&self) -> Result<Node<Req, Resp, E>, PolicySpecError>
+    where
+        Req: Send + Clone + 'static,
+        Resp: Send + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Ok(match self {
+            PolicySpec::Timeout { timeout_ms } => flattening(
+                TimeoutLayer::new(Duration::from_millis(*timeout_ms))
+                    .map_err(PolicySpecError::Timeout)?,
+            ),
+            PolicySpec::Retry { max_attempts, backoff, jitter } => flattening(
+                RetryLayer::new(
+                    *max_attempts,
+                    backoff.build()?,
+                    jitter.build()?,
+                    Arc::new(|_: &ResilienceError<E>| true),
+                    Arc::new(TokioSleeper),
+                )
+                .map_err(PolicySpecError::Retry)?,
+            ),
+            PolicySpec::CircuitBreaker {
+                failure_threshold,
+                recovery_timeout_ms,
+                half_open_max_calls,
+            } => {
+                let config = CircuitBreakerConfig::new(
+                    *failure_threshold,
+                    Duration::from_millis(*recovery_timeout_ms),
+                    *half_open_max_calls,
+                )
+                .map_err(PolicySpecError::CircuitBreaker)?;
+                flattening(
+                    CircuitBreakerLayer::new(config).map_err(PolicySpecError::CircuitBreaker)?,
+                )
+            }
+            PolicySpec::Bulkhead { max_concurrent } => {
+                flattening(BulkheadLayer::new(*max_concurrent).map_err(PolicySpecError::Bulkhead)?)
+            }
+            PolicySpec::Sequence { policies } => {
+                let mut nodes = compile_all::<Req, Resp, E>(policies)?.into_iter().rev();
+                let innermost = nodes.next().ok_or(empty())?;
+                nodes.fold(innermost, |inner, outer| BoxLayer::new(CombinedLayer { outer, inner }))
+            }
+            PolicySpec::Fallback { policies } => BoxLayer::new(
+                FallbackChainLayer::new(compile_all::<Req, Resp, E>(policies)?)
+                    .map_err(PolicySpecError::Composition)?,
+            ),
+            PolicySpec::ForkJoin { policies } => {
+                let mut nodes = compile_all::<Req, Resp, E>(policies)?.into_iter();
+                let first = nodes.next().ok_or(empty())?;
+                nodes.fold(first, |left, right| {
+                    let fork = (Policy(left) & Policy(right)).0;
+                    BoxLayer::new(layer_fn(move |service: Stage<Req, Resp, E>| {
+                        MapErr::new(fork.layer(service), ResilienceError::from)
+                    }))
+                })
+            }
+            PolicySpec::Named { name, policy } => {
+                BoxLayer::new(NamedLayer::new(name.as_str(), policy.compile::<Req, Resp, E>()?))
+            }
+        })
+    }
+}
+
+fn compile_all<Req, Resp, E>(
+    specs: &[PolicySpec],
+) -> Result<Vec<Node<Req, Resp, E>>, PolicySpecError>
+where
+    Req: Send + Clone + 'static,
+    Resp: Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    specs.iter().map(PolicySpec::compile::<Req, Resp, E>).collect()
+}
+
+fn empty() -> PolicySpecError {
+    PolicySpecError::Composition(CompositionError::Empty)
+}
+
+/// Box a leaf layer, collapsing the extra `ResilienceError` level it adds.
+fn flattening<L, Req, Resp, E>(layer: L) -> Node<Req, Resp, E>
+where
+    L: Layer<Stage<Req, Resp, E>> + Send + Sync + 'static,
+    L::Service: Service<Req, Response = Resp, Error = ResilienceError<ResilienceError<E>>>
+        + Clone
+        + Send
+        + 'static,
+    <L::Service as Service<Req>>::Future: Send + 'static,
+    Req: 'static,
+    Resp: 'static,
+    E: 'static,
+{
+    BoxLayer::new(layer_fn(move |service| {
+        MapErr::new(
+            layer.layer(service),
+            ResilienceError::flatten as fn(ResilienceError<ResilienceError<E>>) -> _,
+        )
+    }))
+}
+
+impl BackoffSpec {
+    fn build(&self) -> Result<Backoff, PolicySpecError> {
+        let ms = Duration::from_millis;
+        Ok(match *self {
+            BackoffSpec::Constant { delay_ms } => Backoff::constant(ms(delay_ms)).into(),
+            BackoffSpec::Linear { base_ms, max_ms: None } => Backoff::linear(ms(base_ms)).into(),
+            BackoffSpec::Linear { base_ms, max_ms: Some(max) } => Backoff::linear(ms(base_ms))
+                .with_max(ms(max))
+                .map_err(PolicySpecError::Backoff)?
+                .into(),
+            BackoffSpec::Exponential { base_ms, max_ms: None } => {
+                Backoff::exponential(ms(base_ms)).into()
+            }
+            BackoffSpec::Exponential { base_ms, max_ms: Some(max) } => {
+                Backoff::exponential(ms(base_ms))
+                    .with_max(ms(max))
+                    .map_err(PolicySpecError::Backoff)?
+                    .into()
+            }
+        })
+    }
+}
+
+impl JitterSpec {
+    fn build(&self) -> Result<Jitter, PolicySpecError> {
+        Ok(match *self {
+            JitterSpec::None => Jitter::None,
+            JitterSpec::Full => Jitter::full(),
+            JitterSpec::Equal => Jitter::equal(),
+            JitterSpec::Decorrelated { base_ms, max_ms } => {
+                Jitter::decorrelated(Duration::from_millis(base_ms), Duration::from_millis(max_ms))
+                    .map_err(PolicySpecError::Jitter)?
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    fn flaky(failures: usize) -> (BoxCloneService<(), &'static str, TestError>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let svc = BoxCloneService::new(tower::service_fn(move |_: ()| {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if n < failures {
+                    Err(TestError(format!("fail {}", n)))
+                } else {
+                    Ok("ok")
+                }
+            }
+        }));
+        (svc, calls)
+    }
+
+    fn retry(max_attempts: usize) -> PolicySpec {
+        PolicySpec::Retry {
+            max_attempts,
+            backoff: BackoffSpec::Constant { delay_ms: 1 },
+            jitter: JitterSpec::None,
+        }
+    }
+
+    #[tokio::test]
+    async fn compiled_sequence_retries_inner_failures() {
+        let spec = PolicySpec::Sequence {
+            policies: vec![PolicySpec::Timeout { timeout_ms: 1_000 }, retry(3)],
+        };
+        let (inner, calls) = flaky(2);
+        let mut svc = spec.build().unwrap().layer(inner);
+
+        assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn compiled_errors_are_flattened() {
+        let spec = PolicySpec::Named { name: "db".into(), policy: Box::new(retry(2)) };
+        let (inner, _) = flaky(usize::MAX);
+        let mut svc = spec.build().unwrap().layer(inner);
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert_eq!(err.retry_exhausted_info(), Some((2, 2)));
+        assert_eq!(err.failures().unwrap()[0].0, "fail 0");
+    }
+
+    #[tokio::test]
+    async fn fallback_and_fork_join_compile() {
+        for kind in ["fallback", "fork_join"] {
+            let policies = vec![PolicySpec::Bulkhead { max_concurrent: 1 }, retry(2)];
+            let spec = if kind == "fallback" {
+                PolicySpec::Fallback { policies }
+            } else {
+                PolicySpec::ForkJoin { policies }
+            };
+            let (inner, _) = flaky(1);
+            let mut svc = spec.build().unwrap().layer(inner);
+            assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), "ok", "{}", kind);
+        }
+    }
+
+    #[test]
+    fn invalid_specs_are_rejected() {
+        let build = |spec: PolicySpec| {
+            spec.build::<BoxCloneService<(), (), TestError>, (), (), TestError>()
+                .map(|_| ())
+                .unwrap_err()
+        };
+        assert_eq!(
+            build(PolicySpec::Timeout { timeout_ms: 0 }),
+            PolicySpecError::Timeout(TimeoutError::ZeroDuration)
+        );
+        assert_eq!(
+            build(PolicySpec::Fallback { policies: vec![] }),
+            PolicySpecError::Composition(CompositionError::Empty)
+        );
+        assert_eq!(build(retry(0)), PolicySpecError::Retry(BuildError::InvalidMaxAttempts(0)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializes_from_json() {
+        let json = r#"{ "kind": "fallback", "policies": [
+            { "kind": "timeout", "timeout_ms": 100 },
+            { "kind": "sequence", "policies": [
+                { "kind": "timeout", "timeout_ms": 2000 },
+                { "kind": "retry", "max_attempts": 3,
+                  "backoff": { "kind": "exponential", "base_ms": 50, "max_ms": 1000 } },
+                { "kind": "bulkhead", "max_concurrent": 32 } ] } ] }"#;
+        let spec: PolicySpec = serde_json::from_str(json).unwrap();
+        let PolicySpec::Fallback { policies } = &spec else { panic!("expected fallback") };
+        assert_eq!(policies[0], PolicySpec::Timeout { timeout_ms: 100 });
+        assert_eq!(
+            serde_json::from_str::<PolicySpec>(&serde_json::to_string(&spec).unwrap()).unwrap(),
+            spec
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Overflow routing from a primary service to a secondary one.
+//!
+//! Semantics
+//! - Requests go to the wrapped (primary) service while it has fewer than `max_in_flight`
+//!   requests running and, if [`SpilloverLayer::max_rate`] is set, while the current rate window
+//!   has room. Anything beyond that is sent to the secondary service instead of being rejected.
+//! - Unlike fallback (`Policy(A) | Policy(B)`), spillover decides before the call and never
+//!   reacts to errors: a failing primary keeps receiving traffic, and secondary errors are
+//!   returned as-is.
+//! - Each spilled request emits [`SpilloverEvent::Spilled`] saying which threshold was hit.
+//!
+//! Invariants
+//! - Services built from the same layer share the in-flight count and the rate window.
+//! - Spilled requests do not count against the primary's thresholds.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let replica = tower::service_fn(|_: u32| async { Ok::<_, std::io::Error>("replica") });
+//! let spill = SpilloverLayer::new(replica, 64)?.max_rate(500, Duration::from_secs(1))?;
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(spill)
+//!     .service_fn(|_: u32| async { Ok::<_, std::io::Error>("primary") });
+//! assert_eq!(svc.ready().await?.call(7).await?, "primary");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, SpillReason, SpilloverEvent};
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::ServiceExt;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Errors produced while configuring a [`SpilloverLayer`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpilloverError {
+    /// `max_in_flight` was zero, so every request would spill.
+    ZeroConcurrency,
+    /// The rate limit was zero, so every request would spill.
+    ZeroRate,
+    /// The rate window was zero.
+    ZeroWindow,
+}
+
+impl fmt::Display for SpilloverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroConcurrency => write!(f, "spillover needs max_in_flight > 0"),
+            Self::ZeroRate => write!(f, "spillover rate limit must be > 0"),
+            Self::ZeroWindow => write!(f, "spillover rate window must be > 0"),
+        }
+    }
+}
+
+impl std::error::Error for SpilloverError {}
+
+/// Layer sending traffic beyond the primary's thresholds to a secondary service.
+#[derive(Clone)]
+pub struct SpilloverLayer<T, Sink = NullSink> {
+    secondary: T,
+    max_in_flight: usize,
+    rate: Option<(usize, Duration)>,
+    state: Arc<Mutex<State>>,
+    sink: Sink,
+}
+
+impl<T> SpilloverLayer<T, NullSink> {
+    /// Spill to `secondary` once the primary has `max_in_flight` requests running.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpilloverError::ZeroConcurrency`] if `max_in_flight` is zero.
+    pub fn new(secondary: T, max_in_flight: usize) -> Result<Self, SpilloverError> {
+        if max_in_flight == 0 {
+            return Err(SpilloverError::ZeroConcurrency);
+        }
+        let state = State { in_flight: 0, window_start: Instant::now(), window_count: 0 };
+        Ok(Self {
+            secondary,
+            max_in_flight,
+            rate: None,
+            state: Arc::new(Mutex::new(state)),
+            sink: NullSink,
+        })
+    }
+}
+
+impl<T, Sink> SpilloverLayer<T, Sink> {
+    /// Also spill once the primary has been sent `limit` requests in the current `per` window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpilloverError::ZeroRate`] or [`SpilloverError::ZeroWindow`] for zero values.
+    pub fn max_rate(mut self, limit: usize, per: Duration) -> Result<Self, SpilloverError> {
+        if limit == 0 {
+            return Err(SpilloverError::ZeroRate);
+        }
+        if per.is_zero() {
+            return Err(SpilloverError::ZeroWindow);
+        }
+        self.rate = Some((limit, per));
+        Ok(self)
+    }
+
+    /// Attach a telemetry sink; every spilled request emits [`SpilloverEvent::Spilled`].
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> SpilloverLayer<T, NewSink>
+    where
+        NewSink: Clone,
+    {
+        SpilloverLayer {
+            secondary: self.secondary,
+            max_in_flight: self.max_in_flight,
+            rate: self.rate,
+            state: self.state,
+            sink,
+        }
+    }
+
+    /// Requests currently running on the primary.
+    pub fn in_flight(&self) -> usize {
+        lock(&self.state).in_flight
+    }
+}
+
+impl<T, Sink: fmt::Debug> fmt::Debug for SpilloverLayer<T, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpilloverLayer")
+            .field("max_in_flight", &self.max_in_flight)
+            .field("rate", &self.rate)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T: Clone, Sink: Clone> Layer<S> for SpilloverLayer<T, Sink> {
+    type Service = SpilloverService<S, T, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SpilloverService {
+            primary: inner,
+            secondary: self.secondary.clone(),
+            max_in_flight: self.max_in_flight,
+            rate: self.rate,
+            state: Arc::clone(&self.state),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<T, Sink> crate::Describe for SpilloverLayer<T, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        let label = match self.rate {
+            Some((limit, per)) => {
+                format!("Spillover({} in flight, {} per {:?})", self.max_in_flight, limit, per)
+            }
+            None => format!("Spillover({} in flight)", self.max_in_flight),
+        };
+        crate::PolicyNode::layer(label)
+    }
+}
+
+/// Service produced by [`SpilloverLayer`].
+#[derive(Clone)]
+pub struct SpilloverService<S, T, Sink = NullSink> {
+    primary: S,
+    secondary: T,
+    max_in_flight: usize,
+    rate: Option<(usize, Duration)>,
+    state: Arc<Mutex<State>>,
+    sink: Sink,
+}
+
+impl<S: fmt::Debug, T: fmt::Debug, Sink: fmt::Debug> fmt::Debug for SpilloverService<S, T, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpilloverService")
+            .field("primary", &self.primary)
+            .field("secondary", &self.secondary)
+            .field("max_in_flight", &self.max_in_flight)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T, Request, Sink> Service<Request> for SpilloverService<S, T, Sink>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    T: Service<Request, Response = S::Response, Error = S::Error> + Clone + Send + 'static,
+    T::Future: Send + 'static,
+    Request: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.primary.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let reason = {
+            let mut state = lock(&self.state);
+            let now = Instant::now();
+            let reason = if state.in_flight >= self.max_in_flight {
+                Some(SpillReason::Concurrency { in_flight: state.in_flight })
+            } else {
+                match self.rate {
+                    Some((limit, per)) => {
+                        if now.duration_since(state.window_start) >= per {
+                            state.window_start = now;
+                            state.window_count = 0;
+                        }
+                        (state.window_count >= limit)
+                            .then_some(SpillReason::Rate { requests: state.window_count })
+                    }
+                    None => None,
+                }
+            };
+            if reason.is_none() {
+                state.in_flight += 1;
+                state.window_count += 1;
+            }
+            reason
+        };
+
+        match reason {
+            None => {
+                let guard = InFlight(Arc::clone(&self.state));
+                let fut = self.primary.call(req);
+                Box::pin(async move {
+                    let result = fut.await;
+                    drop(guard);
+                    result
+                })
+            }
+            Some(reason) => {
+                let secondary = self.secondary.clone();
+                let sink = self.sink.clone();
+                Box::pin(async move {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::Spillover(SpilloverEvent::Spilled { reason }),
+                    )
+                    .await;
+                    secondary.oneshot(req).await
+                })
+            }
+        }
+    }
+}
+
+struct State {
+    in_flight: usize,
+    window_start: Instant,
+    window_count: usize,
+}
+
+fn lock(state: &Mutex<State>) -> std::sync::MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Releases a primary slot when the request finishes or is dropped.
+struct InFlight(Arc<Mutex<State>>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        lock(&self.0).in_flight -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    fn secondary(
+    ) -> impl Service<u32, Response = &'static str, Error = TestError, Future = impl Send + 'static>
+           + Clone {
+        tower::service_fn(|_: u32| async { Ok::<_, TestError>("secondary") })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn overflow_beyond_concurrency_goes_to_the_secondary() {
+        let sink = MemorySink::new();
+        let layer = SpilloverLayer::new(secondary(), 1).unwrap().with_sink(sink.clone());
+        let svc = layer.layer(tower::service_fn(|_: u32| async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok::<_, TestError>("primary")
+        }));
+
+        let slow = tokio::spawn(svc.clone().oneshot(1));
+        tokio::task::yield_now().await;
+        assert_eq!(layer.in_flight(), 1);
+        assert_eq!(svc.clone().oneshot(2).await.unwrap(), "secondary");
+        assert_eq!(slow.await.unwrap().unwrap(), "primary");
+        assert_eq!(layer.in_flight(), 0);
+        assert_eq!(svc.clone().oneshot(3).await.unwrap(), "primary");
+        assert_eq!(
+            sink.events(),
+            vec![PolicyEvent::Spillover(SpilloverEvent::Spilled {
+                reason: SpillReason::Concurrency { in_flight: 1 }
+            })]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_window_spills_until_it_rolls_over() {
+        let mut svc = SpilloverLayer::new(secondary(), 10)
+            .unwrap()
+            .max_rate(2, Duration::from_secs(1))
+            .unwrap()
+            .layer(tower::service_fn(|_: u32| async { Ok::<_, TestError>("primary") }));
+
+        let mut served = Vec::new();
+        for i in 0..3 {
+            served.push(svc.ready().await.unwrap().call(i).await.unwrap());
+        }
+        assert_eq!(served, ["primary", "primary", "secondary"]);
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(svc.ready().await.unwrap().call(4).await.unwrap(), "primary");
+    }
+
+    #[tokio::test]
+    async fn primary_errors_are_not_spilled() {
+        let mut svc =
+            SpilloverLayer::new(secondary(), 1).unwrap().layer(tower::service_fn(|_: u32| async {
+                Err::<&'static str, _>(TestError("primary down".into()))
+            }));
+        let err = svc.ready().await.unwrap().call(1).await.unwrap_err();
+        assert_eq!(err.0, "primary down");
+    }
+
+    #[test]
+    fn rejects_zero_thresholds() {
+        assert_eq!(SpilloverLayer::new((), 0).unwrap_err(), SpilloverError::ZeroConcurrency);
+        let layer = SpilloverLayer::new((), 1).unwrap();
+        assert_eq!(
+            layer.clone().max_rate(0, Duration::from_secs(1)).unwrap_err(),
+            SpilloverError::ZeroRate
+        );
+        assert_eq!(layer.max_rate(1, Duration::ZERO).unwrap_err(), SpilloverError::ZeroWindow);
+    }
+}
@@ -0,0 +1,503 @@
+//! Response cache with stale-while-revalidate and stale-if-error fallbacks.
+//!
+//! Semantics
+//! - Successful responses are cached per key (derived from the request by a closure) and served
+//!   without calling the inner service while younger than `ttl`.
+//! - Stale-while-revalidate: an entry past `ttl` but within the `stale_while_revalidate` window is
+//!   returned immediately while one background call per key refreshes it.
+//! - Stale-if-error: an entry past `ttl` but within the `stale_if_error` window backs up the inner
+//!   call. If that call fails, the stale value is returned instead of the error. With
+//!   [`StaleCacheLayer::stale_after`], a call that is merely slow is also answered from the stale
+//!   entry; it keeps running in the background and refreshes the cache when it completes.
+//! - Errors are never cached; a miss with no usable stale entry returns the inner error unchanged.
+//! - Every stale answer emits [`CacheEvent::ServedStale`] with the entry's age and the reason.
+//!
+//! Invariants
+//! - Services built from the same layer share one cache.
+//! - At most `max_entries` keys are kept; inserting beyond that evicts the oldest entry.
+//! - Background work uses `tokio::spawn`, so calls must be made inside a Tokio runtime.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let cache = StaleCacheLayer::new(Duration::from_secs(30), |user: &u64| *user)
+//!     .stale_while_revalidate(Duration::from_secs(60))
+//!     .stale_if_error(Duration::from_secs(3600))
+//!     .stale_after(Duration::from_millis(250));
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(cache)
+//!     .service_fn(|user: u64| async move { Ok::<_, std::io::Error>(format!("profile-{}", user)) });
+//! assert_eq!(svc.ready().await?.call(7).await?, "profile-7");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, CacheEvent, NullSink, PolicyEvent, StaleReason};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer caching responses and serving stale entries while refreshing or on failure.
+///
+/// `F` is the closure deriving a cache key of type `K` from each request.
+pub struct StaleCacheLayer<K, V, F, Sink = NullSink> {
+    key: Arc<F>,
+    config: Config,
+    store: Arc<Mutex<Store<K, V>>>,
+    sink: Sink,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    ttl: Duration,
+    stale_while_revalidate: Duration,
+    stale_if_error: Duration,
+    stale_after: Option<Duration>,
+    max_entries: usize,
+}
+
+impl<K, V, F> StaleCacheLayer<K, V, F, NullSink> {
+    /// Cache responses for `ttl`, keyed by `key(&request)`.
+    ///
+    /// Both stale windows start at zero, so until one is configured this is a plain TTL cache.
+    pub fn new<Request>(ttl: Duration, key: F) -> Self
+    where
+        F: Fn(&Request) -> K,
+    {
+        Self {
+            key: Arc::new(key),
+            config: Config {
+                ttl,
+                stale_while_revalidate: Duration::ZERO,
+                stale_if_error: Duration::ZERO,
+                stale_after: None,
+                max_entries: 1024,
+            },
+            store: Arc::new(Mutex::new(Store { entries: HashMap::new() })),
+            sink: NullSink,
+        }
+    }
+}
+
+impl<K, V, F, Sink> StaleCacheLayer<K, V, F, Sink> {
+    /// Serve entries up to `window` past their TTL immediately, refreshing them in the background.
+    pub fn stale_while_revalidate(mut self, window: Duration) -> Self {
+        self.config.stale_while_revalidate = window;
+        self
+    }
+
+    /// Fall back to entries up to `window` past their TTL when the inner call fails.
+    pub fn stale_if_error(mut self, window: Duration) -> Self {
+        self.config.stale_if_error = window;
+        self
+    }
+
+    /// Also fall back to a stale-if-error entry when the inner call takes longer than `patience`.
+    pub fn stale_after(mut self, patience: Duration) -> Self {
+        self.config.stale_after = Some(patience);
+        self
+    }
+
+    /// Keep at most `max_entries` keys (default 1024), evicting the oldest first.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.config.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Attach a telemetry sink to this cache.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> StaleCacheLayer<K, V, F, NewSink>
+    where
+        NewSink: Clone,
+    {
+        StaleCacheLayer { key: self.key, config: self.config, store: self.store, sink }
+    }
+}
+
+impl<K, V, F, Sink: Clone> Clone for StaleCacheLayer<K, V, F, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            key: Arc::clone(&self.key),
+            config: self.config,
+            store: Arc::clone(&self.store),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<K, V, F, Sink: fmt::Debug> fmt::Debug for StaleCacheLayer<K, V, F, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaleCacheLayer")
+            .field("config", &self.config)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, K, V, F, Sink: Clone> Layer<S> for StaleCacheLayer<K, V, F, Sink> {
+    type Service = StaleCacheService<S, K, V, F, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        StaleCacheService {
+            inner,
+            key: Arc::clone(&self.key),
+            config: self.config,
+            store: Arc::clone(&self.store),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<K, V, F, Sink> crate::Describe for StaleCacheLayer<K, V, F, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        let Config { ttl, stale_while_revalidate, stale_if_error, .. } = self.config;
+        crate::PolicyNode::layer(format!(
+            "StaleCache(ttl={:?}, swr={:?}, sie={:?})",
+            ttl, stale_while_revalidate, stale_if_error
+        ))
+    }
+}
+
+/// Service produced by [`StaleCacheLayer`].
+pub struct StaleCacheService<S, K, V, F, Sink = NullSink> {
+    inner: S,
+    key: Arc<F>,
+    config: Config,
+    store: Arc<Mutex<Store<K, V>>>,
+    sink: Sink,
+}
+
+impl<S: Clone, K, V, F, Sink: Clone> Clone for StaleCacheService<S, K, V, F, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            key: Arc::clone(&self.key),
+            config: self.config,
+            store: Arc::clone(&self.store),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<S, K, V, F, Sink> fmt::Debug for StaleCacheService<S, K, V, F, Sink>
+where
+    S: fmt::Debug,
+    Sink: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaleCacheService")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, Request, K, V, F, Sink> Service<Request> for StaleCacheService<S, K, V, F, Sink>
+where
+    S: Service<Request, Response = V>,
+    F: Fn(&Request) -> K,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    K: Eq + Hash + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = V;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<V, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let key = (self.key)(&req);
+        let store = Arc::clone(&self.store);
+        let sink = self.sink.clone();
+        let config = self.config;
+
+        let lookup = store.lock().unwrap_or_else(PoisonError::into_inner).lookup(&key, &config);
+        match lookup {
+            Lookup::Fresh(value) => Box::pin(async move { Ok(value) }),
+            Lookup::Revalidate { value, age, start_refresh } => {
+                if start_refresh {
+                    let refresh = refreshing(self.inner.call(req), store, key, config);
+                    tokio::spawn(refresh);
+                }
+                Box::pin(async move {
+                    emit_stale(sink, age, StaleReason::Revalidating).await;
+                    Ok(value)
+                })
+            }
+            Lookup::Miss { fallback } => {
+                let mut fut = Box::pin(refreshing(self.inner.call(req), store, key, config));
+                Box::pin(async move {
+                    let Some((value, age)) = fallback else {
+                        return fut.await;
+                    };
+                    let result = match config.stale_after {
+                        Some(patience) => match tokio::time::timeout(patience, &mut fut).await {
+                            Ok(result) => result,
+                            Err(_) => {
+                                tokio::spawn(fut);
+                                emit_stale(sink, age, StaleReason::Slow).await;
+                                return Ok(value);
+                            }
+                        },
+                        None => fut.await,
+                    };
+                    match result {
+                        Ok(fresh) => Ok(fresh),
+                        Err(_) => {
+                            emit_stale(sink, age, StaleReason::Error).await;
+                            Ok(value)
+                        }
+                    }
+                })
+            }
+        }
+    }
+}
+
+/// Run `fut`, storing a successful response and clearing the key's refresh flag either way.
+async fn refreshing<K, V, E, F>(
+    fut: F,
+    store: Arc<Mutex<Store<K, V>>>,
+    key: K,
+    config: Config,
+) -> Result<V, E>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: std::future::Future<Output = Result<V, E>>,
+{
+    let result = fut.await;
+    let mut guard = store.lock().unwrap_or_else(PoisonError::into_inner);
+    match &result {
+        Ok(value) => guard.insert(key, value.clone(), config.max_entries),
+        Err(_) => {
+            if let Some(entry) = guard.entries.get_mut(&key) {
+                entry.refreshing = false;
+            }
+        }
+    }
+    result
+}
+
+async fn emit_stale<Sink>(sink: Sink, age: Duration, reason: StaleReason)
+where
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    emit_best_effort(sink, PolicyEvent::Cache(CacheEvent::ServedStale { age, reason })).await;
+}
+
+struct Store<K, V> {
+    entries: HashMap<K, Entry<V>>,
+}
+
+struct Entry<V> {
+    value: V,
+    stored: Instant,
+    refreshing: bool,
+}
+
+enum Lookup<V> {
+    Fresh(V),
+    Revalidate { value: V, age: Duration, start_refresh: bool },
+    Miss { fallback: Option<(V, Duration)> },
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Store<K, V> {
+    fn lookup(&mut self, key: &K, config: &Config) -> Lookup<V> {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return Lookup::Miss { fallback: None };
+        };
+        let age = entry.stored.elapsed();
+        if age < config.ttl {
+            return Lookup::Fresh(entry.value.clone());
+        }
+        let staleness = age - config.ttl;
+        if staleness < config.stale_while_revalidate {
+            let start_refresh = !std::mem::replace(&mut entry.refreshing, true);
+            return Lookup::Revalidate { value: entry.value.clone(), age, start_refresh };
+        }
+        if staleness < config.stale_if_error {
+            return Lookup::Miss { fallback: Some((entry.value.clone(), age)) };
+        }
+        if staleness >= config.stale_while_revalidate.max(config.stale_if_error) {
+            self.entries.remove(key);
+        }
+        Lookup::Miss { fallback: None }
+    }
+
+    fn insert(&mut self, key: K, value: V, max_entries: usize) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= max_entries {
+            let oldest = self.entries.iter().min_by_key(|(_, e)| e.stored).map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, Entry { value, stored: Instant::now(), refreshing: false });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Backend returning `v<call number>`, failing or stalling on demand.
+    #[derive(Clone, Default)]
+    struct Backend {
+        calls: Arc<AtomicUsize>,
+        failing: Arc<AtomicBool>,
+        stall: Arc<AtomicBool>,
+    }
+
+    impl Service<&'static str> for Backend {
+        type Response = String;
+        type Error = TestError;
+        type Future = BoxFuture<'static, Result<String, TestError>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), TestError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: &'static str) -> Self::Future {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let (failing, stall) =
+                (self.failing.load(Ordering::SeqCst), self.stall.load(Ordering::SeqCst));
+            Box::pin(async move {
+                if stall {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+                if failing {
+                    Err(TestError("backend down".into()))
+                } else {
+                    Ok(format!("v{}", n))
+                }
+            })
+        }
+    }
+
+    type KeyFn = fn(&&'static str) -> &'static str;
+
+    fn layer() -> StaleCacheLayer<&'static str, String, KeyFn> {
+        StaleCacheLayer::new(Duration::from_secs(10), |k: &&'static str| *k)
+    }
+
+    async fn get(
+        svc: &mut StaleCacheService<Backend, &'static str, String, KeyFn, MemorySink>,
+    ) -> String {
+        svc.ready().await.unwrap().call("k").await.unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn serves_fresh_entries_without_calling_inner() {
+        let backend = Backend::default();
+        let mut svc = layer().with_sink(MemorySink::new()).layer(backend.clone());
+
+        assert_eq!(get(&mut svc).await, "v1");
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(get(&mut svc).await, "v1");
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stale_while_revalidate_refreshes_in_background() {
+        let backend = Backend::default();
+        let sink = MemorySink::new();
+        let mut svc = layer()
+            .stale_while_revalidate(Duration::from_secs(30))
+            .with_sink(sink.clone())
+            .layer(backend.clone());
+
+        assert_eq!(get(&mut svc).await, "v1");
+        tokio::time::advance(Duration::from_secs(15)).await;
+        assert_eq!(get(&mut svc).await, "v1", "stale value served immediately");
+        assert_eq!(get(&mut svc).await, "v1", "only one refresh in flight");
+        tokio::task::yield_now().await;
+
+        assert_eq!(get(&mut svc).await, "v2");
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 2);
+        assert!(matches!(
+            sink.events()[0],
+            PolicyEvent::Cache(CacheEvent::ServedStale { reason: StaleReason::Revalidating, .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stale_if_error_masks_failures_within_the_window() {
+        let backend = Backend::default();
+        let sink = MemorySink::new();
+        let mut svc = layer()
+            .stale_if_error(Duration::from_secs(60))
+            .with_sink(sink.clone())
+            .layer(backend.clone());
+
+        assert_eq!(get(&mut svc).await, "v1");
+        backend.failing.store(true, Ordering::SeqCst);
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(get(&mut svc).await, "v1");
+        match &sink.events()[0] {
+            PolicyEvent::Cache(CacheEvent::ServedStale { age, reason }) => {
+                assert_eq!(*reason, StaleReason::Error);
+                assert!(*age >= Duration::from_secs(30));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        let err = svc.ready().await.unwrap().call("k").await.unwrap_err();
+        assert_eq!(err.0, "backend down", "beyond the window the error surfaces");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_calls_fall_back_and_refresh_later() {
+        let backend = Backend::default();
+        let mut svc = layer()
+            .stale_if_error(Duration::from_secs(60))
+            .stale_after(Duration::from_millis(100))
+            .with_sink(MemorySink::new())
+            .layer(backend.clone());
+
+        assert_eq!(get(&mut svc).await, "v1");
+        backend.stall.store(true, Ordering::SeqCst);
+        tokio::time::advance(Duration::from_secs(20)).await;
+
+        let start = Instant::now();
+        assert_eq!(get(&mut svc).await, "v1");
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        assert_eq!(get(&mut svc).await, "v2", "the slow call refreshed the cache");
+    }
+}
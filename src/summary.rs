@@ -0,0 +1,282 @@
+//! Periodic heartbeat summaries of the telemetry stream.
+//!
+//! A healthy service with no traffic and a service whose telemetry pipeline is broken look the
+//! same to a backend: no events. [`SummaryReporter`] wraps a sink and emits a
+//! [`SummaryEvent::Heartbeat`] into it on a fixed interval, with counts that are zero when
+//! nothing happened, so silence becomes a signal.
+//!
+//! Semantics
+//! - Every event is forwarded unchanged and tallied: [`RequestOutcome`]s count as requests
+//!   (failures also as failures), [`RetryEvent::Attempt`]s as retries, and refusals by a
+//!   bulkhead, rate limiter, load shedder, adaptive concurrency limit, or priority queue as
+//!   rejections.
+//! - Each heartbeat reports the counts since the previous one, then resets them.
+//! - The first heartbeat fires one interval after construction. Heartbeats are delivered from a
+//!   background task; with [`named`](SummaryReporter::named) they run under that policy name, so
+//!   attribution-aware sinks tell stacks apart.
+//!
+//! Invariants
+//! - Clones share one tally and one background task.
+//! - The task stops after the last clone is dropped.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let sink = SummaryReporter::new(LogSink, Duration::from_secs(60))?.named("checkout");
+//! let retry = RetryPolicy::<std::io::Error>::builder().build()?.into_layer().with_sink(sink);
+//! # let _ = retry;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{
+    BulkheadEvent, ConcurrencyEvent, LoadShedEvent, PolicyEvent, PriorityEvent, RateLimitEvent,
+    RequestOutcome, RetryEvent, SummaryEvent, TelemetrySink,
+};
+use crate::RequestContext;
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::ServiceExt;
+use tower_service::Service;
+
+/// Errors produced while configuring a [`SummaryReporter`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryError {
+    /// The heartbeat interval was zero.
+    ZeroInterval,
+}
+
+impl fmt::Display for SummaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroInterval => write!(f, "heartbeat interval must be > 0"),
+        }
+    }
+}
+
+impl std::error::Error for SummaryError {}
+
+/// Telemetry sink wrapper that emits a heartbeat summary every interval.
+pub struct SummaryReporter<S> {
+    inner: S,
+    tally: Arc<Mutex<Tally>>,
+}
+
+#[derive(Default)]
+struct Tally {
+    name: Option<Arc<str>>,
+    requests: u64,
+    failures: u64,
+    retries: u64,
+    rejections: u64,
+}
+
+impl Tally {
+    fn observe(&mut self, event: &PolicyEvent) {
+        match event {
+            PolicyEvent::Request(outcome) => {
+                self.requests += 1;
+                if matches!(outcome, RequestOutcome::Failure { .. }) {
+                    self.failures += 1;
+                }
+            }
+            PolicyEvent::Retry(RetryEvent::Attempt { .. }) => self.retries += 1,
+            PolicyEvent::Bulkhead(BulkheadEvent::Rejected { .. } | BulkheadEvent::Closed)
+            | PolicyEvent::RateLimit(RateLimitEvent::Rejected { .. })
+            | PolicyEvent::LoadShed(LoadShedEvent::Shed { .. })
+            | PolicyEvent::Concurrency(ConcurrencyEvent::Rejected { .. })
+            | PolicyEvent::Priority(PriorityEvent::Shed { .. }) => self.rejections += 1,
+            _ => {}
+        }
+    }
+
+    fn take(&mut self, interval: Duration) -> SummaryEvent {
+        let summary = SummaryEvent::Heartbeat {
+            interval,
+            requests: self.requests,
+            failures: self.failures,
+            retries: self.retries,
+            rejections: self.rejections,
+        };
+        *self = Tally { name: self.name.take(), ..Tally::default() };
+        summary
+    }
+}
+
+impl<S> SummaryReporter<S>
+where
+    S: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    /// Forward events to `inner` and emit a heartbeat into it every `interval`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SummaryError::ZeroInterval`] if `interval` is zero.
+    pub fn new(inner: S, interval: Duration) -> Result<Self, SummaryError> {
+        if interval.is_zero() {
+            return Err(SummaryError::ZeroInterval);
+        }
+        let tally = Arc::new(Mutex::new(Tally::default()));
+        tokio::spawn(report(inner.clone(), Arc::downgrade(&tally), interval));
+        Ok(Self { inner, tally })
+    }
+}
+
+impl<S> SummaryReporter<S> {
+    /// Attribute heartbeats to `name`, as if emitted inside `Policy::named(name)`.
+    pub fn named(self, name: impl Into<Arc<str>>) -> Self {
+        self.lock().name = Some(name.into());
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Tally> {
+        self.tally.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+async fn report<S>(sink: S, tally: Weak<Mutex<Tally>>, interval: Duration)
+where
+    S: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        let Some(tally) = tally.upgrade() else { return };
+        let (name, summary) = {
+            let mut tally = tally.lock().unwrap_or_else(PoisonError::into_inner);
+            (tally.name.clone(), tally.take(interval))
+        };
+        drop(tally);
+        let ctx = match name {
+            Some(name) => RequestContext::current().with_policy_name(name),
+            None => RequestContext::current(),
+        };
+        let _ = ctx.scope(sink.clone().oneshot(PolicyEvent::Summary(summary))).await;
+    }
+}
+
+impl<S: Clone> Clone for SummaryReporter<S> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), tally: Arc::clone(&self.tally) }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for SummaryReporter<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SummaryReporter").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<PolicyEvent> for SummaryReporter<S>
+where
+    S: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<(), S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        self.lock().observe(&event);
+        Box::pin(self.inner.clone().oneshot(event))
+    }
+}
+
+impl<S> TelemetrySink for SummaryReporter<S>
+where
+    S: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type SinkError = S::Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{BulkheadRejectReason, MemorySink};
+
+    fn heartbeats(sink: &MemorySink) -> Vec<SummaryEvent> {
+        sink.events()
+            .into_iter()
+            .filter_map(|event| match event {
+                PolicyEvent::Summary(summary) => Some(summary),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rejects_zero_interval() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let _guard = rt.enter();
+        assert_eq!(
+            SummaryReporter::new(MemorySink::new(), Duration::ZERO).unwrap_err(),
+            SummaryError::ZeroInterval
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_counts_per_interval_until_dropped() {
+        let sink = MemorySink::new();
+        let reporter = SummaryReporter::new(sink.clone(), Duration::from_secs(10)).unwrap();
+        let ms = Duration::from_millis;
+        for event in [
+            PolicyEvent::Retry(RetryEvent::Attempt { attempt: 1, delay: ms(5) }),
+            PolicyEvent::Request(RequestOutcome::Failure { duration: ms(20) }),
+            PolicyEvent::Request(RequestOutcome::Success { duration: ms(10) }),
+            PolicyEvent::Bulkhead(BulkheadEvent::Rejected {
+                active_count: 4,
+                max_concurrency: 4,
+                reason: BulkheadRejectReason::Saturated,
+            }),
+        ] {
+            reporter.clone().oneshot(event).await.unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_secs(21)).await;
+        let interval = Duration::from_secs(10);
+        assert_eq!(
+            heartbeats(&sink),
+            vec![
+                SummaryEvent::Heartbeat {
+                    interval,
+                    requests: 2,
+                    failures: 1,
+                    retries: 1,
+                    rejections: 1
+                },
+                SummaryEvent::Heartbeat {
+                    interval,
+                    requests: 0,
+                    failures: 0,
+                    retries: 0,
+                    rejections: 0
+                },
+            ]
+        );
+
+        drop(reporter);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert_eq!(heartbeats(&sink).len(), 2);
+    }
+}
@@ -6,13 +6,26 @@
 //!
 //! # Event Types
 //!
-//! Each policy type emits specific events:
+//! Each policy type emits its own family of [`PolicyEvent`] variants:
 //!
-//! - **Retry**: `RetryAttempt`, `RetryExhausted`
-//! - **Circuit Breaker**: `CircuitOpened`, `CircuitClosed`, `CircuitHalfOpen`
-//! - **Bulkhead**: `BulkheadAcquired`, `BulkheadRejected`
-//! - **Timeout**: `TimeoutOccurred`
-//! - **All policies**: `RequestSuccess`, `RequestFailure`
+//! - **Retry** ([`RetryEvent`]): `Attempt`, `Exhausted`
+//! - **Circuit breaker** ([`CircuitBreakerEvent`]): `Opened`, `HalfOpen`, `Closed`
+//! - **Bulkhead** ([`BulkheadEvent`]): `Acquired`, `Released`, `Rejected`, `Closed`
+//! - **Timeout** ([`TimeoutEvent`]): `Approaching`, `Occurred`
+//! - **Fallback and fork-join** ([`FallbackEvent`], [`ForkJoinEvent`]): `Served` / `Won`,
+//!   `Exhausted`
+//! - **Hedge** ([`HedgeEvent`]): `Launched`, `Won`, `Exhausted`
+//! - **Admission control**: [`RateLimitEvent`] (`Delayed`, `Rejected`), [`LoadShedEvent`]
+//!   (`Shed`), [`ConcurrencyEvent`] (`LimitChanged`, `Rejected`), [`PriorityEvent`] (`Queued`,
+//!   `Shed`), [`SpilloverEvent`] (`Spilled`)
+//! - **Request shaping**: [`CacheEvent`] (`ServedStale`), [`CoalesceEvent`] (`Joined`),
+//!   [`ThrottleEvent`] (`Delayed`, `Collapsed`), [`IdempotencyEvent`] (`Replayed`)
+//! - **Watchdog** ([`WatchdogEvent`]): `Stuck`, `Released`
+//! - **Derived**: [`AlertEvent`] (`RetryStorm`, `BreakerFlapping`, `Resolved`) and
+//!   [`SummaryEvent`] (`Heartbeat`)
+//! - **All policies** ([`RequestOutcome`]): `Success`, `Failure`
+//!
+//! [`PolicyEvent::event_name`] and [`PolicyEvent::severity`] cover every variant.
 //!
 //! # Telemetry Sinks
 //!
@@ -33,7 +46,37 @@
 //!     duration: Duration::from_millis(50),
 //! });
 //! ```
+//!
+//! # Serialized Form
+//!
+//! With the `serde` feature every event implements `Serialize` and `Deserialize` in a flat,
+//! stable JSON shape that sinks can ship as-is (see `event_to_json`):
+//!
+//! - `schema_version` leads every object written by `event_to_json` or an [`EventEnvelope`],
+//!   carrying [`EVENT_SCHEMA_VERSION`].
+//! - `policy` names the emitting policy (`retry`, `circuit_breaker`, `bulkhead`, ...) and
+//!   `event` the event within it, both in snake_case.
+//! - Event fields follow under their Rust names. Durations are fractional milliseconds in
+//!   fields suffixed `_ms` (`delay` becomes `delay_ms`).
+//! - Nested enums are snake_case strings, except [`SpillReason`], which is tagged by `kind`.
+//!
+//! ```text
+//! {"schema_version":1,"policy":"retry","event":"attempt","attempt":1,"delay_ms":100.0}
+//! {"schema_version":1,"policy":"circuit_breaker","event":"half_open","open_duration_ms":30000.0}
+//! {"schema_version":1,"policy":"bulkhead","event":"rejected","active_count":8,"max_concurrency":8,"reason":"saturated"}
+//! ```
+//!
+//! The format is versioned by [`EVENT_SCHEMA_VERSION`]; renaming or removing a field or tag
+//! bumps it, while new policies, events, and fields do not. With the `schema` feature,
+//! `event_json_schema` describes the whole format as a JSON Schema document, for consumers that
+//! validate events or derive index mappings from it.
+//!
+//! # Attribution
+//!
+//! Events do not name the stack that emitted them. Wrap policies with `Policy::named` and capture
+//! an [`EventEnvelope`] inside the sink to record the policy name, instance id, and layer kind.
 
+use crate::priority::Priority;
 use std::fmt;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -82,6 +125,11 @@ pub trait TelemetrySink:
 ///
 /// We keep telemetry non-blocking for policy hot paths: if a sink is not ready
 /// or returns an error, we simply drop the event.
+///
+/// With [`NullSink`] (every layer's default) this returns without polling or calling the sink.
+/// The check runs inside the returned future, so the call site still clones the sink, builds
+/// the event, and awaits a future that finishes on its first poll; policies without a sink pay
+/// that small, fixed cost on each emit. `cargo bench --bench telemetry_overhead` measures it.
 pub async fn emit_best_effort<S>(sink: S, event: PolicyEvent)
 where
     S: tower::Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
@@ -90,17 +138,39 @@ where
 {
     use tower::ServiceExt;
 
+    if std::any::TypeId::of::<S>() == std::any::TypeId::of::<NullSink>() {
+        return;
+    }
     if let Ok(mut ready_sink) = sink.ready_oneshot().await {
         let _ = ready_sink.call(event).await;
     }
 }
 
+/// [`emit_best_effort`] for code that cannot await, such as `Drop`: the emit runs as a task on
+/// the current Tokio runtime. Outside a runtime the event is dropped.
+pub(crate) fn emit_detached<S>(sink: S, event: PolicyEvent)
+where
+    S: tower::Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    if std::any::TypeId::of::<S>() == std::any::TypeId::of::<NullSink>() {
+        return;
+    }
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(emit_best_effort(sink, event));
+    }
+}
+
 /// Policy events emitted during execution.
 ///
 /// All Nine Lives policies emit structured events that describe their behavior.
 /// These events can be collected, aggregated, and used for observability,
 /// monitoring, or autonomous control.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "policy", rename_all = "snake_case"))]
 pub enum PolicyEvent {
     /// Retry policy events
     Retry(RetryEvent),
@@ -110,12 +180,199 @@ pub enum PolicyEvent {
     Bulkhead(BulkheadEvent),
     /// Timeout events
     Timeout(TimeoutEvent),
+    /// Fallback combinator events
+    Fallback(FallbackEvent),
+    /// Fork-join (`&`) events
+    ForkJoin(ForkJoinEvent),
+    /// Hedged request events
+    Hedge(HedgeEvent),
+    /// Rate limiter events
+    RateLimit(RateLimitEvent),
+    /// Load shedder events
+    LoadShed(LoadShedEvent),
+    /// Adaptive concurrency limiter events
+    Concurrency(ConcurrencyEvent),
+    /// Response cache events
+    Cache(CacheEvent),
+    /// Request coalescing events
+    Coalesce(CoalesceEvent),
+    /// Throttle and debounce events
+    Throttle(ThrottleEvent),
+    /// Idempotency-key deduplication events
+    Idempotency(IdempotencyEvent),
+    /// Priority admission events
+    Priority(PriorityEvent),
+    /// Spillover routing events
+    Spillover(SpilloverEvent),
+    /// Stuck-request watchdog events
+    Watchdog(WatchdogEvent),
+    /// Synthetic alerts derived from other events (see [`StormDetector`](crate::StormDetector))
+    Alert(AlertEvent),
+    /// Periodic roll-ups (see [`SummaryReporter`](crate::SummaryReporter))
+    Summary(SummaryEvent),
     /// Request outcome events (emitted by all policies)
     Request(RequestOutcome),
 }
 
+impl PolicyEvent {
+    /// Kind of layer that emitted the event, matching the `policy` tag of the serialized form.
+    pub fn layer_kind(&self) -> &'static str {
+        match self {
+            PolicyEvent::Retry(_) => "retry",
+            PolicyEvent::CircuitBreaker(_) => "circuit_breaker",
+            PolicyEvent::Bulkhead(_) => "bulkhead",
+            PolicyEvent::Timeout(_) => "timeout",
+            PolicyEvent::Fallback(_) => "fallback",
+            PolicyEvent::ForkJoin(_) => "fork_join",
+            PolicyEvent::Hedge(_) => "hedge",
+            PolicyEvent::RateLimit(_) => "rate_limit",
+            PolicyEvent::LoadShed(_) => "load_shed",
+            PolicyEvent::Concurrency(_) => "concurrency",
+            PolicyEvent::Cache(_) => "cache",
+            PolicyEvent::Coalesce(_) => "coalesce",
+            PolicyEvent::Throttle(_) => "throttle",
+            PolicyEvent::Idempotency(_) => "idempotency",
+            PolicyEvent::Priority(_) => "priority",
+            PolicyEvent::Spillover(_) => "spillover",
+            PolicyEvent::Watchdog(_) => "watchdog",
+            PolicyEvent::Alert(_) => "alert",
+            PolicyEvent::Summary(_) => "summary",
+            PolicyEvent::Request(_) => "request",
+        }
+    }
+
+    /// Name of the event within its layer, matching the `event` tag of the serialized form.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            PolicyEvent::Retry(RetryEvent::Attempt { .. }) => "attempt",
+            PolicyEvent::Retry(RetryEvent::Exhausted { .. }) => "exhausted",
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { .. }) => "opened",
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::HalfOpen { .. }) => "half_open",
+            PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Closed { .. }) => "closed",
+            PolicyEvent::Bulkhead(BulkheadEvent::Acquired { .. }) => "acquired",
+            PolicyEvent::Bulkhead(BulkheadEvent::Rejected { .. }) => "rejected",
+            PolicyEvent::Bulkhead(BulkheadEvent::Released { .. }) => "released",
+            PolicyEvent::Bulkhead(BulkheadEvent::Closed) => "closed",
+            PolicyEvent::Timeout(TimeoutEvent::Approaching { .. }) => "approaching",
+            PolicyEvent::Timeout(TimeoutEvent::Occurred { .. }) => "occurred",
+            PolicyEvent::Fallback(FallbackEvent::Served { .. }) => "served",
+            PolicyEvent::Fallback(FallbackEvent::Exhausted { .. }) => "exhausted",
+            PolicyEvent::ForkJoin(ForkJoinEvent::Won { .. }) => "won",
+            PolicyEvent::ForkJoin(ForkJoinEvent::Exhausted { .. }) => "exhausted",
+            PolicyEvent::Hedge(HedgeEvent::Launched { .. }) => "launched",
+            PolicyEvent::Hedge(HedgeEvent::Won { .. }) => "won",
+            PolicyEvent::Hedge(HedgeEvent::Exhausted { .. }) => "exhausted",
+            PolicyEvent::RateLimit(RateLimitEvent::Delayed { .. }) => "delayed",
+            PolicyEvent::RateLimit(RateLimitEvent::Rejected { .. }) => "rejected",
+            PolicyEvent::LoadShed(LoadShedEvent::Shed { .. }) => "shed",
+            PolicyEvent::Concurrency(ConcurrencyEvent::LimitChanged { .. }) => "limit_changed",
+            PolicyEvent::Concurrency(ConcurrencyEvent::Rejected { .. }) => "rejected",
+            PolicyEvent::Cache(CacheEvent::ServedStale { .. }) => "served_stale",
+            PolicyEvent::Coalesce(CoalesceEvent::Joined { .. }) => "joined",
+            PolicyEvent::Throttle(ThrottleEvent::Delayed { .. }) => "delayed",
+            PolicyEvent::Throttle(ThrottleEvent::Collapsed { .. }) => "collapsed",
+            PolicyEvent::Idempotency(IdempotencyEvent::Replayed { .. }) => "replayed",
+            PolicyEvent::Priority(PriorityEvent::Queued { .. }) => "queued",
+            PolicyEvent::Priority(PriorityEvent::Shed { .. }) => "shed",
+            PolicyEvent::Spillover(SpilloverEvent::Spilled { .. }) => "spilled",
+            PolicyEvent::Watchdog(WatchdogEvent::Stuck { .. }) => "stuck",
+            PolicyEvent::Watchdog(WatchdogEvent::Released { .. }) => "released",
+            PolicyEvent::Alert(AlertEvent::RetryStorm { .. }) => "retry_storm",
+            PolicyEvent::Alert(AlertEvent::BreakerFlapping { .. }) => "breaker_flapping",
+            PolicyEvent::Alert(AlertEvent::Resolved { .. }) => "resolved",
+            PolicyEvent::Summary(SummaryEvent::Heartbeat { .. }) => "heartbeat",
+            PolicyEvent::Request(RequestOutcome::Success { .. }) => "success",
+            PolicyEvent::Request(RequestOutcome::Failure { .. }) => "failure",
+        }
+    }
+
+    /// Whether the event reports a failed, rejected, or abandoned request (or an alert firing).
+    ///
+    /// Routine events such as retry attempts, permits acquired, cache hits, and recoveries are
+    /// not errors.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            PolicyEvent::Retry(RetryEvent::Exhausted { .. })
+                | PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { .. })
+                | PolicyEvent::Bulkhead(BulkheadEvent::Rejected { .. } | BulkheadEvent::Closed)
+                | PolicyEvent::Timeout(TimeoutEvent::Occurred { .. })
+                | PolicyEvent::Fallback(FallbackEvent::Exhausted { .. })
+                | PolicyEvent::ForkJoin(ForkJoinEvent::Exhausted { .. })
+                | PolicyEvent::Hedge(HedgeEvent::Exhausted { .. })
+                | PolicyEvent::RateLimit(RateLimitEvent::Rejected { .. })
+                | PolicyEvent::LoadShed(LoadShedEvent::Shed { .. })
+                | PolicyEvent::Concurrency(ConcurrencyEvent::Rejected { .. })
+                | PolicyEvent::Priority(PriorityEvent::Shed { .. })
+                | PolicyEvent::Watchdog(WatchdogEvent::Stuck { .. })
+                | PolicyEvent::Alert(
+                    AlertEvent::RetryStorm { .. } | AlertEvent::BreakerFlapping { .. }
+                )
+                | PolicyEvent::Request(RequestOutcome::Failure { .. })
+        )
+    }
+
+    /// How urgently the event deserves attention, for sinks that map events to log levels or
+    /// alert priorities.
+    ///
+    /// - [`Severity::Error`]: a policy gave up, or a dependency looks unhealthy (retries or all
+    ///   branches exhausted, breaker opened, bulkhead closed, request stuck, alert firing).
+    /// - [`Severity::Warn`]: a single request was refused or failed, or was served degraded
+    ///   (every other [`is_error`](Self::is_error) event, plus stale cache hits, spillover,
+    ///   fallback to a secondary, and requests approaching their timeout).
+    /// - [`Severity::Info`]: routine operation.
+    pub fn severity(&self) -> Severity {
+        match self {
+            PolicyEvent::Retry(RetryEvent::Exhausted { .. })
+            | PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { .. })
+            | PolicyEvent::Bulkhead(BulkheadEvent::Closed)
+            | PolicyEvent::Fallback(FallbackEvent::Exhausted { .. })
+            | PolicyEvent::ForkJoin(ForkJoinEvent::Exhausted { .. })
+            | PolicyEvent::Hedge(HedgeEvent::Exhausted { .. })
+            | PolicyEvent::Watchdog(WatchdogEvent::Stuck { .. })
+            | PolicyEvent::Alert(
+                AlertEvent::RetryStorm { .. } | AlertEvent::BreakerFlapping { .. },
+            ) => Severity::Error,
+            PolicyEvent::Timeout(TimeoutEvent::Approaching { .. })
+            | PolicyEvent::Cache(CacheEvent::ServedStale { .. })
+            | PolicyEvent::Spillover(SpilloverEvent::Spilled { .. }) => Severity::Warn,
+            PolicyEvent::Fallback(FallbackEvent::Served { branch, .. }) if *branch > 0 => {
+                Severity::Warn
+            }
+            event if event.is_error() => Severity::Warn,
+            _ => Severity::Info,
+        }
+    }
+}
+
+/// Severity of a [`PolicyEvent`], ordered from least to most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Severity {
+    /// Routine operation.
+    Info,
+    /// A single request was refused, failed, or degraded.
+    Warn,
+    /// A policy gave up or a dependency looks unhealthy.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+        })
+    }
+}
+
 /// Events emitted by retry policies.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
 pub enum RetryEvent {
     /// A retry attempt is about to be made.
     ///
@@ -124,6 +381,8 @@ pub enum RetryEvent {
         /// The attempt number (1-indexed)
         attempt: usize,
         /// The backoff delay before this retry
+        #[cfg_attr(feature = "serde", serde(rename = "delay_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
         delay: Duration,
     },
     /// All retry attempts have been exhausted.
@@ -134,12 +393,17 @@ pub enum RetryEvent {
         /// Total number of attempts made
         total_attempts: usize,
         /// Total time spent retrying
+        #[cfg_attr(feature = "serde", serde(rename = "total_duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
         total_duration: Duration,
     },
 }
 
 /// Events emitted by circuit breaker policies.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
 pub enum CircuitBreakerEvent {
     /// Circuit transitioned to open state.
     ///
@@ -153,15 +417,28 @@ pub enum CircuitBreakerEvent {
     ///
     /// A limited number of test requests will be allowed through
     /// to determine if the inner service has recovered.
-    HalfOpen,
+    HalfOpen {
+        /// Time spent open since the circuit last tripped (or last failed a probe)
+        #[cfg_attr(feature = "serde", serde(rename = "open_duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        open_duration: Duration,
+    },
     /// Circuit transitioned to closed state.
     ///
     /// Normal operation resumes - all requests are forwarded.
-    Closed,
+    Closed {
+        /// Total time away from closed, from the original trip through any failed probes
+        #[cfg_attr(feature = "serde", serde(rename = "open_duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        open_duration: Duration,
+    },
 }
 
 /// Events emitted by bulkhead policies.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
 pub enum BulkheadEvent {
     /// A request successfully acquired a bulkhead permit.
     ///
@@ -183,12 +460,22 @@ pub enum BulkheadEvent {
         /// Reason for rejection
         reason: BulkheadRejectReason,
     },
+    /// A request returned its bulkhead permit, because it finished or its future was dropped.
+    Released {
+        /// Number of active requests after the release
+        active_count: usize,
+        /// Maximum concurrency limit
+        max_concurrency: usize,
+    },
     /// The bulkhead semaphore was closed; no further requests accepted.
     Closed,
 }
 
 /// Reasons a bulkhead rejected a request.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum BulkheadRejectReason {
     /// No permits available (saturated).
     Saturated,
@@ -198,31 +485,565 @@ pub enum BulkheadRejectReason {
 
 /// Events emitted by timeout policies.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
 pub enum TimeoutEvent {
+    /// A request passed the soft `warn_after` threshold but has not timed out yet.
+    Approaching {
+        /// Time the request had been running when the threshold fired
+        #[cfg_attr(feature = "serde", serde(rename = "elapsed_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        elapsed: Duration,
+        /// The hard timeout still in force
+        #[cfg_attr(feature = "serde", serde(rename = "timeout_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        timeout: Duration,
+    },
     /// A request exceeded the timeout duration.
     ///
     /// The request was cancelled and an error returned.
     Occurred {
         /// The timeout duration that was exceeded
+        #[cfg_attr(feature = "serde", serde(rename = "timeout_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
         timeout: Duration,
     },
 }
 
+/// Events emitted by fallback combinators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum FallbackEvent {
+    /// A branch served the request.
+    Served {
+        /// Zero-based index of the branch that succeeded (0 is the primary)
+        branch: usize,
+        /// Time from the first attempt until the serving branch returned
+        #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        duration: Duration,
+    },
+    /// Every branch tried failed.
+    Exhausted {
+        /// Number of branches attempted
+        branches: usize,
+        /// Time spent across all branches
+        #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        duration: Duration,
+    },
+}
+
+/// Events emitted by fork-join (`&`) combinators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum ForkJoinEvent {
+    /// A branch succeeded; the other was cancelled or had already failed.
+    Won {
+        /// Winning branch (0 is the left, 1 the right)
+        branch: usize,
+        /// Time from dispatch until the winner returned
+        #[cfg_attr(feature = "serde", serde(rename = "latency_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        latency: Duration,
+        /// Whether the losing branch had already failed when the winner returned
+        loser_failed: bool,
+    },
+    /// Both branches failed.
+    Exhausted {
+        /// Time until the second failure
+        #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        duration: Duration,
+    },
+}
+
+/// Events emitted by hedged requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum HedgeEvent {
+    /// The backup request was sent to the hedge stack.
+    Launched {
+        /// Time since the primary was dispatched
+        #[cfg_attr(feature = "serde", serde(rename = "elapsed_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        elapsed: Duration,
+        /// `true` if the primary failed before the hedge delay, `false` if it was merely slow
+        primary_failed: bool,
+    },
+    /// A request succeeded.
+    Won {
+        /// Winning branch (0 is the primary, 1 the hedge)
+        branch: usize,
+        /// Time from the primary's dispatch until the winner returned
+        #[cfg_attr(feature = "serde", serde(rename = "latency_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        latency: Duration,
+    },
+    /// The primary and the hedge both failed.
+    Exhausted {
+        /// Time until the last failure
+        #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        duration: Duration,
+    },
+}
+
+/// Events emitted by rate limiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum RateLimitEvent {
+    /// A request was admitted but held back to smooth the outgoing rate.
+    Delayed {
+        /// How long the request waits before being dispatched
+        #[cfg_attr(feature = "serde", serde(rename = "wait_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        wait: Duration,
+    },
+    /// A request was rejected because the limit was reached.
+    Rejected {
+        /// Suggested wait before the next request would be admitted
+        #[cfg_attr(feature = "serde", serde(rename = "retry_after_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        retry_after: Duration,
+    },
+}
+
+/// Events emitted by load shedders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum LoadShedEvent {
+    /// A request was shed without reaching the inner service.
+    Shed {
+        /// Shed probability in force for this request
+        probability: f64,
+        /// Load ratio reported by the health signal (`1.0` is at target)
+        load: f64,
+    },
+}
+
+/// Events emitted by adaptive concurrency limiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum ConcurrencyEvent {
+    /// The estimated limit moved to a new value.
+    LimitChanged {
+        /// New in-flight limit
+        limit: usize,
+    },
+    /// A request was rejected because the current limit was reached.
+    Rejected {
+        /// Requests in flight at the time of rejection
+        in_flight: usize,
+        /// Limit in force
+        limit: usize,
+    },
+}
+
+/// Events emitted by response caches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum CacheEvent {
+    /// An expired entry was returned instead of a fresh response.
+    ServedStale {
+        /// Age of the entry that was served
+        #[cfg_attr(feature = "serde", serde(rename = "age_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        age: Duration,
+        /// Why the stale entry was used
+        reason: StaleReason,
+    },
+}
+
+/// Why a cache served an expired entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum StaleReason {
+    /// The entry is being refreshed in the background (stale-while-revalidate).
+    Revalidating,
+    /// The inner call failed (stale-if-error).
+    Error,
+    /// The inner call was too slow to wait for.
+    Slow,
+}
+
+/// Events emitted by request coalescing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum CoalesceEvent {
+    /// A request joined an identical call already in flight instead of calling the inner service.
+    Joined {
+        /// Requests still waiting on the call, including this one and the one that started it
+        /// if it has not been dropped
+        waiters: usize,
+    },
+}
+
+/// Events emitted by throttle and debounce layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum ThrottleEvent {
+    /// A request was held back to keep the minimum interval between calls.
+    Delayed {
+        /// How long the request waits before starting
+        #[cfg_attr(feature = "serde", serde(rename = "wait_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        wait: Duration,
+    },
+    /// A burst of requests was collapsed into a single trailing call.
+    Collapsed {
+        /// Requests in the burst, including the one that was sent
+        requests: usize,
+    },
+}
+
+/// Events emitted by idempotency-key deduplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum IdempotencyEvent {
+    /// A duplicate request was answered with the stored response.
+    Replayed {
+        /// Age of the stored response
+        #[cfg_attr(feature = "serde", serde(rename = "age_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        age: Duration,
+    },
+}
+
+/// Events emitted by priority admission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum PriorityEvent {
+    /// A request had to wait for a slot.
+    Queued {
+        /// Class of the queued request
+        priority: Priority,
+        /// Waiting requests per class after queueing, highest class first
+        depths: [usize; Priority::COUNT],
+    },
+    /// A request was rejected, either on arrival or evicted from the queue by a higher class.
+    Shed {
+        /// Class of the rejected request
+        priority: Priority,
+        /// Waiting requests per class once the rejection settled, highest class first
+        depths: [usize; Priority::COUNT],
+    },
+}
+
+/// Events emitted by spillover routing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum SpilloverEvent {
+    /// A request was sent to the secondary service.
+    Spilled {
+        /// Which primary threshold was reached
+        reason: SpillReason,
+    },
+}
+
+/// Primary threshold that caused a request to spill over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum SpillReason {
+    /// The primary was at its concurrency limit.
+    Concurrency {
+        /// Requests running on the primary
+        in_flight: usize,
+    },
+    /// The primary's rate window was full.
+    Rate {
+        /// Requests already sent to the primary in the current window
+        requests: usize,
+    },
+}
+
+/// Events emitted by the stuck-request watchdog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum WatchdogEvent {
+    /// A request ran past its watchdog threshold without completing.
+    Stuck {
+        /// Time since the request started
+        #[cfg_attr(feature = "serde", serde(rename = "elapsed_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        elapsed: Duration,
+        /// Threshold it crossed
+        #[cfg_attr(feature = "serde", serde(rename = "threshold_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        threshold: Duration,
+        /// Whether the request was dropped as a result
+        aborted: bool,
+    },
+    /// A request previously reported stuck finally completed.
+    Released {
+        /// Total time the request took
+        #[cfg_attr(feature = "serde", serde(rename = "elapsed_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        elapsed: Duration,
+    },
+}
+
+/// Actionable alerts synthesized from the event stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum AlertEvent {
+    /// Retries exceeded the configured share of traffic.
+    RetryStorm {
+        /// Retry attempts per request over the window
+        ratio: f64,
+        /// Window the ratio was measured over
+        #[cfg_attr(feature = "serde", serde(rename = "window_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        window: Duration,
+    },
+    /// Circuit breakers opened repeatedly.
+    BreakerFlapping {
+        /// Opens seen within the window
+        opens: usize,
+        /// Window the opens were counted over
+        #[cfg_attr(feature = "serde", serde(rename = "window_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        window: Duration,
+    },
+    /// A previously firing alert is no longer active.
+    Resolved {
+        /// Which alert resolved
+        kind: AlertKind,
+    },
+}
+
+/// Roll-ups emitted on a fixed interval, whether or not anything happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum SummaryEvent {
+    /// Counts of the events seen during the last interval.
+    Heartbeat {
+        /// Length of the interval
+        #[cfg_attr(feature = "serde", serde(rename = "interval_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
+        interval: Duration,
+        /// Requests completed, successfully or not
+        requests: u64,
+        /// Requests that failed
+        failures: u64,
+        /// Retry attempts
+        retries: u64,
+        /// Requests refused by a bulkhead, rate limiter, load shedder, or similar
+        rejections: u64,
+    },
+}
+
+/// Kinds of alert raised by [`AlertEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AlertKind {
+    /// See [`AlertEvent::RetryStorm`].
+    RetryStorm,
+    /// See [`AlertEvent::BreakerFlapping`].
+    BreakerFlapping,
+}
+
 /// Request outcome events emitted by all policies.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
 pub enum RequestOutcome {
     /// Request completed successfully.
     Success {
         /// Time taken to complete the request
+        #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
         duration: Duration,
     },
     /// Request failed with an error.
     Failure {
         /// Time taken before failure
+        #[cfg_attr(feature = "serde", serde(rename = "duration_ms", with = "duration_ms"))]
+        #[cfg_attr(feature = "schema", schemars(with = "f64"))]
         duration: Duration,
     },
 }
 
+/// Version of the serialized event format; bumped on breaking changes to field names or tags.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Serialize an event in the stable JSON format described in the [module docs](self), led by
+/// its `schema_version`.
+#[cfg(feature = "serde")]
+pub fn event_to_json(event: &PolicyEvent) -> String {
+    #[derive(serde::Serialize)]
+    struct Versioned<'a> {
+        schema_version: u32,
+        #[serde(flatten)]
+        event: &'a PolicyEvent,
+    }
+
+    serde_json::to_string(&Versioned { schema_version: EVENT_SCHEMA_VERSION, event })
+        .expect("policy events always serialize")
+}
+
+/// JSON Schema (draft 2019-09) for a serialized [`EventEnvelope`], covering every event variant.
+///
+/// A bare event from [`event_to_json`] matches it too, since the attribution fields are
+/// optional. Requires the `schema` feature.
+#[cfg(feature = "schema")]
+pub fn event_json_schema() -> serde_json::Value {
+    let schema = schemars::gen::SchemaSettings::draft2019_09()
+        .into_generator()
+        .into_root_schema_for::<EventEnvelope>();
+    serde_json::to_value(schema).expect("schemas always serialize")
+}
+
+#[cfg(feature = "serde")]
+fn unversioned() -> u32 {
+    1
+}
+
+/// An event together with the policy that emitted it.
+///
+/// Policies emit bare [`PolicyEvent`]s; attribution comes from the
+/// [`RequestContext`](crate::RequestContext) installed by `Policy::named`, which is only visible
+/// while a sink handles the event. Sinks that ship events elsewhere capture an envelope in
+/// `call` so the attribution survives batching, queues, and serialization.
+///
+/// Serialized, the schema version and attribution fields sit next to the event's own fields:
+///
+/// ```text
+/// {"schema_version":1,"policy_name":"db-read","instance_id":3,"policy":"timeout","event":"occurred","timeout_ms":200.0}
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EventEnvelope {
+    /// [`EVENT_SCHEMA_VERSION`] of the producer; envelopes written before the field existed
+    /// read as version 1.
+    #[cfg_attr(feature = "serde", serde(default = "unversioned"))]
+    pub schema_version: u32,
+    /// Name of the innermost named policy, or `None` outside any `Policy::named` stack.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub policy_name: Option<Arc<str>>,
+    /// Instance id of that named policy.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub instance_id: Option<u64>,
+    /// Attributes in scope when the event was captured, such as those added by [`EnrichSink`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")
+    )]
+    pub attributes: std::collections::BTreeMap<Arc<str>, Arc<str>>,
+    /// The event itself.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub event: PolicyEvent,
+}
+
+impl EventEnvelope {
+    /// Wrap `event` with the attribution of the current request context.
+    pub fn capture(event: PolicyEvent) -> Self {
+        let ctx = crate::RequestContext::current();
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            policy_name: ctx.policy_name().map(Arc::from),
+            instance_id: ctx.policy_instance(),
+            attributes: ctx.attributes().map(|(key, value)| (key.into(), value.into())).collect(),
+            event,
+        }
+    }
+
+    /// Kind of layer that emitted the event.
+    pub fn layer_kind(&self) -> &'static str {
+        self.event.layer_kind()
+    }
+
+    /// A [`RequestContext`](crate::RequestContext) carrying this envelope's attribution, for
+    /// handing the event back to a sink that reads the policy name.
+    pub fn context(&self) -> crate::RequestContext {
+        let mut ctx = crate::RequestContext::new();
+        if let Some(name) = &self.policy_name {
+            ctx = ctx.with_policy_name(Arc::clone(name));
+        }
+        if let Some(instance) = self.instance_id {
+            ctx = ctx.with_policy_instance(instance);
+        }
+        for (key, value) in &self.attributes {
+            ctx = ctx.with_attribute(Arc::clone(key), Arc::clone(value));
+        }
+        ctx
+    }
+
+    /// Serialize the envelope in the stable JSON format.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("event envelopes always serialize")
+    }
+}
+
+impl fmt::Display for EventEnvelope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.policy_name, self.instance_id) {
+            (Some(name), Some(id)) => write!(f, "[{}#{}] {}", name, id, self.event),
+            (Some(name), None) => write!(f, "[{}] {}", name, self.event),
+            _ => write!(f, "{}", self.event),
+        }
+    }
+}
+
+/// Durations as fractional milliseconds, exact to the nanosecond for any realistic value.
+#[cfg(feature = "serde")]
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_nanos() as f64 / 1e6)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let ms = f64::deserialize(deserializer)?;
+        if !(ms.is_finite() && ms >= 0.0) {
+            return Err(serde::de::Error::custom("duration must be a non-negative number of ms"));
+        }
+        Ok(Duration::from_nanos((ms * 1e6).round() as u64))
+    }
+}
+
 impl fmt::Display for PolicyEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -230,6 +1051,21 @@ impl fmt::Display for PolicyEvent {
             PolicyEvent::CircuitBreaker(event) => write!(f, "CircuitBreaker::{}", event),
             PolicyEvent::Bulkhead(event) => write!(f, "Bulkhead::{}", event),
             PolicyEvent::Timeout(event) => write!(f, "Timeout::{}", event),
+            PolicyEvent::Fallback(event) => write!(f, "Fallback::{}", event),
+            PolicyEvent::ForkJoin(event) => write!(f, "ForkJoin::{}", event),
+            PolicyEvent::Hedge(event) => write!(f, "Hedge::{}", event),
+            PolicyEvent::RateLimit(event) => write!(f, "RateLimit::{}", event),
+            PolicyEvent::LoadShed(event) => write!(f, "LoadShed::{}", event),
+            PolicyEvent::Concurrency(event) => write!(f, "Concurrency::{}", event),
+            PolicyEvent::Cache(event) => write!(f, "Cache::{}", event),
+            PolicyEvent::Coalesce(event) => write!(f, "Coalesce::{}", event),
+            PolicyEvent::Throttle(event) => write!(f, "Throttle::{}", event),
+            PolicyEvent::Idempotency(event) => write!(f, "Idempotency::{}", event),
+            PolicyEvent::Priority(event) => write!(f, "Priority::{}", event),
+            PolicyEvent::Spillover(event) => write!(f, "Spillover::{}", event),
+            PolicyEvent::Watchdog(event) => write!(f, "Watchdog::{}", event),
+            PolicyEvent::Alert(event) => write!(f, "Alert::{}", event),
+            PolicyEvent::Summary(event) => write!(f, "Summary::{}", event),
             PolicyEvent::Request(event) => write!(f, "Request::{}", event),
         }
     }
@@ -254,8 +1090,12 @@ impl fmt::Display for CircuitBreakerEvent {
             CircuitBreakerEvent::Opened { failure_count } => {
                 write!(f, "Opened(failures={})", failure_count)
             }
-            CircuitBreakerEvent::HalfOpen => write!(f, "HalfOpen"),
-            CircuitBreakerEvent::Closed => write!(f, "Closed"),
+            CircuitBreakerEvent::HalfOpen { open_duration } => {
+                write!(f, "HalfOpen(open_duration={:?})", open_duration)
+            }
+            CircuitBreakerEvent::Closed { open_duration } => {
+                write!(f, "Closed(open_duration={:?})", open_duration)
+            }
         }
     }
 }
@@ -269,6 +1109,9 @@ impl fmt::Display for BulkheadEvent {
             BulkheadEvent::Rejected { active_count, max_concurrency, reason } => {
                 write!(f, "Rejected({}/{}, reason={:?})", active_count, max_concurrency, reason)
             }
+            BulkheadEvent::Released { active_count, max_concurrency } => {
+                write!(f, "Released({}/{})", active_count, max_concurrency)
+            }
             BulkheadEvent::Closed => write!(f, "Closed"),
         }
     }
@@ -277,75 +1120,255 @@ impl fmt::Display for BulkheadEvent {
 impl fmt::Display for TimeoutEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            TimeoutEvent::Approaching { elapsed, timeout } => {
+                write!(f, "Approaching(elapsed={:?}, timeout={:?})", elapsed, timeout)
+            }
             TimeoutEvent::Occurred { timeout } => write!(f, "Occurred(timeout={:?})", timeout),
         }
     }
 }
 
-impl fmt::Display for RequestOutcome {
+impl fmt::Display for FallbackEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            RequestOutcome::Success { duration } => write!(f, "Success(duration={:?})", duration),
-            RequestOutcome::Failure { duration } => write!(f, "Failure(duration={:?})", duration),
+            FallbackEvent::Served { branch, duration } => {
+                write!(f, "Served(branch={}, duration={:?})", branch, duration)
+            }
+            FallbackEvent::Exhausted { branches, duration } => {
+                write!(f, "Exhausted(branches={}, duration={:?})", branches, duration)
+            }
         }
     }
 }
 
-// ============================================================================
-// Built-in Telemetry Sinks
-// ============================================================================
-
-use std::convert::Infallible;
-use std::pin::Pin;
-use std::sync::{Arc, Mutex};
-use std::task::{Context, Poll};
-use tower::Service;
-
-/// A no-op telemetry sink that discards all events.
-///
-/// Useful for testing or when telemetry is disabled.
-///
-/// # Example
-///
-/// ```rust
-/// use ninelives::telemetry::{NullSink, PolicyEvent, RetryEvent};
-/// use tower::Service;
-/// use std::time::Duration;
-///
-/// # #[tokio::main]
-/// # async fn main() {
-/// let mut sink = NullSink;
-/// let event = PolicyEvent::Retry(RetryEvent::Attempt {
-///     attempt: 1,
-///     delay: Duration::from_millis(100),
-/// });
-///
-/// // Event is silently discarded
-/// let _ = sink.call(event).await;
-/// # }
-/// ```
-#[derive(Clone, Debug, Default)]
-pub struct NullSink;
-
-impl Service<PolicyEvent> for NullSink {
-    type Response = ();
-    type Error = Infallible;
-    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
-
-    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+impl fmt::Display for ForkJoinEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForkJoinEvent::Won { branch, latency, loser_failed } => write!(
+                f,
+                "Won(branch={}, latency={:?}, loser_failed={})",
+                branch, latency, loser_failed
+            ),
+            ForkJoinEvent::Exhausted { duration } => {
+                write!(f, "Exhausted(duration={:?})", duration)
+            }
+        }
     }
+}
 
-    fn call(&mut self, _event: PolicyEvent) -> Self::Future {
-        Box::pin(async { Ok(()) })
+impl fmt::Display for HedgeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HedgeEvent::Launched { elapsed, primary_failed } => {
+                write!(f, "Launched(elapsed={:?}, primary_failed={})", elapsed, primary_failed)
+            }
+            HedgeEvent::Won { branch, latency } => {
+                write!(f, "Won(branch={}, latency={:?})", branch, latency)
+            }
+            HedgeEvent::Exhausted { duration } => write!(f, "Exhausted(duration={:?})", duration),
+        }
     }
 }
 
-impl TelemetrySink for NullSink {
-    type SinkError = Infallible;
+impl fmt::Display for RateLimitEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitEvent::Delayed { wait } => write!(f, "Delayed(wait={:?})", wait),
+            RateLimitEvent::Rejected { retry_after } => {
+                write!(f, "Rejected(retry_after={:?})", retry_after)
+            }
+        }
+    }
 }
 
-/// A telemetry sink that logs events using the `tracing` crate.
+impl fmt::Display for LoadShedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadShedEvent::Shed { probability, load } => {
+                write!(f, "Shed(probability={:.2}, load={:.2})", probability, load)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConcurrencyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcurrencyEvent::LimitChanged { limit } => write!(f, "LimitChanged(limit={})", limit),
+            ConcurrencyEvent::Rejected { in_flight, limit } => {
+                write!(f, "Rejected(in_flight={}, limit={})", in_flight, limit)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CacheEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheEvent::ServedStale { age, reason } => {
+                write!(f, "ServedStale(age={:?}, reason={:?})", age, reason)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CoalesceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoalesceEvent::Joined { waiters } => write!(f, "Joined(waiters={})", waiters),
+        }
+    }
+}
+
+impl fmt::Display for ThrottleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrottleEvent::Delayed { wait } => write!(f, "Delayed(wait={:?})", wait),
+            ThrottleEvent::Collapsed { requests } => write!(f, "Collapsed(requests={})", requests),
+        }
+    }
+}
+
+impl fmt::Display for IdempotencyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdempotencyEvent::Replayed { age } => write!(f, "Replayed(age={:?})", age),
+        }
+    }
+}
+
+impl fmt::Display for PriorityEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriorityEvent::Queued { priority, depths } => {
+                write!(f, "Queued(priority={}, depths={:?})", priority, depths)
+            }
+            PriorityEvent::Shed { priority, depths } => {
+                write!(f, "Shed(priority={}, depths={:?})", priority, depths)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SpilloverEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpilloverEvent::Spilled { reason: SpillReason::Concurrency { in_flight } } => {
+                write!(f, "Spilled(in_flight={})", in_flight)
+            }
+            SpilloverEvent::Spilled { reason: SpillReason::Rate { requests } } => {
+                write!(f, "Spilled(window_requests={})", requests)
+            }
+        }
+    }
+}
+
+impl fmt::Display for WatchdogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchdogEvent::Stuck { elapsed, threshold, aborted } => write!(
+                f,
+                "Stuck(elapsed={:?}, threshold={:?}, aborted={})",
+                elapsed, threshold, aborted
+            ),
+            WatchdogEvent::Released { elapsed } => write!(f, "Released(elapsed={:?})", elapsed),
+        }
+    }
+}
+
+impl fmt::Display for AlertEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertEvent::RetryStorm { ratio, window } => {
+                write!(f, "RetryStorm(ratio={:.2}, window={:?})", ratio, window)
+            }
+            AlertEvent::BreakerFlapping { opens, window } => {
+                write!(f, "BreakerFlapping(opens={}, window={:?})", opens, window)
+            }
+            AlertEvent::Resolved { kind } => write!(f, "Resolved(kind={:?})", kind),
+        }
+    }
+}
+
+impl fmt::Display for SummaryEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SummaryEvent::Heartbeat { interval, requests, failures, retries, rejections } => {
+                write!(
+                    f,
+                    "Heartbeat(interval={:?}, requests={}, failures={}, retries={}, rejections={})",
+                    interval, requests, failures, retries, rejections
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for RequestOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestOutcome::Success { duration } => write!(f, "Success(duration={:?})", duration),
+            RequestOutcome::Failure { duration } => write!(f, "Failure(duration={:?})", duration),
+        }
+    }
+}
+
+// ============================================================================
+// Built-in Telemetry Sinks
+// ============================================================================
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tower::Service;
+
+/// A no-op telemetry sink that discards all events.
+///
+/// Useful for testing or when telemetry is disabled.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{NullSink, PolicyEvent, RetryEvent};
+/// use tower::Service;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut sink = NullSink;
+/// let event = PolicyEvent::Retry(RetryEvent::Attempt {
+///     attempt: 1,
+///     delay: Duration::from_millis(100),
+/// });
+///
+/// // Event is silently discarded
+/// let _ = sink.call(event).await;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NullSink;
+
+impl Service<PolicyEvent> for NullSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _event: PolicyEvent) -> Self::Future {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for NullSink {
+    type SinkError = Infallible;
+}
+
+/// A telemetry sink that logs events using the `tracing` crate.
 ///
 /// Events are logged at INFO level with structured fields.
 ///
@@ -379,7 +1402,14 @@ impl Service<PolicyEvent> for LogSink {
     }
 
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
-        tracing::info!(event = %event, "policy_event");
+        let ctx = crate::RequestContext::current();
+        tracing::info!(
+            event = %event,
+            layer = event.layer_kind(),
+            policy = ctx.policy_name(),
+            instance = ctx.policy_instance(),
+            "policy_event"
+        );
         Box::pin(async { Ok(()) })
     }
 }
@@ -388,102 +1418,168 @@ impl TelemetrySink for LogSink {
     type SinkError = Infallible;
 }
 
-/// A telemetry sink that stores events in memory.
+/// Records events as structured `tracing` events inside the current span.
+///
+/// Where [`LogSink`] flattens an event into one display string, this sink gives subscribers
+/// separate `layer`, `event`, `policy`, and `instance` fields to index on, plus the rendered
+/// `detail`. Each event is logged at the level matching its [`PolicyEvent::severity`], under
+/// the `ninelives` target.
 ///
-/// Useful for testing and debugging. Events are stored in a `Vec` protected
-/// by a `Mutex`.
+/// Sinks run while the policy awaits them, so the current span is the caller's; wrap the stack
+/// in [`TracingLayer`](crate::TracingLayer) to give each request a span of its own.
 ///
 /// # Example
 ///
 /// ```rust
-/// use ninelives::telemetry::{MemorySink, PolicyEvent, TimeoutEvent};
-/// use tower::Service;
+/// use ninelives::telemetry::{PolicyEvent, TimeoutEvent, TracingSink};
 /// use std::time::Duration;
+/// use tower::Service;
 ///
 /// # #[tokio::main]
 /// # async fn main() {
-/// let mut sink = MemorySink::new();
-/// let event = PolicyEvent::Timeout(TimeoutEvent::Occurred {
-///     timeout: Duration::from_secs(1),
-/// });
-///
-/// sink.call(event.clone()).await.unwrap();
+/// let mut sink = TracingSink::new();
+/// let event = PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout: Duration::from_secs(1) });
 ///
-/// let events = sink.events();
-/// assert_eq!(events.len(), 1);
-/// assert_eq!(events[0], event);
+/// // WARN ninelives: policy_event layer="timeout" event="occurred" detail=...
+/// let _ = sink.call(event).await;
 /// # }
 /// ```
-#[derive(Clone, Debug)]
-pub struct MemorySink {
-    events: Arc<Mutex<Vec<PolicyEvent>>>,
-    capacity: usize,
-    evicted: Arc<AtomicU64>,
-}
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingSink;
 
-impl MemorySink {
-    /// Creates a bounded memory sink (default cap: 10,000).
-    /// Oldest events are evicted when capacity is exceeded.
+impl TracingSink {
+    /// Create the sink.
     pub fn new() -> Self {
-        Self::with_capacity(10_000)
+        Self
     }
+}
 
-    /// Creates a bounded memory sink with explicit capacity.
-    pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            events: Arc::new(Mutex::new(Vec::new())),
-            capacity: capacity.max(1),
-            evicted: Arc::new(AtomicU64::new(0)),
-        }
+impl Service<PolicyEvent> for TracingSink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
     }
 
-    /// Creates an unbounded memory sink. Dangerous in production.
-    pub fn unbounded() -> Self {
-        Self {
-            events: Arc::new(Mutex::new(Vec::new())),
-            capacity: usize::MAX,
-            evicted: Arc::new(AtomicU64::new(0)),
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let ctx = crate::RequestContext::current();
+        macro_rules! record {
+            ($level:expr) => {
+                tracing::event!(
+                    target: "ninelives",
+                    $level,
+                    layer = event.layer_kind(),
+                    event = event.event_name(),
+                    policy = ctx.policy_name(),
+                    instance = ctx.policy_instance(),
+                    detail = %event,
+                    "policy_event"
+                )
+            };
+        }
+        match event.severity() {
+            Severity::Error => record!(tracing::Level::ERROR),
+            Severity::Warn => record!(tracing::Level::WARN),
+            Severity::Info => record!(tracing::Level::INFO),
         }
+        Box::pin(async { Ok(()) })
     }
+}
 
-    /// Returns a snapshot of all events received so far.
-    pub fn events(&self) -> Vec<PolicyEvent> {
-        self.events.lock().unwrap().clone()
-    }
+impl TelemetrySink for TracingSink {
+    type SinkError = Infallible;
+}
 
-    /// Clears all stored events.
-    pub fn clear(&self) {
-        self.events.lock().unwrap().clear();
-    }
+/// Prints one colorized line per event to stderr, for scanning policy activity while developing.
+///
+/// Each line shows the time since the sink was created, the policy name and instance (each
+/// policy in a color of its own), the severity, and the event:
+///
+/// ```text
+/// +   1.204s checkout#3       WARN  Retry::Attempt(#2, delay=100ms)
+/// +   1.517s checkout#3       ERROR CircuitBreaker::Opened(failures=5)
+/// ```
+///
+/// Colors are on when stderr is a terminal and `NO_COLOR` is unset. Writes are synchronous, so
+/// keep this sink out of production stacks; use [`TracingSink`] there.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{ConsoleSink, PolicyEvent, RetryEvent};
+/// use std::time::Duration;
+/// use tower::Service;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut sink = ConsoleSink::new().with_color(false);
+/// let delay = Duration::from_millis(100);
+/// let event = PolicyEvent::Retry(RetryEvent::Attempt { attempt: 2, delay });
+///
+/// // +   0.000s -                WARN  Retry::Attempt(#2, delay=100ms)
+/// let _ = sink.call(event).await;
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ConsoleSink {
+    started: std::time::Instant,
+    color: bool,
+}
 
-    /// Returns the number of events stored.
-    pub fn len(&self) -> usize {
-        self.events.lock().unwrap().len()
+impl Default for ConsoleSink {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    /// Returns true if no events are stored.
-    pub fn is_empty(&self) -> bool {
-        self.events.lock().unwrap().is_empty()
+impl ConsoleSink {
+    /// Sink timing events from now, colored if stderr is a terminal and `NO_COLOR` is unset.
+    pub fn new() -> Self {
+        use std::io::IsTerminal;
+        let color = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Self { started: std::time::Instant::now(), color }
     }
 
-    /// Returns the configured capacity.
-    pub fn capacity(&self) -> usize {
-        self.capacity
+    /// Force colors on or off.
+    #[must_use]
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
     }
 
-    /// Returns the number of evicted events.
-    pub fn evicted(&self) -> u64 {
-        self.evicted.load(Ordering::Relaxed)
-    }
-}
+    fn line(&self, event: &PolicyEvent, ctx: &crate::RequestContext, elapsed: Duration) -> String {
+        // Severity colors are red and yellow, so policies are drawn from the remaining hues.
+        const POLICY_COLORS: [&str; 8] = ["32", "34", "35", "36", "92", "94", "95", "96"];
 
-impl Default for MemorySink {
-    fn default() -> Self {
-        Self::new()
+        let policy = match (ctx.policy_name(), ctx.policy_instance()) {
+            (Some(name), Some(instance)) => format!("{name}#{instance}"),
+            (Some(name), None) => name.to_owned(),
+            (None, _) => "-".to_owned(),
+        };
+        let (level, level_color) = match event.severity() {
+            Severity::Error => ("ERROR", "1;31"),
+            Severity::Warn => ("WARN", "33"),
+            Severity::Info => ("INFO", "2"),
+        };
+        let elapsed = format!("+{:>8.3}s", elapsed.as_secs_f64());
+        if !self.color {
+            return format!("{elapsed} {policy:<16} {level:<5} {event}");
+        }
+        let policy_color = ctx.policy_name().map_or("2", |name| {
+            let hash =
+                name.bytes().fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b.into()));
+            POLICY_COLORS[hash % POLICY_COLORS.len()]
+        });
+        format!(
+            "\x1b[2m{elapsed}\x1b[0m \x1b[{policy_color}m{policy:<16}\x1b[0m \
+             \x1b[{level_color}m{level:<5}\x1b[0m {event}"
+        )
     }
 }
 
-impl Service<PolicyEvent> for MemorySink {
+impl Service<PolicyEvent> for ConsoleSink {
     type Response = ();
     type Error = Infallible;
     type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
@@ -493,44 +1589,373 @@ impl Service<PolicyEvent> for MemorySink {
     }
 
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
-        let mut guard = self.events.lock().unwrap();
-        if guard.len() >= self.capacity {
-            guard.remove(0);
-            self.evicted.fetch_add(1, Ordering::Relaxed);
-        }
-        guard.push(event);
+        use std::io::Write;
+        let line = self.line(&event, &crate::RequestContext::current(), self.started.elapsed());
+        let _ = writeln!(std::io::stderr().lock(), "{line}");
         Box::pin(async { Ok(()) })
     }
 }
 
-impl TelemetrySink for MemorySink {
+impl TelemetrySink for ConsoleSink {
     type SinkError = Infallible;
 }
 
-/// A streaming telemetry sink that broadcasts events to multiple subscribers.
+/// Build a sink from a closure that is called with every event.
 ///
-/// Uses `tokio::sync::broadcast` to publish events to all active receivers.
-/// Receivers that fall behind will miss events (the channel has bounded capacity).
+/// The closure runs synchronously inside `call`, while the emitting policy waits, so keep it
+/// cheap; use [`async_sink_fn`] when handling an event needs to await. Closures must be `Clone`
+/// because every layer holds its own copy of the sink; share state through an `Arc`.
 ///
 /// # Example
 ///
 /// ```rust
-/// use ninelives::telemetry::{StreamingSink, PolicyEvent, RetryEvent};
-/// use tower::Service;
+/// use ninelives::telemetry::{sink_fn, PolicyEvent, TimeoutEvent};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
 /// use std::time::Duration;
+/// use tower::Service;
 ///
 /// # #[tokio::main]
 /// # async fn main() {
-/// let sink = StreamingSink::new(100); // 100 event buffer
-/// let mut receiver = sink.subscribe();
-///
-/// let mut sink_clone = sink.clone();
-/// let event = PolicyEvent::Retry(RetryEvent::Attempt {
-///     attempt: 1,
-///     delay: Duration::from_millis(100),
+/// let timeouts = Arc::new(AtomicUsize::new(0));
+/// let counter = Arc::clone(&timeouts);
+/// let mut sink = sink_fn(move |event| {
+///     if let PolicyEvent::Timeout(_) = event {
+///         counter.fetch_add(1, Ordering::Relaxed);
+///     }
 /// });
 ///
-/// // Send event through sink
+/// let event = PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout: Duration::from_secs(1) });
+/// sink.call(event).await.unwrap();
+/// assert_eq!(timeouts.load(Ordering::Relaxed), 1);
+/// # }
+/// ```
+pub fn sink_fn<F>(f: F) -> FnSink<F>
+where
+    F: Fn(PolicyEvent) + Clone + Send + 'static,
+{
+    FnSink { f }
+}
+
+/// Sink returned by [`sink_fn`].
+#[derive(Clone)]
+pub struct FnSink<F> {
+    f: F,
+}
+
+impl<F> std::fmt::Debug for FnSink<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnSink").finish_non_exhaustive()
+    }
+}
+
+impl<F> Service<PolicyEvent> for FnSink<F>
+where
+    F: Fn(PolicyEvent) + Clone + Send + 'static,
+{
+    type Response = ();
+    type Error = Infallible;
+    type Future = std::future::Ready<Result<(), Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        (self.f)(event);
+        std::future::ready(Ok(()))
+    }
+}
+
+impl<F> TelemetrySink for FnSink<F>
+where
+    F: Fn(PolicyEvent) + Clone + Send + 'static,
+{
+    type SinkError = Infallible;
+}
+
+/// Build a sink from an async closure; the policy awaits the returned future for each event.
+///
+/// Like any sink, a slow future delays the policy that emitted the event; wrap the result in
+/// [`NonBlockingSink`] to move delivery off the request path.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{async_sink_fn, PolicyEvent, RetryEvent};
+/// use std::time::Duration;
+/// use tower::Service;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+/// let mut sink = async_sink_fn(move |event| {
+///     let tx = tx.clone();
+///     async move {
+///         let _ = tx.send(event).await;
+///     }
+/// });
+///
+/// let event = PolicyEvent::Retry(RetryEvent::Attempt { attempt: 1, delay: Duration::ZERO });
+/// sink.call(event.clone()).await.unwrap();
+/// assert_eq!(rx.recv().await, Some(event));
+/// # }
+/// ```
+pub fn async_sink_fn<F, Fut>(f: F) -> AsyncFnSink<F>
+where
+    F: Fn(PolicyEvent) -> Fut + Clone + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    AsyncFnSink { f }
+}
+
+/// Sink returned by [`async_sink_fn`].
+#[derive(Clone)]
+pub struct AsyncFnSink<F> {
+    f: F,
+}
+
+impl<F> std::fmt::Debug for AsyncFnSink<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncFnSink").finish_non_exhaustive()
+    }
+}
+
+impl<F, Fut> Service<PolicyEvent> for AsyncFnSink<F>
+where
+    F: Fn(PolicyEvent) -> Fut + Clone + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let fut = (self.f)(event);
+        Box::pin(async move {
+            fut.await;
+            Ok(())
+        })
+    }
+}
+
+impl<F, Fut> TelemetrySink for AsyncFnSink<F>
+where
+    F: Fn(PolicyEvent) -> Fut + Clone + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    type SinkError = Infallible;
+}
+
+/// A telemetry sink that stores events in memory.
+///
+/// Useful for testing and debugging. Events are stored in a ring buffer (a `VecDeque` protected
+/// by a `Mutex`, so evicting the oldest event is O(1)), each with the [`tokio::time::Instant`] it arrived at, and can be
+/// queried with [`events_of_kind`](Self::events_of_kind),
+/// [`events_since`](Self::events_since), or awaited with [`wait_for`](Self::wait_for).
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{MemorySink, PolicyEvent, TimeoutEvent};
+/// use tower::Service;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut sink = MemorySink::new();
+/// let event = PolicyEvent::Timeout(TimeoutEvent::Occurred {
+///     timeout: Duration::from_secs(1),
+/// });
+///
+/// sink.call(event.clone()).await.unwrap();
+///
+/// let events = sink.events();
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0], event);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemorySink {
+    events: Arc<Mutex<VecDeque<(tokio::time::Instant, PolicyEvent)>>>,
+    capacity: usize,
+    evicted: Arc<AtomicU64>,
+    arrived: Arc<tokio::sync::Notify>,
+}
+
+impl MemorySink {
+    /// Creates a bounded memory sink (default cap: 10,000).
+    /// Oldest events are evicted when capacity is exceeded.
+    pub fn new() -> Self {
+        Self::with_capacity(10_000)
+    }
+
+    /// Creates a bounded memory sink with explicit capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: Arc::new(Mutex::new(VecDeque::new())),
+            capacity: capacity.max(1),
+            evicted: Arc::new(AtomicU64::new(0)),
+            arrived: Arc::new(tokio::sync::Notify::new()),
+        }
+    }
+
+    /// Creates an unbounded memory sink. Dangerous in production.
+    pub fn unbounded() -> Self {
+        Self {
+            events: Arc::new(Mutex::new(VecDeque::new())),
+            capacity: usize::MAX,
+            evicted: Arc::new(AtomicU64::new(0)),
+            arrived: Arc::new(tokio::sync::Notify::new()),
+        }
+    }
+
+    /// Returns a snapshot of all events received so far.
+    pub fn events(&self) -> Vec<PolicyEvent> {
+        self.events_where(|_, _| true)
+    }
+
+    /// Events whose [`layer_kind`](PolicyEvent::layer_kind) is `kind`, such as `"retry"`.
+    pub fn events_of_kind(&self, kind: &str) -> Vec<PolicyEvent> {
+        self.events_where(|_, event| event.layer_kind() == kind)
+    }
+
+    /// Events that arrived at or after `since`.
+    pub fn events_since(&self, since: tokio::time::Instant) -> Vec<PolicyEvent> {
+        self.events_where(|at, _| at >= since)
+    }
+
+    /// Number of retry attempts ([`RetryEvent::Attempt`]) stored.
+    pub fn count_retries(&self) -> usize {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .filter(|(_, event)| matches!(event, PolicyEvent::Retry(RetryEvent::Attempt { .. })))
+            .count()
+    }
+
+    /// Wait until a stored event satisfies `predicate` and return the first such event, or `None`
+    /// after `timeout`.
+    ///
+    /// Events already stored count, so there is no race with events emitted before the call.
+    pub async fn wait_for<F>(&self, predicate: F, timeout: Duration) -> Option<PolicyEvent>
+    where
+        F: Fn(&PolicyEvent) -> bool,
+    {
+        let found = async {
+            loop {
+                // Registered before the scan, so an event stored in between still wakes us.
+                let arrived = self.arrived.notified();
+                if let Some(event) =
+                    self.events_where(|_, event| predicate(event)).into_iter().next()
+                {
+                    return event;
+                }
+                arrived.await;
+            }
+        };
+        tokio::time::timeout(timeout, found).await.ok()
+    }
+
+    fn events_where(
+        &self,
+        mut keep: impl FnMut(tokio::time::Instant, &PolicyEvent) -> bool,
+    ) -> Vec<PolicyEvent> {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .filter(|(at, event)| keep(*at, event))
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+
+    /// Clears all stored events.
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+
+    /// Returns the number of events stored.
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    /// Returns true if no events are stored.
+    pub fn is_empty(&self) -> bool {
+        self.events.lock().unwrap().is_empty()
+    }
+
+    /// Returns the configured capacity.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of evicted events.
+    pub fn evicted(&self) -> u64 {
+        self.evicted.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for MemorySink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Service<PolicyEvent> for MemorySink {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        {
+            let mut guard = self.events.lock().unwrap();
+            if guard.len() >= self.capacity {
+                guard.pop_front();
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+            }
+            guard.push_back((tokio::time::Instant::now(), event));
+        }
+        self.arrived.notify_waiters();
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl TelemetrySink for MemorySink {
+    type SinkError = Infallible;
+}
+
+/// A streaming telemetry sink that broadcasts events to multiple subscribers.
+///
+/// Uses `tokio::sync::broadcast` to publish events to all active receivers.
+/// Receivers that fall behind will miss events (the channel has bounded capacity).
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{StreamingSink, PolicyEvent, RetryEvent};
+/// use tower::Service;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let sink = StreamingSink::new(100); // 100 event buffer
+/// let mut receiver = sink.subscribe();
+///
+/// let mut sink_clone = sink.clone();
+/// let event = PolicyEvent::Retry(RetryEvent::Attempt {
+///     attempt: 1,
+///     delay: Duration::from_millis(100),
+/// });
+///
+/// // Send event through sink
 /// sink_clone.call(event.clone()).await.unwrap();
 ///
 /// // Receive from subscriber
@@ -566,6 +1991,30 @@ impl StreamingSink {
         self.sender.subscribe()
     }
 
+    /// Subscribe as a [`Stream`](futures::Stream) of events, for use with `StreamExt`.
+    ///
+    /// Events a slow subscriber misses are skipped and counted in [`EventStream::missed`]
+    /// rather than ending the stream. The stream ends once every clone of this sink is dropped.
+    pub fn events(&self) -> EventStream {
+        let missed = Arc::new(AtomicU64::new(0));
+        let stream = futures::stream::unfold(
+            (self.sender.subscribe(), Arc::clone(&missed)),
+            |(mut rx, missed)| async move {
+                use tokio::sync::broadcast::error::RecvError;
+                loop {
+                    match rx.recv().await {
+                        Ok(event) => return Some((event, (rx, missed))),
+                        Err(RecvError::Lagged(n)) => {
+                            missed.fetch_add(n, Ordering::Relaxed);
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            },
+        );
+        EventStream { inner: Box::pin(stream), missed }
+    }
+
     /// Returns the number of active subscribers.
     pub fn receiver_count(&self) -> usize {
         self.sender.receiver_count()
@@ -612,16 +2061,52 @@ impl TelemetrySink for StreamingSink {
     type SinkError = Infallible;
 }
 
+/// Stream of events from a [`StreamingSink`], returned by [`StreamingSink::events`].
+pub struct EventStream {
+    inner: futures::stream::BoxStream<'static, PolicyEvent>,
+    missed: Arc<AtomicU64>,
+}
+
+impl EventStream {
+    /// Events skipped so far because this subscriber fell behind the channel capacity.
+    pub fn missed(&self) -> u64 {
+        self.missed.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for EventStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream").field("missed", &self.missed()).finish_non_exhaustive()
+    }
+}
+
+impl futures::Stream for EventStream {
+    type Item = PolicyEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
 // ============================================================================
 // Non-blocking sink wrapper
 // ============================================================================
 
 /// Offloads telemetry emission to a bounded channel and worker task.
 /// Keeps policy hot paths from awaiting slow sinks.
+///
+/// The caller's [`RequestContext`](crate::RequestContext) travels with each event, so the inner
+/// sink still sees attribution such as the policy name.
+///
+/// Call [`shutdown`](Self::shutdown) before the runtime stops to deliver everything still
+/// queued. Dropping the last clone also closes the queue and lets the worker drain it, but
+/// nothing waits for that to finish.
 #[derive(Clone)]
 pub struct NonBlockingSink<S> {
-    tx: tokio::sync::mpsc::Sender<PolicyEvent>,
+    tx: tokio::sync::mpsc::Sender<(crate::RequestContext, PolicyEvent)>,
     dropped: Arc<AtomicU64>,
+    stop: tokio_util::sync::CancellationToken,
+    worker: Arc<tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
     _sink: Arc<tokio::sync::Mutex<S>>, // keep sink alive
 }
 
@@ -633,193 +2118,946 @@ where
 {
     /// Create a new non-blocking wrapper with bounded queue and background worker.
     pub fn with_capacity(sink: S, capacity: usize) -> Self {
-        let (tx, mut rx) = tokio::sync::mpsc::channel(capacity);
+        let (tx, mut rx) =
+            tokio::sync::mpsc::channel::<(crate::RequestContext, PolicyEvent)>(capacity);
         let dropped = Arc::new(AtomicU64::new(0));
         let dropped_clone = dropped.clone();
         let sink_arc = Arc::new(tokio::sync::Mutex::new(sink));
         let sink_worker = sink_arc.clone();
 
-        tokio::spawn(async move {
-            while let Some(event) = rx.recv().await {
+        let stop = tokio_util::sync::CancellationToken::new();
+        let stopped = stop.clone();
+
+        let worker = tokio::spawn(async move {
+            loop {
+                let (ctx, event) = tokio::select! {
+                    biased;
+                    _ = stopped.cancelled() => {
+                        // Refuse new events but keep everything already queued.
+                        rx.close();
+                        match rx.recv().await {
+                            Some(item) => item,
+                            None => break,
+                        }
+                    }
+                    item = rx.recv() => match item {
+                        Some(item) => item,
+                        None => break,
+                    },
+                };
                 use tower::ServiceExt;
                 let mut guard = sink_worker.lock().await;
                 if let Ok(ready) = guard.ready().await {
-                    let _ = ready.call(event).await;
+                    let fut = ctx.clone().sync_scope(|| ready.call(event));
+                    let _ = ctx.scope(fut).await;
                 }
             }
         });
 
-        Self { tx, dropped: dropped_clone, _sink: sink_arc }
+        Self {
+            tx,
+            dropped: dropped_clone,
+            stop,
+            worker: Arc::new(tokio::sync::Mutex::new(Some(worker))),
+            _sink: sink_arc,
+        }
+    }
+
+    /// How many events were dropped because the queue was full (or closed by
+    /// [`shutdown`](Self::shutdown)).
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop accepting events, deliver everything already queued, and wait for the worker to
+    /// exit.
+    ///
+    /// Affects every clone; later events are counted as [`dropped`](Self::dropped). Calling it
+    /// again, from any clone, returns once the first call has finished draining.
+    pub async fn shutdown(&self) {
+        self.stop.cancel();
+        let mut worker = self.worker.lock().await;
+        if let Some(handle) = worker.take() {
+            let _ = handle.await;
+        }
+    }
+}
+
+impl<S> tower::Service<PolicyEvent> for NonBlockingSink<S>
+where
+    S: tower::Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        if self.tx.try_send((crate::RequestContext::current(), event)).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl<S> TelemetrySink for NonBlockingSink<S>
+where
+    S: tower::Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type SinkError = Infallible;
+}
+
+// ============================================================================
+// Batching sink wrapper
+// ============================================================================
+
+/// Errors produced while configuring a [`BatchingSink`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchError {
+    /// A batch must hold at least one event.
+    ZeroBatchSize,
+    /// The flush interval must be greater than zero.
+    ZeroInterval,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::ZeroBatchSize => write!(f, "batch size must be greater than zero"),
+            BatchError::ZeroInterval => write!(f, "flush interval must be greater than zero"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Buffers events and hands them to a batch sink (`Service<Vec<EventEnvelope>>`) in groups.
+///
+/// A batch is delivered once it holds `max_batch` events or `max_delay` after its first event
+/// arrived, whichever comes first. Delivery runs on a background task, so like
+/// [`NonBlockingSink`] this never blocks the policy that emitted the event; events that arrive
+/// while the queue (twice `max_batch`) is full are dropped and counted in
+/// [`dropped`](Self::dropped). When every clone has been dropped the final partial batch is
+/// flushed and the task exits.
+///
+/// Events are captured as [`EventEnvelope`]s on submission, so `Policy::named` attribution
+/// survives the hop to the worker. Wrap a per-event sink in [`PerEventSink`] to batch in front of
+/// it.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{BatchingSink, LogSink, PerEventSink};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // Up to 500 events per delivery, and never more than a second late.
+/// let sink = BatchingSink::new(PerEventSink::new(LogSink), 500, Duration::from_secs(1))?;
+/// # let _ = sink;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BatchingSink<S> {
+    tx: tokio::sync::mpsc::Sender<EventEnvelope>,
+    dropped: Arc<AtomicU64>,
+    _sink: std::marker::PhantomData<fn(S)>,
+}
+
+impl<S> Clone for BatchingSink<S> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone(), dropped: Arc::clone(&self.dropped), _sink: self._sink }
+    }
+}
+
+impl<S> fmt::Debug for BatchingSink<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchingSink").field("dropped", &self.dropped()).finish_non_exhaustive()
+    }
+}
+
+impl<S> BatchingSink<S>
+where
+    S: tower::Service<Vec<EventEnvelope>, Response = ()> + Send + 'static,
+    S::Error: std::error::Error + Send + 'static,
+    S::Future: Send + 'static,
+{
+    /// Start a worker delivering batches of up to `max_batch` events at least every `max_delay`.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn new(sink: S, max_batch: usize, max_delay: Duration) -> Result<Self, BatchError> {
+        if max_batch == 0 {
+            return Err(BatchError::ZeroBatchSize);
+        }
+        if max_delay.is_zero() {
+            return Err(BatchError::ZeroInterval);
+        }
+        let (tx, rx) = tokio::sync::mpsc::channel(max_batch.saturating_mul(2));
+        tokio::spawn(run_batches(sink, rx, max_batch, max_delay));
+        Ok(Self { tx, dropped: Arc::new(AtomicU64::new(0)), _sink: std::marker::PhantomData })
+    }
+}
+
+impl<S> BatchingSink<S> {
+    /// How many events were dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+async fn run_batches<S>(
+    mut sink: S,
+    mut rx: tokio::sync::mpsc::Receiver<EventEnvelope>,
+    max_batch: usize,
+    max_delay: Duration,
+) where
+    S: tower::Service<Vec<EventEnvelope>, Response = ()>,
+{
+    use tower::ServiceExt;
+
+    while let Some(first) = rx.recv().await {
+        let mut batch = Vec::with_capacity(max_batch);
+        batch.push(first);
+        let flush_at = tokio::time::sleep(max_delay);
+        tokio::pin!(flush_at);
+        while batch.len() < max_batch {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => batch.push(event),
+                    None => break,
+                },
+                () = &mut flush_at => break,
+            }
+        }
+        if let Ok(ready) = sink.ready().await {
+            let _ = ready.call(batch).await;
+        }
+    }
+}
+
+impl<S> tower::Service<PolicyEvent> for BatchingSink<S> {
+    type Response = ();
+    type Error = Infallible;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        if self.tx.try_send(EventEnvelope::capture(event)).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl<S: 'static> TelemetrySink for BatchingSink<S> {
+    type SinkError = Infallible;
+}
+
+/// Adapts a per-event sink to receive batches, calling it once per event in order and stopping
+/// at the first error.
+///
+/// Each call runs inside a [`RequestContext`](crate::RequestContext) rebuilt from the envelope,
+/// so sinks that read the policy name (such as [`LogSink`]) still see it.
+#[derive(Clone, Debug)]
+pub struct PerEventSink<S> {
+    inner: S,
+}
+
+impl<S> PerEventSink<S> {
+    /// Deliver each event of a batch to `sink`.
+    pub fn new(sink: S) -> Self {
+        Self { inner: sink }
+    }
+}
+
+impl<S> tower::Service<Vec<EventEnvelope>> for PerEventSink<S>
+where
+    S: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, batch: Vec<EventEnvelope>) -> Self::Future {
+        let mut sink = self.inner.clone();
+        Box::pin(async move {
+            for envelope in batch {
+                deliver_envelope(&mut sink, envelope).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Call `sink` with the envelope's event inside the envelope's request context.
+async fn deliver_envelope<S>(sink: &mut S, envelope: EventEnvelope) -> Result<(), S::Error>
+where
+    S: tower::Service<PolicyEvent, Response = ()>,
+{
+    use tower::ServiceExt;
+
+    let ctx = envelope.context();
+    let ready = sink.ready().await?;
+    let fut = ctx.clone().sync_scope(|| ready.call(envelope.event));
+    ctx.scope(fut).await
+}
+
+// ============================================================================
+// Telemetry Sink Composition
+// ============================================================================
+
+/// Error type for composed telemetry sinks.
+#[derive(Debug)]
+pub struct ComposedSinkError(Box<dyn std::error::Error + Send + Sync>);
+
+impl std::fmt::Display for ComposedSinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "telemetry sink error: {}", self.0)
+    }
+}
+
+impl std::error::Error for ComposedSinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+/// Multicasts events to two sinks in parallel (operator `+`).
+///
+/// Both sinks receive all events. If either sink fails, the error is propagated.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{LogSink, MemorySink, MulticastSink};
+/// use tower::Service;
+///
+/// let log = LogSink;
+/// let memory = MemorySink::new();
+/// let combined = MulticastSink::new(log, memory);
+/// // Both sinks will receive all events
+/// ```
+#[derive(Clone)]
+pub struct MulticastSink<A, B> {
+    sink_a: A,
+    sink_b: B,
+}
+
+impl<A, B> MulticastSink<A, B> {
+    /// Create a new multicast sink that sends events to both `sink_a` and `sink_b`.
+    pub fn new(sink_a: A, sink_b: B) -> Self {
+        Self { sink_a, sink_b }
+    }
+}
+
+impl<A, B> Service<PolicyEvent> for MulticastSink<A, B>
+where
+    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    A::Error: std::error::Error + Send + Sync + 'static,
+    A::Future: Send + 'static,
+    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    B::Error: std::error::Error + Send + Sync + 'static,
+    B::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = ComposedSinkError;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let mut sink_a = self.sink_a.clone();
+        let mut sink_b = self.sink_b.clone();
+        let event_clone = event.clone();
+
+        Box::pin(async move {
+            // Call both sinks concurrently
+            let (res_a, res_b) = tokio::join!(sink_a.call(event), sink_b.call(event_clone));
+
+            res_a.map_err(|e| ComposedSinkError(Box::new(e)))?;
+            res_b.map_err(|e| ComposedSinkError(Box::new(e)))?;
+
+            Ok(())
+        })
+    }
+}
+
+impl<A, B> TelemetrySink for MulticastSink<A, B>
+where
+    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    A::Error: std::error::Error + Send + Sync + 'static,
+    A::Future: Send + 'static,
+    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    B::Error: std::error::Error + Send + Sync + 'static,
+    B::Future: Send + 'static,
+{
+    type SinkError = ComposedSinkError;
+}
+
+/// Sends events to primary sink, falling back to secondary on error (operator `|`).
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{LogSink, MemorySink, FallbackSink};
+/// use tower::Service;
+///
+/// let primary = LogSink;
+/// let fallback = MemorySink::new();
+/// let combined = FallbackSink::new(primary, fallback);
+/// // Try primary, use fallback if it fails
+/// ```
+#[derive(Clone)]
+pub struct FallbackSink<A, B> {
+    primary: A,
+    fallback: B,
+}
+
+impl<A, B> FallbackSink<A, B> {
+    /// Create a new fallback sink that tries `primary` first, then `fallback` on error.
+    pub fn new(primary: A, fallback: B) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl<A, B> Service<PolicyEvent> for FallbackSink<A, B>
+where
+    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    A::Error: std::error::Error + Send + Sync + 'static,
+    A::Future: Send + 'static,
+    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    B::Error: std::error::Error + Send + Sync + 'static,
+    B::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = ComposedSinkError;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let mut primary = self.primary.clone();
+        let mut fallback = self.fallback.clone();
+        let event_clone = event.clone();
+
+        Box::pin(async move {
+            match primary.call(event).await {
+                Ok(()) => Ok(()),
+                Err(_) => {
+                    // Primary failed, try fallback
+                    fallback.call(event_clone).await.map_err(|e| ComposedSinkError(Box::new(e)))
+                }
+            }
+        })
+    }
+}
+
+impl<A, B> TelemetrySink for FallbackSink<A, B>
+where
+    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    A::Error: std::error::Error + Send + Sync + 'static,
+    A::Future: Send + 'static,
+    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    B::Error: std::error::Error + Send + Sync + 'static,
+    B::Future: Send + 'static,
+{
+    type SinkError = ComposedSinkError;
+}
+
+// ============================================================================
+// Filtering sink
+// ============================================================================
+
+type EventPredicate = Arc<dyn Fn(&PolicyEvent) -> bool + Send + Sync>;
+
+/// Forwards only the events accepted by a predicate; the rest are dropped before reaching the
+/// wrapped sink.
+///
+/// Useful in front of sinks that pay per event, such as network exporters.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{FilterSink, LogSink, MemorySink, PolicyEvent};
+///
+/// // Only failures are worth shipping...
+/// let _errors = FilterSink::errors_only(LogSink);
+/// // ...or only the policies we chart.
+/// let _breakers = FilterSink::kinds(MemorySink::new(), &["circuit_breaker", "bulkhead"]);
+/// // ...or anything else.
+/// let _no_outcomes = FilterSink::new(MemorySink::new(), |event: &PolicyEvent| {
+///     !matches!(event, PolicyEvent::Request(_))
+/// });
+/// ```
+#[derive(Clone)]
+pub struct FilterSink<S> {
+    inner: S,
+    predicate: EventPredicate,
+}
+
+impl<S> FilterSink<S> {
+    /// Forward to `sink` only the events for which `predicate` returns `true`.
+    pub fn new<F>(sink: S, predicate: F) -> Self
+    where
+        F: Fn(&PolicyEvent) -> bool + Send + Sync + 'static,
+    {
+        Self { inner: sink, predicate: Arc::new(predicate) }
+    }
+
+    /// Forward only events for which [`PolicyEvent::is_error`] holds.
+    pub fn errors_only(sink: S) -> Self {
+        Self::new(sink, PolicyEvent::is_error)
+    }
+
+    /// Forward only events whose [`layer_kind`](PolicyEvent::layer_kind) is one of `kinds`.
+    pub fn kinds(sink: S, kinds: &[&str]) -> Self {
+        let kinds: Vec<Box<str>> = kinds.iter().map(|&kind| kind.into()).collect();
+        Self::new(sink, move |event| kinds.iter().any(|kind| **kind == *event.layer_kind()))
+    }
+
+    /// Forward a random `rate` fraction of events (clamped to `0.0..=1.0`; NaN forwards none).
+    pub fn sampled(sink: S, rate: f64) -> Self {
+        let rate = if rate.is_nan() { 0.0 } else { rate.clamp(0.0, 1.0) };
+        Self::new(sink, move |_| rand::random::<f64>() < rate)
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for FilterSink<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilterSink").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<PolicyEvent> for FilterSink<S>
+where
+    S: Service<PolicyEvent, Response = ()>,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        if (self.predicate)(&event) {
+            Box::pin(self.inner.call(event))
+        } else {
+            Box::pin(async { Ok(()) })
+        }
+    }
+}
+
+impl<S> TelemetrySink for FilterSink<S>
+where
+    S: TelemetrySink,
+    S::Future: Send + 'static,
+{
+    type SinkError = S::SinkError;
+}
+
+// ============================================================================
+// Enrichment
+// ============================================================================
+
+/// Adds key/value attributes to every event on its way to the wrapped sink.
+///
+/// Deployment facts such as region, pod name, or build SHA belong on every event but are known
+/// to no policy. The attributes are installed in the [`RequestContext`](crate::RequestContext)
+/// while the inner sink handles the event, so any sink that captures an [`EventEnvelope`]
+/// (batching, dead-letter, and the companion exporters) ships them in its `attributes` field.
+/// An attribute replaces one with the same key already in scope.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{EnrichSink, EventEnvelope, PolicyEvent, TimeoutEvent};
+/// use std::time::Duration;
+/// use tower::ServiceExt;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let exporter = tower::service_fn(|event: PolicyEvent| async move {
+///     let envelope = EventEnvelope::capture(event);
+///     assert_eq!(envelope.attributes["region"].as_ref(), "eu-west-1");
+///     Ok::<_, std::convert::Infallible>(())
+/// });
+/// let sink = EnrichSink::new(exporter, [("region", "eu-west-1")]).with("build", "4f2a9c1");
+///
+/// let event = PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout: Duration::from_secs(1) });
+/// sink.oneshot(event).await.unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnrichSink<S> {
+    inner: S,
+    attributes: Arc<[(Arc<str>, Arc<str>)]>,
+}
+
+impl<S> EnrichSink<S> {
+    /// Add `attributes` to every event forwarded to `sink`.
+    pub fn new<K, V>(sink: S, attributes: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<Arc<str>>,
+        V: Into<Arc<str>>,
+    {
+        let attributes =
+            attributes.into_iter().map(|(key, value)| (key.into(), value.into())).collect();
+        Self { inner: sink, attributes }
+    }
+
+    /// Also add `key = value`.
+    pub fn with(self, key: impl Into<Arc<str>>, value: impl Into<Arc<str>>) -> Self {
+        let mut attributes = self.attributes.to_vec();
+        attributes.push((key.into(), value.into()));
+        Self { inner: self.inner, attributes: attributes.into() }
+    }
+
+    fn context(&self) -> crate::RequestContext {
+        self.attributes.iter().fold(crate::RequestContext::current(), |ctx, (key, value)| {
+            ctx.with_attribute(Arc::clone(key), Arc::clone(value))
+        })
+    }
+}
+
+impl<S> Service<PolicyEvent> for EnrichSink<S>
+where
+    S: Service<PolicyEvent, Response = ()>,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, event: PolicyEvent) -> Self::Future {
+        let ctx = self.context();
+        // Sinks may capture the context in `call` or while their future runs; cover both.
+        let fut = ctx.clone().sync_scope(|| self.inner.call(event));
+        Box::pin(ctx.scope(fut))
+    }
+}
+
+impl<S> TelemetrySink for EnrichSink<S>
+where
+    S: TelemetrySink,
+    S::Future: Send + 'static,
+{
+    type SinkError = S::SinkError;
+}
+
+// ============================================================================
+// Sink pipelines
+// ============================================================================
+
+/// Builds a sink pipeline one wrapper at a time instead of nesting constructors.
+///
+/// Each method wraps everything built so far, so the wrapper added last is the first to see an
+/// event. Put [`fallback`](Self::fallback) right after the sink it backs up, and
+/// [`non_blocking`](Self::non_blocking) last: a non-blocking sink never reports errors, so a
+/// fallback wrapped around it would never fire.
+///
+/// # Example
+///
+/// ```rust
+/// use ninelives::telemetry::{LogSink, MemorySink, SinkBuilder};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// // Equivalent to NonBlockingSink::with_capacity(
+/// //     FilterSink::sampled(FilterSink::errors_only(FallbackSink::new(LogSink, spill)), 0.1),
+/// //     1024)
+/// let spill = MemorySink::new();
+/// let sink = SinkBuilder::new(LogSink)
+///     .fallback(spill)
+///     .errors_only()
+///     .sampled(0.1)
+///     .non_blocking(1024)
+///     .build();
+/// # let _ = sink;
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SinkBuilder<S> {
+    sink: S,
+}
+
+impl<S> SinkBuilder<S> {
+    /// Start a pipeline delivering to `sink`.
+    pub fn new(sink: S) -> Self {
+        Self { sink }
+    }
+
+    /// Drop events rejected by `predicate`; see [`FilterSink::new`].
+    pub fn filtered<F>(self, predicate: F) -> SinkBuilder<FilterSink<S>>
+    where
+        F: Fn(&PolicyEvent) -> bool + Send + Sync + 'static,
+    {
+        SinkBuilder::new(FilterSink::new(self.sink, predicate))
+    }
+
+    /// Keep only error events; see [`FilterSink::errors_only`].
+    pub fn errors_only(self) -> SinkBuilder<FilterSink<S>> {
+        SinkBuilder::new(FilterSink::errors_only(self.sink))
     }
 
-    /// How many events were dropped because the queue was full.
-    pub fn dropped(&self) -> u64 {
-        self.dropped.load(Ordering::Relaxed)
+    /// Keep only events from the given layer kinds; see [`FilterSink::kinds`].
+    pub fn kinds(self, kinds: &[&str]) -> SinkBuilder<FilterSink<S>> {
+        SinkBuilder::new(FilterSink::kinds(self.sink, kinds))
     }
-}
 
-impl<S> tower::Service<PolicyEvent> for NonBlockingSink<S>
-where
-    S: tower::Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
-    S::Error: std::error::Error + Send + 'static,
-    S::Future: Send + 'static,
-{
-    type Response = ();
-    type Error = Infallible;
-    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+    /// Keep a random `rate` fraction of events; see [`FilterSink::sampled`].
+    pub fn sampled(self, rate: f64) -> SinkBuilder<FilterSink<S>> {
+        SinkBuilder::new(FilterSink::sampled(self.sink, rate))
+    }
 
-    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+    /// Send to `fallback` when the pipeline so far fails; see [`FallbackSink`].
+    pub fn fallback<B>(self, fallback: B) -> SinkBuilder<FallbackSink<S, B>> {
+        SinkBuilder::new(FallbackSink::new(self.sink, fallback))
     }
 
-    fn call(&mut self, event: PolicyEvent) -> Self::Future {
-        if self.tx.try_send(event).is_err() {
-            self.dropped.fetch_add(1, Ordering::Relaxed);
-        }
-        Box::pin(async { Ok(()) })
+    /// Add `attributes` to every event; see [`EnrichSink`].
+    pub fn enrich<K, V>(
+        self,
+        attributes: impl IntoIterator<Item = (K, V)>,
+    ) -> SinkBuilder<EnrichSink<S>>
+    where
+        K: Into<Arc<str>>,
+        V: Into<Arc<str>>,
+    {
+        SinkBuilder::new(EnrichSink::new(self.sink, attributes))
+    }
+
+    /// Also send every event to `other`; see [`MulticastSink`].
+    pub fn tee<B>(self, other: B) -> SinkBuilder<MulticastSink<S, B>> {
+        SinkBuilder::new(MulticastSink::new(self.sink, other))
+    }
+
+    /// Apply any other wrapper, such as [`StormDetector::new`](crate::StormDetector::new).
+    pub fn wrap<T>(self, wrapper: impl FnOnce(S) -> T) -> SinkBuilder<T> {
+        SinkBuilder::new(wrapper(self.sink))
+    }
+
+    /// Finish the pipeline.
+    pub fn build(self) -> S {
+        self.sink
     }
 }
 
-impl<S> TelemetrySink for NonBlockingSink<S>
+impl<S> SinkBuilder<S>
 where
     S: tower::Service<PolicyEvent, Response = ()> + Send + Clone + 'static,
     S::Error: std::error::Error + Send + 'static,
     S::Future: Send + 'static,
 {
-    type SinkError = Infallible;
+    /// Deliver from a background task through a queue of `capacity` events; see
+    /// [`NonBlockingSink`]. Must be called from within a Tokio runtime.
+    pub fn non_blocking(self, capacity: usize) -> SinkBuilder<NonBlockingSink<S>> {
+        SinkBuilder::new(NonBlockingSink::with_capacity(self.sink, capacity))
+    }
 }
 
 // ============================================================================
-// Telemetry Sink Composition
+// Dead-letter spool
 // ============================================================================
 
-/// Error type for composed telemetry sinks.
-#[derive(Debug)]
-pub struct ComposedSinkError(Box<dyn std::error::Error + Send + Sync>);
-
-impl std::fmt::Display for ComposedSinkError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "telemetry sink error: {}", self.0)
-    }
-}
-
-impl std::error::Error for ComposedSinkError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(&*self.0)
-    }
-}
-
-/// Multicasts events to two sinks in parallel (operator `+`).
+/// Delivers events to a sink and, when it fails, appends them to a local spool file that
+/// [`replay`](DeadLetterSink::replay) re-sends later.
 ///
-/// Both sinks receive all events. If either sink fails, the error is propagated.
+/// [`FallbackSink`] hands a failed event to another sink; this one keeps it on disk, so events
+/// outlive both the outage and a restart of the process. The spool holds one [`EventEnvelope`] per
+/// line in the JSON format, so attribution is preserved and other tools can read the file.
+///
+/// A call only fails when the inner sink fails *and* the spool cannot be written. Requires the
+/// `serde` feature.
 ///
 /// # Example
 ///
-/// ```rust
-/// use ninelives::telemetry::{LogSink, MemorySink, MulticastSink};
-/// use tower::Service;
+/// ```rust,no_run
+/// use ninelives::telemetry::{DeadLetterSink, LogSink};
 ///
-/// let log = LogSink;
-/// let memory = MemorySink::new();
-/// let combined = MulticastSink::new(log, memory);
-/// // Both sinks will receive all events
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// let sink = DeadLetterSink::new(LogSink, "/var/spool/myapp/telemetry.jsonl");
+/// // ... once the backend is healthy again:
+/// let report = sink.replay().await?;
+/// println!("re-sent {} events, {} still spooled", report.delivered, report.remaining);
+/// # Ok(())
+/// # }
 /// ```
-#[derive(Clone)]
-pub struct MulticastSink<A, B> {
-    sink_a: A,
-    sink_b: B,
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug)]
+pub struct DeadLetterSink<S> {
+    inner: S,
+    spool: Arc<Spool>,
 }
 
-impl<A, B> MulticastSink<A, B> {
-    /// Create a new multicast sink that sends events to both `sink_a` and `sink_b`.
-    pub fn new(sink_a: A, sink_b: B) -> Self {
-        Self { sink_a, sink_b }
+/// Outcome of [`DeadLetterSink::replay`].
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReplayReport {
+    /// Events the inner sink accepted; they are removed from the spool.
+    pub delivered: usize,
+    /// Lines left in the spool, either undelivered or unreadable.
+    pub remaining: usize,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+struct Spool {
+    path: std::path::PathBuf,
+    // Serializes appends against the read-and-rewrite done by replay.
+    lock: tokio::sync::Mutex<()>,
+}
+
+#[cfg(feature = "serde")]
+impl Spool {
+    async fn append(&self, envelope: &EventEnvelope) -> std::io::Result<()> {
+        let line = envelope.to_json() + "\n";
+        let path = self.path.clone();
+        let _guard = self.lock.lock().await;
+        blocking(move || {
+            use std::io::Write;
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(line.as_bytes())
+        })
+        .await
     }
 }
 
-impl<A, B> Service<PolicyEvent> for MulticastSink<A, B>
+#[cfg(feature = "serde")]
+async fn blocking<T, F>(f: F) -> std::io::Result<T>
 where
-    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    A::Error: std::error::Error + Send + Sync + 'static,
-    A::Future: Send + 'static,
-    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    B::Error: std::error::Error + Send + Sync + 'static,
-    B::Future: Send + 'static,
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+    T: Send + 'static,
 {
-    type Response = ();
-    type Error = ComposedSinkError;
-    type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::new(std::io::ErrorKind::Other, e)))
+}
 
-    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+#[cfg(feature = "serde")]
+impl<S> DeadLetterSink<S> {
+    /// Deliver to `sink`, spooling failed events to the file at `path`.
+    ///
+    /// The file is created on the first failure; its directory must already exist.
+    pub fn new(sink: S, path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            inner: sink,
+            spool: Arc::new(Spool { path: path.into(), lock: tokio::sync::Mutex::new(()) }),
+        }
     }
 
-    fn call(&mut self, event: PolicyEvent) -> Self::Future {
-        let mut sink_a = self.sink_a.clone();
-        let mut sink_b = self.sink_b.clone();
-        let event_clone = event.clone();
-
-        Box::pin(async move {
-            // Call both sinks concurrently
-            let (res_a, res_b) = tokio::join!(sink_a.call(event), sink_b.call(event_clone));
-
-            res_a.map_err(|e| ComposedSinkError(Box::new(e)))?;
-            res_b.map_err(|e| ComposedSinkError(Box::new(e)))?;
-
-            Ok(())
-        })
+    /// Location of the spool file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.spool.path
     }
 }
 
-impl<A, B> TelemetrySink for MulticastSink<A, B>
+#[cfg(feature = "serde")]
+impl<S> DeadLetterSink<S>
 where
-    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    A::Error: std::error::Error + Send + Sync + 'static,
-    A::Future: Send + 'static,
-    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    B::Error: std::error::Error + Send + Sync + 'static,
-    B::Future: Send + 'static,
+    S: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Future: Send + 'static,
 {
-    type SinkError = ComposedSinkError;
-}
+    /// Re-send spooled events to the inner sink, oldest first.
+    ///
+    /// Stops at the first event the sink rejects, keeping it and everything after it in the
+    /// spool; delivered events are removed. Lines that do not parse are kept and counted in
+    /// [`ReplayReport::remaining`]. New failures are not spooled until the replay finishes.
+    pub async fn replay(&self) -> std::io::Result<ReplayReport> {
+        let _guard = self.spool.lock.lock().await;
+        let path = self.spool.path.clone();
+        let contents = match blocking(move || std::fs::read_to_string(path)).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(ReplayReport::default())
+            }
+            Err(e) => return Err(e),
+        };
 
-/// Sends events to primary sink, falling back to secondary on error (operator `|`).
-///
-/// # Example
-///
-/// ```rust
-/// use ninelives::telemetry::{LogSink, MemorySink, FallbackSink};
-/// use tower::Service;
-///
-/// let primary = LogSink;
-/// let fallback = MemorySink::new();
-/// let combined = FallbackSink::new(primary, fallback);
-/// // Try primary, use fallback if it fails
-/// ```
-#[derive(Clone)]
-pub struct FallbackSink<A, B> {
-    primary: A,
-    fallback: B,
-}
+        let mut sink = self.inner.clone();
+        let mut report = ReplayReport::default();
+        let mut kept = String::new();
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        let mut rejected = None;
+        for line in lines.by_ref() {
+            match serde_json::from_str::<EventEnvelope>(line) {
+                Ok(envelope) => {
+                    if deliver_envelope(&mut sink, envelope).await.is_err() {
+                        rejected = Some(line);
+                        break;
+                    }
+                    report.delivered += 1;
+                }
+                Err(_) => {
+                    kept.push_str(line);
+                    kept.push('\n');
+                    report.remaining += 1;
+                }
+            }
+        }
+        for line in rejected.into_iter().chain(lines) {
+            kept.push_str(line);
+            kept.push('\n');
+            report.remaining += 1;
+        }
 
-impl<A, B> FallbackSink<A, B> {
-    /// Create a new fallback sink that tries `primary` first, then `fallback` on error.
-    pub fn new(primary: A, fallback: B) -> Self {
-        Self { primary, fallback }
+        if report.delivered > 0 {
+            let path = self.spool.path.clone();
+            blocking(move || {
+                if kept.is_empty() {
+                    return std::fs::remove_file(path);
+                }
+                let mut staging = path.clone().into_os_string();
+                staging.push(".replay");
+                std::fs::write(&staging, kept)?;
+                std::fs::rename(staging, path)
+            })
+            .await?;
+        }
+        Ok(report)
     }
 }
 
-impl<A, B> Service<PolicyEvent> for FallbackSink<A, B>
+#[cfg(feature = "serde")]
+impl<S> Service<PolicyEvent> for DeadLetterSink<S>
 where
-    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    A::Error: std::error::Error + Send + Sync + 'static,
-    A::Future: Send + 'static,
-    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    B::Error: std::error::Error + Send + Sync + 'static,
-    B::Future: Send + 'static,
+    S: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Future: Send + 'static,
 {
     type Response = ();
-    type Error = ComposedSinkError;
+    type Error = std::io::Error;
     type Future = Pin<Box<dyn std::future::Future<Output = Result<(), Self::Error>> + Send>>;
 
     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -827,32 +3065,25 @@ where
     }
 
     fn call(&mut self, event: PolicyEvent) -> Self::Future {
-        let mut primary = self.primary.clone();
-        let mut fallback = self.fallback.clone();
-        let event_clone = event.clone();
-
+        let envelope = EventEnvelope::capture(event);
+        let mut inner = self.inner.clone();
+        let spool = Arc::clone(&self.spool);
         Box::pin(async move {
-            match primary.call(event).await {
-                Ok(()) => Ok(()),
-                Err(_) => {
-                    // Primary failed, try fallback
-                    fallback.call(event_clone).await.map_err(|e| ComposedSinkError(Box::new(e)))
-                }
+            if deliver_envelope(&mut inner, envelope.clone()).await.is_ok() {
+                return Ok(());
             }
+            spool.append(&envelope).await
         })
     }
 }
 
-impl<A, B> TelemetrySink for FallbackSink<A, B>
+#[cfg(feature = "serde")]
+impl<S> TelemetrySink for DeadLetterSink<S>
 where
-    A: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    A::Error: std::error::Error + Send + Sync + 'static,
-    A::Future: Send + 'static,
-    B: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
-    B::Error: std::error::Error + Send + Sync + 'static,
-    B::Future: Send + 'static,
+    S: tower::Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    S::Future: Send + 'static,
 {
-    type SinkError = ComposedSinkError;
+    type SinkError = std::io::Error;
 }
 
 #[cfg(test)]
@@ -942,6 +3173,42 @@ mod tests {
         assert!(sink.is_empty());
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn memory_sink_queries_and_waits() {
+        use tower::ServiceExt;
+
+        let sink = MemorySink::new();
+        let attempt =
+            |attempt| PolicyEvent::Retry(RetryEvent::Attempt { attempt, delay: Duration::ZERO });
+        let opened = PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 2 });
+
+        sink.clone().oneshot(attempt(1)).await.unwrap();
+        tokio::time::advance(Duration::from_secs(1)).await;
+        let checkpoint = tokio::time::Instant::now();
+        sink.clone().oneshot(attempt(2)).await.unwrap();
+        sink.clone().oneshot(opened.clone()).await.unwrap();
+
+        assert_eq!(sink.count_retries(), 2);
+        assert_eq!(sink.events_of_kind("circuit_breaker"), vec![opened.clone()]);
+        assert_eq!(sink.events_since(checkpoint), vec![attempt(2), opened]);
+
+        let writer = sink.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            writer.oneshot(PolicyEvent::Bulkhead(BulkheadEvent::Closed)).await.unwrap();
+        });
+        let closed = |event: &PolicyEvent| matches!(event, PolicyEvent::Bulkhead(_));
+        assert_eq!(
+            sink.wait_for(closed, Duration::from_secs(1)).await,
+            Some(PolicyEvent::Bulkhead(BulkheadEvent::Closed))
+        );
+        assert_eq!(
+            sink.wait_for(|e| e.is_error() && e.layer_kind() == "hedge", Duration::from_secs(1))
+                .await,
+            None
+        );
+    }
+
     #[tokio::test]
     async fn test_streaming_sink_drop_counts() {
         use tower::Service;
@@ -961,6 +3228,63 @@ mod tests {
         assert!(sink.dropped_count() >= 1);
     }
 
+    #[tokio::test]
+    async fn streaming_sink_events_skip_lagged_and_end_on_drop() {
+        use futures::StreamExt;
+        use tower::Service;
+
+        let mut sink = StreamingSink::new(2);
+        let mut stream = sink.events();
+        for attempt in 1..=4 {
+            sink.call(PolicyEvent::Retry(RetryEvent::Attempt { attempt, delay: Duration::ZERO }))
+                .await
+                .unwrap();
+        }
+        drop(sink);
+
+        let attempts: Vec<_> = stream
+            .by_ref()
+            .map(|event| match event {
+                PolicyEvent::Retry(RetryEvent::Attempt { attempt, .. }) => attempt,
+                other => panic!("unexpected {}", other),
+            })
+            .collect()
+            .await;
+        assert_eq!(attempts, vec![3, 4]);
+        assert_eq!(stream.missed(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_blocking_sink_shutdown_drains_the_queue() {
+        use tower::ServiceExt;
+
+        let delivered = MemorySink::new();
+        let slow = {
+            let delivered = delivered.clone();
+            tower::service_fn(move |event: PolicyEvent| {
+                let delivered = delivered.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    delivered.oneshot(event).await
+                }
+            })
+        };
+        let sink = NonBlockingSink::with_capacity(slow, 8);
+        let closed = PolicyEvent::Bulkhead(BulkheadEvent::Closed);
+        for _ in 0..5 {
+            sink.clone().oneshot(closed.clone()).await.unwrap();
+        }
+        assert!(delivered.len() < 5);
+
+        sink.shutdown().await;
+        assert_eq!(delivered.len(), 5);
+        sink.shutdown().await;
+
+        sink.clone().oneshot(closed).await.unwrap();
+        assert_eq!(sink.dropped(), 1);
+        assert_eq!(delivered.len(), 5);
+    }
+
     #[tokio::test]
     async fn test_log_sink() {
         use tower::Service;
@@ -972,4 +3296,367 @@ mod tests {
         // Should succeed without error
         sink.call(event).await.unwrap();
     }
+
+    #[test]
+    fn console_sink_lines_show_policy_severity_and_event() {
+        let event = PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 5 });
+        let ctx = crate::RequestContext::new().with_policy_name("checkout").with_policy_instance(3);
+        let plain = ConsoleSink::new().with_color(false);
+        assert_eq!(
+            plain.line(&event, &ctx, Duration::from_millis(1_204)),
+            "+   1.204s checkout#3       ERROR CircuitBreaker::Opened(failures=5)"
+        );
+
+        let colored = ConsoleSink::new().with_color(true).line(&event, &ctx, Duration::ZERO);
+        assert!(colored.contains("\x1b[1;31mERROR"));
+        let other = crate::RequestContext::new().with_policy_name("checkout");
+        let same_policy = ConsoleSink::new().with_color(true).line(&event, &other, Duration::ZERO);
+        let policy_color = |line: &str| line.split("checkout").next().map(str::to_owned);
+        assert_eq!(policy_color(&colored), policy_color(&same_policy));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn batching_sink_flushes_on_size_then_interval() {
+        let sizes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&sizes);
+        let batches = tower::service_fn(move |batch: Vec<EventEnvelope>| {
+            recorded.lock().unwrap().push(batch.len());
+            async { Ok::<_, Infallible>(()) }
+        });
+        let mut sink = BatchingSink::new(batches, 3, Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            BatchingSink::new(PerEventSink::new(NullSink), 0, Duration::from_secs(1)).unwrap_err(),
+            BatchError::ZeroBatchSize
+        );
+
+        for _ in 0..7 {
+            sink.call(PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened {
+                failure_count: 1,
+            }))
+            .await
+            .unwrap();
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(*sizes.lock().unwrap(), vec![3, 3]);
+
+        tokio::time::sleep(Duration::from_millis(1_100)).await;
+        assert_eq!(*sizes.lock().unwrap(), vec![3, 3, 1]);
+        assert_eq!(sink.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn per_event_sink_restores_attribution() {
+        use tower::ServiceExt;
+
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&names);
+        let recorder = tower::service_fn(move |_: PolicyEvent| {
+            let name = crate::RequestContext::current().policy_name().map(str::to_owned);
+            seen.lock().unwrap().push(name);
+            async { Ok::<_, Infallible>(()) }
+        });
+        let event = PolicyEvent::Bulkhead(BulkheadEvent::Closed);
+        let batch = vec![
+            EventEnvelope {
+                schema_version: EVENT_SCHEMA_VERSION,
+                policy_name: Some("db-read".into()),
+                instance_id: Some(1),
+                attributes: Default::default(),
+                event: event.clone(),
+            },
+            EventEnvelope {
+                schema_version: EVENT_SCHEMA_VERSION,
+                policy_name: None,
+                instance_id: None,
+                attributes: Default::default(),
+                event,
+            },
+        ];
+
+        PerEventSink::new(recorder).oneshot(batch).await.unwrap();
+        assert_eq!(*names.lock().unwrap(), vec![Some("db-read".to_string()), None]);
+    }
+
+    #[tokio::test]
+    async fn enrich_sink_attributes_reach_envelopes_downstream() {
+        use tower::ServiceExt;
+
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&captured);
+        let exporter =
+            sink_fn(move |event| seen.lock().unwrap().push(EventEnvelope::capture(event)));
+        let sink = SinkBuilder::new(exporter)
+            .enrich([("region", "eu-west-1"), ("pod", "api-7")])
+            .wrap(|sink| EnrichSink::new(sink, [("pod", "api-9")]).with("build", "4f2a9c1"))
+            .build();
+
+        let ctx = crate::RequestContext::new().with_policy_name("db-read");
+        let event = PolicyEvent::Bulkhead(BulkheadEvent::Closed);
+        let fut = ctx.clone().sync_scope(|| sink.oneshot(event));
+        ctx.scope(fut).await.unwrap();
+
+        let envelope = captured.lock().unwrap().pop().unwrap();
+        assert_eq!(envelope.policy_name.as_deref(), Some("db-read"));
+        let attributes: Vec<_> = envelope.attributes.iter().map(|(k, v)| (&**k, &**v)).collect();
+        // The inner wrapper runs last, so its value for a shared key wins.
+        assert_eq!(
+            attributes,
+            vec![("build", "4f2a9c1"), ("pod", "api-7"), ("region", "eu-west-1")]
+        );
+        assert_eq!(envelope.context().attribute("region"), Some("eu-west-1"));
+    }
+
+    #[tokio::test]
+    async fn filter_sink_drops_rejected_events() {
+        use tower::ServiceExt;
+
+        let attempt = PolicyEvent::Retry(RetryEvent::Attempt {
+            attempt: 1,
+            delay: Duration::from_millis(10),
+        });
+        let opened = PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 3 });
+        let failure =
+            PolicyEvent::Request(RequestOutcome::Failure { duration: Duration::from_millis(5) });
+
+        let errors = MemorySink::new();
+        let breakers = MemorySink::new();
+        for event in [attempt, opened.clone(), failure.clone()] {
+            FilterSink::errors_only(errors.clone()).oneshot(event.clone()).await.unwrap();
+            FilterSink::kinds(breakers.clone(), &["circuit_breaker"]).oneshot(event).await.unwrap();
+        }
+        assert_eq!(errors.events(), vec![opened.clone(), failure]);
+        assert_eq!(breakers.events(), vec![opened]);
+    }
+
+    #[tokio::test]
+    async fn closure_sinks_receive_events_from_a_layer() {
+        use crate::TimeoutLayer;
+        use tower::{Layer, ServiceExt};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sync_seen = Arc::clone(&seen);
+        let sink = sink_fn(move |event: PolicyEvent| {
+            sync_seen.lock().unwrap().push(event.event_name());
+        });
+        let svc = TimeoutLayer::new(Duration::from_secs(1))
+            .unwrap()
+            .with_sink(sink)
+            .layer(tower::service_fn(|n: u32| async move { Ok::<_, Infallible>(n) }));
+        assert_eq!(svc.oneshot(7).await.unwrap(), 7);
+        assert_eq!(*seen.lock().unwrap(), vec!["success"]);
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let sink = async_sink_fn(move |event| {
+            let tx = tx.clone();
+            async move {
+                tokio::task::yield_now().await;
+                let _ = tx.send(event);
+            }
+        });
+        let event = PolicyEvent::Bulkhead(BulkheadEvent::Closed);
+        sink.oneshot(event.clone()).await.unwrap();
+        assert_eq!(rx.try_recv().unwrap(), event);
+    }
+
+    #[tokio::test]
+    async fn sink_builder_wraps_in_call_order() {
+        use tower::ServiceExt;
+
+        let failing = tower::service_fn(|_: PolicyEvent| {
+            std::future::ready(Err::<(), _>(std::io::Error::new(std::io::ErrorKind::Other, "down")))
+        });
+        let spill = MemorySink::new();
+        let everything = MemorySink::new();
+        let sink = SinkBuilder::new(failing)
+            .fallback(spill.clone())
+            .errors_only()
+            .tee(everything.clone())
+            .sampled(1.0)
+            .build();
+
+        let opened = PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 3 });
+        let success =
+            PolicyEvent::Request(RequestOutcome::Success { duration: Duration::from_millis(5) });
+        for event in [opened.clone(), success.clone()] {
+            sink.clone().oneshot(event).await.unwrap();
+        }
+        assert_eq!(spill.events(), vec![opened.clone()]);
+        assert_eq!(everything.events(), vec![opened, success]);
+
+        let none = SinkBuilder::new(MemorySink::new()).sampled(0.0).build();
+        none.clone().oneshot(PolicyEvent::Bulkhead(BulkheadEvent::Closed)).await.unwrap();
+        assert!(none.inner.is_empty());
+    }
+
+    #[test]
+    fn severity_ranks_give_ups_above_single_failures() {
+        let ms = Duration::from_millis;
+        let cases = [
+            (PolicyEvent::Retry(RetryEvent::Attempt { attempt: 1, delay: ms(10) }), Severity::Info),
+            (
+                PolicyEvent::Fallback(FallbackEvent::Served { branch: 1, duration: ms(3) }),
+                Severity::Warn,
+            ),
+            (PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout: ms(100) }), Severity::Warn),
+            (PolicyEvent::Request(RequestOutcome::Failure { duration: ms(5) }), Severity::Warn),
+            (
+                PolicyEvent::CircuitBreaker(CircuitBreakerEvent::Opened { failure_count: 3 }),
+                Severity::Error,
+            ),
+            (
+                PolicyEvent::Retry(RetryEvent::Exhausted {
+                    total_attempts: 3,
+                    total_duration: ms(30),
+                }),
+                Severity::Error,
+            ),
+        ];
+        for (event, severity) in cases {
+            assert_eq!(event.severity(), severity, "{}", event);
+            assert!(event.is_error() <= (severity > Severity::Info), "{}", event);
+        }
+        assert!(Severity::Info < Severity::Warn && Severity::Warn < Severity::Error);
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn dead_letter_sink_spools_failures_and_replays_them() {
+        use std::sync::atomic::AtomicBool;
+        use tower::ServiceExt;
+
+        let down = Arc::new(AtomicBool::new(true));
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let backend = {
+            let (down, delivered) = (Arc::clone(&down), delivered.clone());
+            tower::service_fn(move |event: PolicyEvent| {
+                let result = if down.load(Ordering::SeqCst) {
+                    Err(std::io::Error::new(std::io::ErrorKind::Other, "backend down"))
+                } else {
+                    delivered.lock().unwrap().push(event);
+                    Ok(())
+                };
+                std::future::ready(result)
+            })
+        };
+        let path = std::env::temp_dir()
+            .join(format!("ninelives-dead-letter-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = DeadLetterSink::new(backend, &path);
+
+        let events: Vec<_> = (1..=3)
+            .map(|attempt| {
+                PolicyEvent::Retry(RetryEvent::Attempt { attempt, delay: Duration::ZERO })
+            })
+            .collect();
+        let ctx = crate::RequestContext::new().with_policy_name("db-read");
+        for event in events.clone() {
+            let fut = ctx.clone().sync_scope(|| sink.clone().oneshot(event));
+            ctx.clone().scope(fut).await.unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+        assert!(std::fs::read_to_string(&path).unwrap().contains(r#""policy_name":"db-read""#));
+
+        let report = sink.replay().await.unwrap();
+        assert_eq!((report.delivered, report.remaining), (0, 3));
+
+        down.store(false, Ordering::SeqCst);
+        let report = sink.replay().await.unwrap();
+        assert_eq!((report.delivered, report.remaining), (3, 0));
+        assert_eq!(*delivered.lock().unwrap(), events);
+        assert!(!path.exists());
+        assert_eq!(sink.replay().await.unwrap(), ReplayReport::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn events_serialize_to_the_documented_shape() {
+        let attempt = PolicyEvent::Retry(RetryEvent::Attempt {
+            attempt: 1,
+            delay: Duration::from_millis(100),
+        });
+        assert_eq!(
+            event_to_json(&attempt),
+            r#"{"schema_version":1,"policy":"retry","event":"attempt","attempt":1,"delay_ms":100.0}"#
+        );
+        assert_eq!(
+            event_to_json(&PolicyEvent::CircuitBreaker(CircuitBreakerEvent::HalfOpen {
+                open_duration: Duration::from_secs(30),
+            })),
+            r#"{"schema_version":1,"policy":"circuit_breaker","event":"half_open","open_duration_ms":30000.0}"#
+        );
+
+        let events = [
+            attempt,
+            PolicyEvent::Bulkhead(BulkheadEvent::Rejected {
+                active_count: 8,
+                max_concurrency: 8,
+                reason: BulkheadRejectReason::Saturated,
+            }),
+            PolicyEvent::Spillover(SpilloverEvent::Spilled {
+                reason: SpillReason::Rate { requests: 3 },
+            }),
+            PolicyEvent::Priority(PriorityEvent::Queued {
+                priority: Priority::High,
+                depths: [0, 1, 2, 3],
+            }),
+            PolicyEvent::Request(RequestOutcome::Success { duration: Duration::from_nanos(1_500) }),
+        ];
+        for event in events {
+            let json = event_to_json(&event);
+            assert_eq!(serde_json::from_str::<PolicyEvent>(&json).unwrap(), event, "{}", json);
+            let tags = format!(
+                r#"{{"schema_version":1,"policy":"{}","event":"{}""#,
+                event.layer_kind(),
+                event.event_name()
+            );
+            assert!(json.starts_with(&tags), "{}", json);
+        }
+
+        let envelope = EventEnvelope {
+            schema_version: EVENT_SCHEMA_VERSION,
+            policy_name: Some("db-read".into()),
+            instance_id: Some(3),
+            attributes: Default::default(),
+            event: PolicyEvent::Timeout(TimeoutEvent::Occurred {
+                timeout: Duration::from_millis(200),
+            }),
+        };
+        let json = envelope.to_json();
+        assert_eq!(
+            json,
+            r#"{"schema_version":1,"policy_name":"db-read","instance_id":3,"policy":"timeout","event":"occurred","timeout_ms":200.0}"#
+        );
+        assert_eq!(serde_json::from_str::<EventEnvelope>(&json).unwrap(), envelope);
+
+        let unversioned = r#"{"policy_name":"db-read","instance_id":3,"policy":"timeout","event":"occurred","timeout_ms":200.0}"#;
+        assert_eq!(serde_json::from_str::<EventEnvelope>(unversioned).unwrap(), envelope);
+
+        let enriched = EventEnvelope {
+            attributes: [("region".into(), "eu-west-1".into())].into(),
+            ..envelope
+        };
+        let json = enriched.to_json();
+        assert!(json.contains(r#""instance_id":3,"attributes":{"region":"eu-west-1"},"policy""#));
+        assert_eq!(serde_json::from_str::<EventEnvelope>(&json).unwrap(), enriched);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn json_schema_describes_every_event_variant() {
+        let schema = event_json_schema();
+        let text = schema.to_string();
+        for (policy, event) in [
+            ("retry", "attempt"),
+            ("circuit_breaker", "half_open"),
+            ("priority", "queued"),
+            ("spillover", "spilled"),
+            ("request", "success"),
+        ] {
+            assert!(text.contains(&format!(r#""{}""#, policy)), "missing policy {}", policy);
+            assert!(text.contains(&format!(r#""{}""#, event)), "missing event {}", event);
+        }
+        assert!(text.contains(r#""delay_ms""#) && text.contains(r#""schema_version""#));
+        assert_eq!(schema["title"], "EventEnvelope");
+        assert_eq!(schema["$schema"], "https://json-schema.org/draft/2019-09/schema");
+    }
 }
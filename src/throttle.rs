@@ -0,0 +1,452 @@
+//! Throttle and debounce layers for spiky request sources.
+//!
+//! Semantics
+//! - [`ThrottleLayer`] enforces a minimum interval between the starts of consecutive calls.
+//!   Requests arriving early are delayed (never rejected) until their slot comes up; each
+//!   delay emits [`ThrottleEvent::Delayed`].
+//! - [`DebounceLayer`] collapses a burst into its trailing call. The first request opens a quiet
+//!   window; each request arriving inside it restarts the window and replaces the pending
+//!   request. Once the window passes without new requests, only the latest request is sent,
+//!   and every caller in the burst receives a clone of its result. Collapsing more than one
+//!   request emits [`ThrottleEvent::Collapsed`].
+//! - Both layers wait through a [`Sleeper`] (default [`TokioSleeper`]); the throttle reads time
+//!   from a [`Clock`] (default [`MonotonicClock`]), so tests can inject a `TrackingSleeper` and
+//!   assert the delays without real waiting.
+//!
+//! Invariants
+//! - Services built from the same layer share the schedule (throttle) or the pending burst
+//!   (debounce).
+//! - The throttle works at the millisecond resolution of [`Clock`].
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // At most one config refresh every 5s; early callers wait for the next slot.
+//! let sleeper = TrackingSleeper::new();
+//! let mut refresh = ServiceBuilder::new()
+//!     .layer(ThrottleLayer::new(Duration::from_secs(5)).with_sleeper(sleeper.clone()))
+//!     .service_fn(|_: ()| async { Ok::<_, std::io::Error>("reloaded") });
+//!
+//! refresh.ready().await?.call(()).await?;
+//! refresh.ready().await?.call(()).await?;
+//! assert!(sleeper.call_at(0).unwrap() > Duration::from_secs(4));
+//!
+//! // Webhook fan-out: a burst of change notifications becomes one delivery of the latest.
+//! let mut notify = ServiceBuilder::new()
+//!     .layer(DebounceLayer::new(Duration::from_millis(50)))
+//!     .service_fn(|version: u32| async move { Ok::<_, String>(version) });
+//! assert_eq!(notify.ready().await?.call(3).await?, 3);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, ThrottleEvent};
+use crate::{Clock, MonotonicClock, Sleeper, TokioSleeper};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::ServiceExt;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer enforcing a minimum interval between calls by delaying early requests.
+#[derive(Clone)]
+pub struct ThrottleLayer<Sink = NullSink> {
+    interval: Duration,
+    next_slot: Arc<Mutex<Option<Duration>>>,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    sink: Sink,
+}
+
+impl ThrottleLayer<NullSink> {
+    /// Start calls at least `interval` apart.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_slot: Arc::new(Mutex::new(None)),
+            clock: Arc::new(MonotonicClock::new()),
+            sleeper: Arc::new(TokioSleeper),
+            sink: NullSink,
+        }
+    }
+}
+
+impl<Sink> ThrottleLayer<Sink> {
+    /// Provide a custom sleeper implementation.
+    pub fn with_sleeper<S>(mut self, sleeper: S) -> Self
+    where
+        S: Sleeper + 'static,
+    {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Provide a custom clock implementation.
+    pub fn with_clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Attach a telemetry sink to this throttle.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> ThrottleLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        ThrottleLayer {
+            interval: self.interval,
+            next_slot: self.next_slot,
+            clock: self.clock,
+            sleeper: self.sleeper,
+            sink,
+        }
+    }
+
+    /// Reserve the next start slot, returning how long the caller must wait for it.
+    fn reserve(&self) -> Duration {
+        let now = Duration::from_millis(self.clock.now_millis());
+        let mut next = self.next_slot.lock().unwrap_or_else(PoisonError::into_inner);
+        let start = next.map_or(now, |slot| slot.max(now));
+        *next = Some(start + self.interval);
+        start - now
+    }
+}
+
+impl<Sink: fmt::Debug> fmt::Debug for ThrottleLayer<Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThrottleLayer")
+            .field("interval", &self.interval)
+            .field("clock", &self.clock)
+            .field("sleeper", &self.sleeper)
+            .field("sink", &self.sink)
+            .finish()
+    }
+}
+
+impl<S, Sink: Clone> Layer<S> for ThrottleLayer<Sink> {
+    type Service = ThrottleService<S, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ThrottleService { inner, throttle: self.clone() }
+    }
+}
+
+impl<Sink> crate::Describe for ThrottleLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Throttle({:?})", self.interval))
+    }
+}
+
+/// Service produced by [`ThrottleLayer`].
+#[derive(Clone, Debug)]
+pub struct ThrottleService<S, Sink = NullSink> {
+    inner: S,
+    throttle: ThrottleLayer<Sink>,
+}
+
+impl<S, Request, Sink> Service<Request> for ThrottleService<S, Sink>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    Request: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        // Readiness is driven per call, after the delay, so a reserved slot is not held up by
+        // an inner service that is ready now but not later.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let wait = self.throttle.reserve();
+        let sleeper = Arc::clone(&self.throttle.sleeper);
+        let sink = self.throttle.sink.clone();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            if !wait.is_zero() {
+                emit_best_effort(sink, PolicyEvent::Throttle(ThrottleEvent::Delayed { wait }))
+                    .await;
+                sleeper.sleep(wait).await;
+            }
+            inner.oneshot(req).await
+        })
+    }
+}
+
+type Burst<V, E> = Shared<BoxFuture<'static, Result<V, E>>>;
+
+struct Pending<Request, V, E> {
+    /// Bumped by every request; the burst fires once it stops changing for a quiet window.
+    generation: u64,
+    latest: Option<Request>,
+    collapsed: usize,
+    burst: Option<Burst<V, E>>,
+}
+
+/// Layer collapsing bursts of requests into the trailing one.
+///
+/// `Request`, `V` and `E` are the request, response and error types of the wrapped service.
+pub struct DebounceLayer<Request, V, E, Sink = NullSink> {
+    quiet: Duration,
+    pending: Arc<Mutex<Pending<Request, V, E>>>,
+    sleeper: Arc<dyn Sleeper>,
+    sink: Sink,
+}
+
+impl<Request, V, E> DebounceLayer<Request, V, E, NullSink> {
+    /// Send only the last request of each burst, once `quiet` passes with no new requests.
+    pub fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            pending: Arc::new(Mutex::new(Pending {
+                generation: 0,
+                latest: None,
+                collapsed: 0,
+                burst: None,
+            })),
+            sleeper: Arc::new(TokioSleeper),
+            sink: NullSink,
+        }
+    }
+}
+
+impl<Request, V, E, Sink> DebounceLayer<Request, V, E, Sink> {
+    /// Provide a custom sleeper implementation.
+    pub fn with_sleeper<S>(mut self, sleeper: S) -> Self
+    where
+        S: Sleeper + 'static,
+    {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Attach a telemetry sink to this debouncer.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> DebounceLayer<Request, V, E, NewSink>
+    where
+        NewSink: Clone,
+    {
+        DebounceLayer { quiet: self.quiet, pending: self.pending, sleeper: self.sleeper, sink }
+    }
+}
+
+impl<Request, V, E, Sink: Clone> Clone for DebounceLayer<Request, V, E, Sink> {
+    fn clone(&self) -> Self {
+        Self {
+            quiet: self.quiet,
+            pending: Arc::clone(&self.pending),
+            sleeper: Arc::clone(&self.sleeper),
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+impl<Request, V, E, Sink: fmt::Debug> fmt::Debug for DebounceLayer<Request, V, E, Sink> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebounceLayer")
+            .field("quiet", &self.quiet)
+            .field("sleeper", &self.sleeper)
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, Request, V, E, Sink: Clone> Layer<S> for DebounceLayer<Request, V, E, Sink> {
+    type Service = DebounceService<S, Request, V, E, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DebounceService { inner, debounce: self.clone() }
+    }
+}
+
+impl<Request, V, E, Sink> crate::Describe for DebounceLayer<Request, V, E, Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Debounce({:?})", self.quiet))
+    }
+}
+
+/// Service produced by [`DebounceLayer`].
+pub struct DebounceService<S, Request, V, E, Sink = NullSink> {
+    inner: S,
+    debounce: DebounceLayer<Request, V, E, Sink>,
+}
+
+impl<S: Clone, Request, V, E, Sink: Clone> Clone for DebounceService<S, Request, V, E, Sink> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), debounce: self.debounce.clone() }
+    }
+}
+
+impl<S, Request, V, E, Sink> fmt::Debug for DebounceService<S, Request, V, E, Sink>
+where
+    S: fmt::Debug,
+    Sink: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebounceService")
+            .field("inner", &self.inner)
+            .field("debounce", &self.debounce)
+            .finish()
+    }
+}
+
+impl<S, Request, V, E, Sink> Service<Request> for DebounceService<S, Request, V, E, Sink>
+where
+    S: Service<Request, Response = V, Error = E> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    Request: Send + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = V;
+    type Error = E;
+    type Future = BoxFuture<'static, Result<V, E>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+        // The trailing request is sent later from a clone of the inner service.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let shared = Arc::clone(&self.debounce.pending);
+        let mut pending = shared.lock().unwrap_or_else(PoisonError::into_inner);
+        pending.generation += 1;
+        pending.latest = Some(req);
+        pending.collapsed += 1;
+        if let Some(burst) = &pending.burst {
+            return Box::pin(burst.clone());
+        }
+
+        let state = Arc::clone(&shared);
+        let quiet = self.debounce.quiet;
+        let sleeper = Arc::clone(&self.debounce.sleeper);
+        let sink = self.debounce.sink.clone();
+        let inner = self.inner.clone();
+        let burst = async move {
+            loop {
+                let seen = state.lock().unwrap_or_else(PoisonError::into_inner).generation;
+                sleeper.sleep(quiet).await;
+                let fired = {
+                    let mut pending = state.lock().unwrap_or_else(PoisonError::into_inner);
+                    (pending.generation == seen).then(|| {
+                        pending.burst = None;
+                        (pending.latest.take(), std::mem::take(&mut pending.collapsed))
+                    })
+                };
+                let Some((Some(req), requests)) = fired else {
+                    continue;
+                };
+                if requests > 1 {
+                    emit_best_effort(
+                        sink,
+                        PolicyEvent::Throttle(ThrottleEvent::Collapsed { requests }),
+                    )
+                    .await;
+                }
+                return inner.oneshot(req).await;
+            }
+        }
+        .boxed()
+        .shared();
+        pending.burst = Some(burst.clone());
+        Box::pin(burst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use crate::TrackingSleeper;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn throttle_spaces_out_calls() {
+        let sleeper = TrackingSleeper::new();
+        let sink = MemorySink::new();
+        let mut svc = ThrottleLayer::new(Duration::from_secs(1))
+            .with_sleeper(sleeper.clone())
+            .with_sink(sink.clone())
+            .layer(tower::service_fn(|req: u32| async move { Ok::<_, TestError>(req) }));
+
+        for i in 0..3 {
+            assert_eq!(svc.ready().await.unwrap().call(i).await.unwrap(), i);
+        }
+        let waits = sleeper.all_calls();
+        assert_eq!(waits.len(), 2, "the first call starts immediately");
+        assert!(waits[0] > Duration::from_millis(900) && waits[0] <= Duration::from_secs(1));
+        assert!(waits[1] > Duration::from_millis(1900) && waits[1] <= Duration::from_secs(2));
+        assert_eq!(sink.events().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn throttle_does_not_delay_spaced_calls() {
+        let sleeper = TrackingSleeper::new();
+        let mut svc = ThrottleLayer::new(Duration::from_millis(10))
+            .with_sleeper(sleeper.clone())
+            .layer(tower::service_fn(|req: u32| async move { Ok::<_, TestError>(req) }));
+
+        svc.ready().await.unwrap().call(1).await.unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        svc.ready().await.unwrap().call(2).await.unwrap();
+        assert_eq!(sleeper.calls(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn debounce_sends_only_the_trailing_request() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let sink = MemorySink::new();
+        let counter = Arc::clone(&calls);
+        let svc = DebounceLayer::new(Duration::from_millis(100)).with_sink(sink.clone()).layer(
+            tower::service_fn(move |req: u32| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async move { Ok::<_, TestError>(req * 10) }
+            }),
+        );
+
+        let mut burst = Vec::new();
+        for i in 1..=3 {
+            burst.push(tokio::spawn(svc.clone().oneshot(i)));
+            tokio::time::sleep(Duration::from_millis(60)).await;
+        }
+        for caller in burst {
+            assert_eq!(caller.await.unwrap(), Ok(30));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            sink.events(),
+            vec![PolicyEvent::Throttle(ThrottleEvent::Collapsed { requests: 3 })]
+        );
+
+        // After the burst, a new request opens a new window.
+        assert_eq!(svc.clone().oneshot(4).await, Ok(40));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
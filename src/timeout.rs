@@ -7,6 +7,25 @@
 //!   cooperative cancellation if that matters.
 //! - Elapsed is measured from just before invoking the closure to timeout firing and can be
 //!   slightly greater than the configured duration due to scheduling/timeout detection overhead.
+//! - When a [`Deadline`](crate::Deadline) is in scope (see [`RequestContext`]), the effective
+//!   timeout is the smaller of the configured duration and the remaining budget. An already
+//!   expired deadline fails fast without invoking the inner operation.
+//! - [`TimeoutLayer::with_warn_after`] sets a soft threshold: requests still running when it
+//!   elapses emit [`TimeoutEvent::Approaching`] and a `tracing` warning, then keep running until
+//!   the hard timeout.
+//! - [`TimeoutLayer::with_cancellation`] installs a per-request [`CancellationToken`] in the
+//!   [`RequestContext`] and cancels it when the timeout fires, so inner services can abort
+//!   transactions or close streams they own. Tokens derive from any token already in scope.
+//! - [`TimeoutLayer::with_jitter`] randomizes the effective timeout per request so a fleet with
+//!   identical settings does not expire (and retry) in lockstep. Jittered values never exceed the
+//!   configured duration; `Jitter::equal()` keeps them within `[duration/2, duration]`.
+//! - [`TimeoutProfile`] splits a request into connect, time-to-first-byte, and total phases for
+//!   transport integrations. [`TimeoutLayer::from_profile`] enforces the total and publishes the
+//!   profile through [`RequestContext`] so the transport can bound each phase with
+//!   [`TimeoutProfile::run_phase`].
+//! - [`TimeoutLayer::with_grace`] hands a timed-out inner future to a detached task that keeps
+//!   driving it for a bounded grace period (e.g. to finish its own rollback), then drops it. The
+//!   caller still receives the timeout error immediately.
 //! - Requires a Tokio runtime.
 //!
 //! Invariants:
@@ -41,9 +60,12 @@
 //! }
 //! ```
 
-use crate::ResilienceError;
+use crate::policy_metrics::{MetricsSnapshot, PolicyMetrics, TimeoutStats};
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, RequestOutcome, TimeoutEvent};
+use crate::{CancellationToken, Jitter, RequestContext, ResilienceError};
 use futures::future::BoxFuture;
 use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::Instant;
 use tower_service::Service;
@@ -53,6 +75,9 @@ use tower_service::Service;
 /// when longer horizons are required.
 pub const MAX_TIMEOUT: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
+/// Floor applied to jittered timeouts so full jitter cannot produce an instant timeout.
+const MIN_JITTERED_TIMEOUT: Duration = Duration::from_millis(1);
+
 /// Errors returned when configuring timeouts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimeoutError {
@@ -150,30 +175,216 @@ impl TimeoutPolicy {
         Op: FnOnce() -> Fut + Send,
     {
         let start = Instant::now();
+        let timeout = budgeted(self.duration);
+        if timeout.is_zero() {
+            return Err(ResilienceError::Timeout { elapsed: Duration::ZERO, timeout });
+        }
 
-        match tokio::time::timeout(self.duration, operation()).await {
+        match tokio::time::timeout(timeout, operation()).await {
             Ok(result) => result,
             Err(_) => {
                 let elapsed = start.elapsed();
-                Err(ResilienceError::Timeout { elapsed, timeout: self.duration })
+                Err(ResilienceError::Timeout { elapsed, timeout })
             }
         }
     }
 }
 
-use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, RequestOutcome, TimeoutEvent};
+/// Request phase bounded by a [`TimeoutProfile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeoutPhase {
+    /// Establishing the connection (TCP/TLS handshake, channel setup).
+    Connect,
+    /// Waiting for the first byte of the response after the request is sent.
+    FirstByte,
+    /// The whole request, end to end.
+    Total,
+}
+
+impl std::fmt::Display for TimeoutPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeoutPhase::Connect => write!(f, "connect"),
+            TimeoutPhase::FirstByte => write!(f, "first_byte"),
+            TimeoutPhase::Total => write!(f, "total"),
+        }
+    }
+}
+
+/// Compound timeout with separate connect, time-to-first-byte, and total limits.
+///
+/// Phase limits are optional and may not exceed the total.
+///
+/// # Examples
+/// ```
+/// use ninelives::{ResilienceError, TimeoutPhase, TimeoutProfile};
+/// use std::time::Duration;
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let profile = TimeoutProfile::new(Duration::from_secs(10))
+///     .unwrap()
+///     .with_connect(Duration::from_millis(500))
+///     .unwrap();
+/// let conn = profile
+///     .run_phase(TimeoutPhase::Connect, async { Ok::<_, std::io::Error>("connected") })
+///     .await;
+/// assert_eq!(conn.unwrap(), "connected");
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutProfile {
+    connect: Option<Duration>,
+    first_byte: Option<Duration>,
+    total: Duration,
+}
+
+impl TimeoutProfile {
+    /// Profile bounding only the total duration.
+    ///
+    /// # Errors
+    ///
+    /// Same validation as [`TimeoutPolicy::new`].
+    pub fn new(total: Duration) -> Result<Self, TimeoutError> {
+        let total = TimeoutPolicy::new(total)?.duration;
+        Ok(Self { connect: None, first_byte: None, total })
+    }
+
+    /// Bound the connect phase.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimeoutError::ZeroDuration`] for zero, or [`TimeoutError::ExceedsMaximum`] if
+    /// `limit` exceeds the total.
+    pub fn with_connect(mut self, limit: Duration) -> Result<Self, TimeoutError> {
+        self.connect = Some(TimeoutPolicy::new_with_max(limit, self.total)?.duration);
+        Ok(self)
+    }
+
+    /// Bound the time-to-first-byte phase.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimeoutError::ZeroDuration`] for zero, or [`TimeoutError::ExceedsMaximum`] if
+    /// `limit` exceeds the total.
+    pub fn with_first_byte(mut self, limit: Duration) -> Result<Self, TimeoutError> {
+        self.first_byte = Some(TimeoutPolicy::new_with_max(limit, self.total)?.duration);
+        Ok(self)
+    }
+
+    /// Connect limit, if set.
+    #[must_use]
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    /// Time-to-first-byte limit, if set.
+    #[must_use]
+    pub fn first_byte(&self) -> Option<Duration> {
+        self.first_byte
+    }
+
+    /// Total limit.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Limit configured for `phase`, if any.
+    #[must_use]
+    pub fn limit(&self, phase: TimeoutPhase) -> Option<Duration> {
+        match phase {
+            TimeoutPhase::Connect => self.connect,
+            TimeoutPhase::FirstByte => self.first_byte,
+            TimeoutPhase::Total => Some(self.total),
+        }
+    }
+
+    /// Drive `fut` as `phase`, failing with `ResilienceError::Timeout` if it overruns the phase
+    /// limit or the request deadline in scope. Phases without a limit are only bounded by the
+    /// deadline.
+    pub async fn run_phase<T, E, Fut>(
+        &self,
+        phase: TimeoutPhase,
+        fut: Fut,
+    ) -> Result<T, ResilienceError<E>>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let limit = match (self.limit(phase), RequestContext::current().deadline()) {
+            (Some(limit), Some(deadline)) => Some(limit.min(deadline.remaining())),
+            (Some(limit), None) => Some(limit),
+            (None, deadline) => deadline.map(|d| d.remaining()),
+        };
+        let Some(limit) = limit else {
+            return fut.await.map_err(ResilienceError::Inner);
+        };
+        let start = Instant::now();
+        match tokio::time::timeout(limit, fut).await {
+            Ok(result) => result.map_err(ResilienceError::Inner),
+            Err(_) => Err(ResilienceError::Timeout { elapsed: start.elapsed(), timeout: limit }),
+        }
+    }
+}
+
+/// Cap `duration` by the remaining budget of the request deadline in scope, if any.
+fn budgeted(duration: Duration) -> Duration {
+    match RequestContext::current().deadline() {
+        Some(deadline) => duration.min(deadline.remaining()),
+        None => duration,
+    }
+}
+
+/// How a timed-out request ended after its grace period (see [`TimeoutLayer::with_grace`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraceOutcome {
+    /// The inner future finished within the grace period; its result was discarded.
+    Completed,
+    /// The grace period elapsed and the inner future was dropped.
+    Aborted,
+}
+
+type GraceHook = Arc<dyn Fn(GraceOutcome) + Send + Sync>;
 
 /// Tower-native timeout layer with optional telemetry.
 #[derive(Clone)]
 pub struct TimeoutLayer<Sink = NullSink> {
     duration: Duration,
+    warn_after: Option<Duration>,
+    cancel_on_timeout: bool,
+    jitter: Jitter,
+    profile: Option<TimeoutProfile>,
+    grace: Option<(Duration, GraceHook)>,
+    stats: Arc<TimeoutStats>,
     sink: Sink,
 }
 
 impl TimeoutLayer<NullSink> {
     /// Build a timeout layer with the provided duration and no telemetry.
     pub fn new(duration: Duration) -> Result<Self, TimeoutError> {
-        TimeoutPolicy::new(duration).map(|p| TimeoutLayer { duration: p.duration, sink: NullSink })
+        TimeoutPolicy::new(duration).map(|p| TimeoutLayer {
+            duration: p.duration,
+            warn_after: None,
+            cancel_on_timeout: false,
+            jitter: Jitter::None,
+            profile: None,
+            grace: None,
+            stats: Arc::default(),
+            sink: NullSink,
+        })
+    }
+
+    /// Build a timeout layer enforcing `profile`'s total and exposing its phase limits to inner
+    /// services through [`RequestContext::timeout_profile`].
+    pub fn from_profile(profile: TimeoutProfile) -> Self {
+        TimeoutLayer {
+            duration: profile.total,
+            warn_after: None,
+            cancel_on_timeout: false,
+            jitter: Jitter::None,
+            profile: Some(profile),
+            grace: None,
+            stats: Arc::default(),
+            sink: NullSink,
+        }
     }
 }
 
@@ -186,7 +397,65 @@ where
     where
         NewSink: Clone,
     {
-        TimeoutLayer { duration: self.duration, sink }
+        TimeoutLayer {
+            duration: self.duration,
+            warn_after: self.warn_after,
+            cancel_on_timeout: self.cancel_on_timeout,
+            jitter: self.jitter,
+            profile: self.profile,
+            grace: self.grace,
+            stats: self.stats,
+            sink,
+        }
+    }
+
+    /// Randomize the effective timeout of each request with `jitter`.
+    ///
+    /// The result is capped at the configured duration and floored at 1ms.
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Give each request a [`CancellationToken`] that is cancelled when the timeout fires.
+    ///
+    /// Inner services read it via [`RequestContext::current`]. The inner future is dropped right
+    /// away unless [`with_grace`](Self::with_grace) keeps it running, so cleanup otherwise has to
+    /// happen in work that outlives it (e.g. a spawned task).
+    pub fn with_cancellation(mut self) -> Self {
+        self.cancel_on_timeout = true;
+        self
+    }
+
+    /// Keep driving timed-out inner futures in a detached task for up to `grace`.
+    ///
+    /// The caller gets the timeout error immediately. `on_timeout` runs once the grace period
+    /// resolves, reporting whether the inner future completed or was dropped.
+    pub fn with_grace<F>(mut self, grace: Duration, on_timeout: F) -> Self
+    where
+        F: Fn(GraceOutcome) + Send + Sync + 'static,
+    {
+        self.grace = Some((grace, Arc::new(on_timeout)));
+        self
+    }
+
+    /// Emit [`TimeoutEvent::Approaching`] for requests still running after `warn_after`.
+    ///
+    /// Thresholds at or beyond the effective timeout never fire.
+    pub fn with_warn_after(mut self, warn_after: Duration) -> Self {
+        self.warn_after = Some(warn_after);
+        self
+    }
+}
+
+impl<Sink> TimeoutLayer<Sink> {
+    fn jittered_duration(&self) -> Duration {
+        match self.jitter {
+            Jitter::None => self.duration,
+            ref jitter => {
+                jitter.apply_with_state(self.duration).clamp(MIN_JITTERED_TIMEOUT, self.duration)
+            }
+        }
     }
 }
 
@@ -194,13 +463,12 @@ where
 #[derive(Clone)]
 pub struct TimeoutService<S, Sink = NullSink> {
     inner: S,
-    duration: Duration,
-    sink: Sink,
+    layer: TimeoutLayer<Sink>,
 }
 
 impl<S, Sink> TimeoutService<S, Sink> {
-    fn new(inner: S, duration: Duration, sink: Sink) -> Self {
-        Self { inner, duration, sink }
+    fn new(inner: S, layer: TimeoutLayer<Sink>) -> Self {
+        Self { inner, layer }
     }
 }
 
@@ -227,14 +495,67 @@ where
     }
 
     fn call(&mut self, req: Request) -> Self::Future {
-        let duration = self.duration;
-        let fut = self.inner.call(req);
-        let sink = self.sink.clone();
+        let duration = budgeted(self.layer.jittered_duration());
+        let warn_after = self.layer.warn_after;
+        let sink = self.layer.sink.clone();
+        let grace = self.layer.grace.clone();
+        let stats = Arc::clone(&self.layer.stats);
+        let mut ctx = RequestContext::current();
+        if let Some(profile) = self.layer.profile {
+            ctx = ctx.with_timeout_profile(profile);
+        }
+        let token = self.layer.cancel_on_timeout.then(|| {
+            let token = ctx
+                .cancellation_token()
+                .map_or_else(CancellationToken::new, CancellationToken::child_token);
+            ctx = ctx.clone().with_cancellation_token(token.clone());
+            token
+        });
+        if duration.is_zero() {
+            // The deadline has already passed: fail without calling `inner`.
+            if let Some(token) = token {
+                token.cancel();
+            }
+            return Box::pin(async move {
+                stats.timeout();
+                emit_best_effort(
+                    sink,
+                    PolicyEvent::Timeout(TimeoutEvent::Occurred { timeout: duration }),
+                )
+                .await;
+                Err(ResilienceError::Timeout { elapsed: Duration::ZERO, timeout: duration })
+            });
+        }
+        let inner = &mut self.inner;
+        let fut = ctx.clone().sync_scope(|| inner.call(req));
+        let fut = ctx.scope(fut);
 
         Box::pin(async move {
             let start = Instant::now();
-            match tokio::time::timeout(duration, fut).await {
+            let expires_at = start + duration;
+            let mut fut = Box::pin(fut);
+            let outcome = match warn_after.filter(|w| *w < duration) {
+                Some(warn_after) => match tokio::time::timeout(warn_after, &mut fut).await {
+                    Ok(result) => Ok(result),
+                    Err(_) => {
+                        let elapsed = start.elapsed();
+                        tracing::warn!(?elapsed, timeout = ?duration, "request approaching timeout");
+                        emit_best_effort(
+                            sink.clone(),
+                            PolicyEvent::Timeout(TimeoutEvent::Approaching {
+                                elapsed,
+                                timeout: duration,
+                            }),
+                        )
+                        .await;
+                        tokio::time::timeout_at(expires_at, &mut fut).await
+                    }
+                },
+                None => tokio::time::timeout_at(expires_at, &mut fut).await,
+            };
+            match outcome {
                 Ok(Ok(r)) => {
+                    stats.success();
                     // Emit success event
                     let elapsed = start.elapsed();
                     emit_best_effort(
@@ -245,6 +566,7 @@ where
                     Ok(r)
                 }
                 Ok(Err(e)) => {
+                    stats.failure();
                     // Emit failure event
                     let elapsed = start.elapsed();
                     emit_best_effort(
@@ -255,7 +577,20 @@ where
                     Err(ResilienceError::Inner(e))
                 }
                 Err(_) => {
+                    stats.timeout();
                     let elapsed = start.elapsed();
+                    if let Some(token) = token {
+                        token.cancel();
+                    }
+                    if let Some((grace, on_timeout)) = grace {
+                        tokio::spawn(async move {
+                            let outcome = match tokio::time::timeout(grace, fut).await {
+                                Ok(_) => GraceOutcome::Completed,
+                                Err(_) => GraceOutcome::Aborted,
+                            };
+                            on_timeout(outcome);
+                        });
+                    }
                     // Emit timeout event
                     emit_best_effort(
                         sink.clone(),
@@ -275,7 +610,19 @@ where
 {
     type Service = TimeoutService<S, Sink>;
     fn layer(&self, service: S) -> Self::Service {
-        TimeoutService::new(service, self.duration, self.sink.clone())
+        TimeoutService::new(service, self.clone())
+    }
+}
+
+impl<Sink> crate::Describe for TimeoutLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        crate::PolicyNode::layer(format!("Timeout({:?})", self.duration))
+    }
+}
+
+impl<Sink> PolicyMetrics for TimeoutLayer<Sink> {
+    fn metrics(&self) -> MetricsSnapshot {
+        self.stats.snapshot(self.duration)
     }
 }
 
@@ -284,6 +631,7 @@ mod tests {
     use super::*;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
+    use tower_layer::Layer;
 
     #[derive(Debug, Clone, PartialEq, Eq)]
     struct TestError(String);
@@ -404,6 +752,241 @@ mod tests {
         assert_eq!(result.unwrap(), 42);
     }
 
+    #[tokio::test]
+    async fn deadline_shortens_configured_timeout() {
+        tokio::time::pause();
+        let timeout = TimeoutPolicy::new(Duration::from_secs(10)).unwrap();
+        let ctx =
+            RequestContext::new().with_deadline(crate::Deadline::after(Duration::from_millis(50)));
+
+        let result = ctx
+            .scope(timeout.execute(|| async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok::<_, ResilienceError<TestError>>(())
+            }))
+            .await;
+
+        match result.unwrap_err() {
+            ResilienceError::Timeout { timeout, .. } => {
+                assert_eq!(timeout, Duration::from_millis(50))
+            }
+            e => panic!("Expected Timeout error, got {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn expired_deadline_fails_fast_in_service() {
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
+        let mut svc = TimeoutLayer::new(Duration::from_secs(1)).unwrap().layer(tower::service_fn(
+            move |_req: ()| {
+                let calls = calls_clone.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok::<_, TestError>(())
+                }
+            },
+        ));
+
+        let deadline = crate::Deadline::after(Duration::from_millis(5));
+        tokio::time::advance(Duration::from_millis(10)).await;
+        let result = RequestContext::new()
+            .with_deadline(deadline)
+            .scope(async { svc.ready().await?.call(()).await })
+            .await;
+
+        assert!(result.unwrap_err().is_timeout());
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "inner future should not be polled");
+    }
+
+    #[tokio::test]
+    async fn expired_deadline_never_calls_inner() {
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let mut svc = TimeoutLayer::new(Duration::from_secs(1)).unwrap().with_cancellation().layer(
+            tower::service_fn(move |_req: ()| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                async { Ok::<_, TestError>(()) }
+            }),
+        );
+
+        let deadline = crate::Deadline::after(Duration::from_millis(5));
+        tokio::time::advance(Duration::from_millis(10)).await;
+        let result = RequestContext::new()
+            .with_deadline(deadline)
+            .scope(async { svc.ready().await?.call(()).await })
+            .await;
+
+        match result.unwrap_err() {
+            ResilienceError::Timeout { elapsed, timeout } => {
+                assert_eq!((elapsed, timeout), (Duration::ZERO, Duration::ZERO))
+            }
+            e => panic!("Expected Timeout error, got {:?}", e),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "inner service should not be called");
+    }
+
+    #[tokio::test]
+    async fn warn_after_emits_approaching_before_timeout() {
+        use crate::telemetry::MemorySink;
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let sink = MemorySink::new();
+        let mut svc = TimeoutLayer::new(Duration::from_millis(100))
+            .unwrap()
+            .with_warn_after(Duration::from_millis(60))
+            .with_sink(sink.clone())
+            .layer(tower::service_fn(|_req: ()| async {
+                tokio::time::sleep(Duration::from_millis(80)).await;
+                Ok::<_, TestError>("slow but fine")
+            }));
+
+        let resp = svc.ready().await.unwrap().call(()).await.unwrap();
+        assert_eq!(resp, "slow but fine");
+
+        let events = sink.events();
+        assert!(matches!(
+            events[0],
+            PolicyEvent::Timeout(TimeoutEvent::Approaching { elapsed, timeout })
+                if elapsed >= Duration::from_millis(60) && timeout == Duration::from_millis(100)
+        ));
+        assert!(matches!(events[1], PolicyEvent::Request(RequestOutcome::Success { .. })));
+    }
+
+    #[tokio::test]
+    async fn cancellation_token_fires_on_timeout() {
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let observed = Arc::new(std::sync::Mutex::new(None));
+        let observed_clone = observed.clone();
+        let mut svc = TimeoutLayer::new(Duration::from_millis(50))
+            .unwrap()
+            .with_cancellation()
+            .layer(tower::service_fn(move |_req: ()| {
+                // Capture the token synchronously in `call`, as a connection pool would.
+                *observed_clone.lock().unwrap() =
+                    RequestContext::current().cancellation_token().cloned();
+                async {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    Ok::<_, TestError>(())
+                }
+            }));
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert!(err.is_timeout());
+        let token = observed.lock().unwrap().clone().expect("token installed");
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn jittered_timeouts_stay_within_bounds() {
+        let layer =
+            TimeoutLayer::new(Duration::from_millis(300)).unwrap().with_jitter(Jitter::equal());
+        for _ in 0..100 {
+            let d = layer.jittered_duration();
+            assert!(d >= Duration::from_millis(150) && d <= Duration::from_millis(300), "{:?}", d);
+        }
+
+        let full = TimeoutLayer::new(Duration::from_millis(2)).unwrap().with_jitter(Jitter::full());
+        for _ in 0..100 {
+            assert!(full.jittered_duration() >= MIN_JITTERED_TIMEOUT);
+        }
+    }
+
+    #[test]
+    fn profile_rejects_phase_longer_than_total() {
+        let profile = TimeoutProfile::new(Duration::from_secs(1)).unwrap();
+        let err = profile.with_first_byte(Duration::from_secs(2)).unwrap_err();
+        assert!(
+            matches!(err, TimeoutError::ExceedsMaximum { limit, .. } if limit == Duration::from_secs(1))
+        );
+        assert!(matches!(profile.with_connect(Duration::ZERO), Err(TimeoutError::ZeroDuration)));
+    }
+
+    #[tokio::test]
+    async fn profile_phases_are_enforced_by_inner_service() {
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let profile = TimeoutProfile::new(Duration::from_secs(5))
+            .unwrap()
+            .with_connect(Duration::from_millis(100))
+            .unwrap();
+        let mut svc =
+            TimeoutLayer::from_profile(profile).layer(tower::service_fn(|_req: ()| async {
+                let profile =
+                    RequestContext::current().timeout_profile().expect("profile in scope");
+                profile
+                    .run_phase(TimeoutPhase::Connect, async {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        Ok::<_, TestError>(())
+                    })
+                    .await
+                    .map_err(|e| TestError(e.to_string()))
+            }));
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        match err {
+            ResilienceError::Inner(TestError(msg)) => assert!(msg.contains("100ms"), "{}", msg),
+            e => panic!("expected connect phase failure, got {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn grace_period_lets_inner_future_finish() {
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let finished = Arc::new(AtomicUsize::new(0));
+        let finished_clone = finished.clone();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = std::sync::Mutex::new(Some(tx));
+        let mut svc = TimeoutLayer::new(Duration::from_millis(50))
+            .unwrap()
+            .with_grace(Duration::from_millis(100), move |outcome| {
+                if let Some(tx) = tx.lock().unwrap().take() {
+                    let _ = tx.send(outcome);
+                }
+            })
+            .layer(tower::service_fn(move |_req: ()| {
+                let finished = finished_clone.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(80)).await;
+                    finished.fetch_add(1, Ordering::SeqCst);
+                    Ok::<_, TestError>(())
+                }
+            }));
+
+        let err = svc.ready().await.unwrap().call(()).await.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(rx.await.unwrap(), GraceOutcome::Completed);
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn grace_period_aborts_stragglers() {
+        use tower::{Service, ServiceExt};
+        tokio::time::pause();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = std::sync::Mutex::new(Some(tx));
+        let mut svc = TimeoutLayer::new(Duration::from_millis(50))
+            .unwrap()
+            .with_grace(Duration::from_millis(10), move |outcome| {
+                if let Some(tx) = tx.lock().unwrap().take() {
+                    let _ = tx.send(outcome);
+                }
+            })
+            .layer(tower::service_fn(|_req: ()| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok::<_, TestError>(())
+            }));
+
+        assert!(svc.ready().await.unwrap().call(()).await.unwrap_err().is_timeout());
+        assert_eq!(rx.await.unwrap(), GraceOutcome::Aborted);
+    }
+
     #[test]
     fn rejects_zero_duration() {
         let err = TimeoutPolicy::new(Duration::ZERO).unwrap_err();
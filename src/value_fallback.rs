@@ -0,0 +1,192 @@
+//! Last-resort fallback to a constant or computed response.
+//!
+//! Semantics
+//! - [`ValueFallbackLayer`] turns any error from the wrapped service into `Ok(value)`, where the
+//!   value is a clone of a constant ([`ValueFallbackLayer::new`]) or the result of a closure
+//!   ([`ValueFallbackLayer::from_fn`]), e.g. an empty list or the last known good config.
+//! - `policy.or_else_value(v)` places the layer outside `policy`, so it only answers once
+//!   everything beneath it (retries, fallback stacks, ...) has given up.
+//! - Readiness errors are not masked: `poll_ready` failures from the inner service are returned
+//!   unchanged, since no request has been accepted yet.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let policy = Policy(TimeoutLayer::new(Duration::from_millis(50))?).or_else_value(Vec::new());
+//!
+//! let mut svc = ServiceBuilder::new().layer(policy).service_fn(|_: ()| async {
+//!     Err::<Vec<u32>, _>(std::io::Error::new(std::io::ErrorKind::Other, "backend down"))
+//! });
+//! assert!(svc.ready().await?.call(()).await?.is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::algebra::{CombinedLayer, Policy};
+use crate::describe::{Describe, PolicyNode};
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer answering failed requests with a default response.
+pub struct ValueFallbackLayer<T> {
+    make: Arc<dyn Fn() -> T + Send + Sync>,
+}
+
+impl<T> ValueFallbackLayer<T> {
+    /// Answer failures with a clone of `value`.
+    pub fn new(value: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        Self::from_fn(move || value.clone())
+    }
+
+    /// Answer failures with the result of `make`, called once per failed request.
+    pub fn from_fn<F>(make: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self { make: Arc::new(make) }
+    }
+}
+
+impl<T> Clone for ValueFallbackLayer<T> {
+    fn clone(&self) -> Self {
+        Self { make: Arc::clone(&self.make) }
+    }
+}
+
+impl<T> fmt::Debug for ValueFallbackLayer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValueFallbackLayer").finish_non_exhaustive()
+    }
+}
+
+impl<L> Policy<L> {
+    /// Answer requests that fail through this policy with a clone of `value`.
+    pub fn or_else_value<T>(self, value: T) -> Policy<CombinedLayer<ValueFallbackLayer<T>, L>>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        Policy(ValueFallbackLayer::new(value)) + self
+    }
+
+    /// Answer requests that fail through this policy with the result of `make`.
+    pub fn or_else_with<T, F>(self, make: F) -> Policy<CombinedLayer<ValueFallbackLayer<T>, L>>
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Policy(ValueFallbackLayer::from_fn(make)) + self
+    }
+}
+
+impl<S, T> Layer<S> for ValueFallbackLayer<T> {
+    type Service = ValueFallbackService<S, T>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        ValueFallbackService { inner: service, make: Arc::clone(&self.make) }
+    }
+}
+
+impl<T> Describe for ValueFallbackLayer<T> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::layer("ValueFallback")
+    }
+}
+
+/// Service produced by [`ValueFallbackLayer`].
+pub struct ValueFallbackService<S, T> {
+    inner: S,
+    make: Arc<dyn Fn() -> T + Send + Sync>,
+}
+
+impl<S: Clone, T> Clone for ValueFallbackService<S, T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), make: Arc::clone(&self.make) }
+    }
+}
+
+impl<S: fmt::Debug, T> fmt::Debug for ValueFallbackService<S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValueFallbackService").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+impl<S, T, Request> Service<Request> for ValueFallbackService<S, T>
+where
+    S: Service<Request, Response = T>,
+    S::Future: Send + 'static,
+    T: 'static,
+{
+    type Response = T;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<T, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let fut = self.inner.call(req);
+        let make = Arc::clone(&self.make);
+        Box::pin(async move {
+            match fut.await {
+                Ok(resp) => Ok(resp),
+                Err(_) => Ok(make()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_successes_through_and_replaces_failures() {
+        let mut svc = Policy(tower_layer::Identity::new()).or_else_value("cached").layer(
+            tower::service_fn(|fail: bool| async move {
+                if fail {
+                    Err(TestError("boom".into()))
+                } else {
+                    Ok("fresh")
+                }
+            }),
+        );
+
+        assert_eq!(svc.ready().await.unwrap().call(false).await.unwrap(), "fresh");
+        assert_eq!(svc.ready().await.unwrap().call(true).await.unwrap(), "cached");
+    }
+
+    #[tokio::test]
+    async fn computes_value_per_failure() {
+        let made = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&made);
+        let mut svc = ValueFallbackLayer::from_fn(move || counter.fetch_add(1, Ordering::SeqCst))
+            .layer(tower::service_fn(|_: ()| async { Err::<usize, _>(TestError("down".into())) }));
+
+        assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), 0);
+        assert_eq!(svc.ready().await.unwrap().call(()).await.unwrap(), 1);
+        assert_eq!(made.load(Ordering::SeqCst), 2);
+    }
+}
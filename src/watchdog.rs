@@ -0,0 +1,379 @@
+//! Watchdog for requests that hang far beyond their typical latency.
+//!
+//! Semantics
+//! - The layer keeps an exponentially weighted average of how long requests take. A request
+//!   still running after `multiplier ×` that average (and at least the configured floor) is
+//!   reported as stuck with [`WatchdogEvent::Stuck`], carrying its elapsed time.
+//! - By default stuck requests keep running, and [`WatchdogEvent::Released`] is emitted if one
+//!   finally completes. With [`WatchdogLayer::abort_stuck`] the inner future is dropped instead
+//!   and the caller receives [`ResilienceError::Timeout`] with the threshold that was crossed.
+//! - Until the first request completes, the floor is the only threshold.
+//! - This is not a timeout: it adapts to observed latency and is meant to surface hung
+//!   connections that silently pin bulkhead permits, especially where no timeout is configured.
+//!
+//! Invariants
+//! - A stuck request that completes feeds the latency average only up to the threshold it
+//!   crossed, so a single hang cannot teach the watchdog that hanging is normal while a service
+//!   that is genuinely slower than the floor still raises the threshold over time.
+//! - [`WatchdogLayer::stuck`] counts requests currently past their threshold and still running.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let watchdog = WatchdogLayer::new(10.0)?.min_threshold(Duration::from_secs(5))?;
+//! let stuck = watchdog.clone();
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(watchdog.with_sink(LogSink))
+//!     .service_fn(|_: ()| async { Ok::<_, std::io::Error>("ok") });
+//! assert_eq!(svc.ready().await?.call(()).await?, "ok");
+//! assert_eq!(stuck.stuck(), 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::telemetry::{emit_best_effort, NullSink, PolicyEvent, WatchdogEvent};
+use crate::ResilienceError;
+use futures::future::BoxFuture;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Weight of each completed request in the latency average.
+const EWMA_ALPHA: f64 = 0.1;
+
+/// Errors produced while configuring a [`WatchdogLayer`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchdogError {
+    /// The multiplier was not a finite number greater than 1.
+    InvalidMultiplier {
+        /// The rejected multiplier.
+        provided: f64,
+    },
+    /// The minimum threshold was zero.
+    ZeroThreshold,
+}
+
+impl fmt::Display for WatchdogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMultiplier { provided } => {
+                write!(f, "watchdog multiplier must be finite and > 1, got {}", provided)
+            }
+            Self::ZeroThreshold => write!(f, "watchdog minimum threshold must be > 0"),
+        }
+    }
+}
+
+impl std::error::Error for WatchdogError {}
+
+/// Layer reporting (and optionally aborting) requests that run far longer than usual.
+#[derive(Debug, Clone)]
+pub struct WatchdogLayer<Sink = NullSink> {
+    multiplier: f64,
+    floor: Duration,
+    abort: bool,
+    shared: Arc<Shared>,
+    sink: Sink,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    /// Average latency in seconds; `None` until a request completes.
+    average: Mutex<Option<f64>>,
+    stuck: AtomicUsize,
+}
+
+impl WatchdogLayer<NullSink> {
+    /// Flag requests running longer than `multiplier ×` the average latency.
+    ///
+    /// The minimum threshold defaults to one second.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WatchdogError::InvalidMultiplier`] unless `multiplier` is finite and above 1.
+    pub fn new(multiplier: f64) -> Result<Self, WatchdogError> {
+        if !(multiplier.is_finite() && multiplier > 1.0) {
+            return Err(WatchdogError::InvalidMultiplier { provided: multiplier });
+        }
+        Ok(Self {
+            multiplier,
+            floor: Duration::from_secs(1),
+            abort: false,
+            shared: Arc::default(),
+            sink: NullSink,
+        })
+    }
+}
+
+impl<Sink> WatchdogLayer<Sink> {
+    /// Never flag a request before `floor` has elapsed, however fast requests usually are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WatchdogError::ZeroThreshold`] if `floor` is zero.
+    pub fn min_threshold(mut self, floor: Duration) -> Result<Self, WatchdogError> {
+        if floor.is_zero() {
+            return Err(WatchdogError::ZeroThreshold);
+        }
+        self.floor = floor;
+        Ok(self)
+    }
+
+    /// Drop stuck requests and fail them with [`ResilienceError::Timeout`].
+    pub fn abort_stuck(mut self) -> Self {
+        self.abort = true;
+        self
+    }
+
+    /// Attach a telemetry sink for [`WatchdogEvent`]s.
+    pub fn with_sink<NewSink>(self, sink: NewSink) -> WatchdogLayer<NewSink>
+    where
+        NewSink: Clone,
+    {
+        WatchdogLayer {
+            multiplier: self.multiplier,
+            floor: self.floor,
+            abort: self.abort,
+            shared: self.shared,
+            sink,
+        }
+    }
+
+    /// Requests currently past their threshold and still running.
+    pub fn stuck(&self) -> usize {
+        self.shared.stuck.load(Ordering::Relaxed)
+    }
+
+    /// The threshold a request starting now would be held to.
+    pub fn threshold(&self) -> Duration {
+        self.shared.threshold(self.multiplier, self.floor)
+    }
+}
+
+impl Shared {
+    fn threshold(&self, multiplier: f64, floor: Duration) -> Duration {
+        let average = *self.average.lock().unwrap_or_else(PoisonError::into_inner);
+        match average {
+            Some(secs) => Duration::from_secs_f64(secs * multiplier).max(floor),
+            None => floor,
+        }
+    }
+
+    fn record(&self, latency: Duration) {
+        let mut average = self.average.lock().unwrap_or_else(PoisonError::into_inner);
+        let sample = latency.as_secs_f64();
+        *average = Some(match *average {
+            Some(avg) => avg + EWMA_ALPHA * (sample - avg),
+            None => sample,
+        });
+    }
+}
+
+impl<S, Sink: Clone> Layer<S> for WatchdogLayer<Sink> {
+    type Service = WatchdogService<S, Sink>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        WatchdogService { inner, layer: self.clone() }
+    }
+}
+
+impl<Sink> crate::Describe for WatchdogLayer<Sink> {
+    fn describe(&self) -> crate::PolicyNode {
+        let action = if self.abort { ", abort" } else { "" };
+        crate::PolicyNode::layer(format!(
+            "Watchdog({}x, min {:?}{})",
+            self.multiplier, self.floor, action
+        ))
+    }
+}
+
+/// Service produced by [`WatchdogLayer`].
+#[derive(Debug, Clone)]
+pub struct WatchdogService<S, Sink = NullSink> {
+    inner: S,
+    layer: WatchdogLayer<Sink>,
+}
+
+impl<S, Request, Sink> Service<Request> for WatchdogService<S, Sink>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Send + 'static,
+    Sink: Service<PolicyEvent, Response = ()> + Clone + Send + 'static,
+    Sink::Error: std::error::Error + Send + 'static,
+    Sink::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = ResilienceError<S::Error>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(ResilienceError::Inner)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let start = Instant::now();
+        let threshold = self.layer.threshold();
+        let abort = self.layer.abort;
+        let shared = Arc::clone(&self.layer.shared);
+        let sink = self.layer.sink.clone();
+        let mut fut = Box::pin(self.inner.call(req));
+
+        Box::pin(async move {
+            if let Ok(result) = tokio::time::timeout(threshold, &mut fut).await {
+                shared.record(start.elapsed());
+                return result.map_err(ResilienceError::Inner);
+            }
+
+            let elapsed = start.elapsed();
+            emit_best_effort(
+                sink.clone(),
+                PolicyEvent::Watchdog(WatchdogEvent::Stuck { elapsed, threshold, aborted: abort }),
+            )
+            .await;
+            if abort {
+                return Err(ResilienceError::Timeout { elapsed, timeout: threshold });
+            }
+
+            let result = {
+                let _stuck = StuckGuard::new(&shared);
+                fut.await
+            };
+            shared.record(threshold);
+            emit_best_effort(
+                sink,
+                PolicyEvent::Watchdog(WatchdogEvent::Released { elapsed: start.elapsed() }),
+            )
+            .await;
+            result.map_err(ResilienceError::Inner)
+        })
+    }
+}
+
+/// Counts a request as stuck until it completes or is dropped.
+struct StuckGuard<'a>(&'a Shared);
+
+impl<'a> StuckGuard<'a> {
+    fn new(shared: &'a Shared) -> Self {
+        shared.stuck.fetch_add(1, Ordering::Relaxed);
+        Self(shared)
+    }
+}
+
+impl Drop for StuckGuard<'_> {
+    fn drop(&mut self) {
+        self.0.stuck.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::MemorySink;
+    use tower::ServiceExt;
+
+    #[derive(Debug)]
+    struct TestError(String);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    fn sleepy(
+    ) -> impl Service<Duration, Response = Duration, Error = TestError, Future = impl Send + 'static>
+           + Clone {
+        tower::service_fn(|d: Duration| async move {
+            tokio::time::sleep(d).await;
+            Ok::<_, TestError>(d)
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn threshold_follows_observed_latency() {
+        let layer = WatchdogLayer::new(3.0).unwrap().min_threshold(Duration::from_secs(1)).unwrap();
+        assert_eq!(layer.threshold(), Duration::from_secs(1));
+
+        let mut svc = layer.layer(sleepy());
+        for _ in 0..3 {
+            svc.ready().await.unwrap().call(Duration::from_millis(500)).await.unwrap();
+        }
+        assert_eq!(layer.threshold(), Duration::from_millis(1500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_stuck_requests_and_their_release() {
+        let sink = MemorySink::new();
+        let layer = WatchdogLayer::new(2.0)
+            .unwrap()
+            .min_threshold(Duration::from_millis(100))
+            .unwrap()
+            .with_sink(sink.clone());
+        let svc = layer.layer(sleepy());
+
+        let hung = tokio::spawn(svc.oneshot(Duration::from_millis(500)));
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(layer.stuck(), 1);
+        assert_eq!(hung.await.unwrap().unwrap(), Duration::from_millis(500));
+        assert_eq!(layer.stuck(), 0);
+
+        assert_eq!(
+            sink.events(),
+            vec![
+                PolicyEvent::Watchdog(WatchdogEvent::Stuck {
+                    elapsed: Duration::from_millis(100),
+                    threshold: Duration::from_millis(100),
+                    aborted: false,
+                }),
+                PolicyEvent::Watchdog(WatchdogEvent::Released {
+                    elapsed: Duration::from_millis(500)
+                }),
+            ]
+        );
+        // The hang counted only as long as the threshold it crossed.
+        assert_eq!(layer.threshold(), Duration::from_millis(200));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn abort_stuck_fails_with_timeout() {
+        let mut svc = WatchdogLayer::new(2.0)
+            .unwrap()
+            .min_threshold(Duration::from_millis(100))
+            .unwrap()
+            .abort_stuck()
+            .layer(sleepy());
+
+        let err = svc.ready().await.unwrap().call(Duration::from_secs(60)).await.unwrap_err();
+        assert_eq!(
+            err.timeout_details(),
+            Some((Duration::from_millis(100), Duration::from_millis(100)))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_configuration() {
+        assert!(matches!(WatchdogLayer::new(1.0), Err(WatchdogError::InvalidMultiplier { .. })));
+        assert!(matches!(
+            WatchdogLayer::new(f64::NAN),
+            Err(WatchdogError::InvalidMultiplier { .. })
+        ));
+        assert_eq!(
+            WatchdogLayer::new(3.0).unwrap().min_threshold(Duration::ZERO).unwrap_err(),
+            WatchdogError::ZeroThreshold
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Weighted random routing between two stacks, for canarying policy changes.
+//!
+//! Semantics
+//! - Each request is sent through the `canary` stack with probability `weight` and through the
+//!   `baseline` stack otherwise.
+//! - The weight lives in an [`Adaptive<f64>`], so the canary share can be raised, lowered, or
+//!   rolled back while traffic is flowing. It is read once per request.
+//! - Weights outside `[0.0, 1.0]` are clamped; `NaN` routes everything to the baseline.
+//! - As with [`CondLayer`](crate::CondLayer), the service is ready only when both stacks are.
+//!
+//! Example
+//! ```
+//! use ninelives::prelude::*;
+//! use std::time::Duration;
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let share = Adaptive::new(0.0);
+//! let canary = Policy(TimeoutLayer::new(Duration::from_millis(250))?);
+//! let baseline = Policy(TimeoutLayer::new(Duration::from_secs(1))?);
+//!
+//! let mut svc = ServiceBuilder::new()
+//!     .layer(Policy::weighted(share.clone(), canary, baseline))
+//!     .service_fn(|req: u32| async move { Ok::<_, std::io::Error>(req) });
+//! assert_eq!(svc.ready().await?.call(1).await?, 1);
+//!
+//! // Promote the new timeout to 10% of traffic without rebuilding the stack.
+//! share.set(0.10);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::adaptive::Adaptive;
+use crate::algebra::Policy;
+use crate::describe::{Describe, PolicyNode};
+use futures::future::Either;
+use rand::Rng;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Layer sending a live-adjustable fraction of requests through a canary stack.
+#[derive(Clone, Debug)]
+pub struct WeightedLayer<A, B> {
+    weight: Adaptive<f64>,
+    canary: A,
+    baseline: B,
+}
+
+impl<A, B> WeightedLayer<A, B> {
+    /// Route a `weight` fraction of requests through `canary`, the rest through `baseline`.
+    pub fn new(weight: impl Into<Adaptive<f64>>, canary: A, baseline: B) -> Self {
+        Self { weight: weight.into(), canary, baseline }
+    }
+
+    /// Handle to the canary weight; adjusting it affects every service built from this layer.
+    pub fn weight(&self) -> &Adaptive<f64> {
+        &self.weight
+    }
+}
+
+impl<A, B> Policy<WeightedLayer<A, B>> {
+    /// Route a `weight` fraction of requests through `canary`, the rest through `baseline`.
+    pub fn weighted(
+        weight: impl Into<Adaptive<f64>>,
+        canary: Policy<A>,
+        baseline: Policy<B>,
+    ) -> Self {
+        Policy(WeightedLayer::new(weight, canary.0, baseline.0))
+    }
+}
+
+impl<S, A, B> Layer<S> for WeightedLayer<A, B>
+where
+    S: Clone,
+    A: Layer<S>,
+    B: Layer<S>,
+{
+    type Service = WeightedService<A::Service, B::Service>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        WeightedService {
+            weight: self.weight.clone(),
+            canary: self.canary.layer(service.clone()),
+            baseline: self.baseline.layer(service),
+        }
+    }
+}
+
+impl<A: Describe, B: Describe> Describe for WeightedLayer<A, B> {
+    fn describe(&self) -> PolicyNode {
+        PolicyNode::branch(
+            format!("Weighted({:.1}% canary)", self.weight.get() * 100.0),
+            [("canary", self.canary.describe()), ("baseline", self.baseline.describe())],
+        )
+    }
+}
+
+/// Service produced by [`WeightedLayer`].
+#[derive(Clone, Debug)]
+pub struct WeightedService<S1, S2> {
+    weight: Adaptive<f64>,
+    canary: S1,
+    baseline: S2,
+}
+
+impl<S1, S2, Request> Service<Request> for WeightedService<S1, S2>
+where
+    S1: Service<Request>,
+    S2: Service<Request, Response = S1::Response, Error = S1::Error>,
+{
+    type Response = S1::Response;
+    type Error = S1::Error;
+    type Future = Either<S1::Future, S2::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match (self.canary.poll_ready(cx), self.baseline.poll_ready(cx)) {
+            (Poll::Ready(Err(e)), _) | (_, Poll::Ready(Err(e))) => Poll::Ready(Err(e)),
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => Poll::Ready(Ok(())),
+            _ => Poll::Pending,
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let weight = self.weight.get();
+        let weight = if weight.is_nan() { 0.0 } else { weight.clamp(0.0, 1.0) };
+        if rand::rng().random_bool(weight) {
+            Either::Left(self.canary.call(req))
+        } else {
+            Either::Right(self.baseline.call(req))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Debug)]
+    struct Tag(&'static str);
+
+    impl<S> Layer<S> for Tag {
+        type Service = TagService;
+
+        fn layer(&self, _inner: S) -> Self::Service {
+            let tag = self.0;
+            tower::util::BoxCloneService::new(tower::service_fn(
+                move |_req: ()| async move { Ok(tag) },
+            ))
+        }
+    }
+
+    type TagService = tower::util::BoxCloneService<(), &'static str, std::convert::Infallible>;
+
+    async fn count_canary(svc: &mut WeightedService<TagService, TagService>, n: usize) -> usize {
+        let mut hits = 0;
+        for _ in 0..n {
+            if svc.ready().await.unwrap().call(()).await.unwrap() == "canary" {
+                hits += 1;
+            }
+        }
+        hits
+    }
+
+    #[tokio::test]
+    async fn weight_changes_apply_to_live_services() {
+        let share = Adaptive::new(0.0);
+        let mut svc =
+            Policy::weighted(share.clone(), Policy(Tag("canary")), Policy(Tag("baseline")))
+                .layer(());
+
+        assert_eq!(count_canary(&mut svc, 100).await, 0);
+        share.set(1.0);
+        assert_eq!(count_canary(&mut svc, 100).await, 100);
+        share.set(0.5);
+        let hits = count_canary(&mut svc, 1_000).await;
+        assert!((300..=700).contains(&hits), "expected roughly half, got {}", hits);
+    }
+
+    #[tokio::test]
+    async fn out_of_range_weights_are_clamped() {
+        let share = Adaptive::new(7.0);
+        let mut svc =
+            Policy::weighted(share.clone(), Policy(Tag("canary")), Policy(Tag("baseline")))
+                .layer(());
+        assert_eq!(count_canary(&mut svc, 20).await, 20);
+        share.set(f64::NAN);
+        assert_eq!(count_canary(&mut svc, 20).await, 0);
+    }
+}